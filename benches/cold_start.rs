@@ -0,0 +1,75 @@
+//! Time to the first spectrum of a freshly opened dataset, reading the whole
+//! RAD file or only the block of one nuclide.
+//!
+//! Run with and without `--features parallel` to compare the sequential and
+//! the memory-mapped readers.
+
+use std::fmt::Write;
+use std::path::PathBuf;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+
+use radioactive::dataset::Icrp107;
+use radioactive::primitive::Nuclide;
+
+/// Spectrum lines per nuclide of the synthetic RAD file
+const LINES: usize = 100;
+
+/// Directory with a synthetic ICRP-07.RAD file of about the size of the
+/// published one, and the nuclides it lists
+fn synthetic_dataset() -> (PathBuf, Vec<Nuclide>) {
+    let dir = std::env::temp_dir().join(format!("radioactive-cold-start-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+
+    let nuclides: Vec<Nuclide> = (1..=92u32)
+        .flat_map(|z| (0..12).map(move |i| Nuclide::WithId(z * 10_000_000 + (2 * z + i) * 10_000)))
+        .collect();
+
+    let mut rad = String::new();
+    for nuclide in &nuclides {
+        writeln!(rad, "{:<7}{:>10}", nuclide.to_string(), LINES).unwrap();
+        for i in 0..LINES {
+            let energy = 1e-3 * (i + 1) as f64;
+            writeln!(rad, "  {:<12.5E}{:<12.5E}G  ", 0.5 / (i + 1) as f64, energy).unwrap();
+        }
+    }
+    std::fs::write(dir.join("ICRP-07.RAD"), rad).unwrap();
+
+    (dir, nuclides)
+}
+
+fn cold_start(c: &mut Criterion) {
+    let (dir, nuclides) = synthetic_dataset();
+    let index_dir = dir.join("index");
+    let nuclide = nuclides[nuclides.len() / 2];
+
+    let data = Icrp107::open(&dir).unwrap();
+    assert_eq!(data.rad().unwrap().len(), nuclides.len());
+    let data = Icrp107::open(&dir).unwrap().index_dir(&index_dir);
+    assert_eq!(data.rad_for(nuclide).unwrap().len(), LINES);
+
+    let mut group = c.benchmark_group("cold start");
+    group.sample_size(10);
+
+    group.bench_function("rad, whole file", |b| {
+        b.iter(|| Icrp107::open(&dir).unwrap().rad().unwrap().len())
+    });
+    group.bench_function("rad_for, scanned", |b| {
+        b.iter(|| Icrp107::open(&dir).unwrap().rad_for(nuclide).unwrap())
+    });
+    group.bench_function("rad_for, stored index", |b| {
+        b.iter(|| {
+            Icrp107::open(&dir)
+                .unwrap()
+                .index_dir(&index_dir)
+                .rad_for(nuclide)
+                .unwrap()
+        })
+    });
+
+    group.finish();
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+criterion_group!(benches, cold_start);
+criterion_main!(benches);
@@ -0,0 +1,75 @@
+use std::sync::Arc;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+
+use radioactive::decaychain::DecayChainBuilder;
+use radioactive::error::Error;
+use radioactive::primitive::attr::{NuclideHalfLife, NuclideProgeny};
+use radioactive::primitive::{DecayMode, DecayModeSet, HalfLife, Nuclide, Progeny, TimeUnit};
+
+/// Synthetic chain of `len` nuclides, each decaying to its two successors
+/// with a spontaneous fission branch.
+struct LongChain {
+    len: u32,
+}
+
+impl LongChain {
+    fn nuclide(i: u32) -> Nuclide {
+        Nuclide::WithId(10_000_000 + i * 10_000)
+    }
+
+    fn position(&self, nuclide: Nuclide) -> Result<u32, Error> {
+        match nuclide {
+            Nuclide::WithId(id) => id.checked_sub(10_000_000).map(|id| id / 10_000),
+            Nuclide::FissionProducts => None,
+        }
+        .filter(|&i| i < self.len)
+        .ok_or_else(|| Error::InvalidNuclide(nuclide.to_string()))
+    }
+}
+
+impl NuclideHalfLife for LongChain {
+    fn half_life(&self, nuclide: Nuclide) -> Result<HalfLife, Error> {
+        self.position(nuclide).map(|i| HalfLife {
+            value: (i + 1) as f64,
+            unit: TimeUnit::Day,
+        })
+    }
+}
+
+impl NuclideProgeny for LongChain {
+    fn progeny(&self, nuclide: Nuclide) -> Result<Vec<Progeny>, Error> {
+        let i = self.position(nuclide)?;
+        let beta = DecayModeSet::default() | DecayMode::BetaMinus;
+        let progeny = |nuclide, branch_rate, decay_mode| Progeny {
+            nuclide,
+            branch_rate,
+            decay_mode,
+        };
+
+        Ok((i + 1..(i + 3).min(self.len))
+            .map(|j| progeny(Self::nuclide(j), 0.49, beta))
+            .chain(std::iter::once(progeny(
+                Nuclide::FissionProducts,
+                0.02,
+                DecayModeSet::default() | DecayMode::SpontaneousFission,
+            )))
+            .collect())
+    }
+}
+
+fn build(c: &mut Criterion) {
+    let mut group = c.benchmark_group("DecayChainBuilder::build");
+
+    for len in [100, 1_000, 5_000] {
+        let data = Arc::new(LongChain { len });
+        group.bench_with_input(BenchmarkId::from_parameter(len), &data, |b, data| {
+            b.iter(|| DecayChainBuilder::new(data.clone()).build(LongChain::nuclide(0)))
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, build);
+criterion_main!(benches);
@@ -0,0 +1,58 @@
+//! Compare decay results exported from radioactivedecay (or PyNE) with this crate.
+//!
+//! Usage: `cargo run --example crosscheck -- <icrp107 data dir> <reference.json> [floor (Bq)]`
+//!
+//! The reference file is a JSON array of cases, which can be exported with:
+//!
+//! ```python
+//! import json, radioactivedecay as rd
+//!
+//! cases = []
+//! for nuclide in ["Ra-226", "Cs-137", "Mo-99"]:
+//!     for t in [3600.0, 86400.0, 3.15e7]:
+//!         inv = rd.Inventory({nuclide: 1.0}, "Bq")
+//!         cases.append({
+//!             "initial": {nuclide: 1.0},
+//!             "time": t,
+//!             "activities": inv.decay(t, "s").activities("Bq"),
+//!         })
+//! json.dump(cases, open("reference.json", "w"))
+//! ```
+
+use std::sync::Arc;
+
+use radioactive::dataset::Icrp107;
+use radioactive::decaychain::crosscheck::{cross_check, ReferenceCase};
+use radioactive::decaychain::BatemanDecaySolver;
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args: Vec<String> = std::env::args().collect();
+    if args.len() < 3 {
+        eprintln!(
+            "usage: {} <icrp107 data dir> <reference.json> [floor]",
+            args[0]
+        );
+        std::process::exit(2);
+    }
+
+    let data = Arc::new(Icrp107::open(&args[1])?);
+    let cases = ReferenceCase::from_json(&std::fs::read_to_string(&args[2])?)?;
+    let floor = args.get(3).map_or(Ok(1e-30), |s| s.parse())?;
+
+    let report = cross_check(&BatemanDecaySolver::new(data), &cases, floor);
+    print!("{}", report);
+    if let Some(max) = report.max() {
+        match max.relative {
+            Some(relative) => println!(
+                "max relative deviation: {:.3e} ({} at t = {} s, case {})",
+                relative, max.nuclide, max.time, max.case
+            ),
+            None => println!(
+                "{} missing from the reference (t = {} s, case {})",
+                max.nuclide, max.time, max.case
+            ),
+        }
+    }
+
+    Ok(())
+}
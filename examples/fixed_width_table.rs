@@ -0,0 +1,58 @@
+//! Read an inventory from a fixed-width table, as written by older codes.
+//!
+//! Usage: `cargo run --example fixed_width_table -- <table> [fortran format]`
+//!
+//! Each line holds a nuclide and its activity (Bq), in the format given or by
+//! default `(a10,e12.0)`; lines starting with `#` are comments:
+//!
+//! ```text
+//! # nuclide  activity
+//! Cs-137      1.500E+06
+//! Co-60       3.200E+04
+//! ```
+
+use std::fs::File;
+use std::io::BufReader;
+use std::sync::OnceLock;
+
+use radioactive::decaychain::Inventory;
+use radioactive::fixedformat::{fortran_fields, FieldSet, FixedWidth, RecordReader};
+use radioactive::primitive::Nuclide;
+use serde::Deserialize;
+
+static FORMAT: OnceLock<String> = OnceLock::new();
+
+#[derive(Debug, Deserialize)]
+struct Row {
+    nuclide: Nuclide,
+    activity: f64,
+}
+
+impl FixedWidth for Row {
+    fn fields() -> FieldSet {
+        fortran_fields(FORMAT.get().unwrap()).unwrap().0
+    }
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args: Vec<String> = std::env::args().collect();
+    if args.len() < 2 {
+        eprintln!("usage: {} <table> [fortran format]", args[0]);
+        std::process::exit(2);
+    }
+
+    let format = args.get(2).map_or("(a10,e12.0)", String::as_str);
+    // fail early on an invalid format instead of in `Row::fields`
+    fortran_fields(format)?;
+    FORMAT.set(format.to_string()).unwrap();
+
+    let mut inv = Inventory::new();
+    let rows = RecordReader::<_, Row>::new(BufReader::new(File::open(&args[1])?)).comment("#");
+    for row in rows {
+        let row = row?;
+        inv.add(row.nuclide, row.activity);
+    }
+    print!("{}", inv);
+
+    Ok(())
+}
@@ -0,0 +1,6 @@
+//! Shared library of the C ABI in `radioactive::ffi`, declared by `include/icrp107.h`
+//!
+//! Built with `cargo build --release -p radioactive-ffi`, which leaves the
+//! `radioactive` crate itself an rlib.
+
+pub use radioactive::ffi::*;
@@ -0,0 +1,154 @@
+/* C ABI of the radioactive crate (feature `ffi`), generated by cbindgen; do not edit */
+
+#ifndef ICRP107_H
+#define ICRP107_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#define ICRP107_OK 0
+
+/*
+ A pointer argument is null
+ */
+#define ICRP107_ERR_NULL 1
+
+/*
+ An argument could not be parsed
+ */
+#define ICRP107_ERR_INVALID 2
+
+/*
+ The nuclide is not in the dataset, or has no coefficient
+ */
+#define ICRP107_ERR_NOT_FOUND 3
+
+/*
+ The output buffer is too small, the required length is written back
+ */
+#define ICRP107_ERR_BUFFER 4
+
+/*
+ The dataset could not be read
+ */
+#define ICRP107_ERR_DATA 5
+
+/*
+ Internal error (panic)
+ */
+#define ICRP107_ERR_PANIC 6
+
+/*
+ ICRP-107 decay data with its Bateman solver
+ */
+typedef struct Icrp107Decay Icrp107Decay;
+
+/*
+ Internal dose coefficients of a Radiological Toolbox database
+ */
+typedef struct Icrp107Dcf Icrp107Dcf;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/*
+ Message of the last error of the calling thread (empty after a success)
+
+ `buf` must point to `*len` writable bytes, `len` must be valid.
+ */
+int icrp107_last_error(char *buf, size_t *len);
+
+/*
+ Id of a nuclide given in notation like "Cs-137" or "Tc-99m"
+
+ `name` must be a NUL-terminated string, `id` must be valid for writes.
+ */
+int icrp107_nuclide_parse(const char *name, uint32_t *id);
+
+/*
+ Notation of a nuclide id, written NUL-terminated to `buf` of `*len` bytes
+
+ `buf` must point to `*len` writable bytes, `len` must be valid.
+ */
+int icrp107_nuclide_name(uint32_t id, char *buf, size_t *len);
+
+/*
+ Open an ICRP-107 data directory, null on failure
+
+ `path` must be a NUL-terminated string.
+ */
+Icrp107Decay *icrp107_decay_open(const char *path);
+
+/*
+ `handle` must come from `icrp107_decay_open` and not be used afterwards.
+ */
+void icrp107_decay_close(Icrp107Decay *handle);
+
+/*
+ Half-life (s)
+
+ `handle` must be a live handle, `half_life` must be valid for writes.
+ */
+int icrp107_half_life(const Icrp107Decay *handle, uint32_t id, double *half_life);
+
+/*
+ Activities after `time` (s) of a mixture of `n` nuclides
+
+ On input `*out_len` is the capacity of `out_ids` and `out_activities`, on
+ output the number of nuclides written (or needed, with `ICRP107_ERR_BUFFER`).
+
+ `handle` must be a live handle, `ids` and `activities` must point to `n`
+ values, `out_ids` and `out_activities` to `*out_len` writable values.
+ */
+int icrp107_decay(const Icrp107Decay *handle,
+                  size_t n,
+                  const uint32_t *ids,
+                  const double *activities,
+                  double time,
+                  uint32_t *out_ids,
+                  double *out_activities,
+                  size_t *out_len);
+
+/*
+ Open `icrp68.mdb` or `icrp72.mdb` of the Radiological Toolbox, null on failure
+
+ `path` must be a NUL-terminated string.
+ */
+Icrp107Dcf *icrp107_dcf_open(const char *path);
+
+/*
+ `handle` must come from `icrp107_dcf_open` and not be used afterwards.
+ */
+void icrp107_dcf_close(Icrp107Dcf *handle);
+
+/*
+ Largest ingestion coefficient (Sv/Bq) of the chemical forms of a nuclide
+
+ `age_group` and `organ` are given as in the datasets, e.g. "Adult" and "EffectiveDose".
+
+ `handle` must be a live handle, `age_group` and `organ` NUL-terminated
+ strings, `value` valid for writes.
+ */
+int icrp107_dcf_ingestion(const Icrp107Dcf *handle,
+                          uint32_t id,
+                          const char *age_group,
+                          const char *organ,
+                          double *value);
+
+/*
+ Largest inhalation coefficient (Sv/Bq) of the absorption types of a nuclide
+
+ As `icrp107_dcf_ingestion`.
+ */
+int icrp107_dcf_inhalation(const Icrp107Dcf *handle,
+                           uint32_t id,
+                           const char *age_group,
+                           const char *organ,
+                           double *value);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* ICRP107_H */
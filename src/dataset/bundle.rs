@@ -0,0 +1,236 @@
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use once_cell::sync::OnceCell;
+
+use super::reader::FileReader;
+use crate::error::Error;
+
+/// File of an archive, decompressed on first read
+struct Entry {
+    data: Arc<[u8]>,
+    decompressed: OnceCell<Arc<[u8]>>,
+}
+
+impl From<Vec<u8>> for Entry {
+    fn from(data: Vec<u8>) -> Self {
+        Self {
+            data: data.into(),
+            decompressed: OnceCell::new(),
+        }
+    }
+}
+
+/// Data files of a zip or tar (optionally gzip/zstd compressed) archive, kept in memory
+///
+/// Files are looked up by their path in the archive, or by their name alone
+/// if no other file of the archive has the same name.
+pub struct Bundle {
+    path: PathBuf,
+    files: BTreeMap<String, Entry>,
+}
+
+impl Bundle {
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        let path = path.as_ref().to_path_buf();
+        let mut file = File::open(&path)?;
+
+        let mut magic = [0u8; 4];
+        let is_zip = file.read(&mut magic)? == 4 && magic == *b"PK\x03\x04";
+        drop(file);
+
+        let files = if is_zip {
+            Self::read_zip(File::open(&path)?)?
+        } else {
+            Self::read_tar(FileReader::from_file(File::open(&path)?)?)?
+        };
+
+        Ok(Self { path, files })
+    }
+
+    fn read_zip(file: File) -> Result<BTreeMap<String, Entry>, Error> {
+        let mut archive = zip::ZipArchive::new(file).map_err(|e| Error::Unexpected(e.into()))?;
+        let mut files = BTreeMap::new();
+
+        for i in 0..archive.len() {
+            let mut entry = archive
+                .by_index(i)
+                .map_err(|e| Error::Unexpected(e.into()))?;
+            if !entry.is_file() {
+                continue;
+            }
+            let name = entry.name().trim_start_matches("./").to_string();
+            let mut data = vec![];
+            entry.read_to_end(&mut data)?;
+            files.insert(name, data.into());
+        }
+
+        Ok(files)
+    }
+
+    fn read_tar<R: Read>(reader: R) -> Result<BTreeMap<String, Entry>, Error> {
+        let mut archive = tar::Archive::new(reader);
+        let mut files = BTreeMap::new();
+
+        for entry in archive.entries()? {
+            let mut entry = entry?;
+            if !entry.header().entry_type().is_file() {
+                continue;
+            }
+            let name = entry
+                .path()?
+                .to_string_lossy()
+                .trim_start_matches("./")
+                .to_string();
+            let mut data = vec![];
+            entry.read_to_end(&mut data)?;
+            files.insert(name, data.into());
+        }
+
+        Ok(files)
+    }
+
+    /// Path of the archive
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Paths of the files in the archive
+    pub fn files(&self) -> impl Iterator<Item = &str> {
+        self.files.keys().map(String::as_str)
+    }
+
+    /// Reader of a file, falling back to its compressed variants like [`super::reader::resolve`]
+    pub(crate) fn reader(&self, name: &str) -> Result<FileReader, Error> {
+        let entry = self.entry(name)?.ok_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                format!("{} not found in {}", name, self.path.display()),
+            )
+        })?;
+
+        let data = entry
+            .decompressed
+            .get_or_try_init(|| FileReader::decompress(entry.data.clone()))?;
+        Ok(FileReader::from_memory(data.clone()))
+    }
+
+    /// File of a path, or of a name unique in the archive
+    fn entry(&self, name: &str) -> Result<Option<&Entry>, Error> {
+        let names = [
+            name.to_string(),
+            format!("{}.gz", name),
+            format!("{}.zst", name),
+        ];
+        if let Some(entry) = names.iter().find_map(|name| self.files.get(name)) {
+            return Ok(Some(entry));
+        }
+
+        for name in &names {
+            let mut found = self
+                .files
+                .iter()
+                .filter(|(path, _)| file_name(Path::new(path)) == *name);
+            match (found.next(), found.next()) {
+                (Some((_, entry)), None) => return Ok(Some(entry)),
+                (Some((a, _)), Some((b, _))) => {
+                    return Err(Error::Unexpected(anyhow::anyhow!(
+                        "{} is ambiguous in {}: {}, {}",
+                        name,
+                        self.path.display(),
+                        a,
+                        b
+                    )))
+                }
+                _ => {}
+            }
+        }
+
+        Ok(None)
+    }
+}
+
+impl std::fmt::Debug for Bundle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Bundle")
+            .field("path", &self.path)
+            .field("files", &self.files.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+impl PartialEq for Bundle {
+    fn eq(&self, other: &Self) -> bool {
+        self.path == other.path
+    }
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::Write;
+
+    use super::*;
+
+    #[test]
+    fn zip_and_tar() {
+        let dir = crate::fixtures::TempDir::new("bundle");
+
+        let zip_path = dir.join("data.zip");
+        let mut zip = zip::ZipWriter::new(File::create(&zip_path).unwrap());
+        zip.start_file("data/ICRP-07.NDX", zip::write::SimpleFileOptions::default())
+            .unwrap();
+        zip.write_all(b"header\nline 1\n").unwrap();
+        zip.start_file(
+            "other/ICRP-07.RAD",
+            zip::write::SimpleFileOptions::default(),
+        )
+        .unwrap();
+        zip.start_file("data/ICRP-07.RAD", zip::write::SimpleFileOptions::default())
+            .unwrap();
+        zip.finish().unwrap();
+
+        let tar_path = dir.join("data.tar");
+        let mut tar = tar::Builder::new(File::create(&tar_path).unwrap());
+        let content = b"header\nline 1\n";
+        let mut header = tar::Header::new_gnu();
+        header.set_size(content.len() as u64);
+        header.set_cksum();
+        tar.append_data(&mut header, "data/ICRP-07.NDX", &content[..])
+            .unwrap();
+        for path in ["other/ICRP-07.RAD", "data/ICRP-07.RAD"] {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(0);
+            header.set_cksum();
+            tar.append_data(&mut header, path, &[][..]).unwrap();
+        }
+        tar.finish().unwrap();
+        drop(tar);
+
+        for path in [zip_path, tar_path] {
+            let bundle = Bundle::open(&path).unwrap();
+            assert_eq!(
+                bundle.files().collect::<Vec<_>>(),
+                vec!["data/ICRP-07.NDX", "data/ICRP-07.RAD", "other/ICRP-07.RAD"]
+            );
+
+            let mut reader = bundle.reader("ICRP-07.NDX").unwrap().seek(7).unwrap();
+            let mut buf = String::new();
+            reader.read_line(&mut buf).unwrap();
+            assert_eq!(buf, "line 1\n");
+
+            // same name in two directories
+            assert!(bundle.reader("ICRP-07.RAD").is_err());
+            assert!(bundle.reader("other/ICRP-07.RAD").is_ok());
+            assert!(bundle.reader("ICRP-07.BET").is_err());
+        }
+    }
+}
@@ -0,0 +1,201 @@
+use std::sync::Arc;
+
+use crate::error::Error;
+use crate::primitive::attr::{
+    AtomicMass, DcfIngestion, DcfInhalation, MassAttenuationCoefficient, NuclideAirKerma,
+    NuclideDecayMode, NuclideHalfLife, NuclidePhotonLines, NuclideProgeny,
+};
+use crate::primitive::{DecayModeSet, HalfLife, Nuclide, Progeny};
+use crate::provenance::{DatasetIdentity, DatasetInfo};
+
+/// Object-safe decay data, for datasets selected at run time
+///
+/// Solvers and builders accept `Arc<dyn DecaySource>` as well as concrete datasets:
+///
+/// ```
+/// # use std::sync::Arc;
+/// # use radioactive::dataset::DecaySource;
+/// # use radioactive::decaychain::{BatemanDecaySolver, DecayChainBuilder, Inventory};
+/// # use radioactive::fixtures::FixtureDecayData;
+/// let data: Arc<dyn DecaySource> = Arc::new(FixtureDecayData::new());
+/// let sr90 = "Sr-90".parse().unwrap();
+///
+/// let chain = DecayChainBuilder::new(data.clone()).build(sr90);
+/// assert_eq!(chain.node_count(), 2);
+///
+/// let mut inventory = Inventory::new();
+/// inventory.add(sr90, 1e3);
+/// let solver = BatemanDecaySolver::new(data);
+/// assert_eq!(solver.decay(&inventory, 0.).len(), 2);
+/// ```
+pub trait DecaySource:
+    NuclideProgeny + NuclideHalfLife + NuclideDecayMode + DatasetInfo + Send + Sync
+{
+}
+
+impl<T> DecaySource for T where
+    T: NuclideProgeny + NuclideHalfLife + NuclideDecayMode + DatasetInfo + Send + Sync
+{
+}
+
+/// Object-safe intake dose coefficients, the counterpart of [`DecaySource`]
+pub trait DcfSource: DcfIngestion + DcfInhalation + Send + Sync {}
+
+impl<T> DcfSource for T where T: DcfIngestion + DcfInhalation + Send + Sync {}
+
+/// Object-safe photon emission data for external exposure
+pub trait PhotonSource: NuclidePhotonLines + NuclideAirKerma + Send + Sync {}
+
+impl<T> PhotonSource for T where T: NuclidePhotonLines + NuclideAirKerma + Send + Sync {}
+
+/// Object-safe attenuation data for shielding
+pub trait AttenuationSource: AtomicMass + MassAttenuationCoefficient + Send + Sync {}
+
+impl<T> AttenuationSource for T where T: AtomicMass + MassAttenuationCoefficient + Send + Sync {}
+
+/// Decay data queried from sources in order of priority
+///
+/// A nuclide is answered entirely by the first source knowing its half-life,
+/// so half-life, decay modes and progeny of a nuclide never mix sources.
+///
+/// ```
+/// # use std::sync::Arc;
+/// # use radioactive::dataset::ChainedDecayData;
+/// # use radioactive::fixtures::FixtureDecayData;
+/// # use radioactive::primitive::attr::NuclideHalfLife;
+/// let data = ChainedDecayData::new().with(Arc::new(FixtureDecayData::new()));
+/// let cs137 = "Cs-137".parse().unwrap();
+///
+/// assert!(data.half_life(cs137).is_ok());
+/// assert_eq!(data.source_of(cs137).unwrap().name, "fixtures");
+/// ```
+#[derive(Default, Clone)]
+pub struct ChainedDecayData {
+    sources: Vec<Arc<dyn DecaySource>>,
+}
+
+impl ChainedDecayData {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append a source with lower priority than the ones added before
+    pub fn with(mut self, source: Arc<dyn DecaySource>) -> Self {
+        self.sources.push(source);
+        self
+    }
+
+    pub fn sources(&self) -> &[Arc<dyn DecaySource>] {
+        &self.sources
+    }
+
+    fn find(&self, nuclide: Nuclide) -> Result<&dyn DecaySource, Error> {
+        self.sources
+            .iter()
+            .find(|source| source.half_life(nuclide).is_ok())
+            .map(|source| source.as_ref())
+            .ok_or_else(|| Error::InvalidNuclide(nuclide.to_string()))
+    }
+
+    /// Identity of the source answering for a nuclide
+    pub fn source_of(&self, nuclide: Nuclide) -> Option<DatasetIdentity> {
+        self.find(nuclide).ok().map(|s| s.dataset_identity())
+    }
+}
+
+impl NuclideProgeny for ChainedDecayData {
+    fn progeny(&self, nuclide: Nuclide) -> Result<Vec<Progeny>, Error> {
+        self.find(nuclide)?.progeny(nuclide)
+    }
+
+    /// Changed by a change of any source
+    fn epoch(&self) -> u64 {
+        self.sources
+            .iter()
+            .fold(0, |epoch, source| epoch.wrapping_add(source.epoch()))
+    }
+}
+
+impl NuclideHalfLife for ChainedDecayData {
+    fn half_life(&self, nuclide: Nuclide) -> Result<HalfLife, Error> {
+        self.find(nuclide)?.half_life(nuclide)
+    }
+}
+
+impl NuclideDecayMode for ChainedDecayData {
+    fn decay_mode(&self, nuclide: Nuclide) -> Result<DecayModeSet, Error> {
+        self.find(nuclide)?.decay_mode(nuclide)
+    }
+}
+
+impl DatasetInfo for ChainedDecayData {
+    fn dataset_identity(&self) -> DatasetIdentity {
+        let names: Vec<String> = self
+            .sources
+            .iter()
+            .map(|s| s.dataset_identity().name)
+            .collect();
+
+        DatasetIdentity::new(&names.join(" > "))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::fixtures::FixtureDecayData;
+    use crate::primitive::TimeUnit;
+
+    // Overrides the half-life of Cs-137
+    struct Overrides;
+
+    impl NuclideHalfLife for Overrides {
+        fn half_life(&self, nuclide: Nuclide) -> Result<HalfLife, Error> {
+            match nuclide.to_string().as_str() {
+                "Cs-137" => Ok(HalfLife {
+                    value: 30.05,
+                    unit: TimeUnit::Year,
+                }),
+                _ => Err(Error::InvalidNuclide(nuclide.to_string())),
+            }
+        }
+    }
+
+    impl NuclideProgeny for Overrides {
+        fn progeny(&self, _: Nuclide) -> Result<Vec<Progeny>, Error> {
+            Ok(vec![])
+        }
+    }
+
+    impl NuclideDecayMode for Overrides {
+        fn decay_mode(&self, _: Nuclide) -> Result<DecayModeSet, Error> {
+            Ok(DecayModeSet::default())
+        }
+    }
+
+    impl DatasetInfo for Overrides {
+        fn dataset_identity(&self) -> DatasetIdentity {
+            DatasetIdentity::new("overrides")
+        }
+    }
+
+    #[test]
+    fn failover() {
+        let data = ChainedDecayData::new()
+            .with(Arc::new(Overrides))
+            .with(Arc::new(FixtureDecayData::new()));
+        let cs137 = "Cs-137".parse().unwrap();
+        let sr90 = "Sr-90".parse().unwrap();
+
+        assert_eq!(data.half_life(cs137).unwrap().value, 30.05);
+        assert!(data.progeny(cs137).unwrap().is_empty());
+        assert_eq!(data.source_of(cs137).unwrap().name, "overrides");
+
+        assert_eq!(data.half_life(sr90).unwrap().value, 28.79);
+        assert_eq!(data.progeny(sr90).unwrap().len(), 1);
+        assert_eq!(data.source_of(sr90).unwrap().name, "fixtures");
+
+        assert!(data.half_life("U-238".parse().unwrap()).is_err());
+        assert_eq!(data.dataset_identity().name, "overrides > fixtures");
+    }
+}
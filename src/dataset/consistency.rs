@@ -0,0 +1,149 @@
+use flagset::FlagSet;
+
+use crate::error::Error;
+use crate::primitive::attr::{NuclideDecayMode, NuclideList, NuclideProgeny};
+use crate::primitive::{DecayMode, DecayModeSet, Nuclide};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DecayModeIssue {
+    /// No decay mode could be inferred for the branch to the daughter
+    Uninferred { parent: Nuclide, daughter: Nuclide },
+    /// Branch modes not listed in the decay modes of the parent
+    Unexpected {
+        parent: Nuclide,
+        modes: DecayModeSet,
+    },
+    /// Decay mode without a branch although its daughter is radioactive
+    Dropped {
+        parent: Nuclide,
+        mode: DecayMode,
+        daughter: Nuclide,
+    },
+}
+
+/// Ground state daughter of a decay mode, `None` for spontaneous fission
+fn daughter_of(parent: Nuclide, mode: DecayMode) -> Option<Nuclide> {
+    let (z, a) = (parent.z()? as u32, parent.a()?);
+    let (z, a) = match mode {
+        DecayMode::Alpha => (z.checked_sub(2)?, a.checked_sub(4)?),
+        DecayMode::BetaMinus => (z + 1, a),
+        DecayMode::BetaPlus | DecayMode::ElectronCapture => (z.checked_sub(1)?, a),
+        DecayMode::IsometricTransition => (z, a),
+        DecayMode::SpontaneousFission => return None,
+    };
+
+    Some(Nuclide::WithId(z * 10_000_000 + a * 10_000))
+}
+
+/// Check that the decay modes of each nuclide are exactly the union of the
+/// modes of its branches
+///
+/// Branches to stable daughters are not listed in decay data files, so a mode
+/// without a branch is only reported when its daughter is part of the dataset.
+pub fn check_decay_modes<D>(data: &D) -> Result<Vec<DecayModeIssue>, Error>
+where
+    D: NuclideList + NuclideDecayMode + NuclideProgeny,
+{
+    let nuclides = data.nuclide_list()?;
+    let mut issues = vec![];
+
+    for &parent in &nuclides {
+        let modes = data.decay_mode(parent)?;
+        let mut union = FlagSet::default();
+
+        for progeny in data.progeny(parent)? {
+            if progeny.decay_mode.0.is_empty() {
+                issues.push(DecayModeIssue::Uninferred {
+                    parent,
+                    daughter: progeny.nuclide,
+                });
+            }
+            union |= progeny.decay_mode.0;
+        }
+
+        let unexpected = union - modes.0;
+        if !unexpected.is_empty() {
+            issues.push(DecayModeIssue::Unexpected {
+                parent,
+                modes: DecayModeSet(unexpected),
+            });
+        }
+
+        for mode in modes.0 - union {
+            let dropped =
+                daughter_of(parent, mode).filter(|&d| d != parent && nuclides.contains(&d));
+            if let Some(daughter) = dropped {
+                issues.push(DecayModeIssue::Dropped {
+                    parent,
+                    mode,
+                    daughter,
+                });
+            }
+        }
+    }
+
+    Ok(issues)
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeSet;
+
+    use super::*;
+    use crate::fixtures::FixtureDecayData;
+    use crate::primitive::Progeny;
+
+    // Fixtures with the progeny of Ra-226 removed and Sr-90 listed as alpha emitter
+    struct Broken(FixtureDecayData);
+
+    impl NuclideList for Broken {
+        fn nuclide_list(&self) -> Result<BTreeSet<Nuclide>, Error> {
+            self.0.nuclide_list()
+        }
+    }
+
+    impl NuclideDecayMode for Broken {
+        fn decay_mode(&self, nuclide: Nuclide) -> Result<DecayModeSet, Error> {
+            match nuclide.to_string().as_str() {
+                "Sr-90" => Ok(DecayModeSet(DecayMode::Alpha.into())),
+                _ => self.0.decay_mode(nuclide),
+            }
+        }
+    }
+
+    impl NuclideProgeny for Broken {
+        fn progeny(&self, nuclide: Nuclide) -> Result<Vec<Progeny>, Error> {
+            match nuclide.to_string().as_str() {
+                "Ra-226" => Ok(vec![]),
+                _ => self.0.progeny(nuclide),
+            }
+        }
+    }
+
+    #[test]
+    fn fixtures_are_consistent() {
+        assert!(check_decay_modes(&FixtureDecayData::new())
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn broken_data() {
+        let issues = check_decay_modes(&Broken(FixtureDecayData::new())).unwrap();
+
+        assert_eq!(
+            issues,
+            vec![
+                DecayModeIssue::Unexpected {
+                    parent: "Sr-90".parse().unwrap(),
+                    modes: DecayModeSet(DecayMode::BetaMinus.into()),
+                },
+                DecayModeIssue::Dropped {
+                    parent: "Ra-226".parse().unwrap(),
+                    mode: DecayMode::Alpha,
+                    daughter: "Rn-222".parse().unwrap(),
+                },
+            ]
+        );
+    }
+}
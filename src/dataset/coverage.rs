@@ -0,0 +1,142 @@
+use crate::error::Error;
+use crate::primitive::attr::{NuclideHalfLife, NuclideList};
+use crate::primitive::{HalfLife, Nuclide};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct HalfLifeMismatch {
+    pub nuclide: Nuclide,
+    pub a: HalfLife,
+    pub b: HalfLife,
+    /// |a - b| / b
+    pub relative: f64,
+}
+
+/// Nuclide coverage and half-life agreement of two decay data sources
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CoverageDiff {
+    pub only_in_a: Vec<Nuclide>,
+    pub only_in_b: Vec<Nuclide>,
+    pub half_life_mismatches: Vec<HalfLifeMismatch>,
+}
+
+impl CoverageDiff {
+    pub fn is_empty(&self) -> bool {
+        self.only_in_a.is_empty()
+            && self.only_in_b.is_empty()
+            && self.half_life_mismatches.is_empty()
+    }
+}
+
+impl std::fmt::Display for CoverageDiff {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let list = |nuclides: &[Nuclide]| {
+            nuclides
+                .iter()
+                .map(|n| n.to_string())
+                .collect::<Vec<_>>()
+                .join(" ")
+        };
+        writeln!(f, "only in a: {}", list(&self.only_in_a))?;
+        writeln!(f, "only in b: {}", list(&self.only_in_b))?;
+        writeln!(
+            f,
+            "{:<10}{:>14}{:>14}{:>14}",
+            "nuclide", "a", "b", "rel. dev."
+        )?;
+        for m in &self.half_life_mismatches {
+            writeln!(
+                f,
+                "{:<10}{:>14}{:>14}{:>14.3e}",
+                m.nuclide.to_string(),
+                m.a.to_string(),
+                m.b.to_string(),
+                m.relative
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Compare the nuclides of two sources, reporting half-lives of common
+/// nuclides differing by more than `rtol`
+pub fn coverage_diff<A, B>(a: &A, b: &B, rtol: f64) -> Result<CoverageDiff, Error>
+where
+    A: NuclideList + NuclideHalfLife,
+    B: NuclideList + NuclideHalfLife,
+{
+    let nuclides_a = a.nuclide_list()?;
+    let nuclides_b = b.nuclide_list()?;
+
+    let mut diff = CoverageDiff {
+        only_in_a: nuclides_a.difference(&nuclides_b).copied().collect(),
+        only_in_b: nuclides_b.difference(&nuclides_a).copied().collect(),
+        ..Default::default()
+    };
+
+    for &nuclide in nuclides_a.intersection(&nuclides_b) {
+        let (t_a, t_b) = (a.half_life(nuclide)?, b.half_life(nuclide)?);
+        let relative = (t_a.as_sec() - t_b.as_sec()).abs() / t_b.as_sec();
+        if relative > rtol {
+            diff.half_life_mismatches.push(HalfLifeMismatch {
+                nuclide,
+                a: t_a,
+                b: t_b,
+                relative,
+            });
+        }
+    }
+
+    Ok(diff)
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeSet;
+
+    use super::*;
+    use crate::fixtures::FixtureDecayData;
+    use crate::primitive::TimeUnit;
+
+    struct Other;
+
+    impl NuclideList for Other {
+        fn nuclide_list(&self) -> Result<BTreeSet<Nuclide>, Error> {
+            Ok(["Cs-137", "Sr-90", "U-238"]
+                .iter()
+                .map(|s| s.parse().unwrap())
+                .collect())
+        }
+    }
+
+    impl NuclideHalfLife for Other {
+        fn half_life(&self, nuclide: Nuclide) -> Result<HalfLife, Error> {
+            let value = match nuclide.to_string().as_str() {
+                "Cs-137" => 30.05,
+                "Sr-90" => 28.79,
+                _ => 4.468e9,
+            };
+            Ok(HalfLife {
+                value,
+                unit: TimeUnit::Year,
+            })
+        }
+    }
+
+    #[test]
+    fn diff() {
+        let fixtures = FixtureDecayData::new();
+        let diff = coverage_diff(&fixtures, &Other, 1e-3).unwrap();
+
+        assert_eq!(diff.only_in_b, vec!["U-238".parse().unwrap()]);
+        assert_eq!(diff.only_in_a.len(), fixtures.nuclides().count() - 2);
+        assert_eq!(diff.half_life_mismatches.len(), 1);
+        assert_eq!(
+            diff.half_life_mismatches[0].nuclide,
+            "Cs-137".parse().unwrap()
+        );
+        assert!(diff.to_string().contains("Cs-137"));
+
+        assert!(coverage_diff(&fixtures, &fixtures, 0.).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,150 @@
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
+
+use super::DecaySource;
+use crate::error::Error;
+use crate::primitive::attr::{NuclideDecayMode, NuclideHalfLife, NuclideProgeny};
+use crate::primitive::{DecayModeSet, HalfLife, Nuclide, Progeny};
+use crate::provenance::{DatasetIdentity, DatasetInfo};
+
+/// Decay data of a source with nuclides overridden at run time
+///
+/// An overridden nuclide is answered entirely by its override, decay modes
+/// being those of its progeny. Every change bumps the [`NuclideProgeny::epoch`],
+/// so that solvers sharing the data drop their cached chains:
+///
+/// ```
+/// # use std::sync::Arc;
+/// # use radioactive::dataset::CustomDecayData;
+/// # use radioactive::decaychain::{BatemanDecaySolver, Inventory};
+/// # use radioactive::fixtures::FixtureDecayData;
+/// # use radioactive::primitive::{HalfLife, TimeUnit};
+/// let data = Arc::new(CustomDecayData::new(Arc::new(FixtureDecayData::new())));
+/// let solver = BatemanDecaySolver::new(data.clone());
+/// let sr90 = "Sr-90".parse().unwrap();
+///
+/// let mut inventory = Inventory::new();
+/// inventory.add(sr90, 1e3);
+/// assert_eq!(solver.decay(&inventory, 0.).len(), 2);
+///
+/// let half_life = HalfLife { value: 28.79, unit: TimeUnit::Year };
+/// data.set(sr90, half_life, vec![]);
+/// assert_eq!(solver.decay(&inventory, 0.).len(), 1);
+/// ```
+pub struct CustomDecayData {
+    base: Arc<dyn DecaySource>,
+    overrides: RwLock<BTreeMap<Nuclide, (HalfLife, Vec<Progeny>)>>,
+    epoch: AtomicU64,
+}
+
+impl CustomDecayData {
+    pub fn new(base: Arc<dyn DecaySource>) -> Self {
+        Self {
+            base,
+            overrides: RwLock::new(BTreeMap::new()),
+            epoch: AtomicU64::new(0),
+        }
+    }
+
+    /// Override the half-life and progeny of a nuclide, listed or not by the base
+    pub fn set(&self, nuclide: Nuclide, half_life: HalfLife, progeny: Vec<Progeny>) {
+        self.overrides
+            .write()
+            .unwrap()
+            .insert(nuclide, (half_life, progeny));
+        self.epoch.fetch_add(1, Ordering::Release);
+    }
+
+    /// Back to the data of the base, `false` if the nuclide was not overridden
+    pub fn remove(&self, nuclide: Nuclide) -> bool {
+        let removed = self.overrides.write().unwrap().remove(&nuclide).is_some();
+        if removed {
+            self.epoch.fetch_add(1, Ordering::Release);
+        }
+        removed
+    }
+
+    pub fn is_overridden(&self, nuclide: Nuclide) -> bool {
+        self.overrides.read().unwrap().contains_key(&nuclide)
+    }
+}
+
+impl NuclideProgeny for CustomDecayData {
+    fn progeny(&self, nuclide: Nuclide) -> Result<Vec<Progeny>, Error> {
+        match self.overrides.read().unwrap().get(&nuclide) {
+            Some((_, progeny)) => Ok(progeny.clone()),
+            None => self.base.progeny(nuclide),
+        }
+    }
+
+    /// Changed by an override or a change of the base
+    fn epoch(&self) -> u64 {
+        (self.epoch.load(Ordering::Acquire)).wrapping_add(self.base.epoch())
+    }
+}
+
+impl NuclideHalfLife for CustomDecayData {
+    fn half_life(&self, nuclide: Nuclide) -> Result<HalfLife, Error> {
+        match self.overrides.read().unwrap().get(&nuclide) {
+            Some((half_life, _)) => Ok(*half_life),
+            None => self.base.half_life(nuclide),
+        }
+    }
+}
+
+impl NuclideDecayMode for CustomDecayData {
+    fn decay_mode(&self, nuclide: Nuclide) -> Result<DecayModeSet, Error> {
+        match self.overrides.read().unwrap().get(&nuclide) {
+            Some((_, progeny)) => Ok(DecayModeSet(
+                progeny
+                    .iter()
+                    .fold(Default::default(), |modes, p| modes | p.decay_mode.0),
+            )),
+            None => self.base.decay_mode(nuclide),
+        }
+    }
+}
+
+impl DatasetInfo for CustomDecayData {
+    fn dataset_identity(&self) -> DatasetIdentity {
+        DatasetIdentity::new(&format!("{} (custom)", self.base.dataset_identity().name))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::fixtures::FixtureDecayData;
+    use crate::primitive::{DecayMode, TimeUnit};
+
+    #[test]
+    fn overrides() {
+        let data = CustomDecayData::new(Arc::new(FixtureDecayData::new()));
+        let (sr90, y90): (Nuclide, Nuclide) = ("Sr-90".parse().unwrap(), "Y-90".parse().unwrap());
+        assert_eq!(data.epoch(), 0);
+
+        // made-up alpha branch
+        let progeny = vec![Progeny {
+            nuclide: y90,
+            branch_rate: 0.5,
+            decay_mode: DecayModeSet::default() | DecayMode::Alpha,
+        }];
+        let half_life = HalfLife {
+            value: 1.,
+            unit: TimeUnit::Day,
+        };
+        data.set(sr90, half_life, progeny);
+        assert_eq!(data.epoch(), 1);
+        assert_eq!(data.half_life(sr90).unwrap().value, 1.);
+        assert_eq!(data.progeny(sr90).unwrap()[0].branch_rate, 0.5);
+        assert!(data.decay_mode(sr90).unwrap().0.contains(DecayMode::Alpha));
+        assert_eq!(data.half_life(y90).unwrap().unit, TimeUnit::Hour);
+
+        assert!(data.remove(sr90));
+        assert!(!data.remove(sr90));
+        assert_eq!(data.epoch(), 2);
+        assert_eq!(data.half_life(sr90).unwrap().value, 28.79);
+        assert_eq!(data.dataset_identity().name, "fixtures (custom)");
+    }
+}
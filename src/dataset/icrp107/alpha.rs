@@ -0,0 +1,64 @@
+use super::spectrum::RadiationType;
+use super::Icrp107;
+use crate::error::Error;
+use crate::primitive::attr::NuclideAtomicMass;
+use crate::primitive::{Energy, Nuclide};
+
+/// Alpha particle mass (amu)
+const ALPHA_MASS: f64 = 4.001_506_179;
+/// He-4 atomic mass (amu)
+const HELIUM_MASS: f64 = 4.002_603_254;
+
+/// Alpha line with the kinetic energy of the recoiling daughter nucleus
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AlphaLine {
+    pub energy: Energy,
+    /// (/nt)
+    pub r#yield: f64,
+    pub recoil: Energy,
+}
+
+/// Kinetic energy of the daughter recoiling from an alpha particle of `energy`
+/// emitted by a nuclide of atomic mass `parent` (amu)
+///
+/// Non-relativistic momentum balance, E_R = E_a m_a / m_R, with the daughter
+/// mass taken as the parent mass less a He-4 atom.
+pub fn recoil_energy(energy: Energy, parent: f64) -> Energy {
+    Energy::ev(energy.as_ev() * ALPHA_MASS / (parent - HELIUM_MASS))
+}
+
+impl Icrp107 {
+    /// Alpha lines of the RAD file with the recoil energies from the NDX atomic mass
+    pub fn alpha_lines(&self, nuclide: Nuclide) -> Result<Vec<AlphaLine>, Error> {
+        let lines = self.rad_for(nuclide)?;
+        if !lines.iter().any(|l| l.r#type == RadiationType::Alpha) {
+            return Ok(vec![]);
+        }
+        let parent = self.nuclide_atomic_mass(nuclide)?;
+
+        Ok(lines
+            .iter()
+            .filter(|l| l.r#type == RadiationType::Alpha)
+            .map(|l| {
+                let energy = Energy::mev(l.energy);
+                AlphaLine {
+                    energy,
+                    r#yield: l.r#yield,
+                    recoil: recoil_energy(energy, parent),
+                }
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn recoil() {
+        // Po-210 (209.98 amu) main line, recoil of Pb-206 about 103 keV
+        let recoil = recoil_energy(Energy::mev(5.304), 209.982_874);
+        assert!((recoil.as_kev() - 103.0).abs() < 0.5);
+    }
+}
@@ -0,0 +1,130 @@
+use std::collections::HashMap;
+
+use once_cell::sync::OnceCell;
+
+use super::Icrp107;
+use crate::error::Error;
+use crate::primitive::attr::{DcfIngestion, DcfInhalation};
+use crate::primitive::{AgeGroup, DcfQueryResult, Nuclide, Organ};
+
+/// Which data files have entries for a nuclide
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Capabilities {
+    pub ndx: bool,
+    pub rad: bool,
+    pub bet: bool,
+    pub ack: bool,
+    pub nsf: bool,
+    /// Adult effective dose coefficient for ingestion, `None` if not queried
+    pub ingestion: Option<bool>,
+    /// Adult effective dose coefficient for inhalation, `None` if not queried
+    pub inhalation: Option<bool>,
+}
+
+impl Capabilities {
+    /// Add the dose coefficient flags of a provider
+    pub fn with_dcf<P>(mut self, provider: &P, nuclide: Nuclide) -> Result<Self, Error>
+    where
+        P: DcfIngestion + DcfInhalation + ?Sized,
+    {
+        let (age_group, organ) = (AgeGroup::Adult, Organ::EffectiveDose);
+        self.ingestion = Some(listed(provider.dcf_ingestion(nuclide, age_group, organ))?);
+        self.inhalation = Some(listed(provider.dcf_inhalation(nuclide, age_group, organ))?);
+
+        Ok(self)
+    }
+}
+
+fn listed(res: Result<DcfQueryResult, Error>) -> Result<bool, Error> {
+    match res {
+        Ok(res) => Ok(!res.is_empty()),
+        Err(Error::InvalidNuclide(_)) => Ok(false),
+        Err(e) => Err(e),
+    }
+}
+
+impl Icrp107 {
+    /// Which data files have entries for the nuclide
+    ///
+    /// Spectrum files already loaded are looked up directly, the others through
+    /// their block index without parsing the spectra.
+    pub fn capabilities(&self, nuclide: Nuclide) -> Result<Capabilities, Error> {
+        Ok(Capabilities {
+            ndx: self.contains(nuclide)?,
+            rad: self.has_spectrum(&self.rad, 0, "ICRP-07.RAD", nuclide)?,
+            bet: self.has_spectrum(&self.bet, 1, "ICRP-07.BET", nuclide)?,
+            ack: self.has_spectrum(&self.ack, 2, "ICRP-07.ACK", nuclide)?,
+            nsf: self.has_spectrum(&self.nsf, 3, "ICRP-07.NSF", nuclide)?,
+            ingestion: None,
+            inhalation: None,
+        })
+    }
+
+    fn has_spectrum<T>(
+        &self,
+        loaded: &OnceCell<HashMap<Nuclide, Vec<T>>>,
+        i: usize,
+        file_name: &str,
+        nuclide: Nuclide,
+    ) -> Result<bool, Error> {
+        match loaded.get() {
+            Some(spectra) => Ok(spectra.get(&nuclide).is_some_and(|s| !s.is_empty())),
+            None => Ok(self.spectrum_index(i, file_name)?.contains(nuclide)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::primitive::DcfValue;
+
+    struct TestDcf;
+
+    impl DcfIngestion for TestDcf {
+        fn dcf_ingestion(
+            &self,
+            _: Nuclide,
+            _: AgeGroup,
+            _: Organ,
+        ) -> Result<DcfQueryResult, Error> {
+            let value = DcfValue {
+                value: 1.3e-8,
+                unit: "Sv/Bq".to_string(),
+                attr: None,
+                provenance: None,
+            };
+            Ok(DcfQueryResult::new("test", vec![value]))
+        }
+    }
+
+    impl DcfInhalation for TestDcf {
+        fn dcf_inhalation(
+            &self,
+            _: Nuclide,
+            _: AgeGroup,
+            _: Organ,
+        ) -> Result<DcfQueryResult, Error> {
+            Err(Error::InvalidNuclide("test".to_string()))
+        }
+    }
+
+    #[test]
+    fn dcf_flags() {
+        let caps = Capabilities::default()
+            .with_dcf(&TestDcf, "Cs-137".parse().unwrap())
+            .unwrap();
+        assert_eq!(caps.ingestion, Some(true));
+        assert_eq!(caps.inhalation, Some(false));
+    }
+
+    #[test]
+    #[ignore]
+    fn icrp107_capabilities() {
+        let data = Icrp107::open("data/icrp107").unwrap();
+        let caps = data.capabilities("Cf-252".parse().unwrap()).unwrap();
+        assert!(caps.ndx && caps.rad && caps.nsf);
+        let caps = data.capabilities("H-3".parse().unwrap()).unwrap();
+        assert!(caps.bet && !caps.nsf);
+    }
+}
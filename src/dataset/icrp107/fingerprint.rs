@@ -0,0 +1,140 @@
+use std::io::Read;
+
+use serde::{Deserialize, Serialize};
+use sha1::{Digest, Sha1};
+
+use crate::dataset::reader::FileReader;
+use crate::error::Error;
+
+/// Number of radionuclides listed by the index of the published ICRP-107 dataset
+pub const PUBLISHED_NUCLIDE_COUNT: usize = 1252;
+
+/// Data files of the ICRP-107 dataset
+pub const FILE_NAMES: [&str; 5] = [
+    "ICRP-07.NDX",
+    "ICRP-07.RAD",
+    "ICRP-07.BET",
+    "ICRP-07.ACK",
+    "ICRP-07.NSF",
+];
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileFingerprint {
+    pub name: String,
+    /// File size (bytes, decompressed)
+    pub size: u64,
+    /// SHA-1 digest (hex)
+    pub sha1: String,
+    /// Number of nuclide entries
+    pub entries: usize,
+}
+
+impl FileFingerprint {
+    /// Digest of the (decompressed) content of a data file
+    pub(super) fn new(name: &str, file: FileReader, entries: usize) -> Result<Self, Error> {
+        let (size, sha1) = digest(file)?;
+
+        Ok(Self {
+            name: name.to_string(),
+            size,
+            sha1,
+            entries,
+        })
+    }
+}
+
+/// Size (bytes) and SHA-1 digest (hex) of the content of a data file
+pub(super) fn digest(mut file: FileReader) -> Result<(u64, String), Error> {
+    let mut hasher = Sha1::new();
+    let mut buf = [0u8; 64 * 1024];
+    let mut size = 0;
+
+    loop {
+        let n = file.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+        size += n as u64;
+    }
+
+    Ok((size, format!("{:x}", hasher.finalize())))
+}
+
+/// Checksums and entry counts of the ICRP-107 data files
+///
+/// A fingerprint checks that a copy of the dataset is complete and compares
+/// two copies; it does not identify the published release, whose file sizes
+/// and digests do not ship with the crate. Applications pinning their data
+/// keep the fingerprint of a copy they trust, e.g. as JSON, and compare with
+/// [`Fingerprint::differences`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Fingerprint {
+    pub files: Vec<FileFingerprint>,
+}
+
+impl Fingerprint {
+    pub fn file(&self, name: &str) -> Option<&FileFingerprint> {
+        self.files.iter().find(|f| f.name == name)
+    }
+
+    /// Whether all data files are present and the index lists as many nuclides
+    /// as the published dataset
+    ///
+    /// Sizes and contents are not checked: a truncated or modified data file
+    /// next to a full index passes.
+    pub fn is_complete(&self) -> bool {
+        FILE_NAMES.iter().all(|&name| self.file(name).is_some())
+            && self
+                .file("ICRP-07.NDX")
+                .is_some_and(|f| f.entries == PUBLISHED_NUCLIDE_COUNT)
+    }
+
+    /// Names of the files that differ from (or are missing in) a reference fingerprint
+    pub fn differences(&self, reference: &Fingerprint) -> Vec<String> {
+        reference
+            .files
+            .iter()
+            .filter(|r| self.file(&r.name) != Some(*r))
+            .map(|r| r.name.clone())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn file_fingerprint() {
+        let dir = crate::fixtures::TempDir::new("fingerprint");
+        let path = dir.join("ICRP-07.TEST");
+        std::fs::write(&path, "abc").unwrap();
+
+        let reader = FileReader::new(&path).unwrap();
+        let fp = FileFingerprint::new("ICRP-07.TEST", reader, 1).unwrap();
+
+        assert_eq!(fp.name, "ICRP-07.TEST");
+        assert_eq!(fp.size, 3);
+        assert_eq!(fp.sha1, "a9993e364706816aba3e25717850c26c9cd0d89d");
+    }
+
+    #[test]
+    fn fingerprint_differences() {
+        let file = |name: &str, sha1: &str| FileFingerprint {
+            name: name.to_string(),
+            size: 3,
+            sha1: sha1.to_string(),
+            entries: 1,
+        };
+        let reference = Fingerprint {
+            files: vec![file("ICRP-07.NDX", "aa"), file("ICRP-07.RAD", "bb")],
+        };
+        let fingerprint = Fingerprint {
+            files: vec![file("ICRP-07.NDX", "aa"), file("ICRP-07.RAD", "cc")],
+        };
+
+        assert_eq!(fingerprint.differences(&reference), vec!["ICRP-07.RAD"]);
+        assert!(!fingerprint.is_complete());
+    }
+}
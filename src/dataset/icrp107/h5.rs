@@ -0,0 +1,610 @@
+//! HDF5 copy of the parsed data files
+//!
+//! The file holds one group per nuclide under `/nuclides`, named as the
+//! nuclide (e.g. `Cs-137`), with the index entry in attributes and one
+//! compound dataset per data file, left out when the nuclide has no entry:
+//!
+//! | Dataset | Fields |
+//! |---|---|
+//! | `progeny` | nuclide (`SF` for fission products), branch_rate, decay_mode |
+//! | `rad` | type, yield (/nt), energy (MeV) |
+//! | `bet` | energy (MeV), number (/MeV/nt) |
+//! | `ack` | yield (/nt), energy (eV), transition |
+//! | `nsf` | energy_lower (MeV), energy_upper (MeV), yield (/nt) |
+//!
+//! Decay modes are the bits of [`DecayModeSet`](crate::primitive::DecayModeSet).
+//! The root group carries the format `version` and the SHA-1 `checksum` of
+//! the index file.
+
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+
+use flagset::FlagSet;
+use hdf5::types::FixedAscii;
+use hdf5::{Group, H5Type, Location};
+
+use super::ndx::Attribute;
+use super::spectrum::{AckSpectrum, BetSpectrum, NsfSpectrum, RadSpectrum};
+use super::Icrp107;
+use crate::dose::{BetaEmission, NuclideBetaEmission};
+use crate::error::Error;
+use crate::primitive::attr::{
+    NuclideDecayMode, NuclideElectronLines, NuclideHalfLife, NuclideList, NuclidePhotonLines,
+    NuclideProgeny,
+};
+use crate::primitive::{DecayModeSet, Energy, HalfLife, Nuclide, Progeny};
+use crate::provenance::{DatasetIdentity, DatasetInfo};
+
+const VERSION: u32 = 1;
+
+#[derive(H5Type, Clone, Copy)]
+#[repr(C)]
+struct ProgenyRecord {
+    nuclide: FixedAscii<8>,
+    branch_rate: f64,
+    decay_mode: u8,
+}
+
+#[derive(H5Type, Clone, Copy)]
+#[repr(C)]
+struct RadRecord {
+    #[hdf5(rename = "type")]
+    r#type: FixedAscii<2>,
+    #[hdf5(rename = "yield")]
+    r#yield: f64,
+    energy: f64,
+}
+
+#[derive(H5Type, Clone, Copy)]
+#[repr(C)]
+struct BetRecord {
+    energy: f64,
+    number: f64,
+}
+
+#[derive(H5Type, Clone, Copy)]
+#[repr(C)]
+struct AckRecord {
+    #[hdf5(rename = "yield")]
+    r#yield: f64,
+    energy: f64,
+    transition: FixedAscii<9>,
+}
+
+#[derive(H5Type, Clone, Copy)]
+#[repr(C)]
+struct NsfRecord {
+    energy_lower: f64,
+    energy_upper: f64,
+    #[hdf5(rename = "yield")]
+    r#yield: f64,
+}
+
+fn h5_error(e: hdf5::Error) -> Error {
+    Error::Unexpected(e.into())
+}
+
+fn ascii<const N: usize>(s: &str) -> Result<FixedAscii<N>, Error> {
+    FixedAscii::from_ascii(s).map_err(|e| Error::Unexpected(e.into()))
+}
+
+fn write_attr<T: H5Type>(location: &Location, name: &str, value: &T) -> Result<(), Error> {
+    location
+        .new_attr::<T>()
+        .shape(())
+        .create(name)
+        .and_then(|attr| attr.write_scalar(value))
+        .map_err(h5_error)
+}
+
+fn read_attr<T: H5Type>(location: &Location, name: &str) -> Result<T, Error> {
+    location
+        .attr(name)
+        .and_then(|attr| attr.read_scalar())
+        .map_err(h5_error)
+}
+
+fn write_records<T: H5Type>(group: &Group, name: &str, records: &[T]) -> Result<(), Error> {
+    if !records.is_empty() {
+        group
+            .new_dataset_builder()
+            .with_data(records)
+            .create(name)
+            .map_err(h5_error)?;
+    }
+
+    Ok(())
+}
+
+fn read_records<T: H5Type>(group: &Group, name: &str) -> Result<Vec<T>, Error> {
+    if !group.link_exists(name) {
+        return Ok(vec![]);
+    }
+
+    group
+        .dataset(name)
+        .and_then(|ds| ds.read_raw())
+        .map_err(h5_error)
+}
+
+fn nuclide_name(nuclide: Nuclide) -> String {
+    match nuclide {
+        Nuclide::FissionProducts => "SF".to_string(),
+        nuclide => nuclide.to_string(),
+    }
+}
+
+/// Parsed data files to write, by nuclide
+struct Tables<'a> {
+    ndx: &'a HashMap<Nuclide, Attribute>,
+    rad: &'a HashMap<Nuclide, Vec<RadSpectrum>>,
+    bet: &'a HashMap<Nuclide, Vec<BetSpectrum>>,
+    ack: &'a HashMap<Nuclide, Vec<AckSpectrum>>,
+    nsf: &'a HashMap<Nuclide, Vec<NsfSpectrum>>,
+}
+
+fn write(path: &Path, checksum: &str, tables: &Tables) -> Result<(), Error> {
+    let file = hdf5::File::create(path).map_err(h5_error)?;
+    write_attr(&file, "version", &VERSION)?;
+    write_attr(&file, "checksum", &ascii::<40>(checksum)?)?;
+
+    let nuclides = file.create_group("nuclides").map_err(h5_error)?;
+    for (&nuclide, attr) in tables.ndx {
+        let group = nuclides
+            .create_group(&nuclide.to_string())
+            .map_err(h5_error)?;
+
+        write_attr(&group, "half_life", &attr.half_life.value)?;
+        let unit = serde_plain::to_string(&attr.half_life.unit).unwrap();
+        write_attr(&group, "half_life_unit", &ascii::<2>(&unit)?)?;
+        write_attr(&group, "decay_mode", &attr.decay_mode.0.bits())?;
+        for (name, value) in [
+            ("alpha_energy", attr.alpha_energy),
+            ("electron_energy", attr.electron_energy),
+            ("photon_energy", attr.photon_energy),
+            ("amu", attr.amu),
+            ("air_kerma_const", attr.air_kerma_const),
+            ("air_kerma_coef", attr.air_kerma_coef),
+        ] {
+            write_attr(&group, name, &value)?;
+        }
+        for (name, value) in [
+            ("n_photon_le_10kev_per_nt", attr.n_photon_le_10kev_per_nt),
+            ("n_photon_gt_10kev_per_nt", attr.n_photon_gt_10kev_per_nt),
+            ("n_beta_per_nt", attr.n_beta_per_nt),
+            ("n_mono_electron_per_nt", attr.n_mono_electron_per_nt),
+            ("n_alpha_per_nt", attr.n_alpha_per_nt),
+        ] {
+            write_attr(&group, name, &value)?;
+        }
+
+        let progeny = attr
+            .progeny
+            .iter()
+            .map(|p| {
+                Ok(ProgenyRecord {
+                    nuclide: ascii(&nuclide_name(p.nuclide))?,
+                    branch_rate: p.branch_rate,
+                    decay_mode: p.decay_mode.0.bits(),
+                })
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        write_records(&group, "progeny", &progeny)?;
+
+        let rad = tables
+            .rad
+            .get(&nuclide)
+            .map_or(&[][..], Vec::as_slice)
+            .iter()
+            .map(|line| {
+                Ok(RadRecord {
+                    r#type: ascii(&line.r#type.to_string())?,
+                    r#yield: line.r#yield,
+                    energy: line.energy,
+                })
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        write_records(&group, "rad", &rad)?;
+
+        let bet: Vec<_> = tables
+            .bet
+            .get(&nuclide)
+            .map_or(&[][..], Vec::as_slice)
+            .iter()
+            .map(|s| BetRecord {
+                energy: s.energy(),
+                number: s.number(),
+            })
+            .collect();
+        write_records(&group, "bet", &bet)?;
+
+        let ack = tables
+            .ack
+            .get(&nuclide)
+            .map_or(&[][..], Vec::as_slice)
+            .iter()
+            .map(|s| {
+                Ok(AckRecord {
+                    r#yield: s.r#yield(),
+                    energy: s.energy(),
+                    transition: ascii(s.transition())?,
+                })
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        write_records(&group, "ack", &ack)?;
+
+        let nsf: Vec<_> = tables
+            .nsf
+            .get(&nuclide)
+            .map_or(&[][..], Vec::as_slice)
+            .iter()
+            .map(|s| NsfRecord {
+                energy_lower: s.energy_lower(),
+                energy_upper: s.energy_upper(),
+                r#yield: s.r#yield(),
+            })
+            .collect();
+        write_records(&group, "nsf", &nsf)?;
+    }
+
+    Ok(())
+}
+
+impl Icrp107 {
+    /// Write the index and the spectra of the data files to an HDF5 file for
+    /// [`Icrp107Hdf5::open`], replacing any file at `path`
+    pub fn write_hdf5<P: AsRef<Path>>(&self, path: P) -> Result<(), Error> {
+        let tables = Tables {
+            ndx: self.ndx()?,
+            rad: self.rad()?,
+            bet: self.bet()?,
+            ack: self.ack()?,
+            nsf: self.nsf()?,
+        };
+
+        let fingerprint = self.fingerprint()?;
+        let checksum = fingerprint.file("ICRP-07.NDX").map_or("", |f| &f.sha1);
+        write(path.as_ref(), checksum, &tables)
+    }
+}
+
+/// ICRP-107 data read from an HDF5 file written by [`Icrp107::write_hdf5`]
+///
+/// Opening reads the whole file, which is much faster than parsing the data
+/// files; the same data is then available as with [`Icrp107`].
+#[derive(Debug)]
+pub struct Icrp107Hdf5 {
+    path: PathBuf,
+    checksum: String,
+    ndx: HashMap<Nuclide, Attribute>,
+    rad: HashMap<Nuclide, Vec<RadSpectrum>>,
+    bet: HashMap<Nuclide, Vec<BetSpectrum>>,
+    ack: HashMap<Nuclide, Vec<AckSpectrum>>,
+    nsf: HashMap<Nuclide, Vec<NsfSpectrum>>,
+}
+
+impl Icrp107Hdf5 {
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        let file = hdf5::File::open(path.as_ref()).map_err(h5_error)?;
+        let version: u32 = read_attr(&file, "version")?;
+        if version != VERSION {
+            return Err(Error::UnsupportedSchemaVersion(version));
+        }
+        let checksum: FixedAscii<40> = read_attr(&file, "checksum")?;
+
+        let mut data = Self {
+            path: path.as_ref().to_path_buf(),
+            checksum: checksum.as_str().to_string(),
+            ndx: HashMap::new(),
+            rad: HashMap::new(),
+            bet: HashMap::new(),
+            ack: HashMap::new(),
+            nsf: HashMap::new(),
+        };
+
+        let nuclides = file.group("nuclides").map_err(h5_error)?;
+        for name in nuclides.member_names().map_err(h5_error)? {
+            let nuclide: Nuclide = name.parse()?;
+            let group = nuclides.group(&name).map_err(h5_error)?;
+            data.read_nuclide(nuclide, &group)?;
+        }
+
+        Ok(data)
+    }
+
+    fn read_nuclide(&mut self, nuclide: Nuclide, group: &Group) -> Result<(), Error> {
+        let decay_modes = |bits: u8| DecayModeSet(FlagSet::new_truncated(bits));
+        let f64_attr = |name| read_attr::<f64>(group, name);
+        let u64_attr = |name| read_attr::<u64>(group, name);
+
+        let unit: FixedAscii<2> = read_attr(group, "half_life_unit")?;
+        let progeny = read_records::<ProgenyRecord>(group, "progeny")?
+            .into_iter()
+            .map(|p| {
+                Ok(Progeny {
+                    nuclide: p.nuclide.as_str().parse()?,
+                    branch_rate: p.branch_rate,
+                    decay_mode: decay_modes(p.decay_mode),
+                })
+            })
+            .collect::<Result<_, Error>>()?;
+
+        let attr = Attribute {
+            half_life: HalfLife {
+                value: f64_attr("half_life")?,
+                unit: unit.as_str().parse().map_err(|_| {
+                    Error::InvalidHalfLife(format!("{}: {}", nuclide, unit.as_str()))
+                })?,
+            },
+            decay_mode: decay_modes(read_attr(group, "decay_mode")?),
+            progeny,
+            alpha_energy: f64_attr("alpha_energy")?,
+            electron_energy: f64_attr("electron_energy")?,
+            photon_energy: f64_attr("photon_energy")?,
+            n_photon_le_10kev_per_nt: u64_attr("n_photon_le_10kev_per_nt")?,
+            n_photon_gt_10kev_per_nt: u64_attr("n_photon_gt_10kev_per_nt")?,
+            n_beta_per_nt: u64_attr("n_beta_per_nt")?,
+            n_mono_electron_per_nt: u64_attr("n_mono_electron_per_nt")?,
+            n_alpha_per_nt: u64_attr("n_alpha_per_nt")?,
+            amu: f64_attr("amu")?,
+            air_kerma_const: f64_attr("air_kerma_const")?,
+            air_kerma_coef: f64_attr("air_kerma_coef")?,
+        };
+        self.ndx.insert(nuclide, attr);
+
+        let rad = read_records::<RadRecord>(group, "rad")?
+            .into_iter()
+            .map(|r| {
+                Ok(RadSpectrum {
+                    r#type: r.r#type.as_str().parse()?,
+                    r#yield: r.r#yield,
+                    energy: r.energy,
+                })
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        let bet: Vec<_> = read_records::<BetRecord>(group, "bet")?
+            .into_iter()
+            .map(|r| BetSpectrum::new(r.energy, r.number))
+            .collect();
+        let ack: Vec<_> = read_records::<AckRecord>(group, "ack")?
+            .into_iter()
+            .map(|r| AckSpectrum::new(r.r#yield, r.energy, r.transition.as_str()))
+            .collect();
+        let nsf: Vec<_> = read_records::<NsfRecord>(group, "nsf")?
+            .into_iter()
+            .map(|r| NsfSpectrum::new(r.energy_lower, r.energy_upper, r.r#yield))
+            .collect();
+
+        if !rad.is_empty() {
+            self.rad.insert(nuclide, rad);
+        }
+        if !bet.is_empty() {
+            self.bet.insert(nuclide, bet);
+        }
+        if !ack.is_empty() {
+            self.ack.insert(nuclide, ack);
+        }
+        if !nsf.is_empty() {
+            self.nsf.insert(nuclide, nsf);
+        }
+
+        Ok(())
+    }
+
+    /// SHA-1 digest of the index file the HDF5 file was written from
+    pub fn checksum(&self) -> &str {
+        &self.checksum
+    }
+
+    pub fn ndx(&self) -> &HashMap<Nuclide, Attribute> {
+        &self.ndx
+    }
+
+    pub fn rad_spectrum(&self, nuclide: Nuclide) -> &[RadSpectrum] {
+        self.rad.get(&nuclide).map_or(&[], Vec::as_slice)
+    }
+
+    pub fn bet_spectrum(&self, nuclide: Nuclide) -> &[BetSpectrum] {
+        self.bet.get(&nuclide).map_or(&[], Vec::as_slice)
+    }
+
+    pub fn ack_spectrum(&self, nuclide: Nuclide) -> &[AckSpectrum] {
+        self.ack.get(&nuclide).map_or(&[], Vec::as_slice)
+    }
+
+    pub fn nsf_spectrum(&self, nuclide: Nuclide) -> &[NsfSpectrum] {
+        self.nsf.get(&nuclide).map_or(&[], Vec::as_slice)
+    }
+
+    fn attribute(&self, nuclide: Nuclide) -> Result<&Attribute, Error> {
+        self.ndx
+            .get(&nuclide)
+            .ok_or_else(|| Error::InvalidNuclide(nuclide.to_string()))
+    }
+}
+
+impl NuclideList for Icrp107Hdf5 {
+    fn nuclide_list(&self) -> Result<BTreeSet<Nuclide>, Error> {
+        Ok(self.ndx.keys().copied().collect())
+    }
+}
+
+impl NuclideProgeny for Icrp107Hdf5 {
+    fn progeny(&self, nuclide: Nuclide) -> Result<Vec<Progeny>, Error> {
+        Ok(self.attribute(nuclide)?.progeny.clone())
+    }
+}
+
+impl NuclideHalfLife for Icrp107Hdf5 {
+    fn half_life(&self, nuclide: Nuclide) -> Result<HalfLife, Error> {
+        Ok(self.attribute(nuclide)?.half_life)
+    }
+}
+
+impl NuclideDecayMode for Icrp107Hdf5 {
+    fn decay_mode(&self, nuclide: Nuclide) -> Result<DecayModeSet, Error> {
+        Ok(self.attribute(nuclide)?.decay_mode)
+    }
+}
+
+impl NuclidePhotonLines for Icrp107Hdf5 {
+    fn photon_lines(&self, nuclide: Nuclide) -> Result<Vec<(Energy, f64)>, Error> {
+        Ok(super::photon_lines(self.rad_spectrum(nuclide)))
+    }
+}
+
+impl NuclideElectronLines for Icrp107Hdf5 {
+    fn electron_lines(&self, nuclide: Nuclide) -> Result<Vec<(Energy, f64)>, Error> {
+        Ok(super::electron_lines(self.rad_spectrum(nuclide)))
+    }
+}
+
+impl NuclideBetaEmission for Icrp107Hdf5 {
+    fn beta_emission(&self, nuclide: Nuclide) -> Result<Option<BetaEmission>, Error> {
+        Ok(super::beta_emission(self.bet_spectrum(nuclide)))
+    }
+}
+
+impl DatasetInfo for Icrp107Hdf5 {
+    fn dataset_identity(&self) -> DatasetIdentity {
+        DatasetIdentity {
+            path: Some(self.path.clone()),
+            version: Some("ICRP Publication 107 (with corrigenda)".to_string()),
+            checksum: Some(self.checksum.clone()),
+            ..DatasetIdentity::new("ICRP-107")
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::spectrum::RadiationType;
+    use super::*;
+    use crate::fixtures::TempDir;
+    use crate::primitive::{DecayMode, TimeUnit};
+
+    #[test]
+    fn roundtrip() {
+        let cs137: Nuclide = "Cs-137".parse().unwrap();
+        let ba137m: Nuclide = "Ba-137m".parse().unwrap();
+        let mode = DecayModeSet::default() | DecayMode::BetaMinus;
+
+        // made-up attributes and lines, not evaluated data
+        let attr = Attribute {
+            half_life: HalfLife {
+                value: 30.,
+                unit: TimeUnit::Year,
+            },
+            decay_mode: mode,
+            progeny: vec![Progeny {
+                nuclide: ba137m,
+                branch_rate: 0.9,
+                decay_mode: mode,
+            }],
+            alpha_energy: 0.,
+            electron_energy: 0.2,
+            photon_energy: 0.1,
+            n_photon_le_10kev_per_nt: 0,
+            n_photon_gt_10kev_per_nt: 0,
+            n_beta_per_nt: 1,
+            n_mono_electron_per_nt: 0,
+            n_alpha_per_nt: 0,
+            amu: 137.,
+            air_kerma_const: 0.,
+            air_kerma_coef: 0.,
+        };
+        let ndx = HashMap::from([(cs137, attr)]);
+        let rad = HashMap::from([(
+            cs137,
+            vec![RadSpectrum {
+                r#type: RadiationType::X,
+                r#yield: 0.05,
+                energy: 0.032,
+            }],
+        )]);
+        let bet = HashMap::from([(
+            cs137,
+            vec![BetSpectrum::new(0., 2.), BetSpectrum::new(0.5, 0.)],
+        )]);
+        let ack = HashMap::from([(cs137, vec![AckSpectrum::new(0.1, 30., "KL1")])]);
+        let tables = Tables {
+            ndx: &ndx,
+            rad: &rad,
+            bet: &bet,
+            ack: &ack,
+            nsf: &HashMap::new(),
+        };
+
+        let dir = TempDir::new("hdf5_roundtrip");
+        let path = dir.join("icrp107.h5");
+        write(&path, "abc", &tables).unwrap();
+
+        let data = Icrp107Hdf5::open(&path).unwrap();
+        assert_eq!(data.checksum(), "abc");
+        assert_eq!(data.nuclide_list().unwrap(), BTreeSet::from([cs137]));
+        assert_eq!(data.half_life(cs137).unwrap().unit, TimeUnit::Year);
+        assert_eq!(data.decay_mode(cs137).unwrap(), mode);
+        let progeny = data.progeny(cs137).unwrap();
+        assert_eq!((progeny[0].nuclide, progeny[0].branch_rate), (ba137m, 0.9));
+        assert_eq!(progeny[0].decay_mode, mode);
+        assert_eq!(
+            data.photon_lines(cs137).unwrap(),
+            vec![(Energy::mev(0.032), 0.05)]
+        );
+        let beta = data.beta_emission(cs137).unwrap().unwrap();
+        assert!((beta.r#yield - 0.5).abs() < 1e-12);
+        assert_eq!(data.ack_spectrum(cs137)[0].transition(), "KL1");
+        assert!(data.nsf_spectrum(cs137).is_empty());
+        assert!(matches!(
+            data.half_life(ba137m),
+            Err(Error::InvalidNuclide(_))
+        ));
+    }
+
+    #[test]
+    #[ignore]
+    fn hdf5() {
+        let data = Icrp107::open("data/icrp107").unwrap();
+        let dir = TempDir::new("hdf5");
+        let path = dir.join("icrp107.h5");
+        data.write_hdf5(&path).unwrap();
+
+        let h5 = Icrp107Hdf5::open(&path).unwrap();
+        assert_eq!(h5.nuclide_list().unwrap(), data.nuclide_list().unwrap());
+        assert_eq!(
+            h5.dataset_identity().checksum,
+            data.dataset_identity().checksum
+        );
+        for nuclide in data.nuclide_list().unwrap() {
+            assert_eq!(
+                h5.half_life(nuclide).unwrap(),
+                data.half_life(nuclide).unwrap()
+            );
+            for (a, b) in h5
+                .progeny(nuclide)
+                .unwrap()
+                .iter()
+                .zip(data.progeny(nuclide).unwrap())
+            {
+                assert_eq!((a.nuclide, a.branch_rate), (b.nuclide, b.branch_rate));
+                assert_eq!(a.decay_mode, b.decay_mode);
+            }
+            assert_eq!(
+                h5.photon_lines(nuclide).unwrap(),
+                data.photon_lines(nuclide).unwrap()
+            );
+            assert_eq!(
+                h5.beta_emission(nuclide).unwrap(),
+                data.beta_emission(nuclide).unwrap()
+            );
+            assert_eq!(
+                h5.nsf_spectrum(nuclide).len(),
+                data.nsf_for(nuclide).unwrap().len()
+            );
+            assert_eq!(
+                h5.ack_spectrum(nuclide).len(),
+                data.ack_for(nuclide).unwrap().len()
+            );
+        }
+    }
+}
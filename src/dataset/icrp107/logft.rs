@@ -0,0 +1,154 @@
+use super::spectrum::RadiationType;
+use super::Icrp107;
+use crate::error::Error;
+use crate::math::quad::gauss_legendre;
+use crate::primitive::attr::NuclideHalfLife;
+use crate::primitive::{Energy, Nuclide};
+
+/// Electron rest energy (MeV)
+const ELECTRON_MASS: f64 = 0.510_998_95;
+/// Fine-structure constant
+const ALPHA: f64 = 7.297_352_569_3e-3;
+/// Subintervals of the quadrature over the spectrum
+const QUADRATURE: usize = 8;
+
+/// Beta branch of a nuclide with derived allowed-shape estimates
+///
+/// The RAD file gives the mean energy of each branch only. The endpoint is
+/// the one whose allowed spectrum has that mean energy, and `log_ft` follows
+/// from the Fermi integral of that spectrum with the non-relativistic Fermi
+/// function, for classifying transitions rather than for precise values.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BetaBranch {
+    /// `true` for positron emission
+    pub positron: bool,
+    /// Mean energy of the branch
+    pub mean_energy: Energy,
+    /// Branch intensity (/nt)
+    pub r#yield: f64,
+    /// Estimated endpoint energy
+    pub endpoint: Energy,
+    /// log10 of the comparative half-life ft (s)
+    pub log_ft: f64,
+}
+
+/// Allowed spectrum shape at total energy `w` (electron masses) for an endpoint `w0`
+fn allowed_shape(w: f64, w0: f64, daughter_z: u8, positron: bool) -> f64 {
+    let p = (w * w - 1.).max(0.).sqrt();
+    let sign = if positron { -1. } else { 1. };
+    let eta = 2. * std::f64::consts::PI * sign * ALPHA * f64::from(daughter_z) * w / p;
+    let fermi = if eta.abs() < 1e-12 {
+        1.
+    } else {
+        eta / (1. - (-eta).exp())
+    };
+
+    fermi * p * w * (w0 - w).powi(2)
+}
+
+/// Fermi integral f of an allowed transition of `endpoint` to a daughter of atomic number `daughter_z`
+pub fn fermi_integral(endpoint: Energy, daughter_z: u8, positron: bool) -> f64 {
+    let w0 = 1. + endpoint.as_mev() / ELECTRON_MASS;
+    gauss_legendre(
+        |w| allowed_shape(w, w0, daughter_z, positron),
+        1.,
+        w0,
+        QUADRATURE,
+    )
+}
+
+/// Mean kinetic energy of an allowed spectrum of `endpoint`
+pub fn allowed_mean_energy(endpoint: Energy, daughter_z: u8, positron: bool) -> Energy {
+    let w0 = 1. + endpoint.as_mev() / ELECTRON_MASS;
+    let kinetic = gauss_legendre(
+        |w| (w - 1.) * allowed_shape(w, w0, daughter_z, positron),
+        1.,
+        w0,
+        QUADRATURE,
+    );
+
+    Energy::mev(kinetic / fermi_integral(endpoint, daughter_z, positron) * ELECTRON_MASS)
+}
+
+/// Endpoint of the allowed spectrum with `mean` kinetic energy
+pub fn allowed_endpoint(mean: Energy, daughter_z: u8, positron: bool) -> Energy {
+    let (mut lo, mut hi) = (mean.as_mev(), 10. * mean.as_mev());
+    for _ in 0..60 {
+        let mid = (lo + hi) / 2.;
+        if allowed_mean_energy(Energy::mev(mid), daughter_z, positron).as_mev() < mean.as_mev() {
+            lo = mid;
+        } else {
+            hi = mid;
+        }
+    }
+
+    Energy::mev((lo + hi) / 2.)
+}
+
+/// log10 of ft (s) for a branch of `endpoint` and intensity `branch` of a nuclide with `half_life` (s)
+pub fn log_ft(
+    endpoint: Energy,
+    daughter_z: u8,
+    positron: bool,
+    half_life: f64,
+    branch: f64,
+) -> f64 {
+    (fermi_integral(endpoint, daughter_z, positron) * half_life / branch).log10()
+}
+
+impl Icrp107 {
+    /// Beta-minus and beta-plus branches of the RAD file with estimated endpoints and log ft
+    pub fn beta_branches(&self, nuclide: Nuclide) -> Result<Vec<BetaBranch>, Error> {
+        let lines: Vec<_> = self
+            .rad_for(nuclide)?
+            .into_iter()
+            .filter(|l| {
+                matches!(l.r#type, RadiationType::BetaMinus | RadiationType::BetaPlus)
+                    && l.r#yield > 0.
+                    && l.energy > 0.
+            })
+            .collect();
+        if lines.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let z = nuclide
+            .z()
+            .ok_or_else(|| Error::InvalidNuclide(nuclide.to_string()))?;
+        let half_life = self.half_life(nuclide)?.as_sec();
+
+        Ok(lines
+            .into_iter()
+            .map(|l| {
+                let positron = l.r#type == RadiationType::BetaPlus;
+                let daughter_z = if positron { z - 1 } else { z + 1 };
+                let mean_energy = Energy::mev(l.energy);
+                let endpoint = allowed_endpoint(mean_energy, daughter_z, positron);
+                BetaBranch {
+                    positron,
+                    mean_energy,
+                    r#yield: l.r#yield,
+                    endpoint,
+                    log_ft: log_ft(endpoint, daughter_z, positron, half_life, l.r#yield),
+                }
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn tritium() {
+        // H-3: endpoint 18.59 keV, mean 5.7 keV, half-life 12.32 y, log ft 3.05
+        let endpoint = Energy::kev(18.59);
+        let mean = allowed_mean_energy(endpoint, 2, false);
+        assert!((mean.as_kev() - 5.7).abs() < 0.3);
+        assert!((allowed_endpoint(mean, 2, false).as_kev() - 18.59).abs() < 1e-3);
+
+        let logft = log_ft(endpoint, 2, false, 12.32 * 365.2422 * 86_400., 1.);
+        assert!((logft - 3.05).abs() < 0.1);
+    }
+}
@@ -1,25 +1,68 @@
+mod alpha;
+mod capabilities;
+mod fingerprint;
+#[cfg(feature = "hdf5")]
+mod h5;
+mod logft;
 mod ndx;
+#[cfg(feature = "tokio")]
+mod preload;
 mod reader;
+#[cfg(feature = "rand")]
+mod sample;
+#[cfg(feature = "mmap")]
+mod snapshot;
+mod source;
 pub mod spectrum;
+mod summary;
 
 use once_cell::sync::OnceCell;
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 use std::path::{Path, PathBuf};
+#[cfg(feature = "bundle")]
+use std::sync::Arc;
 
+use super::reader::DataSource;
+#[cfg(feature = "bundle")]
+use super::Bundle;
+use crate::dose::{BetaEmission, NuclideBetaEmission};
 use crate::error::Error;
-use crate::primitive::attr::{NuclideDecayMode, NuclideHalfLife, NuclideProgeny};
-use crate::primitive::{DecayModeSet, HalfLife, Nuclide, Progeny};
-use reader::{IndexReader, SpectrumReader};
-use spectrum::{ack, bet, nsf, rad};
+use crate::primitive::attr::{
+    NuclideAirKerma, NuclideAtomicMass, NuclideDecayEnergy, NuclideDecayMode, NuclideElectronLines,
+    NuclideHalfLife, NuclideList, NuclidePhotonLines, NuclideProgeny,
+};
+use crate::primitive::{DecayModeSet, Energy, HalfLife, Nuclide, Progeny};
+use crate::provenance::{DatasetIdentity, DatasetInfo};
+use reader::{IndexReader, SpectrumIndex, SpectrumReader};
+use spectrum::{ack, bet, nsf, rad, RadiationType};
+
+pub use alpha::{recoil_energy, AlphaLine};
+pub use capabilities::Capabilities;
+pub use fingerprint::{FileFingerprint, Fingerprint, PUBLISHED_NUCLIDE_COUNT};
+#[cfg(feature = "hdf5")]
+pub use h5::Icrp107Hdf5;
+pub use logft::{allowed_endpoint, allowed_mean_energy, fermi_integral, log_ft, BetaBranch};
+#[cfg(feature = "tokio")]
+pub use preload::LoadProgress;
+#[cfg(feature = "rand")]
+pub use sample::Emission;
+#[cfg(feature = "mmap")]
+pub use snapshot::Icrp107Snapshot;
+pub use source::group_lines;
+pub use summary::NuclideSummary;
 
 #[derive(Debug)]
 pub struct Icrp107 {
-    path: PathBuf,
+    source: DataSource,
     ndx: OnceCell<HashMap<Nuclide, ndx::Attribute>>,
     rad: OnceCell<HashMap<Nuclide, Vec<rad::RadSpectrum>>>,
     bet: OnceCell<HashMap<Nuclide, Vec<bet::BetSpectrum>>>,
     ack: OnceCell<HashMap<Nuclide, Vec<ack::AckSpectrum>>>,
     nsf: OnceCell<HashMap<Nuclide, Vec<nsf::NsfSpectrum>>>,
+    fingerprint: OnceCell<Fingerprint>,
+    checksum: OnceCell<String>,
+    spectrum_index: [OnceCell<SpectrumIndex>; 4],
+    index_dir: Option<PathBuf>,
 }
 
 impl Icrp107 {
@@ -27,42 +70,178 @@ impl Icrp107 {
         let path_buf = path.as_ref().to_path_buf();
 
         if path_buf.is_dir() {
-            Ok(Self {
-                path: path_buf,
-                ndx: OnceCell::new(),
-                rad: OnceCell::new(),
-                bet: OnceCell::new(),
-                ack: OnceCell::new(),
-                nsf: OnceCell::new(),
-            })
+            Ok(Self::with_source(DataSource::dir(path_buf)))
         } else {
             Err(Error::InvalidFilePath)
         }
     }
 
+    /// Read the data files from a zip or tar archive without extracting it
+    #[cfg(feature = "bundle")]
+    pub fn open_bundle<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        let bundle = Bundle::open(path)?;
+        Ok(Self::with_source(DataSource::Bundle(Arc::new(bundle))))
+    }
+
+    fn with_source(source: DataSource) -> Self {
+        Self {
+            source,
+            ndx: OnceCell::new(),
+            rad: OnceCell::new(),
+            bet: OnceCell::new(),
+            ack: OnceCell::new(),
+            nsf: OnceCell::new(),
+            fingerprint: OnceCell::new(),
+            checksum: OnceCell::new(),
+            spectrum_index: Default::default(),
+            index_dir: None,
+        }
+    }
+
+    /// Keep the byte-offset indexes of the spectrum files read per nuclide
+    /// (see [`Icrp107::rad_for`]) in `dir`, e.g. a cache directory, so that
+    /// the files are not scanned again on the next open
+    ///
+    /// Without it the files are scanned once per instance and nothing is
+    /// written. An index that cannot be written is kept in memory only.
+    pub fn index_dir<P: Into<PathBuf>>(mut self, dir: P) -> Self {
+        self.index_dir = Some(dir.into());
+        self
+    }
+
     pub fn ndx(&self) -> Result<&HashMap<Nuclide, ndx::Attribute>, Error> {
         self.ndx
-            .get_or_try_init(|| IndexReader::new(&self.path.join("ICRP-07.NDX"))?.read())
+            .get_or_try_init(|| IndexReader::new(&self.source, "ICRP-07.NDX")?.read())
+    }
+
+    /// Whether the index lists the nuclide
+    pub fn contains(&self, nuclide: Nuclide) -> Result<bool, Error> {
+        Ok(self.ndx()?.contains_key(&nuclide))
     }
 
     pub fn rad(&self) -> Result<&HashMap<Nuclide, Vec<rad::RadSpectrum>>, Error> {
         self.rad
-            .get_or_try_init(|| SpectrumReader::new(&self.path.join("ICRP-07.RAD"))?.read())
+            .get_or_try_init(|| SpectrumReader::new(&self.source, "ICRP-07.RAD")?.read())
     }
 
     pub fn bet(&self) -> Result<&HashMap<Nuclide, Vec<bet::BetSpectrum>>, Error> {
         self.bet
-            .get_or_try_init(|| SpectrumReader::new(&self.path.join("ICRP-07.BET"))?.read())
+            .get_or_try_init(|| SpectrumReader::new(&self.source, "ICRP-07.BET")?.read())
     }
 
     pub fn ack(&self) -> Result<&HashMap<Nuclide, Vec<ack::AckSpectrum>>, Error> {
         self.ack
-            .get_or_try_init(|| SpectrumReader::new(&self.path.join("ICRP-07.ACK"))?.read())
+            .get_or_try_init(|| SpectrumReader::new(&self.source, "ICRP-07.ACK")?.read())
     }
 
     pub fn nsf(&self) -> Result<&HashMap<Nuclide, Vec<nsf::NsfSpectrum>>, Error> {
         self.nsf
-            .get_or_try_init(|| SpectrumReader::new(&self.path.join("ICRP-07.NSF"))?.read())
+            .get_or_try_init(|| SpectrumReader::new(&self.source, "ICRP-07.NSF")?.read())
+    }
+
+    /// RAD spectrum of a nuclide, borrowed from the loaded RAD file
+    pub fn rad_spectrum(&self, nuclide: Nuclide) -> Result<&[rad::RadSpectrum], Error> {
+        Ok(self.rad()?.get(&nuclide).map_or(&[], Vec::as_slice))
+    }
+
+    /// Filtered RAD lines of a nuclide, borrowed from the loaded RAD file
+    pub fn rad_lines(&self, nuclide: Nuclide) -> Result<spectrum::RadLines<'_>, Error> {
+        self.rad_spectrum(nuclide).map(spectrum::RadLines::new)
+    }
+
+    /// BET spectrum of a nuclide, borrowed from the loaded BET file
+    pub fn bet_spectrum(&self, nuclide: Nuclide) -> Result<&[bet::BetSpectrum], Error> {
+        Ok(self.bet()?.get(&nuclide).map_or(&[], Vec::as_slice))
+    }
+
+    /// ACK spectrum of a nuclide, borrowed from the loaded ACK file
+    pub fn ack_spectrum(&self, nuclide: Nuclide) -> Result<&[ack::AckSpectrum], Error> {
+        Ok(self.ack()?.get(&nuclide).map_or(&[], Vec::as_slice))
+    }
+
+    /// NSF spectrum of a nuclide, borrowed from the loaded NSF file
+    pub fn nsf_spectrum(&self, nuclide: Nuclide) -> Result<&[nsf::NsfSpectrum], Error> {
+        Ok(self.nsf()?.get(&nuclide).map_or(&[], Vec::as_slice))
+    }
+
+    /// RAD spectrum of a nuclide, parsed without loading the whole file
+    pub fn rad_for(&self, nuclide: Nuclide) -> Result<Vec<rad::RadSpectrum>, Error> {
+        match self.rad.get() {
+            Some(rad) => Ok(rad.get(&nuclide).cloned().unwrap_or_default()),
+            None => self.spectrum_index(0, "ICRP-07.RAD")?.read(nuclide),
+        }
+    }
+
+    /// BET spectrum of a nuclide, parsed without loading the whole file
+    pub fn bet_for(&self, nuclide: Nuclide) -> Result<Vec<bet::BetSpectrum>, Error> {
+        match self.bet.get() {
+            Some(bet) => Ok(bet.get(&nuclide).cloned().unwrap_or_default()),
+            None => self.spectrum_index(1, "ICRP-07.BET")?.read(nuclide),
+        }
+    }
+
+    /// ACK spectrum of a nuclide, parsed without loading the whole file
+    pub fn ack_for(&self, nuclide: Nuclide) -> Result<Vec<ack::AckSpectrum>, Error> {
+        match self.ack.get() {
+            Some(ack) => Ok(ack.get(&nuclide).cloned().unwrap_or_default()),
+            None => self.spectrum_index(2, "ICRP-07.ACK")?.read(nuclide),
+        }
+    }
+
+    /// NSF spectrum of a nuclide, parsed without loading the whole file
+    pub fn nsf_for(&self, nuclide: Nuclide) -> Result<Vec<nsf::NsfSpectrum>, Error> {
+        match self.nsf.get() {
+            Some(nsf) => Ok(nsf.get(&nuclide).cloned().unwrap_or_default()),
+            None => self.spectrum_index(3, "ICRP-07.NSF")?.read(nuclide),
+        }
+    }
+
+    /// Normalized spontaneous fission neutron spectrum, `None` if the nuclide has none
+    pub fn neutron_pdf(&self, nuclide: Nuclide) -> Result<Option<spectrum::NeutronPdf>, Error> {
+        Ok(spectrum::NeutronPdf::new(&self.nsf_for(nuclide)?))
+    }
+
+    fn spectrum_index(&self, i: usize, file_name: &str) -> Result<&SpectrumIndex, Error> {
+        self.spectrum_index[i].get_or_try_init(|| {
+            SpectrumIndex::open(&self.source, file_name, self.index_dir.as_deref())
+        })
+    }
+
+    /// Checksums and entry counts of the data files
+    ///
+    /// Entries are counted from the index lines and the spectrum block
+    /// headers, without parsing the records.
+    pub fn fingerprint(&self) -> Result<&Fingerprint, Error> {
+        self.fingerprint.get_or_try_init(|| {
+            let mut entries = vec![IndexReader::new(&self.source, "ICRP-07.NDX")?.count()?];
+            for (i, name) in fingerprint::FILE_NAMES[1..].iter().enumerate() {
+                entries.push(self.spectrum_index(i, name)?.len());
+            }
+            let files = fingerprint::FILE_NAMES
+                .iter()
+                .zip(entries)
+                .map(|(name, n)| FileFingerprint::new(name, self.source.reader(name)?, n))
+                .collect::<Result<_, _>>()?;
+
+            Ok(Fingerprint { files })
+        })
+    }
+
+    /// SHA-1 digest of the index file, the checksum of the dataset identity
+    fn checksum(&self) -> Result<&str, Error> {
+        if let Some(file) = self.fingerprint.get().and_then(|fp| fp.file("ICRP-07.NDX")) {
+            return Ok(&file.sha1);
+        }
+
+        self.checksum
+            .get_or_try_init(|| Ok(fingerprint::digest(self.source.reader("ICRP-07.NDX")?)?.1))
+            .map(String::as_str)
+    }
+}
+
+impl NuclideList for Icrp107 {
+    fn nuclide_list(&self) -> Result<BTreeSet<Nuclide>, Error> {
+        Ok(self.ndx()?.keys().copied().collect())
     }
 }
 
@@ -92,3 +271,100 @@ impl NuclideDecayMode for Icrp107 {
             .ok_or_else(|| Error::InvalidNuclide(nuclide.to_string()))
     }
 }
+
+impl NuclideAtomicMass for Icrp107 {
+    fn nuclide_atomic_mass(&self, nuclide: Nuclide) -> Result<f64, Error> {
+        self.ndx()?
+            .get(&nuclide)
+            .map(|attr| attr.amu)
+            .ok_or_else(|| Error::InvalidNuclide(nuclide.to_string()))
+    }
+}
+
+impl NuclideAirKerma for Icrp107 {
+    fn air_kerma_constant(&self, nuclide: Nuclide) -> Result<f64, Error> {
+        self.ndx()?
+            .get(&nuclide)
+            .map(|attr| attr.air_kerma_const)
+            .ok_or_else(|| Error::InvalidNuclide(nuclide.to_string()))
+    }
+}
+
+impl NuclideDecayEnergy for Icrp107 {
+    fn decay_energy(&self, nuclide: Nuclide) -> Result<f64, Error> {
+        self.ndx()?
+            .get(&nuclide)
+            .map(|attr| attr.alpha_energy + attr.electron_energy + attr.photon_energy)
+            .ok_or_else(|| Error::InvalidNuclide(nuclide.to_string()))
+    }
+}
+
+impl NuclidePhotonLines for Icrp107 {
+    fn photon_lines(&self, nuclide: Nuclide) -> Result<Vec<(Energy, f64)>, Error> {
+        Ok(photon_lines(&self.rad_for(nuclide)?))
+    }
+}
+
+impl NuclideElectronLines for Icrp107 {
+    fn electron_lines(&self, nuclide: Nuclide) -> Result<Vec<(Energy, f64)>, Error> {
+        Ok(electron_lines(&self.rad_for(nuclide)?))
+    }
+}
+
+fn photon_lines(spectrum: &[rad::RadSpectrum]) -> Vec<(Energy, f64)> {
+    spectrum
+        .iter()
+        .filter(|line| line.r#type.is_photon())
+        .map(|line| (Energy::mev(line.energy), line.r#yield))
+        .collect()
+}
+
+fn electron_lines(spectrum: &[rad::RadSpectrum]) -> Vec<(Energy, f64)> {
+    spectrum
+        .iter()
+        .filter(|line| {
+            matches!(
+                line.r#type,
+                RadiationType::InternalConversionElectron | RadiationType::AugerElectron
+            )
+        })
+        .map(|line| (Energy::mev(line.energy), line.r#yield))
+        .collect()
+}
+
+impl DatasetInfo for Icrp107 {
+    fn dataset_identity(&self) -> DatasetIdentity {
+        DatasetIdentity {
+            path: Some(self.source.path().to_path_buf()),
+            version: Some("ICRP Publication 107 (with corrigenda)".to_string()),
+            checksum: self.checksum().ok().map(str::to_string),
+            ..DatasetIdentity::new("ICRP-107")
+        }
+    }
+}
+
+impl NuclideBetaEmission for Icrp107 {
+    fn beta_emission(&self, nuclide: Nuclide) -> Result<Option<BetaEmission>, Error> {
+        Ok(beta_emission(&self.bet_for(nuclide)?))
+    }
+}
+
+/// Yield and mean energy of the BET spectrum, by the trapezoidal rule
+fn beta_emission(spectrum: &[bet::BetSpectrum]) -> Option<BetaEmission> {
+    let (mut number, mut energy) = (0., 0.);
+    for pair in spectrum.windows(2) {
+        let de = pair[1].energy() - pair[0].energy();
+        number += (pair[0].number() + pair[1].number()) / 2. * de;
+        energy +=
+            (pair[0].energy() * pair[0].number() + pair[1].energy() * pair[1].number()) / 2. * de;
+    }
+
+    match spectrum.last() {
+        Some(last) if number > 0. => Some(BetaEmission {
+            r#yield: number,
+            mean_energy: energy / number,
+            endpoint: last.energy(),
+        }),
+        _ => None,
+    }
+}
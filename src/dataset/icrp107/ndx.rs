@@ -1,9 +1,8 @@
-use fixed_width::{FieldSet, FixedWidth};
 use flagset::FlagSet;
 use serde::Deserialize;
 
-use super::reader;
 use crate::error::Error;
+use crate::fixedformat::{fortran_fields, FieldSet, FixedWidth};
 use crate::primitive::{DecayMode, DecayModeSet, HalfLife, Nuclide, Progeny};
 
 #[derive(Debug, Deserialize)]
@@ -27,12 +26,9 @@ pub(crate) struct NdxEntry {
 
 impl FixedWidth for NdxEntry {
     fn fields() -> FieldSet {
-        reader::fields_from_fortran_format(
-            "(a7,a10,a8,28x,4(a7,6x,e11.0,1x),f7.0,2f8.0,3i4,i5,i4,e11.0,e10.0,e9.0)",
-            0,
-        )
-        .unwrap()
-        .0
+        fortran_fields("(a7,a10,a8,28x,4(a7,6x,e11.0,1x),f7.0,2f8.0,3i4,i5,i4,e11.0,e10.0,e9.0)")
+            .unwrap()
+            .0
     }
 }
 
@@ -56,7 +52,7 @@ pub struct Attribute {
 }
 
 impl FixedWidth for Attribute {
-    fn fields() -> fixed_width::FieldSet {
+    fn fields() -> FieldSet {
         NdxEntry::fields()
     }
 }
@@ -69,8 +65,10 @@ impl From<NdxEntry> for Attribute {
             .flatten()
             .map(|(nuclide, branch_rate)| {
                 let decay_mode = match nuclide {
+                    // left empty if no mode fits, see `dataset::check_decay_modes`
                     Nuclide::WithId(_) => {
-                        check_decay_mode(entry.nuclide, nuclide, entry.decay_mode).unwrap()
+                        check_decay_mode(entry.nuclide, nuclide, entry.decay_mode)
+                            .unwrap_or_default()
                     }
                     Nuclide::FissionProducts => {
                         let mut mode_set = DecayModeSet::default();
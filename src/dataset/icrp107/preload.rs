@@ -0,0 +1,128 @@
+use std::path::Path;
+use std::sync::Arc;
+
+use tokio_util::sync::CancellationToken;
+
+use super::Icrp107;
+use crate::error::Error;
+
+/// Data file parsed by [`Icrp107::preload_async`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LoadProgress {
+    pub file: &'static str,
+    /// Number of files parsed so far, this one included
+    pub loaded: usize,
+    pub total: usize,
+}
+
+type Step = fn(&Icrp107) -> Result<(), Error>;
+
+const STEPS: [(&str, Step); 5] = [
+    ("ICRP-07.NDX", |data| data.ndx().map(|_| ())),
+    ("ICRP-07.RAD", |data| data.rad().map(|_| ())),
+    ("ICRP-07.BET", |data| data.bet().map(|_| ())),
+    ("ICRP-07.ACK", |data| data.ack().map(|_| ())),
+    ("ICRP-07.NSF", |data| data.nsf().map(|_| ())),
+];
+
+impl Icrp107 {
+    /// Open a data directory and parse all files without blocking the runtime
+    pub async fn open_async<P, F>(
+        path: P,
+        cancel: CancellationToken,
+        progress: F,
+    ) -> Result<Arc<Self>, Error>
+    where
+        P: AsRef<Path>,
+        F: FnMut(LoadProgress),
+    {
+        let data = Arc::new(Self::open(path)?);
+        data.clone().preload_async(cancel, progress).await?;
+
+        Ok(data)
+    }
+
+    /// Parse all data files on the blocking thread pool of the tokio runtime
+    ///
+    /// `progress` is called after each file. Cancelling `cancel` (or dropping
+    /// the future) returns [`Error::Cancelled`] without waiting for the file
+    /// being parsed; files parsed so far stay loaded.
+    pub async fn preload_async<F>(
+        self: Arc<Self>,
+        cancel: CancellationToken,
+        mut progress: F,
+    ) -> Result<(), Error>
+    where
+        F: FnMut(LoadProgress),
+    {
+        for (i, &(file, step)) in STEPS.iter().enumerate() {
+            let data = self.clone();
+            let task = tokio::task::spawn_blocking(move || step(&data));
+
+            cancel
+                .run_until_cancelled(task)
+                .await
+                .ok_or(Error::Cancelled)?
+                .map_err(|e| Error::Unexpected(e.into()))??;
+
+            progress(LoadProgress {
+                file,
+                loaded: i + 1,
+                total: STEPS.len(),
+            });
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn block_on<F: std::future::Future>(future: F) -> F::Output {
+        tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap()
+            .block_on(future)
+    }
+
+    #[test]
+    fn cancelled() {
+        let cancel = CancellationToken::new();
+        cancel.cancel();
+
+        let res = block_on(Icrp107::open_async(".", cancel, |_| {}));
+        assert!(matches!(res, Err(Error::Cancelled)));
+    }
+
+    #[test]
+    fn missing_files() {
+        let dir = crate::fixtures::TempDir::new("preload");
+
+        let mut files = vec![];
+        let res = block_on(Icrp107::open_async(
+            dir.path(),
+            CancellationToken::new(),
+            |p| files.push(p.file),
+        ));
+
+        assert!(res.is_err());
+        assert!(files.is_empty());
+    }
+
+    #[test]
+    #[ignore]
+    fn preload() {
+        let mut files = vec![];
+        let data = block_on(Icrp107::open_async(
+            "data/icrp107",
+            CancellationToken::new(),
+            |p| files.push(p.file),
+        ))
+        .unwrap();
+
+        assert_eq!(files, STEPS.map(|(file, _)| file));
+        assert!(data.fingerprint().is_ok());
+    }
+}
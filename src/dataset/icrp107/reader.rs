@@ -1,72 +1,125 @@
-use fixed_width::FieldSet;
 use std::collections::HashMap;
-use std::path::Path;
+use std::fs::File;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
+use std::time::UNIX_EPOCH;
 
-use super::super::reader::FileReader;
+use sha1::{Digest, Sha1};
+
+use super::super::reader::{write_atomic, DataSource, FileReader};
 use super::ndx::{Attribute, NdxEntry};
 
 use crate::error::Error;
 use crate::primitive::Nuclide;
-use crate::regex;
+
+// Data file opened from a directory (which may be memory-mapped) or read from an archive
+enum Input {
+    File(File),
+    Reader(FileReader),
+}
+
+impl Input {
+    fn open(source: &DataSource, name: &str) -> Result<Self, Error> {
+        match source.file_path(name) {
+            Some(path) => Ok(Self::File(File::open(path)?)),
+            None => Ok(Self::Reader(source.reader(name)?)),
+        }
+    }
+
+    fn into_reader(self) -> Result<FileReader, Error> {
+        match self {
+            Self::File(file) => FileReader::from_file(file),
+            Self::Reader(reader) => Ok(reader),
+        }
+    }
+}
 
 pub struct IndexReader {
-    reader: FileReader,
+    input: Input,
 }
 
 impl IndexReader {
-    pub fn new(path: &Path) -> Result<Self, Error> {
-        let reader = FileReader::new(path)?.skip_lines(1)?;
-        Ok(Self { reader })
+    pub fn new(source: &DataSource, name: &str) -> Result<Self, Error> {
+        Ok(Self {
+            input: Input::open(source, name)?,
+        })
     }
 
-    pub fn read(&mut self) -> Result<HashMap<Nuclide, Attribute>, Error> {
+    pub fn read(self) -> Result<HashMap<Nuclide, Attribute>, Error> {
+        #[cfg(feature = "parallel")]
+        if let Input::File(file) = &self.input {
+            if let Some(mmap) = parallel::map(file) {
+                return parallel::read_index(&mmap);
+            }
+        }
+
+        let mut reader = self.input.into_reader()?.skip_lines(1)?;
         let mut ndx = HashMap::new();
 
         let mut buf = String::new();
-        while self.reader.read_line(&mut buf)? != 0 {
-            let row: NdxEntry =
-                fixed_width::from_str(&buf).map_err(|e| Error::Unexpected(e.into()))?;
-            ndx.insert(row.nuclide, row.into());
+        while reader.read_line(&mut buf)? != 0 {
+            let (nuclide, attr) = index_entry(&buf)?;
+            ndx.insert(nuclide, attr);
         }
 
         Ok(ndx)
     }
+
+    /// Number of entries, counted without parsing them
+    pub fn count(self) -> Result<usize, Error> {
+        let mut reader = self.input.into_reader()?.skip_lines(1)?;
+        let mut count = 0;
+
+        let mut buf = String::new();
+        while reader.read_line(&mut buf)? != 0 {
+            if !buf.trim().is_empty() {
+                count += 1;
+            }
+        }
+
+        Ok(count)
+    }
+}
+
+fn index_entry(line: &str) -> Result<(Nuclide, Attribute), Error> {
+    let row: NdxEntry = fixed_width::from_str(line).map_err(|e| Error::Unexpected(e.into()))?;
+    Ok((row.nuclide, row.into()))
 }
 
 pub struct SpectrumReader<T> {
-    reader: FileReader,
+    input: Input,
     _marker: std::marker::PhantomData<T>,
 }
 
 impl<T> SpectrumReader<T>
 where
-    T: FromStr<Err = Error>,
+    T: FromStr<Err = Error> + Send,
 {
-    pub fn new(path: &Path) -> Result<Self, Error> {
+    pub fn new(source: &DataSource, name: &str) -> Result<Self, Error> {
         Ok(Self {
-            reader: FileReader::new(path)?,
+            input: Input::open(source, name)?,
             _marker: std::marker::PhantomData,
         })
     }
 
-    pub fn read(&mut self) -> Result<HashMap<Nuclide, Vec<T>>, Error> {
+    pub fn read(self) -> Result<HashMap<Nuclide, Vec<T>>, Error> {
+        #[cfg(feature = "parallel")]
+        if let Input::File(file) = &self.input {
+            if let Some(mmap) = parallel::map(file) {
+                return parallel::read_spectrum(&mmap);
+            }
+        }
+
+        let mut reader = self.input.into_reader()?;
         let mut inner = HashMap::new();
 
         let mut buf = String::new();
-        while self.reader.read_line(&mut buf)? != 0 {
-            let nuclide: Nuclide = (buf[0..7]).parse()?;
-            let records = &buf[7..].replace('\0', " ");
-            let records = records.split_whitespace().last().ok_or_else(|| {
-                Error::Unexpected(anyhow::anyhow!("failed to get spectrum for {}", nuclide))
-            })?;
-            let records = records
-                .parse()
-                .map_err(|_| Error::InvalidInteger(records.to_string()))?;
+        while reader.read_line(&mut buf)? != 0 {
+            let (nuclide, records) = spectrum_header(&buf)?;
 
             let mut spectrum = vec![];
             for _ in 0..(records) {
-                self.reader.read_line(&mut buf)?;
+                reader.read_line(&mut buf)?;
                 spectrum.push(buf.parse()?);
             }
             inner.insert(nuclide, spectrum);
@@ -76,126 +129,357 @@ where
     }
 }
 
-pub(crate) fn fields_from_fortran_format(
-    fmt: &str,
-    offset: usize,
-) -> Result<(FieldSet, usize), String> {
-    let re = regex!(
-        r"(?P<repeat>\d*)(?:(?P<type>[a-z]{1,2})|(?P<nested>\([^\(\)]+?\)))(?:(?P<length>\d+)(?:\.\d+)?)?"
-    );
-    let mut start = 0;
-    let mut fields = FieldSet::Seq(vec![]);
-
-    let fmt: String = fmt
-        .trim_matches(|c: char| c.is_whitespace() || c == '(' || c == ')')
-        .chars()
-        .filter(|c| !c.is_whitespace())
-        .collect();
-
-    let captures: Vec<_> = re.captures_iter(&fmt).collect();
-    if captures.is_empty() {
-        Err("invalid fortran format".to_string())
-    } else {
-        for cap in captures.into_iter() {
-            let rep: usize = cap
-                .name("repeat")
-                .map(|m| m.as_str().parse().unwrap_or(1))
-                .unwrap();
-
-            match cap.name("type") {
-                Some(m) => {
-                    let typ = m.as_str();
-                    let len: usize = cap
-                        .name("length")
-                        .map(|m| m.as_str().parse().unwrap())
-                        .unwrap_or(1);
-                    match typ {
-                        "x" => start += rep * len,
-                        _ => {
-                            for _ in 0..rep {
-                                fields = fields.extend(FieldSet::new_field(
-                                    offset + start..offset + start + len,
-                                ));
-                                start += len
-                            }
-                        }
-                    }
-                }
-                None => match cap.name("nested") {
-                    Some(m) => {
-                        if cap.name("length").is_some() {
-                            return Err("invalid fortran format".to_string());
-                        } else {
-                            let fmt = m.as_str();
-                            let mut nested_fields = vec![];
-                            for _ in 0..rep {
-                                let (nested_field, len) =
-                                    fields_from_fortran_format(fmt, offset + start)?;
-                                nested_fields.push(nested_field);
-                                start += len;
-                            }
-                            fields = fields.append(FieldSet::Seq(nested_fields))
-                        }
-                    }
-                    None => return Err("invalid fortran format".to_string()),
-                },
+/// Byte offsets and record counts of the spectrum blocks in a spectrum file
+#[derive(Debug, PartialEq)]
+pub struct SpectrumIndex {
+    source: DataSource,
+    name: String,
+    blocks: HashMap<Nuclide, (u64, usize)>,
+}
+
+impl SpectrumIndex {
+    /// Scan the spectrum file, or with an `index_dir` load the index file
+    /// stored there by an earlier scan and store it after scanning
+    ///
+    /// An index that cannot be stored, e.g. in a read-only directory, is kept
+    /// in memory only. Archived spectrum files are always scanned.
+    pub fn open(source: &DataSource, name: &str, index_dir: Option<&Path>) -> Result<Self, Error> {
+        let (path, index_dir) = match (source.file_path(name), index_dir) {
+            (Some(path), Some(index_dir)) => (path, index_dir),
+            _ => return Self::scan(source, name),
+        };
+        let stamp = Stamp::of(&path)?;
+        let index_path = Self::index_path(&path, index_dir);
+
+        if let Some(blocks) = Self::load(&index_path, &stamp) {
+            return Ok(Self {
+                source: source.clone(),
+                name: name.to_string(),
+                blocks,
+            });
+        }
+
+        let index = Self::scan(source, name)?;
+        // not stored, the index is built again next time
+        let _ = index.store(&index_path, &stamp);
+
+        Ok(index)
+    }
+
+    // `<file>.<digest of its path>.idx`, so that the spectrum files of several
+    // datasets can share a directory
+    fn index_path(path: &Path, index_dir: &Path) -> PathBuf {
+        let path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+        let digest = format!("{:x}", Sha1::digest(path.as_os_str().as_encoded_bytes()));
+
+        let mut name = path.file_name().unwrap_or_default().to_os_string();
+        name.push(format!(".{}.idx", &digest[..16]));
+        index_dir.join(name)
+    }
+
+    fn scan(source: &DataSource, name: &str) -> Result<Self, Error> {
+        let mut reader = source.reader(name)?;
+        let mut blocks = HashMap::new();
+        let mut offset = 0u64;
+
+        let mut buf = String::new();
+        loop {
+            let n = reader.read_line(&mut buf)?;
+            if n == 0 {
+                break;
+            }
+            offset += n as u64;
+
+            let (nuclide, records) = spectrum_header(&buf)?;
+            blocks.insert(nuclide, (offset, records));
+            for _ in 0..records {
+                offset += reader.read_line(&mut buf)? as u64;
+            }
+        }
+
+        Ok(Self {
+            source: source.clone(),
+            name: name.to_string(),
+            blocks,
+        })
+    }
+
+    // Index file: size and modification time of the spectrum file and number
+    // of blocks, followed by a `nuclide offset records` line per block
+    fn load(index_path: &Path, stamp: &Stamp) -> Option<HashMap<Nuclide, (u64, usize)>> {
+        let content = std::fs::read_to_string(index_path).ok()?;
+        let mut lines = content.lines();
+
+        let mut header = lines.next()?.split_whitespace();
+        let stored = Stamp {
+            size: header.next()?.parse().ok()?,
+            modified: header.next()?.parse().ok()?,
+        };
+        let count: usize = header.next()?.parse().ok()?;
+        if stored != *stamp {
+            return None;
+        }
+
+        let mut blocks = HashMap::new();
+        for line in lines {
+            let mut fields = line.split_whitespace();
+            let nuclide = fields.next()?.parse().ok()?;
+            let offset = fields.next()?.parse().ok()?;
+            let records = fields.next()?.parse().ok()?;
+            blocks.insert(nuclide, (offset, records));
+        }
+
+        // a truncated index is scanned again
+        (blocks.len() == count).then_some(blocks)
+    }
+
+    fn store(&self, index_path: &Path, stamp: &Stamp) -> Result<(), Error> {
+        let mut content = format!("{} {} {}\n", stamp.size, stamp.modified, self.blocks.len());
+        for (nuclide, (offset, records)) in &self.blocks {
+            content += &format!("{} {} {}\n", nuclide, offset, records);
+        }
+
+        if let Some(dir) = index_path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        write_atomic(index_path, content.as_bytes())
+    }
+
+    /// Number of spectrum blocks
+    pub fn len(&self) -> usize {
+        self.blocks.len()
+    }
+
+    /// Whether the file has a non-empty spectrum block for the nuclide
+    pub fn contains(&self, nuclide: Nuclide) -> bool {
+        self.blocks
+            .get(&nuclide)
+            .is_some_and(|&(_, records)| records > 0)
+    }
+
+    /// Parse the spectrum block of a nuclide, empty if the file has no entry for it
+    pub fn read<T>(&self, nuclide: Nuclide) -> Result<Vec<T>, Error>
+    where
+        T: FromStr<Err = Error>,
+    {
+        let mut spectrum = vec![];
+
+        if let Some(&(offset, records)) = self.blocks.get(&nuclide) {
+            let mut reader = self.source.reader(&self.name)?.seek(offset)?;
+            let mut buf = String::new();
+            for _ in 0..records {
+                reader.read_line(&mut buf)?;
+                spectrum.push(buf.parse()?);
             }
         }
 
-        Ok((fields, start))
+        Ok(spectrum)
+    }
+}
+
+/// Size (bytes) and modification time (ns since the epoch) of a spectrum file
+#[derive(Debug, PartialEq)]
+struct Stamp {
+    size: u64,
+    modified: u128,
+}
+
+impl Stamp {
+    fn of(path: &Path) -> Result<Self, Error> {
+        let metadata = std::fs::metadata(path)?;
+        let modified = metadata
+            .modified()
+            .ok()
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .map_or(0, |d| d.as_nanos());
+
+        Ok(Self {
+            size: metadata.len(),
+            modified,
+        })
+    }
+}
+
+// Nuclide and number of records of a spectrum block
+fn spectrum_header(line: &str) -> Result<(Nuclide, usize), Error> {
+    let nuclide: Nuclide = (line[0..7]).parse()?;
+    let records = &line[7..].replace('\0', " ");
+    let records = records.split_whitespace().last().ok_or_else(|| {
+        Error::Unexpected(anyhow::anyhow!("failed to get spectrum for {}", nuclide))
+    })?;
+    let records = records
+        .parse()
+        .map_err(|_| Error::InvalidInteger(records.to_string()))?;
+
+    Ok((nuclide, records))
+}
+
+/// Memory-mapped readers parsing records on the rayon thread pool
+#[cfg(feature = "parallel")]
+mod parallel {
+    use std::collections::HashMap;
+    use std::fs::File;
+    use std::str::FromStr;
+
+    use memmap2::Mmap;
+    use rayon::prelude::*;
+
+    use super::{index_entry, spectrum_header};
+    use crate::dataset::icrp107::ndx::Attribute;
+    use crate::dataset::reader::Compression;
+    use crate::error::Error;
+    use crate::primitive::Nuclide;
+
+    /// Map the file into memory, `None` if it can not be mapped or is compressed
+    /// (the sequential reader is used then)
+    pub fn map(file: &File) -> Option<Mmap> {
+        // Safety: the data files are opened read-only and are not expected to be modified while parsing.
+        let mmap = unsafe { Mmap::map(file) }.ok()?;
+        Compression::detect(&mmap).is_none().then_some(mmap)
+    }
+
+    // Lines with their line terminators, as returned by `BufRead::read_line`
+    fn lines(data: &[u8]) -> Result<Vec<&str>, Error> {
+        std::str::from_utf8(data)
+            .map(|s| s.split_inclusive('\n').collect())
+            .map_err(|e| Error::Unexpected(e.into()))
+    }
+
+    pub fn read_index(data: &[u8]) -> Result<HashMap<Nuclide, Attribute>, Error> {
+        lines(data)?
+            .into_par_iter()
+            .skip(1)
+            .map(index_entry)
+            .collect()
+    }
+
+    pub fn read_spectrum<T>(data: &[u8]) -> Result<HashMap<Nuclide, Vec<T>>, Error>
+    where
+        T: FromStr<Err = Error> + Send,
+    {
+        let lines = lines(data)?;
+
+        let mut blocks = vec![];
+        let mut i = 0;
+        while i < lines.len() {
+            let (nuclide, records) = spectrum_header(lines[i])?;
+            let end = i + 1 + records;
+            let block = lines.get(i + 1..end).ok_or_else(|| {
+                Error::Unexpected(anyhow::anyhow!("incomplete spectrum for {}", nuclide))
+            })?;
+            blocks.push((nuclide, block));
+            i = end;
+        }
+
+        blocks
+            .into_par_iter()
+            .map(|(nuclide, block)| {
+                let spectrum = block.iter().map(|l| l.parse()).collect::<Result<_, _>>()?;
+                Ok((nuclide, spectrum))
+            })
+            .collect()
     }
 }
 
 #[cfg(test)]
 mod test {
-    use fixed_width::{field, field_seq, FieldConfig};
+    use super::*;
+    use crate::fixtures::TempDir;
+
+    #[derive(Debug, PartialEq)]
+    struct Record(f64);
+
+    impl FromStr for Record {
+        type Err = Error;
+
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+            s.trim()
+                .parse()
+                .map(Record)
+                .map_err(|_| Error::InvalidFloat(s.to_string()))
+        }
+    }
+
+    const SPECTRUM: &str = "H-3      12.32y  1\n1.0\nTc-99m   6.015h  2\n2.0\n3.0\n";
+
+    #[test]
+    fn test_spectrum_reader() {
+        let dir = TempDir::new("spectrum_reader");
+        std::fs::write(dir.join("ICRP-07.TEST"), SPECTRUM).unwrap();
+        let source = DataSource::dir(dir.path());
+        let spectrum = SpectrumReader::<Record>::new(&source, "ICRP-07.TEST")
+            .unwrap()
+            .read();
+
+        let spectrum = spectrum.unwrap();
+        assert_eq!(spectrum[&"H-3".parse().unwrap()], vec![Record(1.0)]);
+        assert_eq!(
+            spectrum[&"Tc-99m".parse().unwrap()],
+            vec![Record(2.0), Record(3.0)]
+        );
+    }
 
-    use super::fields_from_fortran_format;
+    #[test]
+    fn test_index_count() {
+        let dir = TempDir::new("index_count");
+        std::fs::write(dir.join("ICRP-07.NDX"), "header\nH-3\nC-14\n\n").unwrap();
+        let source = DataSource::dir(dir.path());
+
+        let count = IndexReader::new(&source, "ICRP-07.NDX").unwrap().count();
+        assert_eq!(count.unwrap(), 2);
+    }
 
     #[test]
-    fn test_fields_from_fortran_format() {
-        let fortran_format = "(a10,2i10,3f10.0,4e10.0)";
-        let (fields, len) = fields_from_fortran_format(fortran_format, 0).unwrap();
-        let fields = fields.flatten();
+    fn test_spectrum_index() {
+        let dir = TempDir::new("spectrum_index");
+        std::fs::write(dir.join("ICRP-07.TEST"), SPECTRUM).unwrap();
+
+        let source = DataSource::dir(dir.path());
+        let index_dir = dir.join("index");
+        let index = SpectrumIndex::open(&source, "ICRP-07.TEST", Some(&index_dir)).unwrap();
+        let tc99m: Vec<Record> = index.read("Tc-99m".parse().unwrap()).unwrap();
+        assert_eq!(tc99m, vec![Record(2.0), Record(3.0)]);
+        let i131: Vec<Record> = index.read("I-131".parse().unwrap()).unwrap();
+        assert!(i131.is_empty());
+        assert!(index.contains("Tc-99m".parse().unwrap()));
+        assert!(!index.contains("I-131".parse().unwrap()));
 
-        assert_eq!(len, 100);
-        for i in 0..10 {
-            assert_eq!(
-                format!("{:?}", fields[i]),
-                format!("{:?}", FieldConfig::new(i * 10..(i + 1) * 10))
-            );
+        // loaded from the stored index file
+        let stored = SpectrumIndex::open(&source, "ICRP-07.TEST", Some(&index_dir)).unwrap();
+        assert_eq!(stored, index);
+
+        // a truncated index file is not trusted
+        let index_path = std::fs::read_dir(&index_dir)
+            .unwrap()
+            .next()
+            .unwrap()
+            .unwrap()
+            .path();
+        let content = std::fs::read_to_string(&index_path).unwrap();
+        std::fs::write(&index_path, content.lines().next().unwrap()).unwrap();
+        let rescanned = SpectrumIndex::open(&source, "ICRP-07.TEST", Some(&index_dir)).unwrap();
+        assert_eq!(rescanned, index);
+
+        // nothing is written without an index directory, or where it cannot be created
+        let unwritable = dir.join("ICRP-07.TEST").join("index");
+        for index_dir in [None, Some(unwritable.as_path())] {
+            let scanned = SpectrumIndex::open(&source, "ICRP-07.TEST", index_dir).unwrap();
+            assert_eq!(scanned, index);
         }
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 2);
     }
 
+    #[cfg(feature = "parallel")]
     #[test]
-    fn test_nested_fields_from_fortran_format() {
-        let fortran_format =
-            "(a7,a10,a8,28x,4(a7,6x,e11.0,1x),f7.0,2f8.0,3i4,i5,i4,e11.0,e10.0,e9.0)";
-        let (fields, _len) = fields_from_fortran_format(fortran_format, 0).unwrap();
-
-        let complex_fields = field_seq![
-            field!(0..7),
-            field!(7..17),
-            field!(17..25),
-            field_seq![
-                field_seq![field!(53..60), field!(66..77)],
-                field_seq![field!(78..85), field!(91..102)],
-                field_seq![field!(103..110), field!(116..127)],
-                field_seq![field!(128..135), field!(141..152)],
-            ],
-            field!(153..160),
-            field!(160..168),
-            field!(168..176),
-            field!(176..180),
-            field!(180..184),
-            field!(184..188),
-            field!(188..193),
-            field!(193..197),
-            field!(197..208),
-            field!(208..218),
-            field!(218..227),
-        ];
-
-        assert_eq!(format!("{:?}", fields), format!("{:?}", complex_fields));
+    fn test_parallel_spectrum_reader() {
+        let spectrum = parallel::read_spectrum::<Record>(SPECTRUM.as_bytes()).unwrap();
+        assert_eq!(spectrum[&"H-3".parse().unwrap()], vec![Record(1.0)]);
+        assert_eq!(
+            spectrum[&"Tc-99m".parse().unwrap()],
+            vec![Record(2.0), Record(3.0)]
+        );
+
+        let truncated = parallel::read_spectrum::<Record>(&SPECTRUM.as_bytes()[..30]);
+        assert!(truncated.is_err());
     }
 }
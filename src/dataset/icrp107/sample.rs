@@ -0,0 +1,194 @@
+use rand::Rng;
+
+use super::spectrum::{RadSpectrum, RadiationType};
+use super::Icrp107;
+use crate::error::Error;
+use crate::math::random;
+use crate::primitive::Nuclide;
+
+/// Particle emitted in a sampled disintegration
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Emission {
+    pub r#type: RadiationType,
+    /// Kinetic energy of the particle or energy of the photon (MeV)
+    pub energy: f64,
+}
+
+impl Icrp107 {
+    /// Particles emitted in one disintegration of a nuclide
+    ///
+    /// Each RAD line is emitted with a Bernoulli draw of its yield, or a
+    /// Poisson draw for yields above one (e.g. Auger electrons). Beta-minus
+    /// energies are sampled from the continuous BET spectrum when available,
+    /// in place of the mean energy of the RAD line.
+    pub fn sample_decay<R: Rng + ?Sized>(
+        &self,
+        nuclide: Nuclide,
+        rng: &mut R,
+    ) -> Result<Vec<Emission>, Error> {
+        Ok(sample_emissions(
+            self.rad_spectrum(nuclide)?,
+            self.bet_spectrum(nuclide)?
+                .iter()
+                .map(|b| (b.energy(), b.number()))
+                .collect(),
+            rng,
+        ))
+    }
+
+    /// Emissions of `n` disintegrations, reproducible for a given seed
+    ///
+    /// See [`crate::math::random`] for the reproducibility guarantees.
+    pub fn sample_decays(
+        &self,
+        nuclide: Nuclide,
+        n: usize,
+        seed: u64,
+    ) -> Result<Vec<Vec<Emission>>, Error> {
+        let mut rng = random::seeded(seed);
+        (0..n)
+            .map(|_| self.sample_decay(nuclide, &mut rng))
+            .collect()
+    }
+}
+
+fn sample_emissions<R: Rng + ?Sized>(
+    rad: &[RadSpectrum],
+    bet: Vec<(f64, f64)>,
+    rng: &mut R,
+) -> Vec<Emission> {
+    let beta = BetaSampler::new(bet);
+    let mut emissions = vec![];
+
+    for line in rad {
+        for _ in 0..count(line.r#yield, rng) {
+            let energy = match (&beta, line.r#type) {
+                (Some(beta), RadiationType::BetaMinus) => beta.sample(rng),
+                _ => line.energy,
+            };
+            emissions.push(Emission {
+                r#type: line.r#type,
+                energy,
+            });
+        }
+    }
+
+    emissions
+}
+
+/// Number of emissions of a line with mean `mean` per disintegration
+fn count<R: Rng + ?Sized>(mean: f64, rng: &mut R) -> usize {
+    if mean <= 1. {
+        (rng.random::<f64>() < mean) as usize
+    } else {
+        // Knuth's method, yields per disintegration are small
+        let limit = (-mean).exp();
+        let (mut n, mut p) = (0, rng.random::<f64>());
+        while p > limit {
+            n += 1;
+            p *= rng.random::<f64>();
+        }
+        n
+    }
+}
+
+/// Inverse transform sampling of a piecewise linear spectrum
+struct BetaSampler {
+    /// Energy (MeV) and particles per MeV at each grid point
+    spectrum: Vec<(f64, f64)>,
+    /// Cumulative number of particles at each grid point
+    cdf: Vec<f64>,
+}
+
+impl BetaSampler {
+    fn new(spectrum: Vec<(f64, f64)>) -> Option<Self> {
+        let mut cdf = vec![0.];
+        for pair in spectrum.windows(2) {
+            let de = pair[1].0 - pair[0].0;
+            let area = (pair[0].1 + pair[1].1) / 2. * de;
+            cdf.push(cdf.last().unwrap() + area);
+        }
+
+        (*cdf.last().unwrap() > 0.).then_some(Self { spectrum, cdf })
+    }
+
+    /// Energy (MeV)
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> f64 {
+        let target = rng.random::<f64>() * self.cdf.last().unwrap();
+        let i = self
+            .cdf
+            .partition_point(|&c| c <= target)
+            .clamp(1, self.cdf.len() - 1);
+
+        let ((e0, f0), (e1, f1)) = (self.spectrum[i - 1], self.spectrum[i]);
+        let de = e1 - e0;
+        let area = target - self.cdf[i - 1];
+
+        // solve f0 x + (f1 - f0) x^2 / (2 de) = area for the offset x within the bin
+        let slope = (f1 - f0) / de;
+        let x = if slope.abs() < f64::EPSILON * f0.abs().max(1.) {
+            area / f0
+        } else {
+            ((f0 * f0 + 2. * slope * area).max(0.).sqrt() - f0) / slope
+        };
+
+        e0 + x.clamp(0., de)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn sampled_yields() {
+        let lines = vec![
+            RadSpectrum {
+                r#type: RadiationType::BetaMinus,
+                r#yield: 1.,
+                energy: 0.2,
+            },
+            RadSpectrum {
+                r#type: RadiationType::Gamma,
+                r#yield: 0.5,
+                energy: 0.662,
+            },
+            RadSpectrum {
+                r#type: RadiationType::AugerElectron,
+                r#yield: 2.5,
+                energy: 0.003,
+            },
+        ];
+        // triangular spectrum, mean energy 1/3 MeV
+        let bet = vec![(0., 2.), (1., 0.)];
+
+        let mut rng = random::seeded(107);
+        let n = 20_000;
+        let (mut gammas, mut augers, mut betas, mut beta_energy) = (0, 0, 0, 0.);
+        for _ in 0..n {
+            for e in sample_emissions(&lines, bet.clone(), &mut rng) {
+                match e.r#type {
+                    RadiationType::Gamma => gammas += 1,
+                    RadiationType::AugerElectron => augers += 1,
+                    _ => {
+                        assert!((0. ..=1.).contains(&e.energy));
+                        betas += 1;
+                        beta_energy += e.energy;
+                    }
+                }
+            }
+        }
+
+        assert_eq!(betas, n);
+        assert!((gammas as f64 / n as f64 - 0.5).abs() < 0.02);
+        assert!((augers as f64 / n as f64 - 2.5).abs() < 0.05);
+        assert!((beta_energy / n as f64 - 1. / 3.).abs() < 0.01);
+
+        let seeded = |seed| sample_emissions(&lines, bet.clone(), &mut random::seeded(seed));
+        assert_eq!(seeded(1), seeded(1));
+
+        // mean RAD energy without a BET spectrum
+        let e = sample_emissions(&lines[..1], vec![], &mut rng);
+        assert_eq!(e[0].energy, 0.2);
+    }
+}
@@ -0,0 +1,401 @@
+//! Memory-mapped snapshot of the parsed decay data
+//!
+//! The snapshot holds the fields of the index file needed by the decay data
+//! traits in fixed-size little-endian records sorted by nuclide id, so that
+//! opening it only maps the file; records are decoded on each query.
+//!
+//! | Section | Size (bytes) |
+//! |---|---|
+//! | header: magic, version, nuclide and progeny counts, NDX SHA-1 | 64 |
+//! | nuclide: id, decay modes, time unit, half-life, energies, amu, air kerma constant, progeny range | 64 each |
+//! | progeny: id (`u32::MAX` for fission products), decay modes, branch ratio | 16 each |
+
+use std::collections::{BTreeSet, HashMap};
+use std::fs::File;
+use std::path::{Path, PathBuf};
+
+use flagset::FlagSet;
+use memmap2::Mmap;
+
+use super::super::reader::write_atomic;
+use super::ndx::Attribute;
+use super::Icrp107;
+use crate::error::Error;
+use crate::primitive::attr::{
+    NuclideAirKerma, NuclideAtomicMass, NuclideDecayEnergy, NuclideDecayMode, NuclideHalfLife,
+    NuclideList, NuclideProgeny,
+};
+use crate::primitive::{DecayModeSet, HalfLife, Nuclide, Progeny, TimeUnit};
+use crate::provenance::{DatasetIdentity, DatasetInfo};
+
+const MAGIC: &[u8; 8] = b"ICRP107S";
+const VERSION: u32 = 1;
+const HEADER: usize = 64;
+const RECORD: usize = 64;
+const PROGENY: usize = 16;
+const FISSION_PRODUCTS: u32 = u32::MAX;
+
+const TIME_UNITS: [TimeUnit; 7] = [
+    TimeUnit::MicroSecond,
+    TimeUnit::MilliSecond,
+    TimeUnit::Second,
+    TimeUnit::Minute,
+    TimeUnit::Hour,
+    TimeUnit::Day,
+    TimeUnit::Year,
+];
+
+fn invalid() -> Error {
+    anyhow::anyhow!("invalid snapshot file").into()
+}
+
+fn u32_at(data: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(data[offset..offset + 4].try_into().unwrap())
+}
+
+fn f64_at(data: &[u8], offset: usize) -> f64 {
+    f64::from_le_bytes(data[offset..offset + 8].try_into().unwrap())
+}
+
+fn nuclide_id(nuclide: Nuclide) -> u32 {
+    nuclide.id().unwrap_or(FISSION_PRODUCTS)
+}
+
+fn decay_modes(bits: u8) -> DecayModeSet {
+    DecayModeSet(FlagSet::new_truncated(bits))
+}
+
+/// Snapshot bytes of the index entries
+fn encode(ndx: &HashMap<Nuclide, Attribute>, checksum: &str) -> Vec<u8> {
+    let mut nuclides: Vec<(&Nuclide, &Attribute)> = ndx.iter().collect();
+    nuclides.sort_by_key(|(n, _)| nuclide_id(**n));
+    let progeny_count: usize = nuclides.iter().map(|(_, a)| a.progeny.len()).sum();
+
+    let mut buf = Vec::with_capacity(HEADER + nuclides.len() * RECORD + progeny_count * PROGENY);
+    buf.extend_from_slice(MAGIC);
+    buf.extend_from_slice(&VERSION.to_le_bytes());
+    buf.extend_from_slice(&(nuclides.len() as u32).to_le_bytes());
+    buf.extend_from_slice(&(progeny_count as u32).to_le_bytes());
+    buf.extend_from_slice(&[0; 4]);
+    let mut digest = [0u8; 40];
+    let n = checksum.len().min(40);
+    digest[..n].copy_from_slice(&checksum.as_bytes()[..n]);
+    buf.extend_from_slice(&digest);
+
+    let mut progeny_start = 0;
+    for (&nuclide, attr) in &nuclides {
+        let unit = TIME_UNITS
+            .iter()
+            .position(|&u| u == attr.half_life.unit)
+            .unwrap() as u8;
+        buf.extend_from_slice(&nuclide_id(nuclide).to_le_bytes());
+        buf.extend_from_slice(&[attr.decay_mode.0.bits(), unit, 0, 0]);
+        for v in [
+            attr.half_life.value,
+            attr.alpha_energy,
+            attr.electron_energy,
+            attr.photon_energy,
+            attr.amu,
+            attr.air_kerma_const,
+        ] {
+            buf.extend_from_slice(&v.to_le_bytes());
+        }
+        buf.extend_from_slice(&(progeny_start as u32).to_le_bytes());
+        buf.extend_from_slice(&(attr.progeny.len() as u32).to_le_bytes());
+        progeny_start += attr.progeny.len();
+    }
+
+    for (_, attr) in &nuclides {
+        for p in &attr.progeny {
+            buf.extend_from_slice(&nuclide_id(p.nuclide).to_le_bytes());
+            buf.extend_from_slice(&[p.decay_mode.0.bits(), 0, 0, 0]);
+            buf.extend_from_slice(&p.branch_rate.to_le_bytes());
+        }
+    }
+
+    buf
+}
+
+impl Icrp107 {
+    /// Write the decay data of the index file to a snapshot for [`Icrp107Snapshot::open`]
+    ///
+    /// The file is written to a temporary file next to `path`, unique to the
+    /// writer, and renamed into place, so that processes mapping a previous
+    /// snapshot keep reading a complete file.
+    pub fn write_snapshot<P: AsRef<Path>>(&self, path: P) -> Result<(), Error> {
+        let ndx = self.ndx()?;
+        let checksum = self
+            .fingerprint
+            .get()
+            .and_then(|fp| fp.file("ICRP-07.NDX"))
+            .map(|f| f.sha1.clone());
+        let checksum = match checksum {
+            Some(checksum) => checksum,
+            None => {
+                super::FileFingerprint::new(
+                    "ICRP-07.NDX",
+                    self.source.reader("ICRP-07.NDX")?,
+                    ndx.len(),
+                )?
+                .sha1
+            }
+        };
+
+        write_atomic(path.as_ref(), &encode(ndx, &checksum))
+    }
+}
+
+/// ICRP-107 decay data read from a snapshot written by [`Icrp107::write_snapshot`]
+///
+/// Opening maps the file read-only, and the pages are shared by all
+/// processes mapping the same snapshot. Only the decay data of the index file
+/// is available; spectra need the data files opened with [`Icrp107::open`].
+#[derive(Debug)]
+pub struct Icrp107Snapshot {
+    mmap: Mmap,
+    path: PathBuf,
+    count: usize,
+}
+
+impl Icrp107Snapshot {
+    /// Map a snapshot, checking its header and the progeny range of each record
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        let file = File::open(path.as_ref())?;
+        // Safety: snapshots are replaced by renaming, never modified in place.
+        // A file truncated by another process while mapped raises SIGBUS on
+        // access to the lost pages, which no check here can prevent.
+        let mmap = unsafe { Mmap::map(&file) }?;
+
+        if mmap.len() < HEADER || &mmap[..8] != MAGIC {
+            return Err(invalid());
+        }
+        let version = u32_at(&mmap, 8);
+        if version != VERSION {
+            return Err(Error::UnsupportedSchemaVersion(version));
+        }
+        let count = u32_at(&mmap, 12) as usize;
+        let progeny_count = u32_at(&mmap, 16) as usize;
+        let len = (count.checked_mul(RECORD))
+            .zip(progeny_count.checked_mul(PROGENY))
+            .and_then(|(records, progeny)| HEADER.checked_add(records)?.checked_add(progeny));
+        if len != Some(mmap.len()) {
+            return Err(invalid());
+        }
+
+        let snapshot = Self {
+            mmap,
+            path: path.as_ref().to_path_buf(),
+            count,
+        };
+        for i in 0..count {
+            let record = snapshot.record(i);
+            let (start, n) = (u32_at(record, 56) as usize, u32_at(record, 60) as usize);
+            if start.checked_add(n).is_none_or(|end| end > progeny_count) {
+                return Err(invalid());
+            }
+        }
+
+        Ok(snapshot)
+    }
+
+    /// SHA-1 digest of the index file the snapshot was written from
+    pub fn checksum(&self) -> &str {
+        std::str::from_utf8(&self.mmap[24..HEADER])
+            .unwrap_or_default()
+            .trim_end_matches('\0')
+    }
+
+    fn record(&self, i: usize) -> &[u8] {
+        &self.mmap[HEADER + i * RECORD..HEADER + (i + 1) * RECORD]
+    }
+
+    fn find(&self, nuclide: Nuclide) -> Result<&[u8], Error> {
+        let id = nuclide_id(nuclide);
+        let (mut lo, mut hi) = (0, self.count);
+        while lo < hi {
+            let mid = (lo + hi) / 2;
+            match u32_at(self.record(mid), 0).cmp(&id) {
+                std::cmp::Ordering::Less => lo = mid + 1,
+                std::cmp::Ordering::Greater => hi = mid,
+                std::cmp::Ordering::Equal => return Ok(self.record(mid)),
+            }
+        }
+
+        Err(Error::InvalidNuclide(nuclide.to_string()))
+    }
+}
+
+impl NuclideList for Icrp107Snapshot {
+    fn nuclide_list(&self) -> Result<BTreeSet<Nuclide>, Error> {
+        Ok((0..self.count)
+            .map(|i| Nuclide::WithId(u32_at(self.record(i), 0)))
+            .collect())
+    }
+}
+
+impl NuclideProgeny for Icrp107Snapshot {
+    fn progeny(&self, nuclide: Nuclide) -> Result<Vec<Progeny>, Error> {
+        let record = self.find(nuclide)?;
+        let start = HEADER + self.count * RECORD + u32_at(record, 56) as usize * PROGENY;
+
+        Ok((0..u32_at(record, 60) as usize)
+            .map(|i| {
+                let p = &self.mmap[start + i * PROGENY..start + (i + 1) * PROGENY];
+                Progeny {
+                    nuclide: match u32_at(p, 0) {
+                        FISSION_PRODUCTS => Nuclide::FissionProducts,
+                        id => Nuclide::WithId(id),
+                    },
+                    branch_rate: f64_at(p, 8),
+                    decay_mode: decay_modes(p[4]),
+                }
+            })
+            .collect())
+    }
+}
+
+impl NuclideHalfLife for Icrp107Snapshot {
+    fn half_life(&self, nuclide: Nuclide) -> Result<HalfLife, Error> {
+        let record = self.find(nuclide)?;
+        Ok(HalfLife {
+            value: f64_at(record, 8),
+            unit: *TIME_UNITS.get(record[5] as usize).ok_or_else(invalid)?,
+        })
+    }
+}
+
+impl NuclideDecayMode for Icrp107Snapshot {
+    fn decay_mode(&self, nuclide: Nuclide) -> Result<DecayModeSet, Error> {
+        Ok(decay_modes(self.find(nuclide)?[4]))
+    }
+}
+
+impl NuclideAtomicMass for Icrp107Snapshot {
+    fn nuclide_atomic_mass(&self, nuclide: Nuclide) -> Result<f64, Error> {
+        Ok(f64_at(self.find(nuclide)?, 40))
+    }
+}
+
+impl NuclideAirKerma for Icrp107Snapshot {
+    fn air_kerma_constant(&self, nuclide: Nuclide) -> Result<f64, Error> {
+        Ok(f64_at(self.find(nuclide)?, 48))
+    }
+}
+
+impl NuclideDecayEnergy for Icrp107Snapshot {
+    fn decay_energy(&self, nuclide: Nuclide) -> Result<f64, Error> {
+        let record = self.find(nuclide)?;
+        Ok(f64_at(record, 16) + f64_at(record, 24) + f64_at(record, 32))
+    }
+}
+
+impl DatasetInfo for Icrp107Snapshot {
+    fn dataset_identity(&self) -> DatasetIdentity {
+        DatasetIdentity {
+            path: Some(self.path.clone()),
+            version: Some("ICRP Publication 107 (with corrigenda)".to_string()),
+            checksum: Some(self.checksum().to_string()),
+            ..DatasetIdentity::new("ICRP-107")
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::fixtures::TempDir;
+    use crate::primitive::DecayMode;
+
+    #[test]
+    fn roundtrip() {
+        let cs137: Nuclide = "Cs-137".parse().unwrap();
+        let ba137m: Nuclide = "Ba-137m".parse().unwrap();
+        let mode = DecayModeSet::default() | DecayMode::BetaMinus;
+
+        // made-up attributes, not evaluated data
+        let attr = Attribute {
+            half_life: HalfLife {
+                value: 30.,
+                unit: TimeUnit::Year,
+            },
+            decay_mode: mode,
+            progeny: vec![Progeny {
+                nuclide: ba137m,
+                branch_rate: 0.9,
+                decay_mode: mode,
+            }],
+            alpha_energy: 0.,
+            electron_energy: 0.2,
+            photon_energy: 0.1,
+            n_photon_le_10kev_per_nt: 0,
+            n_photon_gt_10kev_per_nt: 0,
+            n_beta_per_nt: 1,
+            n_mono_electron_per_nt: 0,
+            n_alpha_per_nt: 0,
+            amu: 137.,
+            air_kerma_const: 0.,
+            air_kerma_coef: 0.,
+        };
+        let dir = TempDir::new("snapshot_roundtrip");
+        let path = dir.join("snapshot.bin");
+        std::fs::write(&path, encode(&HashMap::from([(cs137, attr)]), "abc")).unwrap();
+
+        let snapshot = Icrp107Snapshot::open(&path).unwrap();
+        assert_eq!(snapshot.checksum(), "abc");
+        assert_eq!(snapshot.nuclide_list().unwrap(), BTreeSet::from([cs137]));
+        assert_eq!(snapshot.half_life(cs137).unwrap().unit, TimeUnit::Year);
+        assert_eq!(snapshot.decay_mode(cs137).unwrap(), mode);
+        assert!((snapshot.decay_energy(cs137).unwrap() - 0.3).abs() < 1e-15);
+        let progeny = snapshot.progeny(cs137).unwrap();
+        assert_eq!((progeny[0].nuclide, progeny[0].branch_rate), (ba137m, 0.9));
+        assert!(matches!(
+            snapshot.half_life(ba137m),
+            Err(Error::InvalidNuclide(_))
+        ));
+
+        // progeny range past the end of the progeny section
+        let mut bytes = std::fs::read(&path).unwrap();
+        drop(snapshot);
+        bytes[HEADER + 56..HEADER + 60].copy_from_slice(&1u32.to_le_bytes());
+        std::fs::write(&path, bytes).unwrap();
+        assert!(Icrp107Snapshot::open(&path).is_err());
+
+        std::fs::write(&path, b"ICRP107S").unwrap();
+        assert!(Icrp107Snapshot::open(&path).is_err());
+    }
+
+    #[test]
+    #[ignore]
+    fn snapshot() {
+        let data = Icrp107::open("data/icrp107").unwrap();
+        let dir = TempDir::new("snapshot");
+        let path = dir.join("snapshot.bin");
+        data.write_snapshot(&path).unwrap();
+
+        let snapshot = Icrp107Snapshot::open(&path).unwrap();
+        assert_eq!(
+            snapshot.nuclide_list().unwrap(),
+            data.nuclide_list().unwrap()
+        );
+        for nuclide in data.nuclide_list().unwrap() {
+            assert_eq!(
+                snapshot.half_life(nuclide).unwrap(),
+                data.half_life(nuclide).unwrap()
+            );
+            assert_eq!(
+                snapshot.decay_mode(nuclide).unwrap(),
+                data.decay_mode(nuclide).unwrap()
+            );
+            for (a, b) in snapshot
+                .progeny(nuclide)
+                .unwrap()
+                .iter()
+                .zip(data.progeny(nuclide).unwrap())
+            {
+                assert_eq!((a.nuclide, a.branch_rate), (b.nuclide, b.branch_rate));
+                assert_eq!(a.decay_mode, b.decay_mode);
+            }
+        }
+        std::fs::remove_file(path).unwrap();
+    }
+}
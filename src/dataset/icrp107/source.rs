@@ -0,0 +1,128 @@
+use std::collections::BTreeMap;
+
+use super::spectrum::{RadiationType, SpectrumBinner};
+use super::Icrp107;
+use crate::decaychain::Inventory;
+use crate::error::Error;
+use crate::primitive::Nuclide;
+use crate::provenance::{SkipReason, Warnings, WithWarnings};
+
+/// Emission rates (1/s) of lines in groups of ascending `edges` (MeV)
+///
+/// A line on an edge goes into the group above it; lines outside the
+/// outermost edges are left out.
+pub fn group_lines(lines: &[(f64, f64)], edges: &[f64]) -> Vec<f64> {
+    let mut binner = SpectrumBinner::new(edges);
+    for &(energy, rate) in lines {
+        binner.add_line(energy, rate, 1.);
+    }
+    binner.number().to_vec()
+}
+
+impl Icrp107 {
+    /// Source spectrum of an inventory (Bq), the RAD lines of each nuclide
+    /// weighted by its activity, as energy (MeV) and emission rate (1/s)
+    ///
+    /// `types` selects the radiation types, all photons if empty. Lines are
+    /// sorted by energy, lines of the same energy added up. Fission products
+    /// and nuclides without a RAD block are left out with a warning.
+    pub fn photon_spectrum(
+        &self,
+        inventory: &Inventory,
+        types: &[RadiationType],
+    ) -> Result<WithWarnings<Vec<(f64, f64)>>, Error> {
+        let mut warnings = Warnings::new();
+        let mut lines = BTreeMap::<u64, f64>::new();
+        for (&nuclide, &activity) in inventory.iter() {
+            if nuclide == Nuclide::FissionProducts {
+                warnings.push(nuclide, SkipReason::FissionProducts);
+                continue;
+            }
+
+            let spectrum = self.rad_for(nuclide)?;
+            if spectrum.is_empty() {
+                warnings.push(nuclide, SkipReason::NoPhotonLines);
+                continue;
+            }
+
+            for line in spectrum {
+                let selected = if types.is_empty() {
+                    line.r#type.is_photon()
+                } else {
+                    types.contains(&line.r#type)
+                };
+                if selected {
+                    // energies are non-negative, so their bits sort like the values
+                    *lines.entry(line.energy.to_bits()).or_default() += activity * line.r#yield;
+                }
+            }
+        }
+
+        Ok(WithWarnings {
+            value: lines
+                .into_iter()
+                .map(|(energy, rate)| (f64::from_bits(energy), rate))
+                .collect(),
+            warnings,
+        })
+    }
+
+    /// [`Icrp107::photon_spectrum`] in energy groups, see [`group_lines`]
+    pub fn grouped_photon_spectrum(
+        &self,
+        inventory: &Inventory,
+        types: &[RadiationType],
+        edges: &[f64],
+    ) -> Result<WithWarnings<Vec<f64>>, Error> {
+        let WithWarnings { value, warnings } = self.photon_spectrum(inventory, types)?;
+        Ok(WithWarnings {
+            value: group_lines(&value, edges),
+            warnings,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn grouped_lines() {
+        let lines = [(0.01, 1.), (0.1, 2.), (0.662, 4.), (1.5, 8.), (20., 16.)];
+        assert_eq!(group_lines(&lines, &[0.1, 1., 10.]), vec![6., 8.]);
+        assert!(group_lines(&lines, &[1.]).is_empty());
+    }
+
+    #[test]
+    #[ignore]
+    fn cs137_spectrum() {
+        let data = Icrp107::open("data/icrp107").unwrap();
+        let mut inv = Inventory::new();
+        inv.add("Cs-137".parse().unwrap(), 1e3);
+        inv.add("Ba-137m".parse().unwrap(), 0.944e3);
+        inv.add(Nuclide::FissionProducts, 1.);
+
+        let WithWarnings {
+            value: spectrum,
+            warnings,
+        } = data.photon_spectrum(&inv, &[]).unwrap();
+        assert_eq!(
+            warnings
+                .nuclides(SkipReason::FissionProducts)
+                .collect::<Vec<_>>(),
+            vec![Nuclide::FissionProducts]
+        );
+        assert!(spectrum.windows(2).all(|w| w[0].0 < w[1].0));
+        let (_, rate) = spectrum
+            .iter()
+            .find(|(energy, _)| (energy - 0.661657).abs() < 1e-4)
+            .unwrap();
+        assert!((rate / 1e3 - 0.851).abs() < 0.01);
+
+        let gammas = data
+            .grouped_photon_spectrum(&inv, &[RadiationType::Gamma], &[0.6, 0.7])
+            .unwrap()
+            .value;
+        assert!((gammas[0] - rate).abs() < 1e-9 * rate);
+    }
+}
@@ -1,12 +1,11 @@
 use fixed_width_derive::FixedWidth;
 use serde::Deserialize;
-use std::str::FromStr;
+use std::sync::Arc;
 
 use super::Spectrum;
 use crate::derive_from_str;
-use crate::error::Error;
 
-#[derive(Debug, FixedWidth, Deserialize)]
+#[derive(Debug, Clone, FixedWidth, Deserialize)]
 pub struct AckSpectrum {
     // yield (/nt)
     #[fixed_width(range = "0..11")]
@@ -17,11 +16,36 @@ pub struct AckSpectrum {
     energy: f64,
 
     #[fixed_width(range = "23..32")]
-    transition: String,
+    #[serde(deserialize_with = "crate::intern::deserialize")]
+    transition: Arc<str>,
 }
 
 derive_from_str!(AckSpectrum);
 
+impl AckSpectrum {
+    pub fn new(r#yield: f64, energy: f64, transition: &str) -> Self {
+        Self {
+            r#yield,
+            energy,
+            transition: transition.into(),
+        }
+    }
+
+    /// Electrons per nuclear transformation
+    pub fn r#yield(&self) -> f64 {
+        self.r#yield
+    }
+
+    /// Energy (eV)
+    pub fn energy(&self) -> f64 {
+        self.energy
+    }
+
+    pub fn transition(&self) -> &str {
+        &self.transition
+    }
+}
+
 impl From<AckSpectrum> for Spectrum {
     fn from(ack: AckSpectrum) -> Self {
         Self::AugerCosterKronigElectron {
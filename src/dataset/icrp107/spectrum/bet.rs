@@ -1,13 +1,11 @@
 use fixed_width_derive::FixedWidth;
 use serde::Deserialize;
-use std::str::FromStr;
 
 use super::Spectrum;
 use crate::derive_from_str;
-use crate::error::Error;
 
 // beta- emission data
-#[derive(Debug, FixedWidth, Deserialize)]
+#[derive(Debug, Clone, FixedWidth, Deserialize)]
 pub struct BetSpectrum {
     // energy grid point (MeV)
     #[fixed_width(range = "0..7")]
@@ -20,6 +18,22 @@ pub struct BetSpectrum {
 
 derive_from_str!(BetSpectrum);
 
+impl BetSpectrum {
+    pub fn new(energy: f64, number: f64) -> Self {
+        Self { energy, number }
+    }
+
+    /// Energy grid point (MeV)
+    pub fn energy(&self) -> f64 {
+        self.energy
+    }
+
+    /// Beta particles per MeV per nuclear transformation
+    pub fn number(&self) -> f64 {
+        self.number
+    }
+}
+
 impl From<BetSpectrum> for Spectrum {
     fn from(bet: BetSpectrum) -> Self {
         Self::Beta {
@@ -0,0 +1,253 @@
+use super::{AckSpectrum, BetSpectrum, NsfSpectrum, RadSpectrum};
+
+/// Line and continuous spectra binned onto groups between ascending energy
+/// edges (MeV)
+///
+/// Each group collects the number of particles and the energy they carry
+/// (MeV), both per nuclear transformation times the weight given, so that
+/// the group mean energies stay consistent with the spectra. Whatever falls
+/// outside the outermost edges is kept apart, so that totals are conserved.
+///
+/// A line on an edge goes into the group above it. Beta spectra (BET) are
+/// integrated exactly as piecewise linear functions between the grid points,
+/// grouped neutron spectra (NSF) split between groups in proportion to the
+/// overlap, as flat within each of their groups.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpectrumBinner {
+    edges: Vec<f64>,
+    number: Vec<f64>,
+    energy: Vec<f64>,
+    outside: (f64, f64),
+}
+
+impl SpectrumBinner {
+    /// Groups between `edges` (MeV), sorted; fewer than two edges make no group
+    pub fn new(edges: &[f64]) -> Self {
+        let mut edges = edges.to_vec();
+        edges.sort_by(f64::total_cmp);
+        edges.dedup();
+        let groups = edges.len().saturating_sub(1);
+
+        Self {
+            edges,
+            number: vec![0.; groups],
+            energy: vec![0.; groups],
+            outside: (0., 0.),
+        }
+    }
+
+    pub fn edges(&self) -> &[f64] {
+        &self.edges
+    }
+
+    /// Group of an energy (MeV), `None` outside the outermost edges
+    fn group(&self, energy: f64) -> Option<usize> {
+        let i = self.edges.partition_point(|&edge| edge <= energy);
+        (i > 0 && i < self.edges.len()).then(|| i - 1)
+    }
+
+    fn add(&mut self, group: Option<usize>, number: f64, energy: f64) {
+        match group {
+            Some(i) => {
+                self.number[i] += number;
+                self.energy[i] += energy;
+            }
+            None => {
+                self.outside.0 += number;
+                self.outside.1 += energy;
+            }
+        }
+    }
+
+    /// Add a line of `energy` (MeV) and `yield` (/nt)
+    pub fn add_line(&mut self, energy: f64, r#yield: f64, weight: f64) {
+        let number = r#yield * weight;
+        self.add(self.group(energy), number, number * energy);
+    }
+
+    /// Add RAD lines, e.g. filtered by [`super::RadLines`]
+    pub fn add_lines<'a, I>(&mut self, lines: I, weight: f64)
+    where
+        I: IntoIterator<Item = &'a RadSpectrum>,
+    {
+        for line in lines {
+            self.add_line(line.energy, line.r#yield, weight);
+        }
+    }
+
+    /// Add a beta spectrum of particles per MeV per nuclear transformation
+    pub fn add_beta(&mut self, spectrum: &[BetSpectrum], weight: f64) {
+        for pair in spectrum.windows(2) {
+            let (e0, e1) = (pair[0].energy(), pair[1].energy());
+            let (n0, n1) = (pair[0].number(), pair[1].number());
+            if e1 <= e0 {
+                continue;
+            }
+            let at = |e: f64| n0 + (e - e0) / (e1 - e0) * (n1 - n0);
+
+            let mut cuts = vec![e0];
+            cuts.extend(self.edges.iter().copied().filter(|&e| e > e0 && e < e1));
+            cuts.push(e1);
+            for cut in cuts.windows(2) {
+                let (a, b) = (cut[0], cut[1]);
+                let m = (a + b) / 2.;
+                let (na, nm, nb) = (at(a), at(m), at(b));
+                // Simpson's rule is exact for E n(E), quadratic on the piece
+                self.add(
+                    self.group(m),
+                    nm * (b - a) * weight,
+                    (a * na + 4. * m * nm + b * nb) / 6. * (b - a) * weight,
+                );
+            }
+        }
+    }
+
+    /// Add Auger and Coster-Kronig electron lines (ACK), of energies in eV
+    pub fn add_ack(&mut self, spectrum: &[AckSpectrum], weight: f64) {
+        for line in spectrum {
+            self.add_line(line.energy() * 1e-6, line.r#yield(), weight);
+        }
+    }
+
+    /// Add a grouped spectrum of particles per nuclear transformation
+    pub fn add_groups(&mut self, spectrum: &[NsfSpectrum], weight: f64) {
+        for group in spectrum {
+            let (lower, upper) = (group.energy_lower(), group.energy_upper());
+            if upper <= lower {
+                self.add_line(lower, group.r#yield(), weight);
+                continue;
+            }
+            let density = group.r#yield() / (upper - lower) * weight;
+
+            let mut cuts = vec![lower];
+            cuts.extend(
+                self.edges
+                    .iter()
+                    .copied()
+                    .filter(|&e| e > lower && e < upper),
+            );
+            cuts.push(upper);
+            for cut in cuts.windows(2) {
+                let (a, b) = (cut[0], cut[1]);
+                let m = (a + b) / 2.;
+                self.add(self.group(m), density * (b - a), density * (b - a) * m);
+            }
+        }
+    }
+
+    /// Number of particles in each group
+    pub fn number(&self) -> &[f64] {
+        &self.number
+    }
+
+    /// Energy (MeV) carried by the particles of each group
+    pub fn energy(&self) -> &[f64] {
+        &self.energy
+    }
+
+    /// Number of particles per MeV of each group, for codes and response
+    /// functions that take spectra as densities
+    pub fn densities(&self) -> Vec<f64> {
+        self.number
+            .iter()
+            .zip(self.edges.windows(2))
+            .map(|(&n, edge)| n / (edge[1] - edge[0]))
+            .collect()
+    }
+
+    /// Mean energy (MeV) of each group, `None` for empty groups
+    pub fn mean_energies(&self) -> Vec<Option<f64>> {
+        self.number
+            .iter()
+            .zip(&self.energy)
+            .map(|(&n, &e)| (n > 0.).then(|| e / n))
+            .collect()
+    }
+
+    /// Number of particles and their energy (MeV) outside the groups
+    pub fn outside(&self) -> (f64, f64) {
+        self.outside
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::dataset::icrp107::spectrum::RadiationType;
+
+    fn bet(points: &[(f64, f64)]) -> Vec<BetSpectrum> {
+        points
+            .iter()
+            .map(|&(e, n)| BetSpectrum::new(e, n))
+            .collect()
+    }
+
+    #[test]
+    fn binned_lines() {
+        let lines = [
+            RadSpectrum {
+                r#type: RadiationType::Gamma,
+                r#yield: 0.5,
+                energy: 0.1,
+            },
+            RadSpectrum {
+                r#type: RadiationType::X,
+                r#yield: 0.2,
+                energy: 0.03,
+            },
+        ];
+        let mut binner = SpectrumBinner::new(&[1., 0.1, 0.01]);
+        binner.add_lines(&lines, 2.);
+        binner.add_line(5., 1., 1.);
+
+        assert_eq!(binner.edges(), [0.01, 0.1, 1.]);
+        assert_eq!(binner.number(), [0.4, 1.]);
+        assert_eq!(binner.mean_energies(), vec![Some(0.03), Some(0.1)]);
+        assert_eq!(binner.outside(), (1., 5.));
+        assert!(SpectrumBinner::new(&[1.]).number().is_empty());
+    }
+
+    #[test]
+    fn binned_beta() {
+        // made-up triangular spectrum of one particle with a mean energy of 1/3 MeV
+        let spectrum = bet(&[(0., 2.), (1., 0.)]);
+        let mut binner = SpectrumBinner::new(&[0., 0.25, 0.5, 2.]);
+        binner.add_beta(&spectrum, 1.);
+
+        let number: f64 = binner.number().iter().sum();
+        let energy: f64 = binner.energy().iter().sum();
+        assert!((number - 1.).abs() < 1e-12);
+        assert!((energy - 1. / 3.).abs() < 1e-12);
+        assert_eq!(binner.outside(), (0., 0.));
+        // 2 - 2 E integrated over [0, 0.25]
+        assert!((binner.number()[0] - 0.4375).abs() < 1e-12);
+
+        let mut narrow = SpectrumBinner::new(&[0.5, 2.]);
+        narrow.add_beta(&spectrum, 1.);
+        assert!((narrow.number()[0] + narrow.outside().0 - 1.).abs() < 1e-12);
+    }
+
+    #[test]
+    fn binned_groups() {
+        // made-up neutron groups: 1 neutron flat on [0, 1] MeV, 2 on [1, 3] MeV
+        let spectrum = [NsfSpectrum::new(0., 1., 1.), NsfSpectrum::new(1., 3., 2.)];
+        let mut binner = SpectrumBinner::new(&[0., 0.5, 2., 2.5]);
+        binner.add_groups(&spectrum, 2.);
+
+        assert_eq!(binner.number(), [1., 3., 1.]);
+        assert_eq!(binner.mean_energies()[0], Some(0.25));
+        assert_eq!(binner.densities(), vec![2., 2., 2.]);
+        assert_eq!(binner.outside(), (1., 2.75));
+
+        let number = binner.number().iter().sum::<f64>() + binner.outside().0;
+        let energy = binner.energy().iter().sum::<f64>() + binner.outside().1;
+        assert!((number - 6.).abs() < 1e-12);
+        // 2 (1 × 0.5 + 2 × 2) MeV
+        assert!((energy - 9.).abs() < 1e-12);
+
+        let mut binner = SpectrumBinner::new(&[0., 0.001, 0.01]);
+        binner.add_ack(&[AckSpectrum::new(0.5, 2_500., "KLL")], 1.);
+        assert_eq!(binner.number(), [0., 0.5]);
+        assert!((binner.energy()[1] - 1.25e-3).abs() < 1e-15);
+    }
+}
@@ -1,11 +1,22 @@
 pub(super) mod ack;
 pub(super) mod bet;
+mod binner;
 pub(super) mod nsf;
 pub(super) mod rad;
 
-use serde::Deserialize;
+use std::sync::Arc;
 
-#[derive(Debug, Copy, Clone, Deserialize, PartialEq, Eq)]
+use serde::{Deserialize, Serialize};
+
+use crate::error::Error;
+
+pub use ack::AckSpectrum;
+pub use bet::BetSpectrum;
+pub use binner::SpectrumBinner;
+pub use nsf::{NeutronPdf, NsfSpectrum, WattParameters};
+pub use rad::{RadLines, RadSpectrum};
+
+#[derive(Debug, Copy, Clone, Deserialize, Serialize, PartialEq, Eq)]
 pub enum RadiationType {
     #[serde(rename = "G")]
     Gamma,
@@ -36,6 +47,11 @@ pub enum RadiationType {
     NeutronEmission,
 }
 
+serde_plain::derive_fromstr_from_deserialize!(RadiationType, |e| -> Error {
+    Error::InvalidRadiationType(e.to_string())
+});
+serde_plain::derive_display_from_serialize!(RadiationType);
+
 impl RadiationType {
     pub fn is_photon(self) -> bool {
         match self {
@@ -63,7 +79,7 @@ pub enum Spectrum {
     AugerCosterKronigElectron {
         r#yield: f64,
         energy: f64,
-        transition: String,
+        transition: Arc<str>,
     },
     SpontaneousFissionNeutron {
         energy_lower: f64,
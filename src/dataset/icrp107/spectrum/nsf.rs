@@ -1,12 +1,10 @@
 use fixed_width_derive::FixedWidth;
 use serde::Deserialize;
-use std::str::FromStr;
 
 use super::Spectrum;
 use crate::derive_from_str;
-use crate::error::Error;
 
-#[derive(Debug, FixedWidth, Deserialize)]
+#[derive(Debug, Clone, FixedWidth, Deserialize)]
 pub struct NsfSpectrum {
     // lower energy (MeV)
     #[fixed_width(range = "0..8")]
@@ -23,6 +21,31 @@ pub struct NsfSpectrum {
 
 derive_from_str!(NsfSpectrum);
 
+impl NsfSpectrum {
+    pub fn new(energy_lower: f64, energy_upper: f64, r#yield: f64) -> Self {
+        Self {
+            energy_lower,
+            energy_upper,
+            r#yield,
+        }
+    }
+
+    /// Lower energy (MeV)
+    pub fn energy_lower(&self) -> f64 {
+        self.energy_lower
+    }
+
+    /// Upper energy (MeV)
+    pub fn energy_upper(&self) -> f64 {
+        self.energy_upper
+    }
+
+    /// Neutrons in the group per nuclear transformation
+    pub fn r#yield(&self) -> f64 {
+        self.r#yield
+    }
+}
+
 impl From<NsfSpectrum> for Spectrum {
     fn from(nsf: NsfSpectrum) -> Self {
         Self::SpontaneousFissionNeutron {
@@ -32,3 +55,166 @@ impl From<NsfSpectrum> for Spectrum {
         }
     }
 }
+
+/// Spontaneous fission neutron spectrum normalized to unit area
+#[derive(Debug, Clone, PartialEq)]
+pub struct NeutronPdf {
+    /// (lower energy (MeV), upper energy (MeV), probability density (/MeV))
+    bins: Vec<(f64, f64, f64)>,
+    total_yield: f64,
+}
+
+impl NeutronPdf {
+    /// Normalize a binned spectrum, `None` if it has no neutron yield
+    pub fn new(spectrum: &[NsfSpectrum]) -> Option<Self> {
+        let total_yield: f64 = spectrum.iter().map(|s| s.r#yield).sum();
+        if total_yield <= 0. {
+            return None;
+        }
+
+        let bins = spectrum
+            .iter()
+            .filter(|s| s.energy_upper > s.energy_lower)
+            .map(|s| {
+                let density = s.r#yield / total_yield / (s.energy_upper - s.energy_lower);
+                (s.energy_lower, s.energy_upper, density)
+            })
+            .collect();
+
+        Some(Self { bins, total_yield })
+    }
+
+    /// Neutrons per nuclear transformation
+    pub fn total_yield(&self) -> f64 {
+        self.total_yield
+    }
+
+    pub fn bins(&self) -> &[(f64, f64, f64)] {
+        &self.bins
+    }
+
+    /// Probability density at `energy` (/MeV)
+    pub fn pdf(&self, energy: f64) -> f64 {
+        self.bins
+            .iter()
+            .find(|(lower, upper, _)| (*lower..*upper).contains(&energy))
+            .map_or(0., |b| b.2)
+    }
+
+    /// Probability of an energy below `energy` (MeV)
+    pub fn cdf(&self, energy: f64) -> f64 {
+        self.bins
+            .iter()
+            .map(|&(lower, upper, density)| density * (energy.min(upper) - lower).max(0.))
+            .sum()
+    }
+
+    /// Mean energy (MeV)
+    pub fn mean_energy(&self) -> f64 {
+        self.moment(|lower, upper| (lower + upper) / 2.)
+    }
+
+    /// Watt parameters with the same mean and variance as this spectrum
+    pub fn fit_watt(&self) -> WattParameters {
+        let mean = self.mean_energy();
+        let variance = self
+            .moment(|lower, upper| (lower * lower + lower * upper + upper * upper) / 3.)
+            - mean * mean;
+
+        // mean = 3a/2 + E_f, variance = 3a²/2 + 2aE_f, where E_f = a²b/4; a
+        // Maxwellian (b = 0) when the spectrum is too wide for a Watt shape
+        let disc = 4. * mean * mean - 6. * variance;
+        if disc <= 0. {
+            return WattParameters {
+                a: 2. * mean / 3.,
+                b: 0.,
+            };
+        }
+        let a = (2. * mean - disc.sqrt()) / 3.;
+        let e_f = mean - 1.5 * a;
+
+        WattParameters {
+            a,
+            b: 4. * e_f / (a * a),
+        }
+    }
+
+    /// Probability-weighted bin average of `f(lower, upper)`
+    fn moment<F: Fn(f64, f64) -> f64>(&self, f: F) -> f64 {
+        self.bins
+            .iter()
+            .map(|&(lower, upper, density)| density * (upper - lower) * f(lower, upper))
+            .sum()
+    }
+}
+
+/// Watt fission spectrum, f(E) = C exp(-E/a) sinh(√(bE))
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WattParameters {
+    /// (MeV)
+    pub a: f64,
+    /// (1/MeV)
+    pub b: f64,
+}
+
+impl WattParameters {
+    /// Normalized probability density at `energy` (/MeV)
+    pub fn pdf(&self, energy: f64) -> f64 {
+        let Self { a, b } = *self;
+        if energy < 0. {
+            0.
+        } else if b == 0. {
+            2. / (std::f64::consts::PI * a.powi(3)).sqrt() * energy.sqrt() * (-energy / a).exp()
+        } else {
+            2. * (-a * b / 4.).exp() / (std::f64::consts::PI * a.powi(3) * b).sqrt()
+                * (-energy / a).exp()
+                * (b * energy).sqrt().sinh()
+        }
+    }
+
+    /// Mean energy (MeV)
+    pub fn mean_energy(&self) -> f64 {
+        1.5 * self.a + self.a * self.a * self.b / 4.
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    // Cf-252 Watt parameters
+    const WATT: WattParameters = WattParameters { a: 1.025, b: 2.926 };
+
+    fn spectrum() -> Vec<NsfSpectrum> {
+        (0..800)
+            .map(|i| {
+                let energy_lower = i as f64 * 0.05;
+                let energy_upper = energy_lower + 0.05;
+                NsfSpectrum {
+                    energy_lower,
+                    energy_upper,
+                    r#yield: 3.77 * WATT.pdf(energy_lower + 0.025) * 0.05,
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn neutron_pdf() {
+        let pdf = NeutronPdf::new(&spectrum()).unwrap();
+
+        assert!((pdf.total_yield() - 3.77).abs() < 1e-2);
+        assert!((pdf.cdf(40.) - 1.).abs() < 1e-12);
+        assert!((pdf.pdf(1.) - WATT.pdf(1.)).abs() / WATT.pdf(1.) < 1e-2);
+        assert!((pdf.mean_energy() - WATT.mean_energy()).abs() < 1e-2);
+        assert!(NeutronPdf::new(&[]).is_none());
+    }
+
+    #[test]
+    fn watt_fit() {
+        let fit = NeutronPdf::new(&spectrum()).unwrap().fit_watt();
+
+        assert!((fit.a - WATT.a).abs() / WATT.a < 1e-2);
+        assert!((fit.b - WATT.b).abs() / WATT.b < 2e-2);
+    }
+}
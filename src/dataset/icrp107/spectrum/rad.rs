@@ -1,12 +1,10 @@
 use fixed_width_derive::FixedWidth;
 use serde::Deserialize;
-use std::str::FromStr;
 
 use super::{RadiationType, Spectrum};
 use crate::derive_from_str;
-use crate::error::Error;
 
-#[derive(Debug, FixedWidth, Deserialize)]
+#[derive(Debug, Clone, FixedWidth, Deserialize)]
 pub struct RadSpectrum {
     #[fixed_width(range = "26..29")]
     pub r#type: RadiationType,
@@ -31,3 +29,106 @@ impl From<RadSpectrum> for Spectrum {
         }
     }
 }
+
+/// Filtered view of the RAD lines of a nuclide
+#[derive(Debug, Clone)]
+pub struct RadLines<'a> {
+    spectrum: &'a [RadSpectrum],
+    types: Vec<RadiationType>,
+    photons: bool,
+    /// (MeV)
+    energy: (f64, f64),
+    min_yield: f64,
+}
+
+impl<'a> RadLines<'a> {
+    pub fn new(spectrum: &'a [RadSpectrum]) -> Self {
+        Self {
+            spectrum,
+            types: vec![],
+            photons: false,
+            energy: (f64::NEG_INFINITY, f64::INFINITY),
+            min_yield: 0.,
+        }
+    }
+
+    /// Keep lines of this radiation type (may be given several times)
+    pub fn of_type(mut self, r#type: RadiationType) -> Self {
+        self.types.push(r#type);
+        self
+    }
+
+    /// Keep photon lines
+    pub fn photons(mut self) -> Self {
+        self.photons = true;
+        self
+    }
+
+    /// Keep lines with energy (MeV) in [min, max]
+    pub fn energy(mut self, min: f64, max: f64) -> Self {
+        self.energy = (min, max);
+        self
+    }
+
+    /// Keep lines with a yield (/nt) of at least `min`
+    pub fn min_yield(mut self, min: f64) -> Self {
+        self.min_yield = min;
+        self
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &'a RadSpectrum> + '_ {
+        self.spectrum.iter().filter(move |line| {
+            let by_type = (self.types.is_empty() && !self.photons)
+                || self.types.contains(&line.r#type)
+                || (self.photons && line.r#type.is_photon());
+
+            by_type
+                && (self.energy.0..=self.energy.1).contains(&line.energy)
+                && line.r#yield >= self.min_yield
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn line(r#type: RadiationType, r#yield: f64, energy: f64) -> RadSpectrum {
+        RadSpectrum {
+            r#type,
+            r#yield,
+            energy,
+        }
+    }
+
+    #[test]
+    fn rad_lines_filter() {
+        let spectrum = vec![
+            line(RadiationType::BetaMinus, 1., 0.0958),
+            line(RadiationType::Gamma, 0.9985, 1.173),
+            line(RadiationType::Gamma, 0.9998, 1.332),
+            line(RadiationType::X, 1e-4, 0.0075),
+        ];
+
+        assert_eq!(RadLines::new(&spectrum).iter().count(), 4);
+        assert_eq!(RadLines::new(&spectrum).photons().iter().count(), 3);
+        assert_eq!(
+            RadLines::new(&spectrum)
+                .photons()
+                .min_yield(1e-2)
+                .energy(1.2, 2.)
+                .iter()
+                .map(|l| l.energy)
+                .collect::<Vec<_>>(),
+            vec![1.332]
+        );
+        assert_eq!(
+            RadLines::new(&spectrum)
+                .of_type(RadiationType::BetaMinus)
+                .of_type(RadiationType::X)
+                .iter()
+                .count(),
+            2
+        );
+    }
+}
@@ -0,0 +1,109 @@
+use super::spectrum::RadiationType;
+use super::Icrp107;
+use crate::dose::NuclideBetaEmission;
+use crate::error::Error;
+use crate::primitive::attr::{NuclideDecayMode, NuclideHalfLife, NuclideProgeny};
+use crate::primitive::{DecayModeSet, Energy, HalfLife, Nuclide, NumberFormat, Progeny};
+
+/// Number of gamma lines listed in a [`NuclideSummary`]
+const GAMMA_LINES: usize = 3;
+
+/// Decay properties of a nuclide at a glance
+#[derive(Debug, Clone)]
+pub struct NuclideSummary {
+    pub nuclide: Nuclide,
+    pub half_life: HalfLife,
+    pub decay_mode: DecayModeSet,
+    /// By decreasing branch ratio
+    pub progeny: Vec<Progeny>,
+    /// Most intense gamma lines as energy and yield (/nt), by decreasing yield
+    pub gamma_lines: Vec<(Energy, f64)>,
+    /// Mean energy of the beta spectrum, `None` without beta emission
+    pub mean_beta_energy: Option<Energy>,
+}
+
+impl NuclideSummary {
+    /// Summary as text, the branch ratios and yields written in `number`
+    pub fn to_string_with(&self, number: NumberFormat) -> String {
+        let mut s = String::new();
+        // writing to a String does not fail
+        let _ = self.write(&mut s, number);
+        s
+    }
+
+    fn write<W: std::fmt::Write>(&self, f: &mut W, number: NumberFormat) -> std::fmt::Result {
+        writeln!(
+            f,
+            "{}  {}  {}",
+            self.nuclide, self.half_life, self.decay_mode
+        )?;
+        for p in &self.progeny {
+            writeln!(f, "  -> {} ({})", p.nuclide, number.format(p.branch_rate))?;
+        }
+        for (energy, r#yield) in &self.gamma_lines {
+            writeln!(f, "  gamma {} ({} /nt)", energy, number.format(*r#yield))?;
+        }
+        if let Some(energy) = self.mean_beta_energy {
+            writeln!(f, "  mean beta {}", energy)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::fmt::Display for NuclideSummary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.write(f, NumberFormat::DEFAULT)
+    }
+}
+
+impl Icrp107 {
+    /// Half-life, decay modes, progeny, main gamma lines and mean beta energy
+    pub fn summary(&self, nuclide: Nuclide) -> Result<NuclideSummary, Error> {
+        let mut progeny = self.progeny(nuclide)?;
+        progeny.sort_by(|a, b| b.branch_rate.total_cmp(&a.branch_rate));
+
+        let mut gamma_lines: Vec<(Energy, f64)> = self
+            .rad_for(nuclide)?
+            .iter()
+            .filter(|line| {
+                matches!(
+                    line.r#type,
+                    RadiationType::Gamma | RadiationType::PromptGamma | RadiationType::DelayedGamma
+                )
+            })
+            .map(|line| (Energy::mev(line.energy), line.r#yield))
+            .collect();
+        gamma_lines.sort_by(|a, b| b.1.total_cmp(&a.1));
+        gamma_lines.truncate(GAMMA_LINES);
+
+        Ok(NuclideSummary {
+            nuclide,
+            half_life: self.half_life(nuclide)?,
+            decay_mode: self.decay_mode(nuclide)?,
+            progeny,
+            gamma_lines,
+            mean_beta_energy: self
+                .beta_emission(nuclide)?
+                .map(|beta| Energy::mev(beta.mean_energy)),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    #[ignore]
+    fn cs137_summary() {
+        let data = Icrp107::open("data/icrp107").unwrap();
+        let summary = data.summary("Cs-137".parse().unwrap()).unwrap();
+
+        assert_eq!(summary.progeny[0].nuclide, "Ba-137m".parse().unwrap());
+        assert!(summary.gamma_lines.is_empty());
+        assert!(summary.mean_beta_energy.is_some());
+
+        let summary = data.summary("Co-60".parse().unwrap()).unwrap();
+        assert_eq!(summary.gamma_lines.len(), GAMMA_LINES);
+    }
+}
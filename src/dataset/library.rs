@@ -0,0 +1,255 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use crate::error::Error;
+use crate::primitive::attr::{NuclideDecayMode, NuclideHalfLife, NuclideList, NuclideProgeny};
+use crate::primitive::nuclide::parse_lenient;
+use crate::primitive::{DecayMode, DecayModeSet, HalfLife, Nuclide, Progeny};
+use crate::provenance::{DatasetIdentity, DatasetInfo};
+
+#[derive(Debug, Clone)]
+struct Entry {
+    half_life: HalfLife,
+    progeny: Vec<(Nuclide, f64)>,
+}
+
+/// Nuclide transformation library in a plain text layout, e.g. transcribed
+/// from the libraries of environmental codes to compare their results with
+/// the solvers of this crate
+///
+/// This is not the native file format of any such code: their libraries are
+/// converted to the layout below first. Each record lists a parent, its
+/// half-life and unit (`us`, `ms`, `s`, `m`,
+/// `h`, `d` or `y`) and any number of daughter and branching fraction pairs,
+/// separated by whitespace:
+///
+/// ```text
+/// * nuclide  half-life  unit  daughter  fraction ...
+/// SR-90      28.79      y     Y-90      1.0
+/// Y-90       64.0       h
+/// CS-137     30.17      y     BA-137M   0.946
+/// ```
+///
+/// Nuclide names may be in any case and notation accepted by
+/// [`crate::primitive::normalize_nuclide_list`]; blank lines and lines
+/// starting with `#`, `*` or `!` are ignored. Daughters without a record of
+/// their own are stable. Decay modes are inferred from the change of atomic
+/// and mass numbers, where EC and β+ cannot be told apart. A parent listed
+/// twice is an error.
+#[derive(Debug, Clone, Default)]
+pub struct TextDecayLibrary {
+    entries: BTreeMap<Nuclide, Entry>,
+    identity: Option<DatasetIdentity>,
+}
+
+impl TextDecayLibrary {
+    /// Library of a file, named after the file stem, e.g. `chains` for `chains.txt`
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        let path = path.as_ref();
+        let mut library: Self = std::fs::read_to_string(path)?.parse()?;
+
+        let name = path
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_default();
+        library.identity = Some(DatasetIdentity {
+            path: Some(PathBuf::from(path)),
+            ..DatasetIdentity::new(&name)
+        });
+
+        Ok(library)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    fn entry(&self, nuclide: Nuclide) -> Result<&Entry, Error> {
+        self.entries
+            .get(&nuclide)
+            .ok_or_else(|| Error::InvalidNuclide(nuclide.to_string()))
+    }
+}
+
+/// Decay mode leading from `parent` to `daughter`
+fn infer_decay_mode(parent: Nuclide, daughter: Nuclide) -> DecayModeSet {
+    let mode = DecayModeSet::default();
+    let (Some(zp), Some(ap), Some(zd), Some(ad)) =
+        (parent.z(), parent.a(), daughter.z(), daughter.a())
+    else {
+        return match daughter {
+            Nuclide::FissionProducts => mode | DecayMode::SpontaneousFission,
+            Nuclide::WithId(_) => mode,
+        };
+    };
+
+    match (zd as i32 - zp as i32, ad as i32 - ap as i32) {
+        (-2, -4) => mode | DecayMode::Alpha,
+        (1, 0) => mode | DecayMode::BetaMinus,
+        (-1, 0) => mode | DecayMode::ElectronCapture | DecayMode::BetaPlus,
+        (0, 0) => mode | DecayMode::IsometricTransition,
+        _ => mode,
+    }
+}
+
+impl FromStr for TextDecayLibrary {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut library = Self::default();
+
+        for line in s.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with(['#', '*', '!']) {
+                continue;
+            }
+
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            if fields.len() < 3 || fields.len().is_multiple_of(2) {
+                return Err(Error::Unexpected(anyhow::anyhow!(
+                    "invalid decay library record: {}",
+                    line
+                )));
+            }
+
+            let parent = parse_lenient(fields[0])?;
+            let value = fields[1]
+                .parse()
+                .map_err(|_| Error::InvalidFloat(fields[1].to_string()))?;
+            let unit = fields[2].to_lowercase().parse().map_err(|_| {
+                Error::Unexpected(anyhow::anyhow!("invalid time unit: {}", fields[2]))
+            })?;
+
+            let mut progeny = vec![];
+            for pair in fields[3..].chunks(2) {
+                let fraction = pair[1]
+                    .parse()
+                    .map_err(|_| Error::InvalidFloat(pair[1].to_string()))?;
+                progeny.push((parse_lenient(pair[0])?, fraction));
+            }
+
+            let entry = Entry {
+                half_life: HalfLife { value, unit },
+                progeny,
+            };
+            if library.entries.insert(parent, entry).is_some() {
+                return Err(Error::Unexpected(anyhow::anyhow!(
+                    "duplicate decay library record: {}",
+                    parent
+                )));
+            }
+        }
+
+        Ok(library)
+    }
+}
+
+impl NuclideList for TextDecayLibrary {
+    fn nuclide_list(&self) -> Result<BTreeSet<Nuclide>, Error> {
+        Ok(self.entries.keys().copied().collect())
+    }
+}
+
+impl NuclideHalfLife for TextDecayLibrary {
+    fn half_life(&self, nuclide: Nuclide) -> Result<HalfLife, Error> {
+        Ok(self.entry(nuclide)?.half_life)
+    }
+}
+
+impl NuclideProgeny for TextDecayLibrary {
+    fn progeny(&self, nuclide: Nuclide) -> Result<Vec<Progeny>, Error> {
+        Ok(self
+            .entry(nuclide)?
+            .progeny
+            .iter()
+            .map(|&(daughter, branch_rate)| Progeny {
+                nuclide: daughter,
+                branch_rate,
+                decay_mode: infer_decay_mode(nuclide, daughter),
+            })
+            .collect())
+    }
+}
+
+impl NuclideDecayMode for TextDecayLibrary {
+    fn decay_mode(&self, nuclide: Nuclide) -> Result<DecayModeSet, Error> {
+        Ok(self
+            .progeny(nuclide)?
+            .iter()
+            .fold(DecayModeSet::default(), |modes, p| {
+                DecayModeSet(modes.0 | p.decay_mode.0)
+            }))
+    }
+}
+
+impl DatasetInfo for TextDecayLibrary {
+    fn dataset_identity(&self) -> DatasetIdentity {
+        self.identity
+            .clone()
+            .unwrap_or_else(|| DatasetIdentity::new("text decay library"))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::decaychain::{BatemanDecaySolver, Inventory};
+    use crate::primitive::TimeUnit;
+
+    // made-up library in the documented layout, not taken from any code
+    const LIBRARY: &str = "\
+* nuclide  half-life  unit  daughter  fraction
+SR-90      28.79      y     Y-90      1.0
+Y-90       64.0       h
+CS-137     30.17      y     BA-137M   0.946
+BA-137M    2.552      M
+TH-228     1.9116     y     RA-224    1.0
+";
+
+    #[test]
+    fn parse_library() {
+        let library: TextDecayLibrary = LIBRARY.parse().unwrap();
+        let nuclide = |s: &str| -> Nuclide { s.parse().unwrap() };
+
+        assert_eq!(library.len(), 5);
+        assert_eq!(
+            library.half_life(nuclide("Ba-137m")).unwrap(),
+            HalfLife {
+                value: 2.552,
+                unit: TimeUnit::Minute
+            }
+        );
+
+        let progeny = library.progeny(nuclide("Cs-137")).unwrap();
+        assert_eq!(progeny.len(), 1);
+        assert_eq!(progeny[0].nuclide, nuclide("Ba-137m"));
+        assert_eq!(progeny[0].branch_rate, 0.946);
+        assert_eq!(
+            library.decay_mode(nuclide("Cs-137")).unwrap(),
+            DecayModeSet::default() | DecayMode::BetaMinus
+        );
+        assert_eq!(
+            library.decay_mode(nuclide("Th-228")).unwrap(),
+            DecayModeSet::default() | DecayMode::Alpha
+        );
+        // Ra-224 has no record
+        assert!(library.progeny(nuclide("Ra-224")).is_err());
+
+        let solver = BatemanDecaySolver::new(std::sync::Arc::new(library));
+        let mut inv = Inventory::new();
+        inv.add(nuclide("Sr-90"), 1.);
+        let res = solver.decay(&inv, 30. * 86_400.);
+        assert!((res[&nuclide("Y-90")] / res[&nuclide("Sr-90")] - 1.).abs() < 1e-3);
+
+        assert!("SR-90 28.79".parse::<TextDecayLibrary>().is_err());
+        assert!("SR-90 28.79 y Y-90".parse::<TextDecayLibrary>().is_err());
+        assert!("SR-90 28.79 w".parse::<TextDecayLibrary>().is_err());
+        assert!("SR-90 28.79 y\nSr90 28.8 y"
+            .parse::<TextDecayLibrary>()
+            .is_err());
+    }
+}
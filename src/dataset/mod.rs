@@ -1,8 +1,26 @@
+#[cfg(feature = "bundle")]
+mod bundle;
+mod chained;
+mod consistency;
+mod coverage;
+mod custom;
 pub mod icrp107;
+mod library;
 pub mod nist;
+mod query;
 pub mod radtoolbox;
 mod reader;
+pub mod transfer;
 
+#[cfg(feature = "bundle")]
+pub use bundle::Bundle;
+pub use chained::{AttenuationSource, ChainedDecayData, DcfSource, DecaySource, PhotonSource};
+pub use consistency::{check_decay_modes, DecayModeIssue};
+pub use coverage::{coverage_diff, CoverageDiff, HalfLifeMismatch};
+pub use custom::CustomDecayData;
 pub use icrp107::Icrp107;
+pub use library::TextDecayLibrary;
 pub use nist::NistMassAttenCoef;
+pub use query::{NuclideQuery, QueryMatch};
 pub use radtoolbox::RadToolbox3;
+pub use transfer::TransferFactors;
@@ -1,23 +1,25 @@
 mod reader;
 
+use std::collections::BTreeMap;
 use std::path::Path;
-use std::{collections::BTreeMap, path::PathBuf};
+#[cfg(feature = "bundle")]
+use std::sync::Arc;
 
 use fixed_width_derive::FixedWidth;
 use num_traits::FromPrimitive;
 use once_cell::sync::OnceCell;
 use serde::Deserialize;
 
+use super::reader::DataSource;
+#[cfg(feature = "bundle")]
+use super::Bundle;
 use crate::error::Error;
-use crate::primitive::attr::{AtomicMass, Energy, MassAttenuationCoefficient};
+use crate::math::interp::log_log;
+use crate::primitive::attr::{AtomicMass, MassAttenuationCoefficient};
 use crate::primitive::notation::Material;
-use crate::primitive::Symbol;
+use crate::primitive::{Energy, Symbol};
 use reader::{MassAttenCoefReader, MaterialConstantReader};
 
-static MATEAIAL_CONSTANTS: OnceCell<BTreeMap<Symbol, MaterialConstant>> = OnceCell::new();
-static ATTENUATION_COEF: OnceCell<BTreeMap<Symbol, BTreeMap<Energy, MassAttenCoef>>> =
-    OnceCell::new();
-
 #[derive(Deserialize, FixedWidth)]
 pub struct MaterialConstantRecord {
     #[fixed_width(range = "4..6")]
@@ -86,47 +88,78 @@ impl From<MassAttenCoefRecord> for MassAttenCoef {
     }
 }
 
+type ElementTable = BTreeMap<u32, MassAttenCoef>;
+
+/// NIST X-ray mass attenuation coefficients of the elements H to U
+///
+/// Tables are read on first use and kept by the instance, so instances
+/// opened on different data do not share them.
 pub struct NistMassAttenCoef {
-    path: PathBuf,
+    source: DataSource,
+    material_constants: OnceCell<BTreeMap<Symbol, MaterialConstant>>,
+    mass_atten_coef: OnceCell<BTreeMap<Symbol, ElementTable>>,
 }
 
 impl NistMassAttenCoef {
+    fn new(source: DataSource) -> Self {
+        Self {
+            source,
+            material_constants: OnceCell::new(),
+            mass_atten_coef: OnceCell::new(),
+        }
+    }
+
     pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
         let path = path.as_ref().to_path_buf();
 
         if path.is_dir() {
-            Ok(Self { path })
+            Ok(Self::new(DataSource::dir(path)))
         } else {
             Err(Error::Unexpected(anyhow::anyhow!("Invalid data path")))
         }
     }
 
+    /// Read the data files from a zip or tar archive without extracting it
+    #[cfg(feature = "bundle")]
+    pub fn open_bundle<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        let bundle = Bundle::open(path)?;
+        Ok(Self::new(DataSource::Bundle(Arc::new(bundle))))
+    }
+
     pub fn material_constants(&self) -> Result<&BTreeMap<Symbol, MaterialConstant>, Error> {
-        MATEAIAL_CONSTANTS.get_or_try_init(|| {
-            MaterialConstantReader::new(&self.path.join("material_constants"))?.read()
+        self.material_constants.get_or_try_init(|| {
+            MaterialConstantReader::new(self.source.reader("material_constants")?)?.read()
         })
     }
 
-    pub fn mass_atten_coef(
-        &self,
-    ) -> Result<&BTreeMap<Symbol, BTreeMap<Energy, MassAttenCoef>>, Error> {
-        ATTENUATION_COEF.get_or_try_init(|| {
-            let mut content = BTreeMap::new();
-
-            for z in 1..=92 {
-                let symbol: Symbol = FromPrimitive::from_u8(z).unwrap();
-                let value = MassAttenCoefReader::new(&self.path, z)?
-                    .read()?
-                    .into_iter()
-                    .map(|r| ((r.energy * 1_000_000f64) as u32, r.into()))
-                    .collect();
-
-                content.insert(symbol, value);
+    /// Tables of each element keyed by energy (eV)
+    ///
+    /// The element files are parsed in parallel with the `parallel` feature.
+    pub fn mass_atten_coef(&self) -> Result<&BTreeMap<Symbol, ElementTable>, Error> {
+        self.mass_atten_coef.get_or_try_init(|| {
+            #[cfg(feature = "parallel")]
+            {
+                use rayon::prelude::*;
+                (1..=92u8)
+                    .into_par_iter()
+                    .map(|z| self.read_element(z))
+                    .collect()
             }
-
-            Ok(content)
+            #[cfg(not(feature = "parallel"))]
+            (1..=92u8).map(|z| self.read_element(z)).collect()
         })
     }
+
+    fn read_element(&self, z: u8) -> Result<(Symbol, ElementTable), Error> {
+        let symbol: Symbol = FromPrimitive::from_u8(z).unwrap();
+        let table = MassAttenCoefReader::new(self.source.reader(&format!("{:02}", z))?)?
+            .read()?
+            .into_iter()
+            .map(|r| (Energy::mev(r.energy).as_ev().round() as u32, r.into()))
+            .collect();
+
+        Ok((symbol, table))
+    }
 }
 
 impl AtomicMass for NistMassAttenCoef {
@@ -147,14 +180,26 @@ impl MassAttenuationCoefficient for NistMassAttenCoef {
         let mut coef = 0f64;
 
         for (symbol, wf) in material.weight_fraction() {
-            coef += wf
-                * self
-                    .mass_atten_coef()?
-                    .get(symbol)
-                    .unwrap()
-                    .get(&energy)
-                    .map(|r| r.mu_over_rho)
-                    .ok_or(Error::InvalidEnergy(energy))?;
+            let table = self
+                .mass_atten_coef()?
+                .get(symbol)
+                .ok_or_else(|| Error::InvalidSymbol(symbol.to_string()))?;
+
+            // log-log interpolation between the tabulated energies
+            let ev = energy.as_ev();
+            let mu_over_rho = match (
+                table.range(..=ev.floor() as u32).next_back(),
+                table.range(ev.ceil() as u32..).next(),
+            ) {
+                (Some((&e0, c0)), Some((&e1, c1))) => log_log(
+                    &[e0 as f64, e1 as f64],
+                    &[c0.mu_over_rho, c1.mu_over_rho],
+                    ev,
+                ),
+                _ => None,
+            };
+
+            coef += wf * mu_over_rho.ok_or(Error::InvalidEnergy(energy))?;
         }
 
         Ok(coef)
@@ -1,5 +1,4 @@
 use std::collections::BTreeMap;
-use std::path::Path;
 
 use super::super::reader::FileReader;
 use super::{MassAttenCoefRecord, MaterialConstant, MaterialConstantRecord};
@@ -12,8 +11,8 @@ pub struct MaterialConstantReader {
 }
 
 impl MaterialConstantReader {
-    pub fn new(path: &Path) -> Result<Self, Error> {
-        let reader = FileReader::new(path)?.skip_lines(2)?;
+    pub fn new(reader: FileReader) -> Result<Self, Error> {
+        let reader = reader.skip_lines(2)?;
         Ok(Self { reader })
     }
 
@@ -36,8 +35,8 @@ pub struct MassAttenCoefReader {
 }
 
 impl MassAttenCoefReader {
-    pub fn new(path: &Path, z: u8) -> Result<Self, Error> {
-        let reader = FileReader::new(&path.join(format!("{:02}", z)))?.skip_lines(2)?;
+    pub fn new(reader: FileReader) -> Result<Self, Error> {
+        let reader = reader.skip_lines(2)?;
         Ok(Self { reader })
     }
 
@@ -0,0 +1,296 @@
+use std::str::FromStr;
+
+use chumsky::prelude::{end, Parser};
+
+use crate::error::Error;
+use crate::primitive::attr::{NuclideDecayMode, NuclideHalfLife, NuclideList, NuclidePhotonLines};
+use crate::primitive::parser::{decaymode, halflife};
+use crate::primitive::{DecayMode, Energy, HalfLife, Nuclide};
+
+/// Energy window of a photon line criterion given without tolerance
+const DEFAULT_TOLERANCE: Energy = Energy::ev(1e3);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct LineCriterion {
+    energy: Energy,
+    tolerance: Energy,
+    min_yield: f64,
+}
+
+impl LineCriterion {
+    /// Best yield of the lines in the window, weighted by 1 - |dE| / (2 tolerance)
+    fn score(&self, lines: &[(Energy, f64)]) -> Option<f64> {
+        let tolerance = self.tolerance.as_ev();
+        lines
+            .iter()
+            .filter(|(_, y)| *y >= self.min_yield)
+            .filter_map(|(e, y)| {
+                let offset = (e.as_ev() - self.energy.as_ev()).abs();
+                (offset <= tolerance).then(|| y * (1. - offset / (2. * tolerance)))
+            })
+            .max_by(f64::total_cmp)
+    }
+}
+
+/// Nuclide ranked by a [`NuclideQuery`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct QueryMatch {
+    pub nuclide: Nuclide,
+    /// Product of the scores of the photon line criteria, 1 without any
+    pub score: f64,
+}
+
+/// Search of the nuclides of a dataset by half-life, decay mode and photon lines
+///
+/// All criteria must hold. Matches are ranked by the yields of the photon
+/// lines found, lines closer to the requested energy counting more.
+///
+/// A query may also be parsed from clauses joined by `and` or `;`:
+///
+/// ```
+/// # use radioactive::dataset::NuclideQuery;
+/// let query: NuclideQuery = "half-life 1 d..100 y and mode A".parse().unwrap();
+/// let query: NuclideQuery = "line 662 keV ± 2 keV > 5%; half-life > 1 y".parse().unwrap();
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct NuclideQuery {
+    /// (s)
+    half_life: (f64, f64),
+    modes: Vec<DecayMode>,
+    lines: Vec<LineCriterion>,
+}
+
+impl Default for NuclideQuery {
+    fn default() -> Self {
+        Self {
+            half_life: (0., f64::INFINITY),
+            modes: vec![],
+            lines: vec![],
+        }
+    }
+}
+
+impl NuclideQuery {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Keep nuclides with a half-life in [min, max]
+    pub fn half_life_between(mut self, min: HalfLife, max: HalfLife) -> Self {
+        self.half_life = (min.as_sec(), max.as_sec());
+        self
+    }
+
+    /// Keep nuclides with a half-life of at least `min`
+    pub fn half_life_above(mut self, min: HalfLife) -> Self {
+        self.half_life.0 = min.as_sec();
+        self
+    }
+
+    /// Keep nuclides with a half-life of at most `max`
+    pub fn half_life_below(mut self, max: HalfLife) -> Self {
+        self.half_life.1 = max.as_sec();
+        self
+    }
+
+    /// Keep nuclides decaying by this mode (may be given several times)
+    pub fn decay_mode(mut self, mode: DecayMode) -> Self {
+        self.modes.push(mode);
+        self
+    }
+
+    /// Keep nuclides with a photon line within `tolerance` of `energy` and a
+    /// yield (/nt) of at least `min_yield` (may be given several times)
+    pub fn photon_line(mut self, energy: Energy, tolerance: Energy, min_yield: f64) -> Self {
+        self.lines.push(LineCriterion {
+            energy,
+            tolerance,
+            min_yield,
+        });
+        self
+    }
+
+    /// Matching nuclides of the dataset, by decreasing score
+    pub fn search<D>(&self, data: &D) -> Result<Vec<QueryMatch>, Error>
+    where
+        D: NuclideList + NuclideHalfLife + NuclideDecayMode + NuclidePhotonLines + ?Sized,
+    {
+        let mut matches = vec![];
+        for nuclide in data.nuclide_list()? {
+            let half_life = data.half_life(nuclide)?.as_sec();
+            if half_life < self.half_life.0 || half_life > self.half_life.1 {
+                continue;
+            }
+
+            let modes = data.decay_mode(nuclide)?;
+            if !self.modes.iter().all(|&mode| modes.0.contains(mode)) {
+                continue;
+            }
+
+            let mut score = 1.;
+            if !self.lines.is_empty() {
+                let lines = data.photon_lines(nuclide)?;
+                match self.lines.iter().map(|c| c.score(&lines)).product() {
+                    Some(s) => score = s,
+                    None => continue,
+                }
+            }
+
+            matches.push(QueryMatch { nuclide, score });
+        }
+        matches.sort_by(|a, b| b.score.total_cmp(&a.score));
+
+        Ok(matches)
+    }
+}
+
+impl FromStr for NuclideQuery {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.split(';')
+            .flat_map(|part| part.split(" and "))
+            .map(str::trim)
+            .filter(|clause| !clause.is_empty())
+            .try_fold(Self::new(), parse_clause)
+    }
+}
+
+fn parse_clause(query: NuclideQuery, clause: &str) -> Result<NuclideQuery, Error> {
+    let invalid = || Error::InvalidQuery(clause.to_string());
+    let (keyword, rest) = clause.split_once(char::is_whitespace).ok_or_else(invalid)?;
+    let rest = rest.trim();
+
+    let half_life = |s: &str| {
+        halflife()
+            .then_ignore(end())
+            .parse(s.trim())
+            .map_err(|_| invalid())
+    };
+    let energy = |s: &str| {
+        let s = s.trim();
+        let (value, scale) = if let Some(v) = s.strip_suffix("MeV") {
+            (v, 1e6)
+        } else if let Some(v) = s.strip_suffix("keV") {
+            (v, 1e3)
+        } else if let Some(v) = s.strip_suffix("eV") {
+            (v, 1.)
+        } else {
+            return Err(invalid());
+        };
+        value
+            .trim()
+            .parse::<f64>()
+            .map(|v| Energy::ev(v * scale))
+            .map_err(|_| invalid())
+    };
+    let fraction = |s: &str| {
+        let s = s.trim();
+        match s.strip_suffix('%') {
+            Some(percent) => percent.trim().parse::<f64>().map(|v| v / 100.),
+            None => s.parse::<f64>(),
+        }
+        .map_err(|_| invalid())
+    };
+
+    match keyword.to_ascii_lowercase().as_str() {
+        "half-life" | "halflife" => {
+            if let Some(min) = rest.strip_prefix('>') {
+                Ok(query.half_life_above(half_life(min)?))
+            } else if let Some(max) = rest.strip_prefix('<') {
+                Ok(query.half_life_below(half_life(max)?))
+            } else {
+                let (min, max) = rest.split_once("..").ok_or_else(invalid)?;
+                Ok(query.half_life_between(half_life(min)?, half_life(max)?))
+            }
+        }
+        "mode" => {
+            let mode = decaymode()
+                .then_ignore(end())
+                .parse(rest)
+                .map_err(|_| invalid())?;
+            Ok(query.decay_mode(mode))
+        }
+        "line" => {
+            let (line, min_yield) = match rest.split_once('>') {
+                Some((line, min_yield)) => (line, fraction(min_yield)?),
+                None => (rest, 0.),
+            };
+            let (line, tolerance) = match line.split_once('±').or_else(|| line.split_once("+-")) {
+                Some((line, tolerance)) => (line, energy(tolerance)?),
+                None => (line, DEFAULT_TOLERANCE),
+            };
+            Ok(query.photon_line(energy(line)?, tolerance, min_yield))
+        }
+        _ => Err(invalid()),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeSet;
+
+    use super::*;
+    use crate::fixtures::FixtureDecayData;
+    use crate::primitive::DecayModeSet;
+
+    /// Fixture decay data with a few photon lines, rounded ICRP-107 values
+    struct WithLines(FixtureDecayData);
+
+    impl NuclideList for WithLines {
+        fn nuclide_list(&self) -> Result<BTreeSet<Nuclide>, Error> {
+            self.0.nuclide_list()
+        }
+    }
+
+    impl NuclideHalfLife for WithLines {
+        fn half_life(&self, nuclide: Nuclide) -> Result<HalfLife, Error> {
+            self.0.half_life(nuclide)
+        }
+    }
+
+    impl NuclideDecayMode for WithLines {
+        fn decay_mode(&self, nuclide: Nuclide) -> Result<DecayModeSet, Error> {
+            self.0.decay_mode(nuclide)
+        }
+    }
+
+    impl NuclidePhotonLines for WithLines {
+        fn photon_lines(&self, nuclide: Nuclide) -> Result<Vec<(Energy, f64)>, Error> {
+            Ok(match nuclide.to_string().as_str() {
+                "Ba-137m" => vec![(Energy::kev(661.657), 0.8998)],
+                "I-131" => vec![(Energy::kev(364.49), 0.815), (Energy::kev(636.989), 0.0716)],
+                _ => vec![],
+            })
+        }
+    }
+
+    fn nuclides(matches: &[QueryMatch]) -> Vec<String> {
+        matches.iter().map(|m| m.nuclide.to_string()).collect()
+    }
+
+    #[test]
+    fn search() {
+        let data = WithLines(FixtureDecayData::new());
+
+        let query: NuclideQuery = "half-life 1 d..100 y and mode A".parse().unwrap();
+        assert_eq!(
+            nuclides(&query.search(&data).unwrap()),
+            vec!["Po-210", "Rn-222", "Cf-252"]
+        );
+
+        let query: NuclideQuery = "line 662 keV > 5%".parse().unwrap();
+        assert_eq!(nuclides(&query.search(&data).unwrap()), vec!["Ba-137m"]);
+
+        let query = NuclideQuery::new().photon_line(Energy::kev(640.), Energy::kev(25.), 0.);
+        assert_eq!(
+            nuclides(&query.search(&data).unwrap()),
+            vec!["Ba-137m", "I-131"]
+        );
+
+        assert!(matches!(
+            "line 662 > 5%".parse::<NuclideQuery>(),
+            Err(Error::InvalidQuery(_))
+        ));
+    }
+}
@@ -5,8 +5,10 @@ use mdbsql::Connection;
 
 use crate::dataset::radtoolbox::utils::AsAdultPhantomOrgan;
 use crate::error::Error;
+use crate::intern::intern;
 use crate::primitive::dose_coefficient::{
-    AgeGroup, BiokineticAttr, DcfValue, Organ, RespiratoryTractAttr,
+    AgeGroup, BiokineticAttr, DcfProvenance, DcfQueryResult, DcfValue, NotFoundReason, Organ,
+    RespiratoryTractAttr,
 };
 use crate::primitive::parser::gi_absorption_factor;
 use crate::primitive::{
@@ -27,26 +29,32 @@ impl Fgr12 {
     }
 }
 
+const SOURCE: &str = "FGR 12";
+
 macro_rules! ext_dcf_fn {
     ($fn:ident, $table:expr, $unit:expr) => {
-        fn $fn(&self, nuclide: Nuclide, organ: Organ) -> Result<Option<DcfValue>, Error> {
+        fn $fn(&self, nuclide: Nuclide, organ: Organ) -> Result<DcfQueryResult, Error> {
+            let mut res = vec![];
+            let column = organ_col!(organ);
             if let Some(row) = self
                 .connection
                 .prepare(&format!(
                     concat!("SELECT \"{}\" FROM \"", $table, "\" WHERE Nuclide='{}'"),
-                    organ.to_col()?,
-                    nuclide
+                    column, nuclide
                 ))?
                 .next()
             {
-                Ok(Some(DcfValue {
+                res.push(DcfValue {
                     value: row.get(0)?,
                     unit: $unit.to_string(),
                     attr: None,
-                }))
-            } else {
-                Ok(None)
+                    provenance: Some(
+                        DcfProvenance::new(SOURCE, $table, &column).key("Nuclide", nuclide),
+                    ),
+                });
             }
+
+            Ok(DcfQueryResult::new(SOURCE, res))
         }
     };
 }
@@ -85,32 +93,43 @@ impl DcfIngestion for Fgr12 {
         nuclide: Nuclide,
         age_group: AgeGroup,
         organ: Organ,
-    ) -> Result<Vec<DcfValue>, Error> {
+    ) -> Result<DcfQueryResult, Error> {
         match age_group {
             AgeGroup::Worker => {
+                let column = organ_col!(organ);
                 let rows = self.connection.prepare(&format!(
                     "SELECT \"{}\", f1 FROM Ingestion WHERE Nuclide='{}'",
-                    organ.to_col()?,
-                    nuclide
+                    column, nuclide
                 ))?;
 
                 let mut res = vec![];
                 for row in rows {
                     let value = row.get(0)?;
                     let unit = "Sv/Bq".to_string();
-                    let (f1, compound) = gi_absorption_factor().parse(row.get::<String>(1)?)?;
+                    let f1_col: String = row.get(1)?;
+                    let (f1, compound) = gi_absorption_factor().parse(f1_col.as_str())?;
                     let attr = Some(BiokineticAttr {
-                        compound,
+                        compound: intern(&compound),
                         f1,
                         respiratory_tract_attr: None,
                     });
 
-                    res.push(DcfValue { value, unit, attr })
+                    let provenance = Some(
+                        DcfProvenance::new(SOURCE, "Ingestion", &column)
+                            .key("Nuclide", nuclide)
+                            .key("f1", f1_col),
+                    );
+                    res.push(DcfValue {
+                        value,
+                        unit,
+                        attr,
+                        provenance,
+                    })
                 }
 
-                Ok(res)
+                Ok(DcfQueryResult::new(SOURCE, res))
             }
-            _ => Ok(vec![]),
+            _ => Ok(DcfQueryResult::not_found(SOURCE, NotFoundReason::AgeGroup)),
         }
     }
 }
@@ -121,13 +140,13 @@ impl DcfInhalation for Fgr12 {
         nuclide: Nuclide,
         age_group: AgeGroup,
         organ: Organ,
-    ) -> Result<Vec<DcfValue>, Error> {
+    ) -> Result<DcfQueryResult, Error> {
         match age_group {
             AgeGroup::Worker => {
+                let column = organ_col!(organ);
                 let rows = self.connection.prepare(&format!(
                     "SELECT \"{}\", Class, f1 FROM Inhalation WHERE Nuclide='{}'",
-                    organ.to_col()?,
-                    nuclide
+                    column, nuclide
                 ))?;
 
                 let mut res = vec![];
@@ -135,18 +154,30 @@ impl DcfInhalation for Fgr12 {
                     let value = row.get(0)?;
                     let unit = "Sv/Bq".to_string();
                     let respiratory_tract_attr = Some(RespiratoryTractAttr::ICRP30(row.get(1)?));
-                    let (f1, compound) = gi_absorption_factor().parse(row.get::<String>(2)?)?;
+                    let f1_col: String = row.get(2)?;
+                    let (f1, compound) = gi_absorption_factor().parse(f1_col.as_str())?;
                     let attr = Some(BiokineticAttr {
-                        compound,
+                        compound: intern(&compound),
                         f1,
                         respiratory_tract_attr,
                     });
-                    res.push(DcfValue { value, unit, attr })
+                    let provenance = Some(
+                        DcfProvenance::new(SOURCE, "Inhalation", &column)
+                            .key("Nuclide", nuclide)
+                            .key("Class", row.get::<String>(1)?)
+                            .key("f1", f1_col),
+                    );
+                    res.push(DcfValue {
+                        value,
+                        unit,
+                        attr,
+                        provenance,
+                    })
                 }
 
-                Ok(res)
+                Ok(DcfQueryResult::new(SOURCE, res))
             }
-            _ => Ok(vec![]),
+            _ => Ok(DcfQueryResult::not_found(SOURCE, NotFoundReason::AgeGroup)),
         }
     }
 }
@@ -166,7 +197,7 @@ mod test {
             .dcf_air_submersion("Cs-137".parse().unwrap(), Organ::EffectiveDose)
             .unwrap();
 
-        assert_eq!(result.unwrap().value, 9.28e-17);
+        assert_eq!(result.first().unwrap().value, 9.28e-17);
     }
 
     #[test]
@@ -177,7 +208,7 @@ mod test {
             .dcf_water_immersion("Cs-137".parse().unwrap(), Organ::EffectiveDose)
             .unwrap();
 
-        assert_eq!(result.unwrap().value, 1.04e-19);
+        assert_eq!(result.first().unwrap().value, 1.04e-19);
     }
 
     #[test]
@@ -188,7 +219,7 @@ mod test {
             .dcf_ground_surface("Cs-137".parse().unwrap(), Organ::EffectiveDose)
             .unwrap();
 
-        assert_eq!(result.unwrap().value, 2.99e-18);
+        assert_eq!(result.first().unwrap().value, 2.99e-18);
     }
 
     #[test]
@@ -199,7 +230,7 @@ mod test {
             .dcf_soil_1cm("Cs-137".parse().unwrap(), Organ::EffectiveDose)
             .unwrap();
 
-        assert_eq!(result.unwrap().value, 2.07e-21);
+        assert_eq!(result.first().unwrap().value, 2.07e-21);
     }
 
     #[test]
@@ -210,7 +241,7 @@ mod test {
             .dcf_soil_5cm("Cs-137".parse().unwrap(), Organ::EffectiveDose)
             .unwrap();
 
-        assert_eq!(result.unwrap().value, 3.62e-21);
+        assert_eq!(result.first().unwrap().value, 3.62e-21);
     }
 
     #[test]
@@ -221,7 +252,7 @@ mod test {
             .dcf_soil_15cm("Cs-137".parse().unwrap(), Organ::EffectiveDose)
             .unwrap();
 
-        assert_eq!(result.unwrap().value, 4.4e-21);
+        assert_eq!(result.first().unwrap().value, 4.4e-21);
     }
 
     #[test]
@@ -232,7 +263,7 @@ mod test {
             .dcf_soil_infinite("Cs-137".parse().unwrap(), Organ::EffectiveDose)
             .unwrap();
 
-        assert_eq!(result.unwrap().value, 4.47e-21);
+        assert_eq!(result.first().unwrap().value, 4.47e-21);
     }
 
     #[test]
@@ -247,16 +278,25 @@ mod test {
             )
             .unwrap();
 
+        let mut values = results.values;
+        let provenance = values[0].provenance.as_ref().unwrap();
+        assert_eq!(provenance.table, "Ingestion");
+        assert_eq!(
+            provenance.row[0],
+            ("Nuclide".to_string(), "H-3".to_string())
+        );
+        values.iter_mut().for_each(|v| v.provenance = None);
         assert_eq!(
-            results,
+            values,
             vec![DcfValue {
                 value: 1.73e-11,
                 unit: "Sv/Bq".to_string(),
                 attr: Some(BiokineticAttr {
-                    compound: "".to_string(),
+                    compound: "".into(),
                     f1: 1.,
                     respiratory_tract_attr: None
                 }),
+                provenance: None,
             }]
         );
     }
@@ -273,18 +313,27 @@ mod test {
             )
             .unwrap();
 
+        let mut values = results.values;
+        let provenance = values[0].provenance.as_ref().unwrap();
+        assert_eq!(provenance.table, "Inhalation");
+        assert_eq!(
+            provenance.row[0],
+            ("Nuclide".to_string(), "H-3".to_string())
+        );
+        values.iter_mut().for_each(|v| v.provenance = None);
         assert_eq!(
-            results,
+            values,
             vec![DcfValue {
                 value: 1.73e-11,
                 unit: "Sv/Bq".to_string(),
                 attr: Some(BiokineticAttr {
-                    compound: "".to_string(),
+                    compound: "".into(),
                     f1: 1.,
                     respiratory_tract_attr: Some(RespiratoryTractAttr::ICRP30(
                         ClearanceClass::WaterVapor
                     )),
                 }),
+                provenance: None,
             },]
         );
     }
@@ -5,13 +5,17 @@ use mdbsql::Connection;
 
 use crate::dataset::radtoolbox::utils::AsAgeDepPhantomOrgan;
 use crate::error::Error;
+use crate::intern::intern;
 use crate::primitive::attr::{DcfIngestion, DcfInhalation};
 use crate::primitive::dose_coefficient::{
-    AgeGroup, BiokineticAttr, DcfValue, Organ, RespiratoryTractAttr,
+    AgeGroup, BiokineticAttr, DcfProvenance, DcfQueryResult, DcfValue, NotFoundReason, Organ,
+    RespiratoryTractAttr,
 };
 use crate::primitive::parser::gi_absorption_factor;
 use crate::primitive::Nuclide;
 
+const SOURCE: &str = "ICRP 68";
+
 #[derive(Debug)]
 pub struct Icrp68 {
     connection: Connection,
@@ -31,31 +35,42 @@ impl DcfIngestion for Icrp68 {
         nuclide: Nuclide,
         age_group: AgeGroup,
         organ: Organ,
-    ) -> Result<Vec<DcfValue>, Error> {
+    ) -> Result<DcfQueryResult, Error> {
         match age_group {
             AgeGroup::Worker => {
+                let column = organ_col!(organ);
                 let rows = self.connection.prepare(&format!(
                     "SELECT {}, f1 FROM Ingestion WHERE Nuclide='{}'",
-                    organ.to_col()?,
-                    nuclide
+                    column, nuclide
                 ))?;
 
                 let mut res = vec![];
                 for row in rows {
                     let value = row.get(0)?;
                     let unit = "Sv/Bq".to_string();
-                    let (f1, compound) = gi_absorption_factor().parse(row.get::<String>(1)?)?;
+                    let f1_col: String = row.get(1)?;
+                    let (f1, compound) = gi_absorption_factor().parse(f1_col.as_str())?;
                     let attr = Some(BiokineticAttr {
                         f1,
-                        compound,
+                        compound: intern(&compound),
                         respiratory_tract_attr: None,
                     });
-                    res.push(DcfValue { value, unit, attr })
+                    let provenance = Some(
+                        DcfProvenance::new(SOURCE, "Ingestion", &column)
+                            .key("Nuclide", nuclide)
+                            .key("f1", f1_col),
+                    );
+                    res.push(DcfValue {
+                        value,
+                        unit,
+                        attr,
+                        provenance,
+                    })
                 }
 
-                Ok(res)
+                Ok(DcfQueryResult::new(SOURCE, res))
             }
-            _ => Err(Error::InvalidAgeGroup(age_group.to_string())),
+            _ => Ok(DcfQueryResult::not_found(SOURCE, NotFoundReason::AgeGroup)),
         }
     }
 }
@@ -66,13 +81,13 @@ impl DcfInhalation for Icrp68 {
         nuclide: Nuclide,
         age_group: AgeGroup,
         organ: Organ,
-    ) -> Result<Vec<DcfValue>, Error> {
+    ) -> Result<DcfQueryResult, Error> {
         match age_group {
             AgeGroup::Worker => {
+                let column = organ_col!(organ);
                 let rows = self.connection.prepare(&format!(
                     "SELECT {}, Type, f1 FROM Inhalation WHERE Nuclide='{}'",
-                    organ.to_col()?,
-                    nuclide
+                    column, nuclide
                 ))?;
 
                 let mut res = vec![];
@@ -80,18 +95,30 @@ impl DcfInhalation for Icrp68 {
                     let value = row.get(0)?;
                     let unit = "Sv/Bq".to_string();
                     let respiratory_tract_attr = Some(RespiratoryTractAttr::ICRP66(row.get(1)?));
-                    let (f1, compound) = gi_absorption_factor().parse(row.get::<String>(2)?)?;
+                    let f1_col: String = row.get(2)?;
+                    let (f1, compound) = gi_absorption_factor().parse(f1_col.as_str())?;
                     let attr = Some(BiokineticAttr {
                         f1,
-                        compound,
+                        compound: intern(&compound),
                         respiratory_tract_attr,
                     });
-                    res.push(DcfValue { value, unit, attr })
+                    let provenance = Some(
+                        DcfProvenance::new(SOURCE, "Inhalation", &column)
+                            .key("Nuclide", nuclide)
+                            .key("Type", row.get::<String>(1)?)
+                            .key("f1", f1_col),
+                    );
+                    res.push(DcfValue {
+                        value,
+                        unit,
+                        attr,
+                        provenance,
+                    })
                 }
 
-                Ok(res)
+                Ok(DcfQueryResult::new(SOURCE, res))
             }
-            _ => Err(Error::InvalidAgeGroup(age_group.to_string())),
+            _ => Ok(DcfQueryResult::not_found(SOURCE, NotFoundReason::AgeGroup)),
         }
     }
 }
@@ -115,26 +142,36 @@ mod test {
             )
             .unwrap();
 
+        let mut values = results.values;
+        let provenance = values[0].provenance.as_ref().unwrap();
+        assert_eq!(provenance.table, "Ingestion");
         assert_eq!(
-            results,
+            provenance.row[0],
+            ("Nuclide".to_string(), "H-3".to_string())
+        );
+        values.iter_mut().for_each(|v| v.provenance = None);
+        assert_eq!(
+            values,
             vec![
                 DcfValue {
                     value: 4.2e-11,
                     unit: "Sv/Bq".to_string(),
                     attr: Some(BiokineticAttr {
                         f1: 1.,
-                        compound: "OBT".to_string(),
+                        compound: "OBT".into(),
                         respiratory_tract_attr: None,
-                    })
+                    }),
+                    provenance: None
                 },
                 DcfValue {
                     value: 1.8e-11,
                     unit: "Sv/Bq".to_string(),
                     attr: Some(BiokineticAttr {
                         f1: 1.,
-                        compound: "".to_string(),
+                        compound: "".into(),
                         respiratory_tract_attr: None,
-                    })
+                    }),
+                    provenance: None
                 }
             ]
         );
@@ -152,52 +189,64 @@ mod test {
             )
             .unwrap();
 
+        let mut values = results.values;
+        let provenance = values[0].provenance.as_ref().unwrap();
+        assert_eq!(provenance.table, "Inhalation");
         assert_eq!(
-            results,
+            provenance.row[0],
+            ("Nuclide".to_string(), "H-3".to_string())
+        );
+        values.iter_mut().for_each(|v| v.provenance = None);
+        assert_eq!(
+            values,
             vec![
                 DcfValue {
                     value: 4.1e-11,
                     unit: "Sv/Bq".to_string(),
                     attr: Some(BiokineticAttr {
                         f1: 1.,
-                        compound: "OBT".to_string(),
+                        compound: "OBT".into(),
                         respiratory_tract_attr: Some(RespiratoryTractAttr::ICRP66(
                             PulmonaryAbsorptionType::Vapor
                         )),
-                    })
+                    }),
+                    provenance: None
                 },
                 DcfValue {
                     value: 1.8e-15,
                     unit: "Sv/Bq".to_string(),
                     attr: Some(BiokineticAttr {
                         f1: 1.,
-                        compound: "HT".to_string(),
+                        compound: "HT".into(),
                         respiratory_tract_attr: Some(RespiratoryTractAttr::ICRP66(
                             PulmonaryAbsorptionType::Vapor
                         )),
-                    })
+                    }),
+                    provenance: None
                 },
                 DcfValue {
                     value: 1.8e-13,
                     unit: "Sv/Bq".to_string(),
                     attr: Some(BiokineticAttr {
                         f1: 1.,
-                        compound: "CH3T".to_string(),
+                        compound: "CH3T".into(),
                         respiratory_tract_attr: Some(RespiratoryTractAttr::ICRP66(
                             PulmonaryAbsorptionType::Vapor
                         )),
-                    })
+                    }),
+                    provenance: None
                 },
                 DcfValue {
                     value: 1.8e-11,
                     unit: "Sv/Bq".to_string(),
                     attr: Some(BiokineticAttr {
                         f1: 1.,
-                        compound: "HTO".to_string(),
+                        compound: "HTO".into(),
                         respiratory_tract_attr: Some(RespiratoryTractAttr::ICRP66(
                             PulmonaryAbsorptionType::Vapor
                         )),
-                    })
+                    }),
+                    provenance: None
                 },
             ]
         );
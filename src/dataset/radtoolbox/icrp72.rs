@@ -5,13 +5,17 @@ use mdbsql::Connection;
 
 use crate::dataset::radtoolbox::utils::AsAgeDepPhantomOrgan;
 use crate::error::Error;
+use crate::intern::intern;
 use crate::primitive::attr::{DcfIngestion, DcfInhalation};
 use crate::primitive::dose_coefficient::{
-    AgeGroup, BiokineticAttr, DcfValue, Organ, RespiratoryTractAttr,
+    AgeGroup, BiokineticAttr, DcfProvenance, DcfQueryResult, DcfValue, NotFoundReason, Organ,
+    RespiratoryTractAttr,
 };
 use crate::primitive::parser::gi_absorption_factor;
 use crate::primitive::Nuclide;
 
+const SOURCE: &str = "ICRP 72";
+
 #[derive(Debug)]
 pub struct Icrp72 {
     connection: Connection,
@@ -31,31 +35,37 @@ impl DcfIngestion for Icrp72 {
         nuclide: Nuclide,
         age_group: AgeGroup,
         organ: Organ,
-    ) -> Result<Vec<DcfValue>, Error> {
+    ) -> Result<DcfQueryResult, Error> {
+        let column = organ_col!(organ);
+        let table = format!("Ingestion {}", age_group);
         let rows = self.connection.prepare(&format!(
-            "SELECT {}, f1 FROM \"Ingestion {}\" WHERE Nuclide='{}'",
-            organ.to_col()?,
-            age_group,
-            nuclide
+            "SELECT {}, f1 FROM \"{}\" WHERE Nuclide='{}'",
+            column, table, nuclide
         ))?;
 
         let mut res = vec![];
         for row in rows {
             let value = row.get(0)?;
             let unit = "Sv/Bq".to_string();
-            let (f1, compound) = gi_absorption_factor().parse(row.get::<String>(1)?)?;
+            let f1_col: String = row.get(1)?;
+            let (f1, compound) = gi_absorption_factor().parse(f1_col.as_str())?;
             res.push(DcfValue {
                 value,
                 unit,
                 attr: Some(BiokineticAttr {
                     f1,
-                    compound,
+                    compound: intern(&compound),
                     respiratory_tract_attr: None,
                 }),
+                provenance: Some(
+                    DcfProvenance::new(SOURCE, &table, &column)
+                        .key("Nuclide", nuclide)
+                        .key("f1", f1_col),
+                ),
             })
         }
 
-        Ok(res)
+        Ok(DcfQueryResult::new(SOURCE, res))
     }
 }
 
@@ -65,18 +75,17 @@ impl DcfInhalation for Icrp72 {
         nuclide: Nuclide,
         age_group: AgeGroup,
         organ: Organ,
-    ) -> Result<Vec<DcfValue>, Error> {
+    ) -> Result<DcfQueryResult, Error> {
         let type_column = match age_group {
             // The column named `Tye` in table `Inhalation 5 yr-old` is a typo, obviously.
             AgeGroup::FiveYear => "Tye",
             _ => "Type",
         };
+        let column = organ_col!(organ);
+        let table = format!("Inhalation {}", age_group);
         let rows = self.connection.prepare(&format!(
-            "SELECT {}, {}, f1 FROM \"Inhalation {}\" WHERE Nuclide='{}'",
-            organ.to_col()?,
-            type_column,
-            age_group,
-            nuclide
+            "SELECT {}, {}, f1 FROM \"{}\" WHERE Nuclide='{}'",
+            column, type_column, table, nuclide
         ))?;
 
         let mut res = vec![];
@@ -84,19 +93,26 @@ impl DcfInhalation for Icrp72 {
             let value = row.get(0)?;
             let unit = "Sv/Bq".to_string();
             let respiratory_tract_attr = Some(RespiratoryTractAttr::ICRP66(row.get(1)?));
-            let (f1, compound) = gi_absorption_factor().parse(row.get::<String>(2)?)?;
+            let f1_col: String = row.get(2)?;
+            let (f1, compound) = gi_absorption_factor().parse(f1_col.as_str())?;
             res.push(DcfValue {
                 value,
                 unit,
                 attr: Some(BiokineticAttr {
                     f1,
-                    compound,
+                    compound: intern(&compound),
                     respiratory_tract_attr,
                 }),
+                provenance: Some(
+                    DcfProvenance::new(SOURCE, &table, &column)
+                        .key("Nuclide", nuclide)
+                        .key(type_column, row.get::<String>(1)?)
+                        .key("f1", f1_col),
+                ),
             })
         }
 
-        Ok(res)
+        Ok(DcfQueryResult::new(SOURCE, res))
     }
 }
 
@@ -119,26 +135,36 @@ mod test {
             )
             .unwrap();
 
+        let mut values = results.values;
+        let provenance = values[0].provenance.as_ref().unwrap();
+        assert_eq!(provenance.table, "Ingestion Adult");
+        assert_eq!(
+            provenance.row[0],
+            ("Nuclide".to_string(), "H-3".to_string())
+        );
+        values.iter_mut().for_each(|v| v.provenance = None);
         assert_eq!(
-            results,
+            values,
             vec![
                 DcfValue {
                     value: 4.2e-11,
                     unit: "Sv/Bq".to_string(),
                     attr: Some(BiokineticAttr {
                         f1: 1.,
-                        compound: "OBT".to_string(),
+                        compound: "OBT".into(),
                         respiratory_tract_attr: None
-                    })
+                    }),
+                    provenance: None
                 },
                 DcfValue {
                     value: 1.8e-11,
                     unit: "Sv/Bq".to_string(),
                     attr: Some(BiokineticAttr {
                         f1: 1.,
-                        compound: "".to_string(),
+                        compound: "".into(),
                         respiratory_tract_attr: None
-                    })
+                    }),
+                    provenance: None
                 }
             ]
         );
@@ -156,85 +182,100 @@ mod test {
             )
             .unwrap();
 
+        let mut values = results.values;
+        let provenance = values[0].provenance.as_ref().unwrap();
+        assert_eq!(provenance.table, "Inhalation Adult");
+        assert_eq!(
+            provenance.row[0],
+            ("Nuclide".to_string(), "H-3".to_string())
+        );
+        values.iter_mut().for_each(|v| v.provenance = None);
         assert_eq!(
-            results,
+            values,
             vec![
                 DcfValue {
                     value: 4.1e-11,
                     unit: "Sv/Bq".to_string(),
                     attr: Some(BiokineticAttr {
                         f1: 1.,
-                        compound: "OBT".to_string(),
+                        compound: "OBT".into(),
                         respiratory_tract_attr: Some(RespiratoryTractAttr::ICRP66(
                             PulmonaryAbsorptionType::Vapor
                         )),
-                    })
+                    }),
+                    provenance: None
                 },
                 DcfValue {
                     value: 1.8e-15,
                     unit: "Sv/Bq".to_string(),
                     attr: Some(BiokineticAttr {
                         f1: 1.,
-                        compound: "HT".to_string(),
+                        compound: "HT".into(),
                         respiratory_tract_attr: Some(RespiratoryTractAttr::ICRP66(
                             PulmonaryAbsorptionType::Vapor
                         )),
-                    })
+                    }),
+                    provenance: None
                 },
                 DcfValue {
                     value: 1.8e-13,
                     unit: "Sv/Bq".to_string(),
                     attr: Some(BiokineticAttr {
                         f1: 1.,
-                        compound: "CH3T".to_string(),
+                        compound: "CH3T".into(),
                         respiratory_tract_attr: Some(RespiratoryTractAttr::ICRP66(
                             PulmonaryAbsorptionType::Vapor
                         )),
-                    })
+                    }),
+                    provenance: None
                 },
                 DcfValue {
                     value: 1.8e-11,
                     unit: "Sv/Bq".to_string(),
                     attr: Some(BiokineticAttr {
                         f1: 1.,
-                        compound: "HTO".to_string(),
+                        compound: "HTO".into(),
                         respiratory_tract_attr: Some(RespiratoryTractAttr::ICRP66(
                             PulmonaryAbsorptionType::Vapor
                         )),
-                    })
+                    }),
+                    provenance: None
                 },
                 DcfValue {
                     value: 6.2e-12,
                     unit: "Sv/Bq".to_string(),
                     attr: Some(BiokineticAttr {
                         f1: 1.,
-                        compound: "".to_string(),
+                        compound: "".into(),
                         respiratory_tract_attr: Some(RespiratoryTractAttr::ICRP66(
                             PulmonaryAbsorptionType::Fast
                         )),
-                    })
+                    }),
+                    provenance: None
                 },
                 DcfValue {
                     value: 4.5e-11,
                     unit: "Sv/Bq".to_string(),
                     attr: Some(BiokineticAttr {
                         f1: 0.1,
-                        compound: "".to_string(),
+                        compound: "".into(),
                         respiratory_tract_attr: Some(RespiratoryTractAttr::ICRP66(
                             PulmonaryAbsorptionType::Moderate
                         )),
-                    })
+                    }),
+                    provenance: None
                 },
                 DcfValue {
                     value: 2.6e-10,
                     unit: "Sv/Bq".to_string(),
                     attr: Some(BiokineticAttr {
                         f1: 0.01,
-                        compound: "".to_string(),
+                        compound: "".into(),
                         respiratory_tract_attr: Some(RespiratoryTractAttr::ICRP66(
                             PulmonaryAbsorptionType::Slow
                         )),
-                    })
+                    }),
+                    provenance: None
                 },
             ]
         );
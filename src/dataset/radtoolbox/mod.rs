@@ -5,6 +5,17 @@ use once_cell::sync::OnceCell;
 
 use crate::error::Error;
 
+/// Column of an organ, or an early return of a not found result of the
+/// `SOURCE` of the calling module
+macro_rules! organ_col {
+    ($organ:expr) => {
+        match $organ.to_col() {
+            Ok(col) => col,
+            Err(_) => return Ok(DcfQueryResult::not_found(SOURCE, NotFoundReason::Organ)),
+        }
+    };
+}
+
 pub mod fgr12;
 pub mod icrp68;
 pub mod icrp72;
@@ -1,26 +1,322 @@
+use std::collections::HashMap;
 use std::fs::File;
-use std::io::{BufRead, BufReader};
-use std::path::Path;
+use std::io::{BufRead, BufReader, Cursor, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
 
+#[cfg(feature = "bundle")]
+use super::bundle::Bundle;
 use crate::error::Error;
 
-pub struct FileReader(BufReader<File>);
+/// Compression of a data file, detected from its magic bytes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    Gzip,
+    Zstd,
+}
+
+impl Compression {
+    /// File name extensions tried when a data file does not exist uncompressed
+    const EXTENSIONS: [(&'static str, Self); 2] = [("gz", Self::Gzip), ("zst", Self::Zstd)];
+
+    pub fn detect(header: &[u8]) -> Option<Self> {
+        if header.starts_with(&[0x1f, 0x8b]) {
+            Some(Self::Gzip)
+        } else if header.starts_with(&[0x28, 0xb5, 0x2f, 0xfd]) {
+            Some(Self::Zstd)
+        } else {
+            None
+        }
+    }
+
+    fn decoder<R: BufRead + 'static>(self, reader: R) -> Result<Box<dyn BufRead>, Error> {
+        match self {
+            #[cfg(feature = "gzip")]
+            Self::Gzip => Ok(Box::new(BufReader::new(
+                flate2::bufread::MultiGzDecoder::new(reader),
+            ))),
+            #[cfg(feature = "zstd")]
+            Self::Zstd => Ok(Box::new(BufReader::new(
+                zstd::stream::read::Decoder::with_buffer(reader)?,
+            ))),
+            #[allow(unreachable_patterns)]
+            _ => {
+                let _ = reader;
+                Err(Error::UnsupportedCompression(format!("{:?}", self)))
+            }
+        }
+    }
+}
+
+/// Path of a data file, falling back to its compressed variants (`<file>.gz`, `<file>.zst`)
+pub fn resolve(path: &Path) -> PathBuf {
+    if !path.exists() {
+        for (ext, _) in Compression::EXTENSIONS {
+            let mut name = path.as_os_str().to_os_string();
+            name.push(".");
+            name.push(ext);
+            let compressed = PathBuf::from(name);
+            if compressed.exists() {
+                return compressed;
+            }
+        }
+    }
+
+    path.to_path_buf()
+}
+
+/// Write a file through a temporary file of the same directory renamed over
+/// it, so that readers never see a partly written file
+pub fn write_atomic(path: &Path, content: &[u8]) -> Result<(), Error> {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    static COUNT: AtomicUsize = AtomicUsize::new(0);
+
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(format!(
+        ".{}.{}.tmp",
+        std::process::id(),
+        COUNT.fetch_add(1, Ordering::Relaxed)
+    ));
+    let tmp = path.with_file_name(name);
+
+    std::fs::write(&tmp, content)
+        .and_then(|_| std::fs::rename(&tmp, path))
+        .map_err(|e| {
+            let _ = std::fs::remove_file(&tmp);
+            e.into()
+        })
+}
+
+/// Decompressed content of the compressed files of a directory, kept for the
+/// life of the source and shared by its clones so that a lookup seeks in
+/// memory instead of decompressing from the start
+#[derive(Clone, Default)]
+pub struct Decompressed(Arc<Mutex<HashMap<PathBuf, Arc<[u8]>>>>);
+
+impl Decompressed {
+    fn reader(&self, path: &Path) -> Result<FileReader, Error> {
+        if let Some(data) = self.0.lock().unwrap().get(path) {
+            return Ok(FileReader::from_memory(data.clone()));
+        }
+
+        let reader = FileReader::new(path)?;
+        if let Source::File(_) = reader.0 {
+            return Ok(reader);
+        }
+        let data = reader.into_bytes()?;
+        self.0
+            .lock()
+            .unwrap()
+            .insert(path.to_path_buf(), data.clone());
+
+        Ok(FileReader::from_memory(data))
+    }
+}
+
+impl std::fmt::Debug for Decompressed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list()
+            .entries(self.0.lock().unwrap().keys())
+            .finish()
+    }
+}
+
+/// Directory or archive the data files of a dataset are read from
+///
+/// Sources are equal when they read the same directory or archive, whatever
+/// they have decompressed so far.
+#[derive(Debug, Clone)]
+pub enum DataSource {
+    Dir(PathBuf, Decompressed),
+    #[cfg(feature = "bundle")]
+    Bundle(Arc<Bundle>),
+}
+
+impl PartialEq for DataSource {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Dir(a, _), Self::Dir(b, _)) => a == b,
+            #[cfg(feature = "bundle")]
+            (Self::Bundle(a), Self::Bundle(b)) => a == b,
+            #[allow(unreachable_patterns)]
+            _ => false,
+        }
+    }
+}
+
+impl DataSource {
+    pub fn dir<P: Into<PathBuf>>(path: P) -> Self {
+        Self::Dir(path.into(), Decompressed::default())
+    }
+
+    /// Path of the directory or archive
+    pub fn path(&self) -> &Path {
+        match self {
+            Self::Dir(path, _) => path,
+            #[cfg(feature = "bundle")]
+            Self::Bundle(bundle) => bundle.path(),
+        }
+    }
+
+    /// Path of a data file, `None` if the file is archived
+    pub fn file_path(&self, name: &str) -> Option<PathBuf> {
+        match self {
+            Self::Dir(path, _) => Some(resolve(&path.join(name))),
+            #[cfg(feature = "bundle")]
+            Self::Bundle(_) => None,
+        }
+    }
+
+    pub fn reader(&self, name: &str) -> Result<FileReader, Error> {
+        match self {
+            Self::Dir(path, decompressed) => decompressed.reader(&path.join(name)),
+            #[cfg(feature = "bundle")]
+            Self::Bundle(bundle) => bundle.reader(name),
+        }
+    }
+}
+
+enum Source {
+    File(BufReader<File>),
+    Memory(Cursor<Arc<[u8]>>),
+    Stream(Box<dyn BufRead>),
+}
+
+/// Line reader of data files, decompressing gzip (feature `gzip`) and zstd (feature `zstd`) files
+pub struct FileReader(Source);
 
 impl FileReader {
     pub fn new(path: &Path) -> Result<Self, Error> {
-        Ok(Self(BufReader::new(File::open(path)?)))
+        Self::from_file(File::open(resolve(path))?)
+    }
+
+    pub fn from_file(file: File) -> Result<Self, Error> {
+        let mut reader = BufReader::new(file);
+
+        match Compression::detect(reader.fill_buf()?) {
+            Some(compression) => Ok(Self(Source::Stream(compression.decoder(reader)?))),
+            None => Ok(Self(Source::File(reader))),
+        }
+    }
+
+    /// Read from decompressed content in memory
+    pub(crate) fn from_memory(data: Arc<[u8]>) -> Self {
+        Self(Source::Memory(Cursor::new(data)))
+    }
+
+    /// Content in memory, decompressed if needed
+    #[cfg_attr(not(feature = "bundle"), allow(dead_code))]
+    pub(crate) fn decompress(data: Arc<[u8]>) -> Result<Arc<[u8]>, Error> {
+        match Compression::detect(&data) {
+            Some(compression) => {
+                Self(Source::Stream(compression.decoder(Cursor::new(data))?)).into_bytes()
+            }
+            None => Ok(data),
+        }
+    }
+
+    fn into_bytes(mut self) -> Result<Arc<[u8]>, Error> {
+        let mut data = vec![];
+        self.inner().read_to_end(&mut data)?;
+        Ok(data.into())
+    }
+
+    fn inner(&mut self) -> &mut dyn BufRead {
+        match &mut self.0 {
+            Source::File(reader) => reader,
+            Source::Memory(reader) => reader,
+            Source::Stream(reader) => reader,
+        }
     }
 
     pub fn skip_lines(mut self, n: usize) -> Result<Self, Error> {
         let mut buf = vec![];
         for _ in 0..n {
-            self.0.read_until(b'\n', &mut buf)?;
+            self.inner().read_until(b'\n', &mut buf)?;
+        }
+        Ok(self)
+    }
+
+    /// Move to `offset` (bytes of the decompressed content) from the start;
+    /// streams are read up to the offset, so this must be called before reading.
+    pub fn seek(mut self, offset: u64) -> Result<Self, Error> {
+        match &mut self.0 {
+            Source::File(reader) => {
+                reader.seek(SeekFrom::Start(offset))?;
+            }
+            Source::Memory(reader) => {
+                reader.seek(SeekFrom::Start(offset))?;
+            }
+            Source::Stream(reader) => {
+                std::io::copy(&mut reader.take(offset), &mut std::io::sink())?;
+            }
         }
         Ok(self)
     }
 
     pub fn read_line(&mut self, buf: &mut String) -> Result<usize, Error> {
         buf.clear();
-        self.0.read_line(buf).map_err(std::convert::Into::into)
+        self.inner()
+            .read_line(buf)
+            .map_err(std::convert::Into::into)
+    }
+}
+
+impl Read for FileReader {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        self.inner().read(buf)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn detect_compression() {
+        assert_eq!(
+            Compression::detect(&[0x1f, 0x8b, 0x08]),
+            Some(Compression::Gzip)
+        );
+        assert_eq!(
+            Compression::detect(&[0x28, 0xb5, 0x2f, 0xfd, 0x00]),
+            Some(Compression::Zstd)
+        );
+        assert_eq!(Compression::detect(b"H-3"), None);
+    }
+
+    #[cfg(feature = "gzip")]
+    #[test]
+    fn gzip_file() {
+        use std::io::Write;
+
+        let dir = crate::fixtures::TempDir::new("gzip_reader");
+        let path = dir.join("data");
+        let mut encoder = flate2::write::GzEncoder::new(
+            File::create(dir.join("data.gz")).unwrap(),
+            flate2::Compression::default(),
+        );
+        encoder.write_all(b"header\nline 1\nline 2\n").unwrap();
+        encoder.finish().unwrap();
+
+        let mut reader = FileReader::new(&path).unwrap().seek(7).unwrap();
+        let mut buf = String::new();
+        reader.read_line(&mut buf).unwrap();
+
+        assert_eq!(buf, "line 1\n");
+
+        // decompressed once by the source
+        let source = DataSource::dir(dir.path());
+        let mut reader = source.reader("data").unwrap().seek(14).unwrap();
+        reader.read_line(&mut buf).unwrap();
+        assert_eq!(buf, "line 2\n");
+        assert!(matches!(
+            source.reader("data").unwrap().0,
+            Source::Memory(_)
+        ));
+        std::fs::remove_file(dir.join("data.gz")).unwrap();
+        let mut reader = source.reader("data").unwrap().seek(7).unwrap();
+        reader.read_line(&mut buf).unwrap();
+        assert_eq!(buf, "line 1\n");
     }
 }
@@ -0,0 +1,136 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::error::Error;
+use crate::primitive::attr::TransferFactor;
+use crate::primitive::{Symbol, TransferPathway};
+
+const TRS472_TRANSFER_FACTORS: &str = include_str!("trs472_transfer_factors.csv");
+
+/// Element transfer factors of the food chain, e.g. from IAEA TRS-472
+///
+/// The tables are read from a CSV file of `element,pathway,value` rows, where
+/// `pathway` is one of `SoilToPlant`, `FeedToMilk` or `FeedToMeat`:
+///
+/// ```text
+/// # element,pathway,value
+/// Cs,FeedToMilk,4.6e-3
+/// Sr,FeedToMilk,1.3e-3
+/// ```
+///
+/// Blank lines and lines starting with `#` are ignored.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TransferFactors {
+    factors: BTreeMap<(Symbol, TransferPathway), f64>,
+}
+
+impl TransferFactors {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feed-to-milk and feed-to-meat coefficients of cattle for Cs, Sr and I
+    /// from IAEA TRS-472
+    ///
+    /// Soil-to-plant factors are not included, they depend on the crop and
+    /// the soil; add those of the site with [`Self::insert`].
+    pub fn trs472() -> Self {
+        TRS472_TRANSFER_FACTORS.parse().unwrap()
+    }
+
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        std::fs::read_to_string(path)?.parse()
+    }
+
+    pub fn insert(&mut self, symbol: Symbol, pathway: TransferPathway, value: f64) {
+        self.factors.insert((symbol, pathway), value);
+    }
+
+    pub fn len(&self) -> usize {
+        self.factors.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.factors.is_empty()
+    }
+}
+
+impl FromStr for TransferFactors {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut factors = Self::new();
+
+        for line in s.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+            if fields.len() != 3 {
+                return Err(Error::Unexpected(anyhow::anyhow!(
+                    "invalid transfer factor record: {}",
+                    line
+                )));
+            }
+            let value = fields[2]
+                .parse()
+                .map_err(|_| Error::InvalidFloat(fields[2].to_string()))?;
+
+            factors.insert(fields[0].parse()?, fields[1].parse()?, value);
+        }
+
+        Ok(factors)
+    }
+}
+
+impl TransferFactor for TransferFactors {
+    fn transfer_factor(
+        &self,
+        symbol: Symbol,
+        pathway: TransferPathway,
+    ) -> Result<Option<f64>, Error> {
+        Ok(self.factors.get(&(symbol, pathway)).copied())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_transfer_factors() {
+        let tf: TransferFactors =
+            "# element,pathway,value\n\nCs,FeedToMilk,4.6e-3\nSr, SoilToPlant, 0.1\n"
+                .parse()
+                .unwrap();
+
+        assert_eq!(tf.len(), 2);
+        assert_eq!(
+            tf.transfer_factor(Symbol::Cs, TransferPathway::FeedToMilk)
+                .unwrap(),
+            Some(4.6e-3)
+        );
+        assert_eq!(
+            tf.transfer_factor(Symbol::Cs, TransferPathway::FeedToMeat)
+                .unwrap(),
+            None
+        );
+        assert!("Cs,FeedToEggs,1".parse::<TransferFactors>().is_err());
+        assert!("Cs,FeedToMilk".parse::<TransferFactors>().is_err());
+
+        let tf = TransferFactors::trs472();
+        assert_eq!(tf.len(), 6);
+        assert_eq!(
+            tf.transfer_factor(Symbol::Sr, TransferPathway::FeedToMilk)
+                .unwrap(),
+            Some(1.3e-3)
+        );
+        assert_eq!(
+            tf.transfer_factor(Symbol::Cs, TransferPathway::SoilToPlant)
+                .unwrap(),
+            None
+        );
+    }
+}
@@ -0,0 +1,227 @@
+use std::collections::BTreeMap;
+
+use super::{BatemanDecaySolver, Inventory};
+use crate::primitive::attr::{DecayConstant, NuclideProgeny};
+use crate::primitive::Nuclide;
+
+/// Iterations of the golden-section refinement of the age
+const REFINEMENT: usize = 40;
+
+/// Candidate source of a measured inventory
+#[derive(Debug, Clone, PartialEq)]
+pub struct SourceMixture {
+    pub name: String,
+    /// Activities at age zero, in any scale
+    pub composition: Inventory,
+}
+
+impl SourceMixture {
+    pub fn new(name: &str, composition: Inventory) -> Self {
+        Self {
+            name: name.to_string(),
+            composition,
+        }
+    }
+}
+
+/// Best fit of a [`SourceMixture`] to a measured inventory
+#[derive(Debug, Clone, PartialEq)]
+pub struct MixtureFit {
+    pub name: String,
+    /// Decay time of the mixture (s)
+    pub age: f64,
+    /// Factor from the composition to the measured activities
+    pub scale: f64,
+    /// Root mean square of the relative deviations over the measured nuclides
+    pub residual: f64,
+}
+
+/// Scale and residual of the least-squares fit of `candidate` to `measured`,
+/// deviations taken relative to the measured activities
+fn fit(measured: &Inventory, candidate: &Inventory) -> (f64, f64) {
+    let ratios: Vec<f64> = measured
+        .iter()
+        .filter(|(_, &m)| m > 0.)
+        .map(|(n, &m)| candidate.get(n).copied().unwrap_or(0.) / m)
+        .collect();
+    if ratios.is_empty() {
+        return (0., f64::INFINITY);
+    }
+
+    let sum: f64 = ratios.iter().sum();
+    let sum_sq: f64 = ratios.iter().map(|r| r * r).sum();
+    let scale = if sum_sq > 0. { sum / sum_sq } else { 0. };
+    let residual: f64 = ratios.iter().map(|r| (scale * r - 1.).powi(2)).sum();
+
+    (scale, (residual / ratios.len() as f64).sqrt())
+}
+
+impl<D> BatemanDecaySolver<D>
+where
+    D: NuclideProgeny + DecayConstant + ?Sized,
+{
+    /// Fit each mixture of the library to a measured inventory, by increasing residual
+    ///
+    /// The age of each mixture is searched over the sorted grid `ages` (s),
+    /// then refined between the neighbours of the best grid point. Only the
+    /// nuclides of the measured inventory are compared; a measured nuclide
+    /// absent from a decayed mixture counts as a full deviation.
+    pub fn match_mixture(
+        &self,
+        measured: &Inventory,
+        library: &[SourceMixture],
+        ages: &[f64],
+    ) -> Vec<MixtureFit> {
+        let mut fits: Vec<MixtureFit> = library
+            .iter()
+            .filter(|_| !ages.is_empty())
+            .map(|mixture| {
+                let evaluate = |age: f64| fit(measured, &self.decay(&mixture.composition, age));
+
+                let best = (0..ages.len())
+                    .min_by(|&a, &b| evaluate(ages[a]).1.total_cmp(&evaluate(ages[b]).1))
+                    .unwrap();
+                let mut lo = ages[best.saturating_sub(1)];
+                let mut hi = ages[(best + 1).min(ages.len() - 1)];
+
+                let ratio = (5f64.sqrt() - 1.) / 2.;
+                for _ in 0..REFINEMENT {
+                    let a = hi - ratio * (hi - lo);
+                    let b = lo + ratio * (hi - lo);
+                    if evaluate(a).1 < evaluate(b).1 {
+                        hi = b;
+                    } else {
+                        lo = a;
+                    }
+                }
+
+                let age = [ages[best], (lo + hi) / 2.]
+                    .into_iter()
+                    .min_by(|&a, &b| evaluate(a).1.total_cmp(&evaluate(b).1))
+                    .unwrap();
+                let (scale, residual) = evaluate(age);
+
+                MixtureFit {
+                    name: mixture.name.clone(),
+                    age,
+                    scale,
+                    residual,
+                }
+            })
+            .collect();
+        fits.sort_by(|a, b| a.residual.total_cmp(&b.residual));
+
+        fits
+    }
+
+    /// Activities (Bq) after `decay_time` (s), split by the nuclide of the
+    /// initial inventory they descend from, as descendant to parent to activity
+    ///
+    /// The activities of each descendant add up to those of [`Self::decay`].
+    pub fn decay_by_parent(
+        &self,
+        inventory: &Inventory,
+        decay_time: f64,
+    ) -> BTreeMap<Nuclide, BTreeMap<Nuclide, f64>> {
+        let mut res = BTreeMap::<Nuclide, BTreeMap<Nuclide, f64>>::new();
+        for (&parent, &activity) in inventory.iter() {
+            for (nuclide, ratio) in self.bateman_eq(parent, decay_time).unwrap_or_default() {
+                *res.entry(nuclide).or_default().entry(parent).or_default() += activity * ratio;
+            }
+        }
+
+        res
+    }
+
+    /// Fractions of the activity of `nuclide` after `decay_time` (s) produced
+    /// by each nuclide of the initial inventory, empty if none produces it
+    pub fn parent_fractions(
+        &self,
+        inventory: &Inventory,
+        decay_time: f64,
+        nuclide: Nuclide,
+    ) -> BTreeMap<Nuclide, f64> {
+        let mut by_parent = self
+            .decay_by_parent(inventory, decay_time)
+            .remove(&nuclide)
+            .unwrap_or_default();
+        let total: f64 = by_parent.values().sum();
+        if total > 0. {
+            by_parent.values_mut().for_each(|a| *a /= total);
+        } else {
+            by_parent.clear();
+        }
+
+        by_parent
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::fixtures::FixtureDecayData;
+
+    const DAY: f64 = 86_400.;
+
+    fn inventory(activities: &[(&str, f64)]) -> Inventory {
+        let mut inv = Inventory::new();
+        for &(nuclide, activity) in activities {
+            inv.add(nuclide.parse::<Nuclide>().unwrap(), activity);
+        }
+        inv
+    }
+
+    #[test]
+    fn match_mixture() {
+        let solver = BatemanDecaySolver::new(Arc::new(FixtureDecayData::new()));
+        let library = [
+            SourceMixture::new("medical", inventory(&[("Mo-99", 10.), ("I-131", 1.)])),
+            SourceMixture::new(
+                "fission",
+                inventory(&[("I-131", 5.), ("Cs-137", 1.), ("Sr-90", 1.)]),
+            ),
+            SourceMixture::new("radium", inventory(&[("Ra-226", 1.)])),
+        ];
+
+        let source = solver.decay(&library[1].composition, 20. * DAY);
+        let measured = inventory(&[
+            ("I-131", 3. * source[&"I-131".parse().unwrap()]),
+            ("Cs-137", 3. * source[&"Cs-137".parse().unwrap()]),
+        ]);
+
+        let ages = [0., DAY, 10. * DAY, 100. * DAY, 1000. * DAY];
+        let fits = solver.match_mixture(&measured, &library, &ages);
+        assert_eq!(fits[0].name, "fission");
+        assert!((fits[0].age / DAY - 20.).abs() < 0.01);
+        assert!((fits[0].scale - 3.).abs() < 1e-6);
+        assert!(fits[0].residual < 1e-6);
+        assert_eq!(fits[2].name, "radium");
+    }
+
+    #[test]
+    fn parent_attribution() {
+        let solver = BatemanDecaySolver::new(Arc::new(FixtureDecayData::new()));
+        let (mo99, tc99m): (Nuclide, Nuclide) =
+            ("Mo-99".parse().unwrap(), "Tc-99m".parse().unwrap());
+        let inv = inventory(&[("Mo-99", 1.), ("Tc-99m", 100.)]);
+
+        let by_parent = solver.decay_by_parent(&inv, DAY);
+        let total = solver.decay(&inv, DAY);
+        for (nuclide, parents) in by_parent.iter() {
+            let sum: f64 = parents.values().sum();
+            assert!((sum - total[nuclide]).abs() < 1e-12 * total[nuclide]);
+        }
+        assert_eq!(by_parent[&mo99].len(), 1);
+
+        let early = solver.parent_fractions(&inv, 60., tc99m);
+        let late = solver.parent_fractions(&inv, 5. * DAY, tc99m);
+        assert!(early[&tc99m] > 0.99);
+        assert!(late[&mo99] > 0.99);
+        assert!((late.values().sum::<f64>() - 1.).abs() < 1e-12);
+        assert!(solver
+            .parent_fractions(&inv, DAY, "Ra-226".parse().unwrap())
+            .is_empty());
+    }
+}
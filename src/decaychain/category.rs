@@ -0,0 +1,155 @@
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::str::FromStr;
+
+use crate::error::Error;
+use crate::primitive::Nuclide;
+
+const NUCLIDE_CATEGORIES: &str = include_str!("nuclide_categories.csv");
+
+/// Category of a nuclide for regulatory reports
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum NuclideCategory {
+    /// Naturally occurring radioactive material
+    Norm,
+    FissionProduct,
+    ActivationProduct,
+    /// Elements from actinium (Z = 89) to lawrencium (Z = 103)
+    Actinide,
+    Other,
+}
+
+impl Display for NuclideCategory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::Norm => "norm",
+            Self::FissionProduct => "fission_product",
+            Self::ActivationProduct => "activation_product",
+            Self::Actinide => "actinide",
+            Self::Other => "other",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for NuclideCategory {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "norm" => Ok(Self::Norm),
+            "fission_product" => Ok(Self::FissionProduct),
+            "activation_product" => Ok(Self::ActivationProduct),
+            "actinide" => Ok(Self::Actinide),
+            "other" => Ok(Self::Other),
+            _ => Err(Error::Unexpected(anyhow::anyhow!(
+                "invalid nuclide category: {}",
+                s
+            ))),
+        }
+    }
+}
+
+/// Category of each nuclide, from a list with the atomic number as fallback
+///
+/// The lists are read from a CSV file of `nuclide,category` rows, with the
+/// categories named as displayed by [`NuclideCategory`]:
+///
+/// ```text
+/// # nuclide,category
+/// Ra-226,norm
+/// Cs-137,fission_product
+/// ```
+///
+/// Blank lines and lines starting with `#` are ignored. Listed nuclides take
+/// their listed category, so that e.g. U-238 may count as NORM; others are
+/// actinides by their atomic number or [`NuclideCategory::Other`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct NuclideCategories {
+    listed: BTreeMap<Nuclide, NuclideCategory>,
+}
+
+impl NuclideCategories {
+    /// Lists bundled with the crate: the members of the natural decay series
+    /// and K-40 as NORM, and common fission and activation products
+    pub fn bundled() -> Self {
+        NUCLIDE_CATEGORIES.parse().unwrap()
+    }
+
+    /// Set the category of a nuclide, replacing any listed before
+    pub fn insert(&mut self, nuclide: Nuclide, category: NuclideCategory) {
+        self.listed.insert(nuclide, category);
+    }
+
+    pub fn listed(&self) -> &BTreeMap<Nuclide, NuclideCategory> {
+        &self.listed
+    }
+
+    pub fn category(&self, nuclide: Nuclide) -> NuclideCategory {
+        if let Some(&category) = self.listed.get(&nuclide) {
+            return category;
+        }
+
+        match nuclide {
+            Nuclide::FissionProducts => NuclideCategory::FissionProduct,
+            _ if nuclide.z().is_some_and(|z| (89..=103).contains(&z)) => NuclideCategory::Actinide,
+            _ => NuclideCategory::Other,
+        }
+    }
+}
+
+impl FromStr for NuclideCategories {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut categories = Self::default();
+
+        for line in s.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            match line.split(',').map(str::trim).collect::<Vec<_>>()[..] {
+                [nuclide, category] => categories.insert(nuclide.parse()?, category.parse()?),
+                _ => {
+                    return Err(Error::Unexpected(anyhow::anyhow!(
+                        "invalid nuclide category record: {}",
+                        line
+                    )))
+                }
+            }
+        }
+
+        Ok(categories)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn categories() {
+        let categories = NuclideCategories::bundled();
+        let category = |s: &str| categories.category(s.parse().unwrap());
+
+        assert_eq!(category("Ra-226"), NuclideCategory::Norm);
+        assert_eq!(category("U-238"), NuclideCategory::Norm);
+        assert_eq!(category("Pu-239"), NuclideCategory::Actinide);
+        assert_eq!(category("Cs-137"), NuclideCategory::FissionProduct);
+        assert_eq!(category("Co-60"), NuclideCategory::ActivationProduct);
+        assert_eq!(category("Tl-201"), NuclideCategory::Other);
+        assert_eq!(
+            categories.category(Nuclide::FissionProducts),
+            NuclideCategory::FissionProduct
+        );
+
+        let custom: NuclideCategories = "# nuclide,category\nPu-239, other\n".parse().unwrap();
+        assert_eq!(
+            custom.category("Pu-239".parse().unwrap()),
+            NuclideCategory::Other
+        );
+        assert!("Cs-137".parse::<NuclideCategories>().is_err());
+        assert!("Cs-137,waste".parse::<NuclideCategories>().is_err());
+    }
+}
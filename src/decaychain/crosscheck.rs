@@ -0,0 +1,188 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::{Deserialize, Serialize};
+
+use super::{BatemanDecaySolver, Inventory};
+use crate::error::Error;
+use crate::primitive::attr::{DecayConstant, NuclideProgeny};
+use crate::primitive::Nuclide;
+
+/// Decay result computed by another code (e.g. radioactivedecay or PyNE)
+///
+/// Reference files are JSON arrays of cases:
+///
+/// ```json
+/// {
+///   "initial": { "Ra-226": 1.0 },
+///   "time": 2592000.0,
+///   "activities": { "Ra-226": 0.99996, "Rn-222": 0.99562 }
+/// }
+/// ```
+///
+/// Activities are in Bq and `time` in seconds.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReferenceCase {
+    pub initial: BTreeMap<Nuclide, f64>,
+    pub time: f64,
+    pub activities: BTreeMap<Nuclide, f64>,
+}
+
+impl ReferenceCase {
+    /// Load a JSON array of reference cases
+    pub fn from_json(s: &str) -> Result<Vec<Self>, Error> {
+        Ok(serde_json::from_str(s)?)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Deviation {
+    /// Index of the reference case
+    pub case: usize,
+    pub time: f64,
+    pub nuclide: Nuclide,
+    pub reference: f64,
+    pub value: f64,
+    /// |value - reference| / reference, `None` if the reference is zero
+    pub relative: Option<f64>,
+}
+
+impl Deviation {
+    // Deviations from a zero reference rank above all others
+    fn rank(&self) -> f64 {
+        self.relative.unwrap_or(f64::INFINITY)
+    }
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct CrossCheckReport {
+    pub deviations: Vec<Deviation>,
+}
+
+impl CrossCheckReport {
+    /// Largest relative deviation, nuclides missing from the reference first
+    pub fn max(&self) -> Option<&Deviation> {
+        self.deviations
+            .iter()
+            .max_by(|a, b| a.rank().total_cmp(&b.rank()))
+    }
+
+    /// Largest relative deviation of each nuclide
+    pub fn max_by_nuclide(&self) -> BTreeMap<Nuclide, &Deviation> {
+        let mut res: BTreeMap<Nuclide, &Deviation> = BTreeMap::new();
+
+        for dev in &self.deviations {
+            let max = res.entry(dev.nuclide).or_insert(dev);
+            if dev.rank() > max.rank() {
+                *max = dev;
+            }
+        }
+
+        res
+    }
+}
+
+impl std::fmt::Display for CrossCheckReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "{:<10}{:>14}{:>14}{:>14}{:>14}",
+            "nuclide", "time (s)", "reference", "value", "rel. dev."
+        )?;
+        for (nuclide, dev) in self.max_by_nuclide() {
+            writeln!(
+                f,
+                "{:<10}{:>14.6e}{:>14.6e}{:>14.6e}{:>14}",
+                nuclide.to_string(),
+                dev.time,
+                dev.reference,
+                dev.value,
+                dev.relative
+                    .map_or("-".to_string(), |r| format!("{:.3e}", r))
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Compare decay results with reference cases; nuclides whose activities are
+/// below `floor` (Bq) in both results are not compared.
+pub fn cross_check<D>(
+    solver: &BatemanDecaySolver<D>,
+    cases: &[ReferenceCase],
+    floor: f64,
+) -> CrossCheckReport
+where
+    D: NuclideProgeny + DecayConstant,
+{
+    let mut report = CrossCheckReport::default();
+
+    for (i, case) in cases.iter().enumerate() {
+        let mut inventory = Inventory::new();
+        for (&nuclide, &activity) in &case.initial {
+            inventory.add(nuclide, activity);
+        }
+        let result = solver.decay(&inventory, case.time);
+
+        let nuclides: BTreeSet<Nuclide> = result
+            .keys()
+            .chain(case.activities.keys())
+            .copied()
+            .collect();
+        for nuclide in nuclides {
+            let reference = case.activities.get(&nuclide).copied().unwrap_or(0.);
+            let value = result.get(&nuclide).copied().unwrap_or(0.);
+            if reference.abs() < floor && value.abs() < floor {
+                continue;
+            }
+
+            report.deviations.push(Deviation {
+                case: i,
+                time: case.time,
+                nuclide,
+                reference,
+                value,
+                relative: (reference != 0.).then(|| (value - reference).abs() / reference.abs()),
+            });
+        }
+    }
+
+    report
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::fixtures::FixtureDecayData;
+
+    #[test]
+    fn cross_check_sr90() {
+        let solver = BatemanDecaySolver::new(Arc::new(FixtureDecayData::new()));
+        let json = r#"[
+            {
+                "initial": { "Sr-90": 1.0 },
+                "time": 3600.0,
+                "activities": { "Sr-90": 1.0, "Y-90": 0.0107, "Cs-137": 1e-30 }
+            }
+        ]"#;
+        let cases = ReferenceCase::from_json(json).unwrap();
+        let report = cross_check(&solver, &cases, 1e-20);
+
+        assert_eq!(report.deviations.len(), 2);
+        let max = report.max().unwrap();
+        assert_eq!(max.nuclide, "Y-90".parse().unwrap());
+        assert!(max.relative.unwrap() < 1e-2);
+        assert!(report.to_string().contains("Y-90"));
+
+        // Y-90 grows in from a zero reference
+        let cases = ReferenceCase::from_json(
+            r#"[{ "initial": { "Sr-90": 1.0 }, "time": 3600.0, "activities": { "Sr-90": 1.0 } }]"#,
+        )
+        .unwrap();
+        let report = cross_check(&solver, &cases, 1e-20);
+        let max = report.max().unwrap();
+        assert_eq!((max.nuclide, max.relative), ("Y-90".parse().unwrap(), None));
+    }
+}
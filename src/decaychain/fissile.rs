@@ -0,0 +1,193 @@
+use serde::Serialize;
+
+use super::Inventory;
+use crate::error::Error;
+use crate::primitive::attr::{NuclideAtomicMass, NuclideHalfLife};
+use crate::primitive::Nuclide;
+
+/// Mass (g) and activity (Bq) above which a group of fissile nuclides is flagged
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FissileLimit {
+    pub name: String,
+    pub nuclides: Vec<Nuclide>,
+    /// (g)
+    pub mass: Option<f64>,
+    /// (Bq)
+    pub activity: Option<f64>,
+}
+
+impl FissileLimit {
+    fn mass(name: &str, nuclides: &[&str], mass: f64) -> Self {
+        Self {
+            name: name.to_string(),
+            nuclides: nuclides.iter().map(|n| n.parse().unwrap()).collect(),
+            mass: Some(mass),
+            activity: None,
+        }
+    }
+}
+
+/// Advisory limits on the fissile content of an inventory
+///
+/// The default limits are the quantities of special nuclear material of
+/// 10 CFR 150.11 (350 g of U-235, 200 g of U-233, 200 g of plutonium), with
+/// their sum of fractions. They are a heads-up from the same data, not a
+/// criticality safety evaluation: moderation, geometry and reflection are
+/// not considered.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FissileLimits {
+    pub limits: Vec<FissileLimit>,
+    /// Flag the sum of the mass fractions of all limits above one
+    pub sum_of_fractions: bool,
+}
+
+impl Default for FissileLimits {
+    fn default() -> Self {
+        Self {
+            limits: vec![
+                FissileLimit::mass("U-235", &["U-235"], 350.),
+                FissileLimit::mass("U-233", &["U-233"], 200.),
+                FissileLimit::mass(
+                    "Pu",
+                    &["Pu-238", "Pu-239", "Pu-240", "Pu-241", "Pu-242", "Pu-244"],
+                    200.,
+                ),
+            ],
+            sum_of_fractions: true,
+        }
+    }
+}
+
+/// Fissile content above a limit of [`FissileLimits`]
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub enum FissileWarning {
+    /// (g)
+    Mass {
+        name: String,
+        mass: f64,
+        limit: f64,
+    },
+    /// (Bq)
+    Activity {
+        name: String,
+        activity: f64,
+        limit: f64,
+    },
+    SumOfFractions(f64),
+}
+
+impl Inventory {
+    /// Fissile nuclides above the limits, with masses of [`Inventory::masses`]
+    pub fn fissile_warnings<D: NuclideHalfLife + NuclideAtomicMass + ?Sized>(
+        &self,
+        data: &D,
+        limits: &FissileLimits,
+    ) -> Result<Vec<FissileWarning>, Error> {
+        let mut fissile = Inventory::new();
+        for (&nuclide, &activity) in self.iter() {
+            if limits.limits.iter().any(|l| l.nuclides.contains(&nuclide)) {
+                fissile.add(nuclide, activity);
+            }
+        }
+        let masses = fissile.masses(data, None)?;
+
+        let mut warnings = vec![];
+        let mut fractions = 0.;
+        for limit in &limits.limits {
+            let mass: f64 = limit
+                .nuclides
+                .iter()
+                .filter_map(|n| Some(masses.get(n)?.mass))
+                .sum();
+            let activity: f64 = limit.nuclides.iter().filter_map(|n| fissile.get(n)).sum();
+
+            if let Some(max) = limit.mass {
+                fractions += mass / max;
+                if mass > max {
+                    warnings.push(FissileWarning::Mass {
+                        name: limit.name.clone(),
+                        mass,
+                        limit: max,
+                    });
+                }
+            }
+            if let Some(max) = limit.activity.filter(|&max| activity > max) {
+                warnings.push(FissileWarning::Activity {
+                    name: limit.name.clone(),
+                    activity,
+                    limit: max,
+                });
+            }
+        }
+
+        if limits.sum_of_fractions && fractions > 1. {
+            warnings.push(FissileWarning::SumOfFractions(fractions));
+        }
+
+        Ok(warnings)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::primitive::{HalfLife, TimeUnit};
+
+    struct Data;
+
+    impl NuclideHalfLife for Data {
+        fn half_life(&self, nuclide: Nuclide) -> Result<HalfLife, Error> {
+            let value = match nuclide.to_string().as_str() {
+                "U-235" => 7.04e8,
+                "Pu-239" => 24_110.,
+                "Cs-137" => 30.08,
+                _ => return Err(Error::InvalidNuclide(nuclide.to_string())),
+            };
+            Ok(HalfLife {
+                value,
+                unit: TimeUnit::Year,
+            })
+        }
+    }
+
+    impl NuclideAtomicMass for Data {
+        // the mass number, close enough for the test
+        fn nuclide_atomic_mass(&self, nuclide: Nuclide) -> Result<f64, Error> {
+            Ok(nuclide.a().unwrap() as f64)
+        }
+    }
+
+    #[test]
+    fn fissile_warnings() {
+        let (u235, pu239) = ("U-235".parse().unwrap(), "Pu-239".parse().unwrap());
+        let mut inv = Inventory::new();
+        inv.add_mass(&Data, None, u235, 300.).unwrap();
+        inv.add_mass(&Data, None, pu239, 50.).unwrap();
+        inv.add("Cs-137".parse().unwrap(), 1e12);
+
+        let limits = FissileLimits::default();
+        let warnings = inv.fissile_warnings(&Data, &limits).unwrap();
+        // 300/350 + 50/200
+        assert_eq!(warnings.len(), 1);
+        let FissileWarning::SumOfFractions(sum) = warnings[0] else {
+            panic!("{:?}", warnings);
+        };
+        assert!((sum - (300. / 350. + 0.25)).abs() < 1e-9);
+
+        inv.add_mass(&Data, None, pu239, 200.).unwrap();
+        let warnings = inv.fissile_warnings(&Data, &limits).unwrap();
+        assert!(matches!(
+            &warnings[0],
+            FissileWarning::Mass { name, mass, .. } if name == "Pu" && (mass - 250.).abs() < 1e-9
+        ));
+
+        let mut limits = FissileLimits {
+            sum_of_fractions: false,
+            ..Default::default()
+        };
+        limits.limits[0].activity = Some(1e3);
+        let warnings = inv.fissile_warnings(&Data, &limits).unwrap();
+        assert_eq!(warnings.len(), 2);
+        assert!(matches!(warnings[0], FissileWarning::Activity { .. }));
+    }
+}
@@ -1,16 +1,36 @@
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::ops::Deref;
 use std::sync::Arc;
 
-use float_pretty_print::PrettyPrintFloat;
+use petgraph::algo::toposort;
+use petgraph::dot::{Config, Dot};
+use petgraph::visit::EdgeRef;
 use petgraph::{graph::NodeIndex, Graph};
 
 use crate::primitive::attr::{NuclideHalfLife, NuclideProgeny};
-use crate::primitive::{DecayModeSet, HalfLife, Nuclide};
+use crate::primitive::{DecayModeSet, HalfLife, Nuclide, NumberFormat, Progeny};
 
 #[derive(Clone, Copy)]
 pub struct ChainNode {
     nuclide: Nuclide,
     half_life: Option<HalfLife>,
+    truncated: bool,
+}
+
+impl ChainNode {
+    pub fn nuclide(&self) -> Nuclide {
+        self.nuclide
+    }
+
+    pub fn half_life(&self) -> Option<HalfLife> {
+        self.half_life
+    }
+
+    /// Whether this is the daughter of a dropped branch, kept as a terminal
+    /// node without its progeny, see [`DecayChainBuilder::lump_truncated`]
+    pub fn is_truncated(&self) -> bool {
+        self.truncated
+    }
 }
 
 impl std::fmt::Display for ChainNode {
@@ -23,7 +43,11 @@ impl std::fmt::Display for ChainNode {
                 Some(t) => t.to_string(),
                 None => "".to_string(),
             }
-        )
+        )?;
+        if self.truncated {
+            write!(f, "\n(truncated)")?;
+        }
+        Ok(())
     }
 }
 
@@ -31,96 +55,518 @@ impl std::fmt::Display for ChainNode {
 pub struct ChainEdge {
     branch_rate: f64,
     decay_mode: DecayModeSet,
+    partial_decay_constant: Option<f64>,
+}
+
+impl ChainEdge {
+    pub fn branch_rate(&self) -> f64 {
+        self.branch_rate
+    }
+
+    pub fn decay_mode(&self) -> DecayModeSet {
+        self.decay_mode
+    }
+
+    /// Partial decay constant λ·BR of the parent toward this branch (s^-1),
+    /// `None` if the half-life of the parent is unknown
+    pub fn partial_decay_constant(&self) -> Option<f64> {
+        self.partial_decay_constant
+    }
+
+    /// Partial mean lifetime 1 / (λ·BR) of the parent toward this branch (s)
+    pub fn partial_mean_life(&self) -> Option<f64> {
+        self.partial_decay_constant.map(f64::recip)
+    }
+
+    /// Branch ratio and decay modes, the ratio written in `number`
+    fn label(&self, number: NumberFormat) -> String {
+        let mut s = number.format(self.branch_rate) + "\n";
+        for (i, mode) in self.decay_mode.0.into_iter().enumerate() {
+            if i > 0 {
+                s += " or ";
+            }
+            s += &mode.to_string();
+        }
+        s
+    }
 }
 
 impl std::fmt::Display for ChainEdge {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        writeln!(f, "{}", PrettyPrintFloat(self.branch_rate))?;
-        for (i, mode) in self.decay_mode.0.into_iter().enumerate() {
-            if i == 0 {
-                write!(f, "{}", mode)?;
-            } else {
-                write!(f, " or {}", mode)?;
+        write!(f, "{}", self.label(NumberFormat::DEFAULT))
+    }
+}
+
+/// Branches of a nuclide dropped when building a chain
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Truncation {
+    /// Sum of the branch ratios dropped
+    pub dropped: f64,
+    /// Factor applied to the remaining branch ratios, 1 if not renormalized
+    pub scale: f64,
+}
+
+/// Shape of a decay chain, see [`DecayChain::stats`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ChainStats {
+    pub nodes: usize,
+    pub edges: usize,
+    /// Number of decays along the longest path from the root
+    pub max_depth: usize,
+    /// Longest-lived progeny of the root, which sets the pace of ingrowth
+    pub bottleneck: Option<(Nuclide, HalfLife)>,
+    /// Number of nuclides decaying through more than one branch
+    pub branches: usize,
+    /// Whether any branch ends in spontaneous fission
+    pub spontaneous_fission: bool,
+}
+
+/// Decay chain graph with the branches dropped and the nuclides collapsed
+/// while building it
+///
+/// Derefs to the underlying [`petgraph::Graph`] for reading; a chain converts
+/// from a graph with `From` and back with [`DecayChain::into_graph`].
+#[derive(Clone, Default)]
+pub struct DecayChain {
+    graph: Graph<ChainNode, ChainEdge>,
+    truncations: BTreeMap<Nuclide, Truncation>,
+    collapsed: BTreeMap<Nuclide, Vec<(Nuclide, f64)>>,
+}
+
+impl Deref for DecayChain {
+    type Target = Graph<ChainNode, ChainEdge>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.graph
+    }
+}
+
+impl From<Graph<ChainNode, ChainEdge>> for DecayChain {
+    fn from(graph: Graph<ChainNode, ChainEdge>) -> Self {
+        Self {
+            graph,
+            ..Default::default()
+        }
+    }
+}
+
+impl From<DecayChain> for Graph<ChainNode, ChainEdge> {
+    fn from(chain: DecayChain) -> Self {
+        chain.graph
+    }
+}
+
+impl DecayChain {
+    pub fn into_graph(self) -> Graph<ChainNode, ChainEdge> {
+        self.graph
+    }
+
+    /// Nuclides whose branches were dropped by the builder options
+    pub fn truncations(&self) -> &BTreeMap<Nuclide, Truncation> {
+        &self.truncations
+    }
+
+    /// Short-lived nuclides folded into each remaining nuclide by
+    /// [`DecayChain::collapse_short_lived`], with the fraction of its decays
+    /// passing through them
+    ///
+    /// In secular equilibrium the activity of a folded nuclide is this
+    /// fraction of the activity of the nuclide it is listed under.
+    pub fn collapsed(&self) -> &BTreeMap<Nuclide, Vec<(Nuclide, f64)>> {
+        &self.collapsed
+    }
+
+    /// Chain without the progeny whose half-life is below `half_life` (s)
+    ///
+    /// Each path through removed nuclides becomes a direct edge with the
+    /// product of the branch ratios and the decay modes along it; parallel
+    /// paths between the same nuclides add up. The root, stable nuclides and
+    /// fission products are always kept.
+    pub fn collapse_short_lived(&self, half_life: f64) -> DecayChain {
+        let graph = &self.graph;
+        let keep = |i: NodeIndex| {
+            i.index() == 0 || graph[i].half_life.is_none_or(|t| t.as_sec() >= half_life)
+        };
+
+        let mut simplified = Graph::new();
+        let mut index = HashMap::new();
+        for i in graph.node_indices().filter(|&i| keep(i)) {
+            index.insert(i, simplified.add_node(graph[i]));
+        }
+
+        let mut collapsed = BTreeMap::new();
+        for (&parent, &p_node) in index.iter().collect::<BTreeMap<_, _>>() {
+            let lambda = graph[parent].half_life.map(|t| t.as_lambda());
+            let mut folded_edges = BTreeMap::<NodeIndex, (f64, DecayModeSet)>::new();
+            let mut folded = BTreeMap::<Nuclide, f64>::new();
+
+            let mut stack = vec![(parent, 1., DecayModeSet::default())];
+            while let Some((node, ratio, modes)) = stack.pop() {
+                for edge in graph.edges(node) {
+                    let ratio = ratio * edge.weight().branch_rate;
+                    let modes = DecayModeSet(modes.0 | edge.weight().decay_mode.0);
+                    let daughter = edge.target();
+
+                    if keep(daughter) {
+                        let entry = folded_edges
+                            .entry(index[&daughter])
+                            .or_insert((0., DecayModeSet::default()));
+                        entry.0 += ratio;
+                        entry.1 = DecayModeSet(entry.1 .0 | modes.0);
+                    } else {
+                        *folded.entry(graph[daughter].nuclide).or_default() += ratio;
+                        stack.push((daughter, ratio, modes));
+                    }
+                }
+            }
+
+            for (d_node, (branch_rate, decay_mode)) in folded_edges {
+                let weight = ChainEdge {
+                    branch_rate,
+                    decay_mode,
+                    partial_decay_constant: lambda.map(|lambda| lambda * branch_rate),
+                };
+                simplified.add_edge(p_node, d_node, weight);
             }
+            if !folded.is_empty() {
+                collapsed.insert(graph[parent].nuclide, folded.into_iter().collect());
+            }
+        }
+
+        DecayChain {
+            graph: simplified,
+            truncations: self.truncations.clone(),
+            collapsed,
         }
-        write!(f, "")
+    }
+
+    /// Number of decays along the longest path from the root to each node
+    pub(super) fn depths(&self) -> Vec<usize> {
+        let graph = &self.graph;
+
+        // decay chains are acyclic, so a topological order visits parents first
+        let mut depth = vec![0; graph.node_count()];
+        if let Ok(order) = toposort(graph, None) {
+            for node in order {
+                for daughter in graph.neighbors(node) {
+                    depth[daughter.index()] = depth[daughter.index()].max(depth[node.index()] + 1);
+                }
+            }
+        }
+
+        depth
+    }
+
+    pub fn stats(&self) -> ChainStats {
+        let graph = &self.graph;
+        let depth = self.depths();
+
+        // the root is the first node added by the builder
+        let bottleneck = graph
+            .node_indices()
+            .skip(1)
+            .filter_map(|i| Some((graph[i].nuclide, graph[i].half_life?)))
+            .max_by(|(_, a), (_, b)| a.as_sec().total_cmp(&b.as_sec()));
+
+        ChainStats {
+            nodes: graph.node_count(),
+            edges: graph.edge_count(),
+            max_depth: depth.into_iter().max().unwrap_or(0),
+            bottleneck,
+            branches: graph
+                .node_indices()
+                .filter(|&i| graph.neighbors(i).count() > 1)
+                .count(),
+            spontaneous_fission: graph
+                .node_weights()
+                .any(|node| node.nuclide == Nuclide::FissionProducts),
+        }
+    }
+
+    /// Graphviz DOT with half-lives, branch ratios and decay modes, see [`to_dot`]
+    pub fn to_dot(&self) -> String {
+        to_dot(self, false)
+    }
+
+    /// Mermaid flowchart with half-lives, branch ratios and decay modes
+    pub fn to_mermaid(&self) -> String {
+        self.to_mermaid_with(NumberFormat::DEFAULT)
+    }
+
+    /// [`DecayChain::to_mermaid`] with the numbers written in `number`
+    pub fn to_mermaid_with(&self, number: NumberFormat) -> String {
+        let label = |s: String| s.replace('"', "#quot;").replace('\n', "<br/>");
+
+        let mut res = "graph TD\n".to_string();
+        for i in self.graph.node_indices() {
+            res += &format!(
+                "    n{}[\"{}\"]\n",
+                i.index(),
+                label(self.graph[i].to_string())
+            );
+        }
+        for edge in self.graph.edge_references() {
+            res += &format!(
+                "    n{} -->|\"{}\"| n{}\n",
+                edge.source().index(),
+                label(edge.weight().label(number)),
+                edge.target().index()
+            );
+        }
+
+        res
     }
 }
 
-pub type DecayChain = Graph<ChainNode, ChainEdge>;
+/// Graphviz DOT of a decay chain, optionally labelling edges with partial decay constants
+pub fn to_dot(chain: &DecayChain, decay_constants: bool) -> String {
+    to_dot_with(chain, decay_constants, NumberFormat::DEFAULT)
+}
+
+/// [`to_dot`] with the numbers written in `number`
+pub fn to_dot_with(chain: &DecayChain, decay_constants: bool, number: NumberFormat) -> String {
+    type G = Graph<ChainNode, ChainEdge>;
 
-pub struct DecayChainBuilder<D> {
+    let edge_attr = |_: &G, edge: petgraph::graph::EdgeReference<'_, ChainEdge>| {
+        let weight = edge.weight();
+        let mut label = weight.label(number).replace('\n', "\\n");
+        if let Some(lambda) = weight.partial_decay_constant.filter(|_| decay_constants) {
+            label += &format!("\\nλ·BR = {} /s", number.format(lambda));
+        }
+        format!("label = \"{}\"", label)
+    };
+    let node_attr = |_: &G, (_, node): (NodeIndex, &ChainNode)| {
+        format!("label = \"{}\"", node.to_string().replace('\n', "\\n"))
+    };
+
+    format!(
+        "{}",
+        Dot::with_attr_getters(
+            &chain.graph,
+            &[Config::NodeNoLabel, Config::EdgeNoLabel],
+            &edge_attr,
+            &node_attr,
+        )
+    )
+}
+
+pub struct DecayChainBuilder<D: ?Sized> {
     data: Arc<D>,
+    min_branch_ratio: f64,
+    renormalize: bool,
+    max_depth: Option<usize>,
+    cut_below_half_life: Option<HalfLife>,
+    lump_truncated: bool,
 }
 
 impl<D> DecayChainBuilder<D>
 where
-    D: NuclideHalfLife + NuclideProgeny,
+    D: NuclideHalfLife + NuclideProgeny + ?Sized,
 {
     pub fn new(data: Arc<D>) -> Self {
-        Self { data }
+        Self {
+            data,
+            min_branch_ratio: 0.,
+            renormalize: false,
+            max_depth: None,
+            cut_below_half_life: None,
+            lump_truncated: false,
+        }
+    }
+
+    /// Drop the branches reached by less than `ratio` of the decays of the
+    /// root, the product of the branch ratios from the root along any path,
+    /// like [`super::BatemanDecaySolver::with_min_branch_ratio`]
+    pub fn min_branch_ratio(mut self, ratio: f64) -> Self {
+        self.min_branch_ratio = ratio;
+        self
+    }
+
+    /// Scale the remaining branch ratios of a nuclide so that they add up to the
+    /// ratios before dropping branches, instead of keeping them absolute
+    pub fn renormalize(mut self, renormalize: bool) -> Self {
+        self.renormalize = renormalize;
+        self
+    }
+
+    /// Stop at nuclides `depth` decays away from the root, counted along the
+    /// longest path like [`ChainStats::max_depth`]
+    pub fn max_depth(mut self, depth: usize) -> Self {
+        self.max_depth = Some(depth);
+        self
+    }
+
+    /// Stop at progeny with a half-life below `half_life`, e.g. at the
+    /// short-lived progeny of radon
+    ///
+    /// The root is always expanded; stable progeny end the chain anyway.
+    pub fn cut_below_half_life(mut self, half_life: HalfLife) -> Self {
+        self.cut_below_half_life = Some(half_life);
+        self
+    }
+
+    /// Keep the daughters of the dropped branches as terminal nodes whose own
+    /// progeny are left out, so that the activity leaving the chain is kept
+    /// track of, see [`ChainNode::is_truncated`]
+    ///
+    /// Branches redistributed by [`Self::renormalize`] are not lumped. The
+    /// terminal nodes of a chain cut by [`Self::max_depth`] are one decay
+    /// deeper.
+    pub fn lump_truncated(mut self, lump: bool) -> Self {
+        self.lump_truncated = lump;
+        self
+    }
+
+    /// Whether the branch to `daughter` is kept, `fraction` of the decays of
+    /// the root reaching its parent
+    fn keep(&self, daughter: &Progeny, fraction: f64) -> bool {
+        fraction * daughter.branch_rate >= self.min_branch_ratio
+    }
+
+    /// Whether the progeny of `nuclide` are left out by the depth or half-life cut
+    fn cut(&self, root: Nuclide, nuclide: Nuclide, depth: usize) -> bool {
+        let too_deep = self.max_depth.is_some_and(|max| depth >= max);
+        let short_lived = nuclide != root
+            && self.cut_below_half_life.is_some_and(|cut| {
+                self.data
+                    .half_life(nuclide)
+                    .is_ok_and(|t| t.as_sec() < cut.as_sec())
+            });
+        too_deep || short_lived
+    }
+
+    /// Nuclides reachable from the root, parents before their progeny
+    fn topological_order(&self, root: Nuclide) -> Vec<Nuclide> {
+        let mut order = vec![];
+        let mut visited = HashSet::from([root]);
+        let mut stack = vec![(root, false)];
+        while let Some((nuclide, expanded)) = stack.pop() {
+            if expanded {
+                order.push(nuclide);
+                continue;
+            }
+            stack.push((nuclide, true));
+            for daughter in self.data.progeny(nuclide).unwrap_or_default() {
+                if let Nuclide::WithId(_) = daughter.nuclide {
+                    if visited.insert(daughter.nuclide) {
+                        stack.push((daughter.nuclide, false));
+                    }
+                }
+            }
+        }
+        order.reverse();
+        order
     }
 
     pub fn build(self, root: Nuclide) -> DecayChain {
         let mut graph: Graph<ChainNode, ChainEdge> = Graph::new();
 
+        let mut index: HashMap<Nuclide, NodeIndex> = HashMap::new();
+
         let mut get_or_insert_node = |nuclide: Nuclide| -> NodeIndex {
             match nuclide {
-                Nuclide::WithId(_) => {
-                    match graph
-                        .raw_nodes()
-                        .iter()
-                        .position(|n| n.weight.nuclide == nuclide)
-                    {
-                        Some(i) => NodeIndex::new(i),
-                        None => {
-                            let half_life = self.data.half_life(nuclide).ok();
-                            graph.add_node(ChainNode { nuclide, half_life })
-                        }
-                    }
-                }
+                Nuclide::WithId(_) => *index.entry(nuclide).or_insert_with(|| {
+                    let half_life = self.data.half_life(nuclide).ok();
+                    graph.add_node(ChainNode {
+                        nuclide,
+                        half_life,
+                        truncated: false,
+                    })
+                }),
                 Nuclide::FissionProducts => {
                     let half_life = None;
-                    graph.add_node(ChainNode { nuclide, half_life })
+                    graph.add_node(ChainNode {
+                        nuclide,
+                        half_life,
+                        truncated: false,
+                    })
                 }
             }
         };
 
-        let mut stack: Vec<Nuclide> = vec![root];
-        let mut visited = HashSet::new();
+        // longest path and largest fraction of the decays of the root reaching
+        // each nuclide over the kept branches, parents being expanded first
+        let mut reached = HashMap::from([(root, (0, 1.))]);
         let mut edges = vec![];
+        let mut lumped = vec![];
+        let mut truncations = BTreeMap::new();
 
-        while let Some(parent) = stack.pop() {
-            match parent {
-                Nuclide::WithId(_) => {
-                    visited.insert(parent);
-
-                    if let Ok(progeny) = self.data.progeny(parent) {
-                        let p_node = get_or_insert_node(parent);
-                        for daughter in progeny {
-                            {
-                                if !visited.contains(&daughter.nuclide) {
-                                    stack.push(daughter.nuclide)
-                                }
-
-                                let d_node = get_or_insert_node(daughter.nuclide);
-                                let weight = ChainEdge {
-                                    branch_rate: daughter.branch_rate,
-                                    decay_mode: daughter.decay_mode,
-                                };
-                                edges.push((p_node, d_node, weight));
-                            }
-                        }
-                    };
+        for parent in self.topological_order(root) {
+            let Some(&(depth, fraction)) = reached.get(&parent) else {
+                continue;
+            };
+            let Ok(progeny) = self.data.progeny(parent) else {
+                continue;
+            };
+            let p_node = get_or_insert_node(parent);
+            let lambda = self.data.half_life(parent).ok().map(|t| t.as_lambda());
+
+            let cut = self.cut(root, parent, depth);
+            let total: f64 = progeny.iter().map(|d| d.branch_rate).sum();
+            let (kept, dropped): (Vec<_>, Vec<_>) = progeny
+                .into_iter()
+                .partition(|d| !cut && self.keep(d, fraction));
+            let dropped_rate: f64 = dropped.iter().map(|d| d.branch_rate).sum();
+            let kept_total = total - dropped_rate;
+            let scale = if self.renormalize && dropped_rate > 0. && kept_total > 0. {
+                total / kept_total
+            } else {
+                1.
+            };
+            if dropped_rate > 0. {
+                truncations.insert(
+                    parent,
+                    Truncation {
+                        dropped: dropped_rate,
+                        scale,
+                    },
+                );
+                if self.lump_truncated && scale == 1. {
+                    lumped.extend(dropped.iter().map(|d| (p_node, d.clone(), lambda)));
                 }
-                Nuclide::FissionProducts => {}
             }
+
+            for daughter in kept {
+                let branch_rate = daughter.branch_rate * scale;
+                let entry = reached.entry(daughter.nuclide).or_insert((0, 0.));
+                *entry = (
+                    entry.0.max(depth + 1),
+                    entry.1.max(fraction * daughter.branch_rate),
+                );
+
+                let d_node = get_or_insert_node(daughter.nuclide);
+                let weight = ChainEdge {
+                    branch_rate,
+                    decay_mode: daughter.decay_mode,
+                    partial_decay_constant: lambda.map(|lambda| lambda * branch_rate),
+                };
+                edges.push((p_node, d_node, weight));
+            }
+        }
+
+        // daughters of dropped branches not expanded through another branch
+        for (p_node, daughter, lambda) in lumped {
+            let d_node = get_or_insert_node(daughter.nuclide);
+            let weight = ChainEdge {
+                branch_rate: daughter.branch_rate,
+                decay_mode: daughter.decay_mode,
+                partial_decay_constant: lambda.map(|lambda| lambda * daughter.branch_rate),
+            };
+            edges.push((p_node, d_node, weight));
+        }
+        for node in graph.node_weights_mut() {
+            node.truncated =
+                matches!(node.nuclide, Nuclide::WithId(_)) && !reached.contains_key(&node.nuclide);
         }
         for (p_node, d_node, weight) in edges {
             graph.add_edge(p_node, d_node, weight);
         }
 
-        graph
+        DecayChain {
+            graph,
+            truncations,
+            collapsed: BTreeMap::new(),
+        }
     }
 }
 
@@ -128,7 +574,8 @@ where
 mod test {
     use super::*;
     use crate::error::Error;
-    use crate::primitive::{DecayMode, DecayModeSet, Progeny, TimeUnit};
+    use crate::fixtures::FixtureDecayData;
+    use crate::primitive::{DecayMode, DecayModeSet, TimeUnit};
 
     struct TestData {
         pub mo99: Nuclide,
@@ -215,5 +662,235 @@ mod test {
             edges[0].weight.decay_mode,
             DecayModeSet::default() | DecayMode::IsometricTransition
         );
+        let lambda = 2f64.ln() / (2.7489 * 86_400.);
+        assert_eq!(edges[0].weight.partial_decay_constant(), Some(lambda));
+        assert_eq!(edges[0].weight.partial_mean_life(), Some(lambda.recip()));
+
+        let dot = to_dot(&chain, true);
+        assert!(dot.contains("label = \"Mo-99\\n2.7489 d\""));
+        assert!(dot.contains("λ·BR = 2.9185e-6 /s"));
+        assert!(!chain.to_dot().contains("λ·BR"));
+        let number = NumberFormat {
+            significant_digits: 2,
+            ..NumberFormat::DEFAULT
+        };
+        assert!(to_dot_with(&chain, true, number).contains("λ·BR = 2.9e-6 /s"));
+
+        let mermaid = chain.to_mermaid();
+        assert!(mermaid.starts_with("graph TD\n"));
+        assert!(mermaid.contains("n0[\"Mo-99<br/>2.7489 d\"]"));
+        assert!(mermaid.contains("n0 -->|\"1<br/>IT\"| n1"));
+    }
+
+    #[test]
+    fn shared_progeny() {
+        // Pb-210 is reached through both branches of Bi-214
+        let data = Arc::new(FixtureDecayData::new());
+        let chain = DecayChainBuilder::new(data).build("Ra-226".parse().unwrap());
+        assert_eq!(chain.node_count(), 11);
+        assert_eq!(chain.edge_count(), 12);
+    }
+
+    #[test]
+    fn truncated_chain() {
+        let data = Arc::new(FixtureDecayData::new());
+        let ra226: Nuclide = "Ra-226".parse().unwrap();
+        let bi214: Nuclide = "Bi-214".parse().unwrap();
+        let branch_rates = |chain: &DecayChain, parent: Nuclide| -> Vec<f64> {
+            let node = chain
+                .node_indices()
+                .find(|&i| chain[i].nuclide == parent)
+                .unwrap();
+            chain.edges(node).map(|e| e.weight().branch_rate).collect()
+        };
+
+        let full = DecayChainBuilder::new(data.clone()).build(ra226);
+        assert_eq!(full.node_count(), 11);
+        assert_eq!(full.edge_count(), 12);
+        assert!(full.truncations().is_empty());
+
+        let absolute = DecayChainBuilder::new(data.clone())
+            .min_branch_ratio(1e-3)
+            .build(ra226);
+        assert_eq!(absolute.node_count(), 9);
+        assert_eq!(absolute.truncations().len(), 2);
+        assert_eq!(branch_rates(&absolute, bi214), vec![0.99979]);
+        assert_eq!(
+            absolute.truncations()[&bi214],
+            Truncation {
+                dropped: 2.1e-4,
+                scale: 1.
+            }
+        );
+
+        let renormalized = DecayChainBuilder::new(data)
+            .min_branch_ratio(1e-3)
+            .renormalize(true)
+            .build(ra226);
+        assert!((branch_rates(&renormalized, bi214)[0] - 1.).abs() < 1e-12);
+        assert_eq!(renormalized.truncations()[&bi214].scale, 1. / 0.99979);
+
+        let graph = renormalized.into_graph();
+        assert_eq!(graph.node_count(), 9);
+        assert!(DecayChain::from(graph).truncations().is_empty());
+    }
+
+    #[test]
+    fn depth_and_half_life_cuts() {
+        let data = Arc::new(FixtureDecayData::new());
+        let nuclide = |s: &str| -> Nuclide { s.parse().unwrap() };
+        let nuclides = |chain: &DecayChain| -> Vec<Nuclide> {
+            chain
+                .node_weights()
+                .filter(|n| !n.is_truncated())
+                .map(|n| n.nuclide)
+                .collect()
+        };
+
+        let shallow = DecayChainBuilder::new(data.clone())
+            .max_depth(2)
+            .build(nuclide("Ra-226"));
+        assert_eq!(
+            nuclides(&shallow),
+            ["Ra-226", "Rn-222", "Po-218"].map(nuclide)
+        );
+        assert_eq!(shallow.stats().max_depth, 2);
+        assert_eq!(
+            shallow.truncations()[&nuclide("Po-218")],
+            Truncation {
+                dropped: 1.,
+                scale: 1.
+            }
+        );
+
+        // counted along the longest path, like the stats
+        for depth in 0..=8 {
+            let chain = DecayChainBuilder::new(data.clone())
+                .max_depth(depth)
+                .build(nuclide("Ra-226"));
+            assert_eq!(chain.stats().max_depth, depth);
+        }
+
+        // the short-lived Po-218 ends the chain
+        let cut = DecayChainBuilder::new(data.clone())
+            .cut_below_half_life(HalfLife {
+                value: 1.,
+                unit: TimeUnit::Day,
+            })
+            .build(nuclide("Ra-226"));
+        assert_eq!(nuclides(&cut), ["Ra-226", "Rn-222", "Po-218"].map(nuclide));
+        assert_eq!(cut.truncations().len(), 1);
+
+        // At-218 and Tl-210 carry less than 1e-3 of the decays of Ra-226
+        let minor = DecayChainBuilder::new(data.clone())
+            .min_branch_ratio(1e-3)
+            .build(nuclide("Ra-226"));
+        assert_eq!(minor.node_count(), 9);
+        assert_eq!(minor.truncations().len(), 2);
+        // Tc-99 is reached through Tc-99m, not by the direct branch
+        let mo99 = DecayChainBuilder::new(data.clone())
+            .min_branch_ratio(0.5)
+            .build(nuclide("Mo-99"));
+        assert_eq!((mo99.node_count(), mo99.edge_count()), (3, 2));
+        assert_eq!(mo99.truncations()[&nuclide("Mo-99")].dropped, 0.1227);
+
+        let lumped = DecayChainBuilder::new(data)
+            .min_branch_ratio(1e-3)
+            .max_depth(6)
+            .lump_truncated(true)
+            .build(nuclide("Ra-226"));
+        let terminal: Vec<Nuclide> = lumped
+            .node_weights()
+            .filter(|n| n.is_truncated())
+            .map(|n| n.nuclide)
+            .collect();
+        assert_eq!(terminal, ["At-218", "Tl-210", "Bi-210"].map(nuclide));
+        // one node per nuclide
+        let mut all: Vec<Nuclide> = lumped.node_weights().map(|n| n.nuclide).collect();
+        all.sort();
+        all.dedup();
+        assert_eq!(all.len(), lumped.node_count());
+        for (&parent, truncation) in lumped.truncations() {
+            let node = lumped
+                .node_indices()
+                .find(|&i| lumped[i].nuclide == parent)
+                .unwrap();
+            let lumped_rate: f64 = lumped
+                .edges(node)
+                .filter(|e| lumped[e.target()].is_truncated())
+                .map(|e| e.weight().branch_rate)
+                .sum();
+            assert_eq!(lumped_rate, truncation.dropped);
+        }
+        assert!(lumped.to_dot().contains("Bi-210\\n5.012 d\\n(truncated)"));
+    }
+
+    #[test]
+    fn collapsed_chain() {
+        let data = Arc::new(FixtureDecayData::new());
+        let nuclide = |s: &str| -> Nuclide { s.parse().unwrap() };
+        let chain = DecayChainBuilder::new(data).build(nuclide("Ra-226"));
+
+        // fold the short-lived radon progeny, half-lives below a day
+        let simplified = chain.collapse_short_lived(86_400.);
+        let nuclides: Vec<Nuclide> = simplified.node_weights().map(|n| n.nuclide).collect();
+        assert_eq!(
+            nuclides,
+            ["Ra-226", "Rn-222", "Pb-210", "Bi-210", "Po-210"].map(nuclide)
+        );
+        assert_eq!(simplified.edge_count(), 4);
+
+        let rn222 = simplified
+            .edges(NodeIndex::new(1))
+            .map(|e| e.weight())
+            .collect::<Vec<_>>();
+        assert_eq!(rn222.len(), 1);
+        assert!((rn222[0].branch_rate - 1.).abs() < 1e-12);
+        assert!(rn222[0].decay_mode.0.contains(DecayMode::BetaMinus));
+        let lambda = chain[NodeIndex::new(1)].half_life.unwrap().as_lambda();
+        assert!((rn222[0].partial_decay_constant().unwrap() / lambda - 1.).abs() < 1e-12);
+
+        let folded: BTreeMap<_, _> = simplified.collapsed()[&nuclide("Rn-222")]
+            .iter()
+            .copied()
+            .collect();
+        assert_eq!(folded.len(), 6);
+        assert!((folded[&nuclide("Bi-214")] - 1.).abs() < 1e-12);
+        assert!((folded[&nuclide("Tl-210")] - 2.1e-4).abs() < 1e-12);
+        assert!(simplified.collapsed().get(&nuclide("Ra-226")).is_none());
+    }
+
+    #[test]
+    fn chain_stats() {
+        let data = Arc::new(FixtureDecayData::new());
+
+        let stats = DecayChainBuilder::new(data.clone())
+            .build("Ra-226".parse().unwrap())
+            .stats();
+        assert_eq!((stats.nodes, stats.edges), (11, 12));
+        // Ra-226 to Po-210
+        assert_eq!(stats.max_depth, 8);
+        assert_eq!(stats.bottleneck.unwrap().0, "Pb-210".parse().unwrap());
+        // Po-218 and Bi-214
+        assert_eq!(stats.branches, 2);
+        assert!(!stats.spontaneous_fission);
+
+        let stats = DecayChainBuilder::new(data.clone())
+            .build("Cf-252".parse().unwrap())
+            .stats();
+        assert!(stats.spontaneous_fission);
+        assert_eq!(stats.max_depth, 2);
+
+        let stable_progeny = DecayChainBuilder::new(data)
+            .build("Co-60".parse().unwrap())
+            .stats();
+        assert_eq!(
+            (
+                stable_progeny.nodes,
+                stable_progeny.max_depth,
+                stable_progeny.bottleneck
+            ),
+            (1, 0, None)
+        );
     }
 }
@@ -0,0 +1,1073 @@
+use std::collections::BTreeMap;
+use std::ops::Deref;
+
+use chrono::{DateTime, Utc};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+use super::{BatemanDecaySolver, NuclideCategories, NuclideCategory};
+use crate::error::Error;
+use crate::primitive::attr::{
+    AtomicMass, DecayConstant, NuclideAtomicMass, NuclideDecayEnergy, NuclideHalfLife,
+    NuclidePhotonLines, NuclideProgeny,
+};
+use crate::primitive::{ActivityUnit, Energy, HalfLifeBins, Nuclide, NumberFormat, Symbol};
+use crate::provenance::{SkipReason, Warnings, WithWarnings};
+
+/// Version of the inventory document written by [`Inventory::to_json`] and [`Inventory::to_toml`]
+pub const INVENTORY_SCHEMA_VERSION: u32 = 1;
+
+/// Avogadro constant (1/mol)
+const AVOGADRO: f64 = 6.022_140_76e23;
+
+/// Number of atoms of an activity (Bq) of a nuclide
+fn atoms<D: NuclideHalfLife + ?Sized>(
+    data: &D,
+    nuclide: Nuclide,
+    activity: f64,
+) -> Result<f64, Error> {
+    Ok(activity / data.half_life(nuclide)?.as_lambda())
+}
+
+/// Molar mass (g/mol) of a nuclide: its nuclide mass in `data`, else the
+/// atomic mass of its element in `elements`, else its mass number; `None`
+/// without an element (fission products)
+fn molar_mass<D: NuclideAtomicMass + ?Sized>(
+    data: &D,
+    elements: Option<&dyn AtomicMass>,
+    nuclide: Nuclide,
+) -> Result<Option<(f64, MassSource)>, Error> {
+    let (Some(z), Some(a)) = (nuclide.z(), nuclide.a()) else {
+        return Ok(None);
+    };
+    let amu = match data.nuclide_atomic_mass(nuclide) {
+        Ok(amu) => (amu, MassSource::Nuclide),
+        Err(Error::InvalidNuclide(_)) => match elements {
+            Some(elements) => (
+                elements.atomic_mass(Symbol::try_from(z)?)?,
+                MassSource::Element,
+            ),
+            None => (a as f64, MassSource::MassNumber),
+        },
+        Err(e) => return Err(e),
+    };
+
+    Ok(Some(amu))
+}
+
+/// Energy of 1 MeV (J)
+pub(super) const MEV: f64 = 1.602_176_634e-13;
+
+/// Molar mass a mass of [`Inventory::masses`] is worked out with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MassSource {
+    /// Nuclide mass of the decay data
+    Nuclide,
+    /// Atomic mass of the element, a fallback
+    Element,
+    /// Mass number, a fallback
+    MassNumber,
+}
+
+/// Mass of a nuclide of an inventory
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NuclideMass {
+    /// (g)
+    pub mass: f64,
+    pub source: MassSource,
+}
+
+/// Activity and mass of the nuclides of an element
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ElementTotal {
+    /// (Bq)
+    pub activity: f64,
+    /// (g)
+    pub mass: f64,
+}
+
+/// Photon emission of an inventory, all lines of all nuclides folded together
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct InventorySpectrumSummary {
+    /// Photon emission rate (1/s)
+    pub photon_rate: f64,
+    /// Photon energy emission rate (MeV/s)
+    pub energy_rate: f64,
+    /// Mean photon energy, weighted by emission rate
+    pub mean_energy: Energy,
+    /// Single-line energy carrying the same energy-weighted spectrum mean,
+    /// sum(n E^2) / sum(n E), for half-value layer rules of thumb
+    pub effective_energy: Energy,
+}
+
+/// Nuclide the photon lines of [`Inventory::photon_source`] are attributed to
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LineAttribution {
+    /// The emitting state, keeping the prompt lines of a metastable state
+    /// apart from those of its ground state
+    #[default]
+    Emitter,
+    /// The ground state, merging the lines of its metastable states
+    GroundState,
+}
+
+/// Photon line of an inventory and the nuclide it is attributed to
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SourceLine {
+    pub nuclide: Nuclide,
+    pub energy: Energy,
+    /// Emission rate (1/s)
+    pub rate: f64,
+}
+
+/// Activity below which [`Inventory::prune`] drops a nuclide
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Threshold {
+    /// (Bq)
+    Absolute(f64),
+    /// Fraction of the total activity
+    Relative(f64),
+}
+
+/// Activities (Bq) of nuclides
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Inventory {
+    activity: BTreeMap<Nuclide, f64>,
+    reference_time: Option<DateTime<Utc>>,
+    metadata: BTreeMap<String, String>,
+}
+
+impl Deref for Inventory {
+    type Target = BTreeMap<Nuclide, f64>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.activity
+    }
+}
+
+impl Inventory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add(&mut self, nuclide: Nuclide, activity: f64) {
+        *self.activity.entry(nuclide).or_insert(0.0) += activity;
+    }
+
+    /// Add an activity given in `unit`
+    pub fn add_with_unit(&mut self, nuclide: Nuclide, activity: f64, unit: ActivityUnit) {
+        self.add(nuclide, activity * unit.as_bq());
+    }
+
+    /// Add the activity of `n` atoms of a nuclide
+    pub fn add_atoms<D: NuclideHalfLife + ?Sized>(
+        &mut self,
+        data: &D,
+        nuclide: Nuclide,
+        n: f64,
+    ) -> Result<(), Error> {
+        self.add(nuclide, n * data.half_life(nuclide)?.as_lambda());
+        Ok(())
+    }
+
+    /// Add the activity of a mass (g) of a nuclide, with the molar mass of
+    /// [`Inventory::masses`]
+    pub fn add_mass<D: NuclideHalfLife + NuclideAtomicMass + ?Sized>(
+        &mut self,
+        data: &D,
+        elements: Option<&dyn AtomicMass>,
+        nuclide: Nuclide,
+        mass: f64,
+    ) -> Result<(), Error> {
+        let (molar_mass, _) = molar_mass(data, elements, nuclide)?
+            .ok_or_else(|| Error::InvalidNuclide(nuclide.to_string()))?;
+        self.add_atoms(data, nuclide, mass / molar_mass * AVOGADRO)
+    }
+
+    /// Number of atoms of a nuclide, see [`Inventory::atoms`] (0 if not in the inventory)
+    pub fn atoms_of<D: NuclideHalfLife + ?Sized>(
+        &self,
+        data: &D,
+        nuclide: Nuclide,
+    ) -> Result<f64, Error> {
+        match self.get(&nuclide) {
+            Some(&activity) => atoms(data, nuclide, activity),
+            None => Ok(0.),
+        }
+    }
+
+    /// Mass (g) of a nuclide, see [`Inventory::masses`] (0 if not in the
+    /// inventory or without an element)
+    pub fn mass_of<D: NuclideHalfLife + NuclideAtomicMass + ?Sized>(
+        &self,
+        data: &D,
+        elements: Option<&dyn AtomicMass>,
+        nuclide: Nuclide,
+    ) -> Result<f64, Error> {
+        match molar_mass(data, elements, nuclide)? {
+            Some((molar_mass, _)) => Ok(self.atoms_of(data, nuclide)? / AVOGADRO * molar_mass),
+            None => Ok(0.),
+        }
+    }
+
+    /// Activities of the nuclides in `unit`
+    pub fn to_unit(&self, unit: ActivityUnit) -> BTreeMap<Nuclide, f64> {
+        self.iter()
+            .map(|(&nuclide, &activity)| (nuclide, activity / unit.as_bq()))
+            .collect()
+    }
+
+    pub fn remove(&mut self, nuclide: Nuclide) -> Option<(Nuclide, f64)> {
+        self.activity.remove_entry(&nuclide)
+    }
+
+    /// Remove the nuclides below the threshold, returning their total activity (Bq)
+    pub fn prune(&mut self, threshold: Threshold) -> f64 {
+        let limit = match threshold {
+            Threshold::Absolute(activity) => activity,
+            Threshold::Relative(fraction) => fraction * self.activity.values().sum::<f64>(),
+        };
+
+        let mut pruned = 0.;
+        self.activity.retain(|_, &mut activity| {
+            let keep = activity >= limit;
+            if !keep {
+                pruned += activity;
+            }
+            keep
+        });
+
+        pruned
+    }
+
+    pub(super) fn replace_activity(&mut self, activity: BTreeMap<Nuclide, f64>) {
+        self.activity = activity;
+    }
+
+    /// Time at which the activities are given
+    pub fn reference_time(&self) -> Option<DateTime<Utc>> {
+        self.reference_time
+    }
+
+    pub fn set_reference_time(&mut self, reference_time: Option<DateTime<Utc>>) {
+        self.reference_time = reference_time;
+    }
+
+    /// Free-form annotations (source description, sample id, ...)
+    pub fn metadata(&self) -> &BTreeMap<String, String> {
+        &self.metadata
+    }
+
+    pub fn metadata_mut(&mut self) -> &mut BTreeMap<String, String> {
+        &mut self.metadata
+    }
+
+    /// Load an inventory from a JSON document of a supported schema version.
+    pub fn from_json(s: &str) -> Result<Self, Error> {
+        serde_json::from_str::<DocumentV1>(s)?.try_into()
+    }
+
+    /// Serialize the inventory as a JSON document of the current schema version.
+    pub fn to_json(&self) -> Result<String, Error> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// Load an inventory from a TOML document of a supported schema version.
+    pub fn from_toml(s: &str) -> Result<Self, Error> {
+        toml::from_str::<DocumentV1>(s)?.try_into()
+    }
+
+    /// Serialize the inventory as a TOML document of the current schema version.
+    pub fn to_toml(&self) -> Result<String, Error> {
+        Ok(toml::to_string(self)?)
+    }
+
+    /// Sum of inventories measured at different times, each decay-corrected to `reference_time`
+    ///
+    /// An inventory measured after the reference time is an error: decaying
+    /// backwards through the Bateman equations does not give back the progeny
+    /// present at the reference time.
+    pub fn merge_at<D>(
+        solver: &BatemanDecaySolver<D>,
+        reference_time: DateTime<Utc>,
+        inventories: &[(Inventory, DateTime<Utc>)],
+    ) -> Result<Inventory, Error>
+    where
+        D: NuclideProgeny + DecayConstant + ?Sized,
+    {
+        let mut merged = Inventory::new();
+        for (inventory, time) in inventories {
+            if *time > reference_time {
+                return Err(Error::InvalidParameter(format!(
+                    "inventory measured at {} after the reference time {}",
+                    time, reference_time
+                )));
+            }
+            let elapsed = (reference_time - *time).num_milliseconds() as f64 / 1e3;
+            for (&nuclide, &activity) in solver.decay(inventory, elapsed).iter() {
+                merged.add(nuclide, activity);
+            }
+        }
+        merged.reference_time = Some(reference_time);
+
+        Ok(merged)
+    }
+
+    /// Number of atoms of each nuclide
+    pub fn atoms<D: NuclideHalfLife + ?Sized>(
+        &self,
+        data: &D,
+    ) -> Result<BTreeMap<Nuclide, f64>, Error> {
+        self.iter()
+            .map(|(&nuclide, &activity)| Ok((nuclide, atoms(data, nuclide, activity)?)))
+            .collect()
+    }
+
+    /// Amount of substance (mol) of each nuclide
+    pub fn moles<D: NuclideHalfLife + ?Sized>(
+        &self,
+        data: &D,
+    ) -> Result<BTreeMap<Nuclide, f64>, Error> {
+        Ok(self
+            .atoms(data)?
+            .into_iter()
+            .map(|(nuclide, atoms)| (nuclide, atoms / AVOGADRO))
+            .collect())
+    }
+
+    /// Mass (g) of each nuclide
+    ///
+    /// Nuclide masses of `data` are preferred. Nuclides it does not list take
+    /// the atomic mass of their element from `elements`, or their mass number
+    /// without it, which is off by up to about 0.5% for the lightest
+    /// radionuclides (H-3: 3.016 u) and much less for heavier ones. The
+    /// source of each molar mass is given with the mass. Entries without an
+    /// element (fission products) are left out.
+    pub fn masses<D: NuclideHalfLife + NuclideAtomicMass + ?Sized>(
+        &self,
+        data: &D,
+        elements: Option<&dyn AtomicMass>,
+    ) -> Result<BTreeMap<Nuclide, NuclideMass>, Error> {
+        let mut masses = BTreeMap::new();
+        for (&nuclide, &activity) in self.iter() {
+            let Some((molar_mass, source)) = molar_mass(data, elements, nuclide)? else {
+                continue;
+            };
+            let moles = atoms(data, nuclide, activity)? / AVOGADRO;
+            let mass = moles * molar_mass;
+            masses.insert(nuclide, NuclideMass { mass, source });
+        }
+
+        Ok(masses)
+    }
+
+    /// Activity and mass summed per element
+    ///
+    /// Masses are those of [`Inventory::masses`]; entries without an element
+    /// (fission products) are left out.
+    pub fn by_element<D: NuclideHalfLife + NuclideAtomicMass + ?Sized>(
+        &self,
+        data: &D,
+        elements: Option<&dyn AtomicMass>,
+    ) -> Result<BTreeMap<Symbol, ElementTotal>, Error> {
+        let mut totals = BTreeMap::<Symbol, ElementTotal>::new();
+        for (nuclide, mass) in self.masses(data, elements)? {
+            let Some(z) = nuclide.z() else {
+                continue;
+            };
+            let total = totals.entry(Symbol::try_from(z)?).or_default();
+            total.activity += self[&nuclide];
+            total.mass += mass.mass;
+        }
+
+        Ok(totals)
+    }
+
+    /// Activity summed per category, e.g. NORM, fission products and actinides
+    pub fn by_category(&self, categories: &NuclideCategories) -> BTreeMap<NuclideCategory, f64> {
+        let mut totals = BTreeMap::new();
+        for (&nuclide, &activity) in self.iter() {
+            *totals.entry(categories.category(nuclide)).or_default() += activity;
+        }
+
+        totals
+    }
+
+    /// Decay heat (W) of each nuclide, from the alpha, electron and photon
+    /// energy per transformation; neutrinos carry the rest away
+    ///
+    /// Fission products are left out. See [`BatemanDecaySolver::cooling_curve`]
+    /// to follow the decay heat with progeny ingrowth over time.
+    pub fn decay_heat_by_nuclide<D: NuclideDecayEnergy + ?Sized>(
+        &self,
+        data: &D,
+    ) -> Result<BTreeMap<Nuclide, f64>, Error> {
+        self.iter()
+            .filter(|(&nuclide, _)| nuclide != Nuclide::FissionProducts)
+            .map(|(&nuclide, &activity)| {
+                Ok((nuclide, activity * data.decay_energy(nuclide)? * MEV))
+            })
+            .collect()
+    }
+
+    /// Decay heat (W) of the inventory, see [`Inventory::decay_heat_by_nuclide`]
+    pub fn decay_heat<D: NuclideDecayEnergy + ?Sized>(&self, data: &D) -> Result<f64, Error> {
+        Ok(self.decay_heat_by_nuclide(data)?.values().sum())
+    }
+
+    /// Activity (Bq) in each half-life range, the longevity profile of the
+    /// inventory, and the activity of nuclides without a half-life
+    pub fn half_life_profile<D: NuclideHalfLife + ?Sized>(
+        &self,
+        data: &D,
+        bins: &HalfLifeBins,
+    ) -> (Vec<f64>, f64) {
+        let mut profile = vec![0.; bins.len()];
+        let mut unknown = 0.;
+        for (&nuclide, &activity) in self.iter() {
+            match data.half_life(nuclide) {
+                Ok(half_life) => profile[bins.bin(&half_life)] += activity,
+                Err(_) => unknown += activity,
+            }
+        }
+
+        (profile, unknown)
+    }
+
+    /// Photon emission rates and mean energies of the whole inventory, with
+    /// the nuclides not listed by the data left out
+    pub fn spectrum_summary<D: NuclidePhotonLines>(
+        &self,
+        data: &D,
+    ) -> Result<WithWarnings<InventorySpectrumSummary>, Error> {
+        let mut warnings = Warnings::new();
+        let (mut n, mut ne, mut ne2) = (0., 0., 0.);
+        for (&nuclide, &activity) in self.iter() {
+            let res = data.photon_lines(nuclide);
+            let Some(lines) = warnings.lookup(nuclide, SkipReason::NoPhotonLines, res)? else {
+                continue;
+            };
+            for (energy, r#yield) in lines {
+                let energy = energy.as_mev();
+                let rate = activity * r#yield;
+                n += rate;
+                ne += rate * energy;
+                ne2 += rate * energy * energy;
+            }
+        }
+
+        Ok(WithWarnings {
+            value: InventorySpectrumSummary {
+                photon_rate: n,
+                energy_rate: ne,
+                mean_energy: Energy::mev(if n > 0. { ne / n } else { 0. }),
+                effective_energy: Energy::mev(if ne > 0. { ne2 / ne } else { 0. }),
+            },
+            warnings,
+        })
+    }
+
+    /// Photon lines of the inventory by attributed nuclide, then energy
+    ///
+    /// Lines of the same energy attributed to the same nuclide are summed;
+    /// the nuclides not listed by the data are left out.
+    pub fn photon_source<D: NuclidePhotonLines>(
+        &self,
+        data: &D,
+        attribution: LineAttribution,
+    ) -> Result<WithWarnings<Vec<SourceLine>>, Error> {
+        let mut warnings = Warnings::new();
+        let mut lines: Vec<SourceLine> = vec![];
+        for (&nuclide, &activity) in self.iter() {
+            let source = match attribution {
+                LineAttribution::Emitter => nuclide,
+                LineAttribution::GroundState => nuclide.ground_state(),
+            };
+            let res = data.photon_lines(nuclide);
+            let Some(emitted) = warnings.lookup(nuclide, SkipReason::NoPhotonLines, res)? else {
+                continue;
+            };
+            for (energy, r#yield) in emitted {
+                lines.push(SourceLine {
+                    nuclide: source,
+                    energy,
+                    rate: activity * r#yield,
+                });
+            }
+        }
+        lines.sort_by(|a, b| {
+            a.nuclide
+                .cmp(&b.nuclide)
+                .then(a.energy.as_mev().total_cmp(&b.energy.as_mev()))
+        });
+        lines.dedup_by(|line, kept| {
+            let same = line.nuclide == kept.nuclide && line.energy == kept.energy;
+            if same {
+                kept.rate += line.rate;
+            }
+            same
+        });
+
+        Ok(WithWarnings {
+            value: lines,
+            warnings,
+        })
+    }
+
+    /// Table of nuclide, half-life, activity and percentage of the total activity
+    pub fn to_table_string<D: NuclideHalfLife>(&self, data: &D) -> String {
+        self.to_table_string_with(data, NumberFormat::DEFAULT)
+    }
+
+    /// [`Inventory::to_table_string`] with the numbers written in `number`
+    pub fn to_table_string_with<D: NuclideHalfLife>(
+        &self,
+        data: &D,
+        number: NumberFormat,
+    ) -> String {
+        self.table(Some(data), number)
+    }
+
+    fn table(&self, data: Option<&dyn NuclideHalfLife>, number: NumberFormat) -> String {
+        let total: f64 = self.values().sum();
+        let mut s = format!("{:<10}", "nuclide");
+        if data.is_some() {
+            s += &format!("{:>14}", "half-life");
+        }
+        s += &format!("{:>14}{:>10}\n", "activity", "%");
+
+        for (&nuclide, &activity) in self.iter() {
+            s += &format!("{:<10}", nuclide.to_string());
+            if let Some(data) = data {
+                let half_life = data
+                    .half_life(nuclide)
+                    .map_or("-".to_string(), |t| t.to_string());
+                s += &format!("{:>14}", half_life);
+            }
+            let percent = if total > 0. {
+                100. * activity / total
+            } else {
+                0.
+            };
+            s += &format!(
+                "{:>14}{:>10}\n",
+                number.si_prefixed(activity, "Bq"),
+                number.format(percent)
+            );
+        }
+
+        s
+    }
+}
+
+impl std::fmt::Display for Inventory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.table(None, NumberFormat::DEFAULT))
+    }
+}
+
+impl Serialize for Inventory {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        DocumentV1::from(self).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Inventory {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        DocumentV1::deserialize(deserializer)?
+            .try_into()
+            .map_err(de::Error::custom)
+    }
+}
+
+/// Inventory document, schema version 1
+///
+/// ```json
+/// {
+///   "version": 1,
+///   "reference_time": "2020-01-01T00:00:00Z",
+///   "metadata": { "sample": "A-01" },
+///   "nuclides": [
+///     { "nuclide": "Cs-137", "activity": 1.5, "unit": "MBq" }
+///   ]
+/// }
+/// ```
+///
+/// `reference_time` (RFC 3339) and `metadata` are optional, `unit` defaults to Bq.
+/// Documents of other versions are rejected when loaded.
+#[derive(Serialize, Deserialize)]
+struct DocumentV1 {
+    version: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    reference_time: Option<DateTime<Utc>>,
+    #[serde(default)]
+    metadata: BTreeMap<String, String>,
+    nuclides: Vec<EntryV1>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct EntryV1 {
+    nuclide: Nuclide,
+    activity: f64,
+    #[serde(default)]
+    unit: ActivityUnit,
+}
+
+impl From<&Inventory> for DocumentV1 {
+    fn from(inventory: &Inventory) -> Self {
+        Self {
+            version: INVENTORY_SCHEMA_VERSION,
+            reference_time: inventory.reference_time,
+            metadata: inventory.metadata.clone(),
+            nuclides: inventory
+                .iter()
+                .map(|(&nuclide, &activity)| EntryV1 {
+                    nuclide,
+                    activity,
+                    unit: ActivityUnit::Bq,
+                })
+                .collect(),
+        }
+    }
+}
+
+impl TryFrom<DocumentV1> for Inventory {
+    type Error = Error;
+
+    fn try_from(document: DocumentV1) -> Result<Self, Self::Error> {
+        if document.version != INVENTORY_SCHEMA_VERSION {
+            return Err(Error::UnsupportedSchemaVersion(document.version));
+        }
+
+        let mut inventory = Inventory::new();
+
+        for entry in document.nuclides {
+            if !entry.activity.is_finite() {
+                return Err(Error::InvalidFloat(entry.activity.to_string()));
+            }
+            inventory.add(entry.nuclide, entry.activity * entry.unit.as_bq());
+        }
+        inventory.reference_time = document.reference_time;
+        inventory.metadata = document.metadata;
+
+        Ok(inventory)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::fixtures::FixtureDecayData;
+    use crate::primitive::HalfLife;
+
+    fn inventory() -> Inventory {
+        let mut inv = Inventory::new();
+        inv.add("Cs-137".parse().unwrap(), 1.5e6);
+        inv.add("Tc-99m".parse().unwrap(), 2.0);
+        inv.set_reference_time(Some("2020-01-01T00:00:00Z".parse().unwrap()));
+        inv.metadata_mut()
+            .insert("sample".to_string(), "A-01".to_string());
+        inv
+    }
+
+    #[test]
+    fn prune() {
+        let mut inv = inventory();
+        inv.add("I-131".parse().unwrap(), 1e3);
+
+        let mut relative = inv.clone();
+        assert_eq!(relative.prune(Threshold::Relative(1e-5)), 2.0);
+        assert_eq!(relative.len(), 2);
+
+        assert_eq!(inv.prune(Threshold::Absolute(1e4)), 1e3 + 2.0);
+        assert_eq!(inv.keys().next(), Some(&"Cs-137".parse().unwrap()));
+    }
+
+    #[test]
+    fn json_round_trip() {
+        let inv = inventory();
+        let json = inv.to_json().unwrap();
+
+        assert_eq!(Inventory::from_json(&json).unwrap(), inv);
+    }
+
+    #[test]
+    fn toml_round_trip() {
+        let inv = inventory();
+        let toml = inv.to_toml().unwrap();
+
+        assert_eq!(Inventory::from_toml(&toml).unwrap(), inv);
+    }
+
+    #[test]
+    fn json_with_units() {
+        let json = r#"{
+            "version": 1,
+            "nuclides": [
+                { "nuclide": "Cs-137", "activity": 1.0, "unit": "MBq" },
+                { "nuclide": "Cs-137", "activity": 1.0 },
+                { "nuclide": "Co-60", "activity": 1.0, "unit": "Ci" }
+            ]
+        }"#;
+        let inv = Inventory::from_json(json).unwrap();
+
+        assert_eq!(inv.get(&"Cs-137".parse().unwrap()), Some(&1_000_001.));
+        assert_eq!(inv.get(&"Co-60".parse().unwrap()), Some(&3.7e10));
+        assert_eq!(inv.reference_time(), None);
+    }
+
+    #[test]
+    fn table() {
+        let mut inv = Inventory::new();
+        inv.add("Sr-90".parse().unwrap(), 3e6);
+        inv.add("Y-90".parse().unwrap(), 1e6);
+
+        let table = inv.to_string();
+        let mut lines = table.lines().skip(1);
+        assert!(lines.next().unwrap().ends_with("3 MBq        75"));
+        assert!(lines.next().unwrap().ends_with("1 MBq        25"));
+
+        let table = inv.to_table_string(&FixtureDecayData::new());
+        assert!(table.contains("28.79 y"));
+        assert!(table.contains("64.1 h"));
+
+        let number = NumberFormat {
+            significant_digits: 1,
+            ..NumberFormat::DEFAULT
+        };
+        let table = inv.to_table_string_with(&FixtureDecayData::new(), number);
+        assert!(table.lines().nth(1).unwrap().ends_with("3 MBq        80"));
+    }
+
+    /// Fixture decay data with the nuclide mass of Sr-90 only
+    struct Masses(FixtureDecayData);
+
+    impl NuclideHalfLife for Masses {
+        fn half_life(&self, nuclide: Nuclide) -> Result<HalfLife, Error> {
+            self.0.half_life(nuclide)
+        }
+    }
+
+    impl NuclideAtomicMass for Masses {
+        fn nuclide_atomic_mass(&self, nuclide: Nuclide) -> Result<f64, Error> {
+            match nuclide.to_string().as_str() {
+                "Sr-90" => Ok(89.907728),
+                _ => Err(Error::InvalidNuclide(nuclide.to_string())),
+            }
+        }
+    }
+
+    struct Elements;
+
+    impl AtomicMass for Elements {
+        fn atomic_mass(&self, symbol: Symbol) -> Result<f64, Error> {
+            match symbol {
+                Symbol::Y => Ok(88.905838),
+                _ => Err(Error::InvalidSymbol(symbol.to_string())),
+            }
+        }
+    }
+
+    /// Made-up decay energies (MeV/nt), not dataset values
+    struct Energies;
+
+    impl NuclideDecayEnergy for Energies {
+        fn decay_energy(&self, nuclide: Nuclide) -> Result<f64, Error> {
+            match nuclide.to_string().as_str() {
+                "Cs-137" => Ok(0.2),
+                "Tc-99m" => Ok(0.1),
+                _ => Err(Error::InvalidNuclide(nuclide.to_string())),
+            }
+        }
+    }
+
+    #[test]
+    fn decay_heat() {
+        let mut inv = inventory();
+        inv.add(Nuclide::FissionProducts, 1e9);
+
+        let by_nuclide = inv.decay_heat_by_nuclide(&Energies).unwrap();
+        assert_eq!(by_nuclide.len(), 2);
+        assert_eq!(by_nuclide[&"Tc-99m".parse().unwrap()], 2. * 0.1 * MEV);
+        let expected = (1.5e6 * 0.2 + 2. * 0.1) * MEV;
+        assert!((inv.decay_heat(&Energies).unwrap() - expected).abs() < 1e-12 * expected);
+
+        inv.add("Sr-90".parse().unwrap(), 1.);
+        assert!(inv.decay_heat(&Energies).is_err());
+    }
+
+    #[test]
+    fn by_element() {
+        let mut inv = Inventory::new();
+        inv.add("Sr-90".parse().unwrap(), 3e6);
+        inv.add("Y-90".parse().unwrap(), 1e6);
+        inv.add("Y-90".parse().unwrap(), 1e6);
+        let data = Masses(FixtureDecayData::new());
+
+        let elements = inv.by_element(&data, None).unwrap();
+        assert_eq!(elements.len(), 2);
+        assert_eq!(elements[&Symbol::Y].activity, 2e6);
+
+        // specific activity of Sr-90: 5.1 TBq/g
+        let sr = elements[&Symbol::Sr];
+        assert!((sr.activity / sr.mass / 5.1e12 - 1.).abs() < 0.01);
+
+        // Y-90 falls back to the mass number, then to the element
+        let y90 = "Y-90".parse().unwrap();
+        let moles = inv.moles(&data).unwrap()[&y90];
+        let masses = inv.masses(&data, None).unwrap();
+        assert_eq!(
+            masses[&y90],
+            NuclideMass {
+                mass: moles * 90.,
+                source: MassSource::MassNumber
+            }
+        );
+        let sr90 = "Sr-90".parse().unwrap();
+        assert_eq!(masses[&sr90].source, MassSource::Nuclide);
+        let masses = inv.masses(&data, Some(&Elements)).unwrap();
+        assert_eq!(
+            masses[&y90],
+            NuclideMass {
+                mass: moles * 88.905838,
+                source: MassSource::Element
+            }
+        );
+
+        let mut xe = Inventory::new();
+        xe.add("Xe-131m".parse().unwrap(), 1.);
+        assert!(matches!(
+            xe.masses(&data, Some(&Elements)),
+            Err(Error::InvalidSymbol(_))
+        ));
+    }
+
+    #[test]
+    fn fission_products_without_mass() {
+        let mut inv = Inventory::new();
+        inv.add("Sr-90".parse().unwrap(), 3e6);
+        inv.add(Nuclide::FissionProducts, 1e9);
+        let data = Masses(FixtureDecayData::new());
+
+        let masses = inv.masses(&data, None).unwrap();
+        assert_eq!(masses.len(), 1);
+        assert!(masses.contains_key(&"Sr-90".parse().unwrap()));
+
+        let elements = inv.by_element(&data, None).unwrap();
+        assert_eq!(elements.len(), 1);
+        assert_eq!(elements[&Symbol::Sr].activity, 3e6);
+    }
+
+    #[test]
+    fn activity_units() {
+        let sr90: Nuclide = "Sr-90".parse().unwrap();
+        let mut inv = Inventory::new();
+        inv.add_with_unit(sr90, 1., ActivityUnit::MilliCi);
+        inv.add_with_unit(sr90, 60., ActivityUnit::Dpm);
+        assert!((inv[&sr90] - 3.7e7 - 1.).abs() < 1e-6);
+        assert!((inv.to_unit(ActivityUnit::MegaBq)[&sr90] - 37.000001).abs() < 1e-9);
+
+        let data = FixtureDecayData::new();
+        let atoms = inv.atoms(&data).unwrap()[&sr90];
+        assert!(
+            (atoms * data.half_life(sr90).unwrap().as_lambda() / inv[&sr90] - 1.).abs() < 1e-12
+        );
+        assert!((inv.moles(&data).unwrap()[&sr90] * AVOGADRO / atoms - 1.).abs() < 1e-12);
+    }
+
+    #[test]
+    fn mass_and_atoms() {
+        let sr90: Nuclide = "Sr-90".parse().unwrap();
+        let data = Masses(FixtureDecayData::new());
+
+        // specific activity of Sr-90: 5.1 TBq/g
+        let mut inv = Inventory::new();
+        inv.add_mass(&data, None, sr90, 1e-3).unwrap();
+        assert!((inv[&sr90] / 5.1e9 - 1.).abs() < 0.01);
+        assert!((inv.mass_of(&data, None, sr90).unwrap() / 1e-3 - 1.).abs() < 1e-12);
+        assert_eq!(
+            inv.mass_of(&data, None, sr90).unwrap(),
+            inv.masses(&data, None).unwrap()[&sr90].mass
+        );
+
+        inv.add_atoms(&data, sr90, 1e6).unwrap();
+        let atoms = 1e-3 / data.nuclide_atomic_mass(sr90).unwrap() * AVOGADRO + 1e6;
+        assert!((inv.atoms_of(&data, sr90).unwrap() / atoms - 1.).abs() < 1e-12);
+
+        // Y-90 falls back to the element, as in the masses of the inventory
+        let y90: Nuclide = "Y-90".parse().unwrap();
+        assert_eq!(inv.mass_of(&data, None, y90).unwrap(), 0.);
+        inv.add_mass(&data, Some(&Elements), y90, 1e-6).unwrap();
+        assert!((inv.mass_of(&data, Some(&Elements), y90).unwrap() / 1e-6 - 1.).abs() < 1e-12);
+        assert!(inv
+            .add_mass(&data, None, Nuclide::FissionProducts, 1.)
+            .is_err());
+    }
+
+    #[test]
+    fn half_life_profile() {
+        let data = FixtureDecayData::new();
+        let mut inv = inventory();
+        inv.add(Nuclide::FissionProducts, 3.);
+
+        let (profile, unknown) = inv.half_life_profile(&data, &HalfLifeBins::default());
+        // Tc-99m in 1 h–1 d, Cs-137 in 1 y–1000 y
+        assert_eq!(profile, vec![0., 0., 2., 0., 1.5e6, 0., 0.]);
+        assert_eq!(unknown, 3.);
+    }
+
+    #[test]
+    fn by_category() {
+        let mut inv = inventory();
+        inv.add("Ra-226".parse().unwrap(), 10.);
+        inv.add("Pu-239".parse().unwrap(), 20.);
+        inv.add("Tl-201".parse().unwrap(), 30.);
+
+        let totals = inv.by_category(&NuclideCategories::bundled());
+        assert_eq!(totals[&NuclideCategory::FissionProduct], 1.5e6 + 2.0);
+        assert_eq!(totals[&NuclideCategory::Norm], 10.);
+        assert_eq!(totals[&NuclideCategory::Actinide], 20.);
+        assert_eq!(totals[&NuclideCategory::Other], 30.);
+        assert!(!totals.contains_key(&NuclideCategory::ActivationProduct));
+    }
+
+    #[test]
+    fn merge_at() {
+        let solver = BatemanDecaySolver::new(Arc::new(FixtureDecayData::new()));
+        let (sr90, y90): (Nuclide, Nuclide) = ("Sr-90".parse().unwrap(), "Y-90".parse().unwrap());
+        let t0: DateTime<Utc> = "2000-01-01T00:00:00Z".parse().unwrap();
+        let half_life = FixtureDecayData::new().half_life(sr90).unwrap().as_sec();
+        let t1 = t0 + chrono::Duration::milliseconds((half_life * 1e3) as i64);
+
+        let mut inv = Inventory::new();
+        inv.add(sr90, 1e6);
+        let merged =
+            Inventory::merge_at(&solver, t1, &[(inv.clone(), t0), (inv.clone(), t1)]).unwrap();
+
+        assert!((merged[&sr90] / 1.5e6 - 1.).abs() < 1e-6);
+        // Y-90 in secular equilibrium with the older Sr-90, none yet grown in the newer
+        assert!((merged[&y90] / 0.5e6 - 1.).abs() < 1e-3);
+        assert_eq!(merged.reference_time(), Some(t1));
+
+        // 30 days later Y-90 (64.0 h) is still in equilibrium with both
+        let t2 = t1 + chrono::Duration::days(30);
+        let merged =
+            Inventory::merge_at(&solver, t2, &[(inv.clone(), t0), (inv.clone(), t1)]).unwrap();
+        assert!((merged[&y90] / merged[&sr90] - 1.).abs() < 1e-3);
+
+        // backwards to the time of the first assay
+        assert!(matches!(
+            Inventory::merge_at(&solver, t0, &[(inv.clone(), t1)]),
+            Err(Error::InvalidParameter(_))
+        ));
+    }
+
+    struct Lines;
+
+    impl NuclidePhotonLines for Lines {
+        fn photon_lines(&self, nuclide: Nuclide) -> Result<Vec<(Energy, f64)>, Error> {
+            Ok(match nuclide.to_string().as_str() {
+                "Co-60" => vec![(Energy::mev(1.173), 0.9985), (Energy::mev(1.332), 0.9998)],
+                "Ba-137m" => vec![(Energy::mev(0.662), 0.8991)],
+                // made-up lines sharing an energy between isomers
+                "Tc-99m" => vec![(Energy::mev(0.02), 0.1), (Energy::mev(0.14), 0.9)],
+                "Tc-99" => vec![(Energy::mev(0.02), 0.01)],
+                "Cs-137" => return Err(Error::InvalidNuclide(nuclide.to_string())),
+                _ => vec![],
+            })
+        }
+    }
+
+    #[test]
+    fn spectrum_summary() {
+        let mut inv = Inventory::new();
+        inv.add("Co-60".parse().unwrap(), 1e3);
+        inv.add("Ba-137m".parse().unwrap(), 1e3);
+        inv.add("Sr-90".parse().unwrap(), 1e3);
+        inv.add("Cs-137".parse().unwrap(), 1e3);
+        inv.add(Nuclide::FissionProducts, 1e3);
+
+        let WithWarnings {
+            value: summary,
+            warnings,
+        } = inv.spectrum_summary(&Lines).unwrap();
+        let n = 1e3 * (0.9985 + 0.9998 + 0.8991);
+        let ne = 1e3 * (1.173 * 0.9985 + 1.332 * 0.9998 + 0.662 * 0.8991);
+        assert!((summary.photon_rate - n).abs() < 1e-9);
+        assert!((summary.energy_rate - ne).abs() < 1e-9);
+        assert!((summary.mean_energy.as_mev() - ne / n).abs() < 1e-12);
+        assert!(summary.effective_energy > summary.mean_energy);
+        assert_eq!(
+            warnings
+                .nuclides(SkipReason::NoPhotonLines)
+                .collect::<Vec<_>>(),
+            vec!["Cs-137".parse().unwrap()]
+        );
+        assert_eq!(warnings.nuclides(SkipReason::FissionProducts).count(), 1);
+
+        let empty = Inventory::new().spectrum_summary(&Lines).unwrap();
+        assert_eq!(empty.value, InventorySpectrumSummary::default());
+        assert!(empty.warnings.is_empty());
+    }
+
+    #[test]
+    fn photon_source() {
+        let (tc99m, tc99): (Nuclide, Nuclide) =
+            ("Tc-99m".parse().unwrap(), "Tc-99".parse().unwrap());
+        let mut inv = Inventory::new();
+        inv.add(tc99m, 1e3);
+        inv.add(tc99, 1e3);
+        inv.add("Cs-137".parse().unwrap(), 1e3);
+
+        let res = inv.photon_source(&Lines, LineAttribution::Emitter).unwrap();
+        assert_eq!(res.warnings.iter().count(), 1);
+        let lines = res.value;
+        assert_eq!(lines.len(), 3);
+        assert_eq!((lines[0].nuclide, lines[0].rate), (tc99, 10.));
+        assert_eq!((lines[1].nuclide, lines[1].rate), (tc99m, 100.));
+
+        let lines = inv
+            .photon_source(&Lines, LineAttribution::GroundState)
+            .unwrap()
+            .value;
+        assert_eq!(lines.len(), 2);
+        assert!(lines.iter().all(|line| line.nuclide == tc99));
+        assert!((lines[0].rate - 110.).abs() < 1e-12);
+        assert_eq!(lines[1].energy, Energy::mev(0.14));
+    }
+
+    #[test]
+    fn unsupported_version() {
+        let json = r#"{ "version": 2, "nuclides": [] }"#;
+
+        assert!(matches!(
+            Inventory::from_json(json),
+            Err(Error::UnsupportedSchemaVersion(2))
+        ));
+    }
+
+    #[test]
+    fn entry_points_agree() {
+        for json in [
+            r#"{ "version": 2, "nuclides": [] }"#,
+            r#"{ "nuclides": [] }"#,
+            r#"{ "Cs-137": 1.5e6 }"#,
+        ] {
+            assert!(Inventory::from_json(json).is_err());
+            assert!(serde_json::from_str::<Inventory>(json).is_err());
+        }
+
+        let json = inventory().to_json().unwrap();
+        assert_eq!(
+            serde_json::from_str::<Inventory>(&json).unwrap(),
+            Inventory::from_json(&json).unwrap()
+        );
+    }
+}
@@ -0,0 +1,114 @@
+use petgraph::visit::EdgeRef;
+use petgraph::Direction;
+
+use super::DecayChain;
+use crate::primitive::Nuclide;
+
+/// Node of a [`ChainLayout`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LayoutNode {
+    pub nuclide: Nuclide,
+    /// Number of decays along the longest path from the root
+    pub layer: usize,
+    pub x: f64,
+    pub y: f64,
+}
+
+/// Decay chain placed on layers, one per decay generation
+///
+/// Layers are one unit apart along `y`, growing downwards from the root at
+/// `y = 0`, and the nodes of a layer one unit apart along `x`, centred on
+/// `x = 0`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ChainLayout {
+    /// In the order of the chain node indices
+    pub nodes: Vec<LayoutNode>,
+    /// Parent and daughter positions in `nodes`
+    pub edges: Vec<(usize, usize)>,
+}
+
+impl DecayChain {
+    /// Layered layout of the chain for drawing without Graphviz
+    ///
+    /// A nuclide sits on the layer of its longest decay path from the root,
+    /// so ingrowth reads top to bottom. Within a layer nuclides are ordered by
+    /// the mean position of their parents, which keeps the branches of a
+    /// nuclide next to each other and limits edge crossings.
+    pub fn layout(&self) -> ChainLayout {
+        let depth = self.depths();
+        let layers = depth.iter().max().map_or(0, |&d| d + 1);
+
+        let mut x = vec![0f64; self.node_count()];
+        let mut nodes = Vec::with_capacity(self.node_count());
+        for layer in 0..layers {
+            let mut members: Vec<(f64, Nuclide, usize)> = self
+                .node_indices()
+                .filter(|i| depth[i.index()] == layer)
+                .map(|i| {
+                    let parents: Vec<f64> = self
+                        .neighbors_directed(i, Direction::Incoming)
+                        .map(|p| x[p.index()])
+                        .collect();
+                    let barycenter = if parents.is_empty() {
+                        0.
+                    } else {
+                        parents.iter().sum::<f64>() / parents.len() as f64
+                    };
+                    (barycenter, self[i].nuclide(), i.index())
+                })
+                .collect();
+            members.sort_by(|a, b| a.0.total_cmp(&b.0).then(a.1.cmp(&b.1)));
+
+            let offset = (members.len() as f64 - 1.) / 2.;
+            for (k, &(_, _, i)) in members.iter().enumerate() {
+                x[i] = k as f64 - offset;
+            }
+        }
+
+        for i in self.node_indices() {
+            nodes.push(LayoutNode {
+                nuclide: self[i].nuclide(),
+                layer: depth[i.index()],
+                x: x[i.index()],
+                y: depth[i.index()] as f64,
+            });
+        }
+
+        ChainLayout {
+            nodes,
+            edges: self
+                .edge_references()
+                .map(|e| (e.source().index(), e.target().index()))
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::decaychain::DecayChainBuilder;
+    use crate::fixtures::FixtureDecayData;
+
+    #[test]
+    fn layered_chain() {
+        let data = Arc::new(FixtureDecayData::new());
+        let chain = DecayChainBuilder::new(data).build("Po-218".parse().unwrap());
+        let layout = chain.layout();
+
+        let node = |name: &str| {
+            let nuclide: Nuclide = name.parse().unwrap();
+            *layout.nodes.iter().find(|n| n.nuclide == nuclide).unwrap()
+        };
+        assert_eq!(node("Po-218").layer, 0);
+        assert_eq!(node("Po-218").x, 0.);
+        assert_eq!(node("Pb-214").layer, 1);
+        assert_eq!(node("At-218").layer, 1);
+        assert_eq!(node("Pb-214").x + node("At-218").x, 0.);
+        // reached in one decay from At-218 but two from Pb-214
+        assert_eq!(node("Bi-214").layer, 2);
+        assert_eq!(layout.edges.len(), chain.edge_count());
+    }
+}
@@ -0,0 +1,210 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::Arc;
+
+use super::Inventory;
+use crate::error::Error;
+use crate::primitive::attr::{DecayConstant, NuclideProgeny};
+use crate::primitive::Nuclide;
+
+/// Matrix A of dN/dt = A N over the numbers of atoms of a chain
+///
+/// Nuclides are indexed in ascending order. The matrix is kept sparse as
+/// (row, column, value) entries (1/s); diagonal entries are the negated
+/// decay constants, off-diagonal ones the production rates of the row
+/// nuclide per atom of the column nuclide.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecayMatrix {
+    nuclides: Vec<Nuclide>,
+    lambda: Vec<f64>,
+    entries: BTreeMap<(usize, usize), f64>,
+}
+
+impl DecayMatrix {
+    pub fn nuclides(&self) -> &[Nuclide] {
+        &self.nuclides
+    }
+
+    /// Decay constants of the nuclides (1/s)
+    pub fn decay_constants(&self) -> &[f64] {
+        &self.lambda
+    }
+
+    pub fn index(&self, nuclide: Nuclide) -> Option<usize> {
+        self.nuclides.binary_search(&nuclide).ok()
+    }
+
+    /// Non-zero entries (row, column, value) in row-major order
+    pub fn entries(&self) -> impl Iterator<Item = (usize, usize, f64)> + '_ {
+        self.entries.iter().map(|(&(i, j), &v)| (i, j, v))
+    }
+
+    /// Add a reaction of `rate` (1/s) per atom, e.g. a neutron capture rate
+    /// σφ from a transport code, turning the decay matrix into a burnup matrix
+    ///
+    /// `product` of `None` removes atoms without production, e.g. for a
+    /// product outside of the chain.
+    pub fn add_reaction(
+        &mut self,
+        target: Nuclide,
+        product: Option<Nuclide>,
+        rate: f64,
+    ) -> Result<(), Error> {
+        let index = |n: Nuclide| {
+            self.index(n)
+                .ok_or_else(|| Error::InvalidNuclide(n.to_string()))
+        };
+        let j = index(target)?;
+        let i = product.map(index).transpose()?;
+
+        *self.entries.entry((j, j)).or_default() -= rate;
+        if let Some(i) = i {
+            *self.entries.entry((i, j)).or_default() += rate;
+        }
+
+        Ok(())
+    }
+
+    /// Dense row-major matrix
+    pub fn to_dense(&self) -> Vec<Vec<f64>> {
+        let mut res = vec![vec![0.; self.nuclides.len()]; self.nuclides.len()];
+        for (i, j, v) in self.entries() {
+            res[i][j] = v;
+        }
+        res
+    }
+
+    #[cfg(feature = "ndarray")]
+    pub fn to_ndarray(&self) -> ndarray::Array2<f64> {
+        let mut res = ndarray::Array2::zeros((self.nuclides.len(), self.nuclides.len()));
+        for (i, j, v) in self.entries() {
+            res[[i, j]] = v;
+        }
+        res
+    }
+
+    /// Numbers of atoms of the matrix nuclides in an inventory (Bq)
+    pub fn atoms(&self, inventory: &Inventory) -> Vec<f64> {
+        let mut res = vec![0.; self.nuclides.len()];
+        for (&nuclide, &activity) in inventory.iter() {
+            if let Some(i) = self.index(nuclide) {
+                res[i] += activity / self.lambda[i];
+            }
+        }
+        res
+    }
+
+    /// Inventory (Bq) of numbers of atoms of the matrix nuclides
+    pub fn activities(&self, atoms: &[f64]) -> Inventory {
+        let mut res = Inventory::new();
+        for ((&nuclide, &lambda), &n) in self.nuclides.iter().zip(&self.lambda).zip(atoms) {
+            res.add(nuclide, lambda * n.max(0.));
+        }
+        res
+    }
+}
+
+/// Builder of the [`DecayMatrix`] of nuclides and their radioactive progeny
+///
+/// Progeny without a decay constant, i.e. stable nuclides, are left out.
+#[derive(Debug)]
+pub struct DecayMatrixBuilder<D: ?Sized> {
+    decay_data: Arc<D>,
+    nuclides: BTreeSet<Nuclide>,
+}
+
+impl<D> DecayMatrixBuilder<D>
+where
+    D: NuclideProgeny + DecayConstant + ?Sized,
+{
+    pub fn new(decay_data: Arc<D>) -> Self {
+        Self {
+            decay_data,
+            nuclides: BTreeSet::new(),
+        }
+    }
+
+    pub fn nuclide(mut self, nuclide: Nuclide) -> Self {
+        self.nuclides.insert(nuclide);
+        self
+    }
+
+    /// Nuclides of an inventory
+    pub fn inventory(mut self, inventory: &Inventory) -> Self {
+        self.nuclides.extend(inventory.keys().copied());
+        self
+    }
+
+    pub fn build(self) -> DecayMatrix {
+        let mut lambda = BTreeMap::new();
+        let mut stack: Vec<Nuclide> = self.nuclides.into_iter().collect();
+        while let Some(nuclide) = stack.pop() {
+            if lambda.contains_key(&nuclide) {
+                continue;
+            }
+            let Ok(l) = self.decay_data.lambda(nuclide) else {
+                continue;
+            };
+            lambda.insert(nuclide, l);
+            stack.extend(
+                self.decay_data
+                    .progeny(nuclide)
+                    .unwrap_or_default()
+                    .iter()
+                    .map(|p| p.nuclide),
+            );
+        }
+
+        let mut matrix = DecayMatrix {
+            nuclides: lambda.keys().copied().collect(),
+            lambda: lambda.values().copied().collect(),
+            entries: BTreeMap::new(),
+        };
+        for (j, &parent) in matrix.nuclides.iter().enumerate() {
+            matrix.entries.insert((j, j), -matrix.lambda[j]);
+            for daughter in self.decay_data.progeny(parent).unwrap_or_default() {
+                if let Some(i) = matrix.index(daughter.nuclide) {
+                    *matrix.entries.entry((i, j)).or_default() +=
+                        daughter.branch_rate * matrix.lambda[j];
+                }
+            }
+        }
+
+        matrix
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::fixtures::FixtureDecayData;
+
+    #[test]
+    fn decay_matrix() {
+        let cs137: Nuclide = "Cs-137".parse().unwrap();
+        let ba137m: Nuclide = "Ba-137m".parse().unwrap();
+
+        let mut matrix = DecayMatrixBuilder::new(Arc::new(FixtureDecayData::new()))
+            .nuclide(cs137)
+            .build();
+        assert_eq!(matrix.nuclides(), &[cs137, ba137m]);
+
+        let dense = matrix.to_dense();
+        let lambda = matrix.decay_constants().to_vec();
+        assert_eq!(dense[0][0], -lambda[0]);
+        assert!((dense[1][0] - 0.944 * lambda[0]).abs() < 1e-12 * lambda[0]);
+        assert_eq!(dense[0][1], 0.);
+        assert_eq!(matrix.entries().count(), 3);
+
+        matrix.add_reaction(cs137, None, 1e-9).unwrap();
+        assert_eq!(matrix.to_dense()[0][0], -lambda[0] - 1e-9);
+        assert!(matrix
+            .add_reaction(cs137, Some("Cs-134".parse().unwrap()), 1.)
+            .is_err());
+
+        let mut inv = Inventory::new();
+        inv.add(cs137, 1.);
+        let atoms = matrix.atoms(&inv);
+        assert_eq!(atoms[1], 0.);
+        assert!((matrix.activities(&atoms)[&cs137] - 1.).abs() < 1e-12);
+    }
+}
@@ -1,63 +1,197 @@
+mod attribution;
+mod category;
+mod cram;
+pub mod crosscheck;
+mod fissile;
 mod graph;
-
-pub use graph::{DecayChain, DecayChainBuilder};
-
-use std::collections::BTreeMap;
-use std::ops::Deref;
+mod inventory;
+mod layout;
+mod matrix;
+mod ode;
+mod reactor;
+mod sensitivity;
+mod series;
+mod transition;
+
+pub use attribution::{MixtureFit, SourceMixture};
+pub use category::{NuclideCategories, NuclideCategory};
+pub use cram::CramDecaySolver;
+pub use fissile::{FissileLimit, FissileLimits, FissileWarning};
+pub use graph::{
+    to_dot, to_dot_with, ChainEdge, ChainNode, ChainStats, DecayChain, DecayChainBuilder,
+    Truncation,
+};
+pub use inventory::{
+    ElementTotal, Inventory, InventorySpectrumSummary, LineAttribution, MassSource, NuclideMass,
+    SourceLine, Threshold, INVENTORY_SCHEMA_VERSION,
+};
+pub use layout::{ChainLayout, LayoutNode};
+pub use matrix::{DecayMatrix, DecayMatrixBuilder};
+pub use ode::OdeDecaySolver;
+pub use reactor::{CoolingPoint, FissionProduct, FissionYields};
+pub use sensitivity::{
+    OutputSpread, Parameter, PerturbedDecayData, Sensitivity, SensitivityAnalysis,
+};
+pub use series::DecaySeries;
+pub use transition::TransitionMatrix;
+
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::{Arc, RwLock};
 
+use crate::error::Error;
 use crate::primitive::attr::{DecayConstant, NuclideProgeny};
 use crate::primitive::Nuclide;
+use crate::provenance::{
+    DatasetIdentity, DatasetInfo, Provenance, SkipReason, Warnings, WithProvenance, WithWarnings,
+};
 
-#[derive(Debug, Clone)]
-pub struct Inventory(BTreeMap<Nuclide, f64>);
+type CachedNode = BTreeMap<Nuclide, Vec<(Vec<f64>, Vec<f64>)>>;
+type CachedData = BTreeMap<Nuclide, Arc<CachedNode>>;
 
-impl Deref for Inventory {
-    type Target = BTreeMap<Nuclide, f64>;
+/// Activity ratios of the progeny of each parent, keyed by the bits of the step length
+#[derive(Debug, Default)]
+struct CachedSteps {
+    ratios: BTreeMap<u64, BTreeMap<Nuclide, Arc<BTreeMap<Nuclide, f64>>>>,
+    /// Step lengths from the least to the most recently used
+    order: VecDeque<u64>,
+}
 
-    fn deref(&self) -> &Self::Target {
-        &self.0
+impl CachedSteps {
+    fn get(&self, key: u64, nuclide: Nuclide) -> Option<&Arc<BTreeMap<Nuclide, f64>>> {
+        self.ratios.get(&key).and_then(|step| step.get(&nuclide))
     }
-}
 
-impl Inventory {
-    pub fn new() -> Self {
-        Self(BTreeMap::new())
+    fn is_latest(&self, key: u64) -> bool {
+        self.order.back() == Some(&key)
     }
 
-    pub fn add(&mut self, nuclide: Nuclide, activity: f64) {
-        let inner = &mut self.0;
-        *inner.entry(nuclide).or_insert(0.0) += activity;
+    /// Mark a step length as the most recently used
+    fn touch(&mut self, key: u64) {
+        if let Some(i) = self.order.iter().position(|&k| k == key) {
+            self.order.remove(i);
+            self.order.push_back(key);
+        }
     }
 
-    pub fn remove(&mut self, nuclide: Nuclide) -> Option<(Nuclide, f64)> {
-        self.0.remove_entry(&nuclide)
+    /// Insert the ratios of a parent, evicting the least recently used step
+    /// length beyond [`MAX_CACHED_STEPS`]
+    fn insert(&mut self, key: u64, nuclide: Nuclide, ratios: Arc<BTreeMap<Nuclide, f64>>) {
+        if self.ratios.contains_key(&key) {
+            self.touch(key);
+        } else {
+            if self.order.len() >= MAX_CACHED_STEPS {
+                if let Some(oldest) = self.order.pop_front() {
+                    self.ratios.remove(&oldest);
+                }
+            }
+            self.order.push_back(key);
+        }
+        self.ratios.entry(key).or_default().insert(nuclide, ratios);
+    }
+
+    #[cfg(test)]
+    fn len(&self) -> usize {
+        self.ratios.len()
+    }
+
+    fn clear(&mut self) {
+        self.ratios.clear();
+        self.order.clear();
     }
 }
 
-impl Default for Inventory {
-    fn default() -> Self {
-        Self::new()
+/// Number of step lengths whose activity ratios are kept by [`BatemanDecaySolver::step`]
+pub const MAX_CACHED_STEPS: usize = 16;
+
+/// Progeny, product of the decay constants and branch ratios of its path, and
+/// each decay constant with its denominator
+type PathTerms = Vec<(Nuclide, f64, Vec<(f64, f64)>)>;
+
+/// Nuclides of an inventory the solvers leave out for lack of a decay constant
+pub(crate) fn skipped<D: DecayConstant + ?Sized>(data: &D, inventory: &Inventory) -> Warnings {
+    let mut warnings = Warnings::new();
+    for &nuclide in inventory.keys() {
+        if data.lambda(nuclide).is_err() {
+            let reason = match nuclide {
+                Nuclide::FissionProducts => SkipReason::FissionProducts,
+                Nuclide::WithId(_) => SkipReason::NoDecayConstant,
+            };
+            warnings.push(nuclide, reason);
+        }
     }
+
+    warnings
 }
 
-type CachedNode = BTreeMap<Nuclide, Vec<(Vec<f64>, Vec<f64>)>>;
-type CachedData = BTreeMap<Nuclide, Arc<CachedNode>>;
+/// Decay of an inventory, implemented by the analytical and the numerical solvers
+pub trait DecaySolver {
+    /// Decay calculation for decay_time in seconds.
+    fn decay(&self, inventory: &Inventory, decay_time: f64) -> Inventory;
+
+    /// Solver type recorded in the provenance of its calculations
+    fn solver_name(&self) -> &str {
+        std::any::type_name::<Self>()
+    }
+
+    /// Solver options recorded in the provenance of its calculations
+    fn solver_options(&self) -> BTreeMap<String, String> {
+        BTreeMap::new()
+    }
+
+    /// Decay calculation for decay_time in seconds, with the provenance record of the calculation.
+    fn decay_with_provenance(
+        &self,
+        inventory: &Inventory,
+        decay_time: f64,
+    ) -> WithProvenance<Inventory>
+    where
+        Self: DatasetInfo,
+    {
+        let recorder = self.solver_options().into_iter().fold(
+            Provenance::start(self.dataset_identity(), self.solver_name()),
+            |recorder, (name, value)| recorder.option(&name, value),
+        );
+        let value = self.decay(inventory, decay_time);
+
+        WithProvenance {
+            value,
+            provenance: recorder.finish(),
+        }
+    }
+}
 
 #[derive(Debug)]
-pub struct BatemanDecaySolver<D> {
+pub struct BatemanDecaySolver<D: ?Sized> {
     decay_data: Arc<D>,
     pub cache: RwLock<CachedData>,
+    steps: RwLock<CachedSteps>,
+    /// [`NuclideProgeny::epoch`] of the data the caches were worked out from
+    epoch: AtomicU64,
+    min_branch_ratio: f64,
 }
 
 impl<D> BatemanDecaySolver<D>
 where
-    D: NuclideProgeny + DecayConstant,
+    D: NuclideProgeny + DecayConstant + ?Sized,
 {
     pub fn new(decay_data: Arc<D>) -> Arc<Self> {
+        Self::with_min_branch_ratio(decay_data, 0.)
+    }
+
+    /// Solver skipping the decay paths whose product of branch ratios is below
+    /// `ratio`, e.g. the minor branches of long actinide chains, like
+    /// [`DecayChainBuilder::min_branch_ratio`]
+    ///
+    /// A skipped path ends at its first nuclide below `ratio`, whose activity
+    /// is kept while its progeny are left out.
+    pub fn with_min_branch_ratio(decay_data: Arc<D>, ratio: f64) -> Arc<Self> {
         Arc::new(Self {
-            decay_data,
             cache: RwLock::new(BTreeMap::new()),
+            steps: RwLock::new(CachedSteps::default()),
+            epoch: AtomicU64::new(decay_data.epoch()),
+            decay_data,
+            min_branch_ratio: ratio,
         })
     }
 
@@ -76,33 +210,204 @@ where
         inv
     }
 
+    /// Decay calculation for decay_time in seconds, with the nuclides of the
+    /// inventory left out for lack of a decay constant
+    pub fn decay_with_warnings(
+        &self,
+        inventory: &Inventory,
+        decay_time: f64,
+    ) -> WithWarnings<Inventory> {
+        WithWarnings {
+            value: self.decay(inventory, decay_time),
+            warnings: skipped(&*self.decay_data, inventory),
+        }
+    }
+
+    /// Decay an inventory in place by `dt` (s), advancing its reference time
+    ///
+    /// The activity ratios of each parent are kept per step length, so that
+    /// repeated steps of the same `dt` with modifications in between only
+    /// multiply and add instead of evaluating the Bateman equations again,
+    /// for the [`MAX_CACHED_STEPS`] most recently used step lengths.
+    ///
+    /// A negative or non-finite `dt`, or a reference time moved out of range,
+    /// is an error and leaves the inventory untouched. The nuclides left out
+    /// for lack of a decay constant are returned.
+    pub fn step(&self, inventory: &mut Inventory, dt: f64) -> Result<Warnings, Error> {
+        if !(dt >= 0. && dt.is_finite()) {
+            return Err(Error::InvalidParameter(format!("decay step {} (s)", dt)));
+        }
+        let reference_time = match inventory.reference_time() {
+            Some(t) => {
+                let nanos = dt * 1e9;
+                let t = (nanos < i64::MAX as f64)
+                    .then(|| chrono::Duration::nanoseconds(nanos as i64))
+                    .and_then(|dt| t.checked_add_signed(dt))
+                    .ok_or_else(|| {
+                        Error::InvalidParameter(format!(
+                            "decay step {} (s) out of the range of the reference time",
+                            dt
+                        ))
+                    })?;
+                Some(t)
+            }
+            None => None,
+        };
+
+        let warnings = skipped(&*self.decay_data, inventory);
+        let mut activity = BTreeMap::new();
+        for (&nuclide, &a) in inventory.iter() {
+            if let Some(ratios) = self.step_ratios(nuclide, dt) {
+                for (&nuc, &ratio) in ratios.iter() {
+                    *activity.entry(nuc).or_insert(0.) += a * ratio;
+                }
+            }
+        }
+
+        inventory.replace_activity(activity);
+        inventory.set_reference_time(reference_time);
+
+        Ok(warnings)
+    }
+
+    fn step_ratios(&self, nuclide: Nuclide, dt: f64) -> Option<Arc<BTreeMap<Nuclide, f64>>> {
+        let epoch = self.invalidate_stale();
+        let key = dt.to_bits();
+        let cached = {
+            let steps = self.steps.read().unwrap();
+            steps
+                .get(key, nuclide)
+                .map(|ratios| (ratios.clone(), steps.is_latest(key)))
+        };
+        if let Some((ratios, latest)) = cached {
+            if !latest {
+                self.steps.write().unwrap().touch(key);
+            }
+            return Some(ratios);
+        }
+
+        let ratios = Arc::new(self.bateman_eq(nuclide, dt)?);
+        let mut steps = self.steps.write().unwrap();
+        if self.epoch.load(Ordering::Acquire) != epoch {
+            return Some(ratios);
+        }
+        steps.insert(key, nuclide, ratios.clone());
+
+        Some(ratios)
+    }
+
+    /// Time-integrated activities (Bq s) between `t_start` and `t_end` (s),
+    /// the numbers of disintegrations of each nuclide
+    ///
+    /// The exponentials of the Bateman equations are integrated analytically.
+    pub fn integrated_activity(
+        &self,
+        inventory: &Inventory,
+        t_start: f64,
+        t_end: f64,
+    ) -> Inventory {
+        let mut inv = Inventory::new();
+
+        for (&nuclide, &activity) in inventory.iter() {
+            let integral = self.bateman_terms(nuclide, |li| {
+                ((-li * t_start).exp() - (-li * t_end).exp()) / li
+            });
+            if let Some(integral) = integral {
+                for (nuc, res) in integral {
+                    inv.add(nuc, activity * res);
+                }
+            }
+        }
+
+        inv
+    }
+
+    /// [`Self::integrated_activity`] with the nuclides of the inventory left
+    /// out for lack of a decay constant
+    pub fn integrated_activity_with_warnings(
+        &self,
+        inventory: &Inventory,
+        t_start: f64,
+        t_end: f64,
+    ) -> WithWarnings<Inventory> {
+        WithWarnings {
+            value: self.integrated_activity(inventory, t_start, t_end),
+            warnings: skipped(&*self.decay_data, inventory),
+        }
+    }
+
     // Bateman Equation
     pub fn bateman_eq(&self, nuclide: Nuclide, dt: f64) -> Option<BTreeMap<Nuclide, f64>> {
-        if let Some(cache) = self.cached_vars(nuclide) {
-            let mut res = BTreeMap::new();
-            for (&nuc, vars) in cache.iter() {
-                for (br, lamb) in vars {
-                    *res.entry(nuc).or_insert(0.) += lamb[1..].iter().product::<f64>()
-                        * br.iter().product::<f64>()
-                        * (lamb.iter().enumerate())
-                            .map(|(i, &li)| {
-                                (-li * dt).exp()
-                                    / (lamb.iter().enumerate().filter(|(j, _)| i != *j))
-                                        .map(|(_, &lj)| lj - li)
-                                        .product::<f64>()
-                            })
-                            .sum::<f64>();
-                }
+        self.bateman_terms(nuclide, |li| (-li * dt).exp())
+    }
+
+    /// Bateman equations with `exp(-λ t)` replaced by `term(λ)`
+    fn bateman_terms<F: Fn(f64) -> f64>(
+        &self,
+        nuclide: Nuclide,
+        term: F,
+    ) -> Option<BTreeMap<Nuclide, f64>> {
+        let mut res = BTreeMap::new();
+        for (nuc, factor, terms) in self.path_terms(nuclide)? {
+            *res.entry(nuc).or_insert(0.) += factor
+                * terms
+                    .iter()
+                    .map(|&(li, denom)| term(li) / denom)
+                    .sum::<f64>();
+        }
+
+        Some(res)
+    }
+
+    /// Decay path of each progeny as the product of its decay constants and
+    /// branch ratios, with the decay constants and their denominators
+    /// `Π (λj - λi)`, which do not depend on time
+    fn path_terms(&self, nuclide: Nuclide) -> Option<PathTerms> {
+        let cache = self.cached_vars(nuclide)?;
+
+        let mut paths = vec![];
+        for (&nuc, vars) in cache.iter() {
+            for (br, lamb) in vars {
+                let factor = lamb[1..].iter().product::<f64>() * br.iter().product::<f64>();
+                let terms = (lamb.iter().enumerate())
+                    .map(|(i, &li)| {
+                        let denom = (lamb.iter().enumerate().filter(|(j, _)| i != *j))
+                            .map(|(_, &lj)| lj - li)
+                            .product::<f64>();
+                        (li, denom)
+                    })
+                    .collect();
+                paths.push((nuc, factor, terms));
             }
+        }
 
-            Some(res)
-        } else {
-            None
+        Some(paths)
+    }
+
+    /// Drop the cached chains and steps once the data has changed, returning
+    /// the epoch of the data
+    ///
+    /// The epoch is updated under the write locks of both caches, and entries
+    /// are only inserted while it is still the epoch they were worked out at,
+    /// so that no entry of stale data outlives the clearing.
+    fn invalidate_stale(&self) -> u64 {
+        let epoch = self.decay_data.epoch();
+        if self.epoch.load(Ordering::Acquire) != epoch {
+            let mut cache = self.cache.write().unwrap();
+            let mut steps = self.steps.write().unwrap();
+            if self.epoch.load(Ordering::Acquire) != epoch {
+                cache.clear();
+                steps.clear();
+                self.epoch.store(epoch, Ordering::Release);
+            }
         }
+
+        epoch
     }
 
     // Variables for calculate with Bateman Equation
     fn cached_vars(&self, parent: Nuclide) -> Option<Arc<CachedNode>> {
+        let epoch = self.invalidate_stale();
         let cache = self.cache.read().unwrap();
 
         if let Some(brs_lambs) = cache.get(&parent) {
@@ -111,43 +416,77 @@ where
             drop(cache);
             let mut cache = self.cache.write().unwrap();
 
-            let mut stack = vec![(parent, vec![], vec![self.decay_data.lambda(parent).ok()?])];
+            let mut stack = vec![(
+                parent,
+                vec![],
+                vec![self.decay_data.lambda(parent).ok()?],
+                false,
+            )];
             let mut brs_lambs: CachedNode = BTreeMap::new();
 
-            while let Some((parent, br, lambda)) = stack.pop() {
+            while let Some((parent, br, lambda, skipped)) = stack.pop() {
                 brs_lambs
                     .entry(parent)
                     // .or_insert(vec![])
                     .or_default()
                     .push((br.clone(), lambda.clone()));
+                if skipped {
+                    continue;
+                }
 
                 for daughter in self.decay_data.progeny(parent).unwrap() {
                     if let Ok(lambda_d) = self.decay_data.lambda(daughter.nuclide) {
                         let mut br = br.clone();
                         br.push(daughter.branch_rate);
+                        let skipped = br.iter().product::<f64>() < self.min_branch_ratio;
                         let mut lambda = lambda.clone();
                         lambda.push(lambda_d);
-                        stack.push((daughter.nuclide, br, lambda));
+                        stack.push((daughter.nuclide, br, lambda, skipped));
                     }
                 }
             }
 
             let brs_lambs = Arc::new(brs_lambs);
-            cache.insert(parent, brs_lambs.clone());
+            if self.epoch.load(Ordering::Acquire) == epoch {
+                cache.insert(parent, brs_lambs.clone());
+            }
 
             Some(brs_lambs)
         }
     }
 }
 
+impl<D> DecaySolver for BatemanDecaySolver<D>
+where
+    D: NuclideProgeny + DecayConstant + ?Sized,
+{
+    fn decay(&self, inventory: &Inventory, decay_time: f64) -> Inventory {
+        BatemanDecaySolver::decay(self, inventory, decay_time)
+    }
+
+    fn solver_name(&self) -> &str {
+        "BatemanDecaySolver"
+    }
+
+    fn solver_options(&self) -> BTreeMap<String, String> {
+        BTreeMap::from([(
+            "min_branch_ratio".to_string(),
+            self.min_branch_ratio.to_string(),
+        )])
+    }
+}
+
+impl<D: DatasetInfo + ?Sized> DatasetInfo for BatemanDecaySolver<D> {
+    fn dataset_identity(&self) -> DatasetIdentity {
+        self.decay_data.dataset_identity()
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
 
-    use crate::{
-        error::Error,
-        primitive::{DecayModeSet, Progeny},
-    };
+    use crate::primitive::{DecayModeSet, Progeny};
 
     struct TestData {
         progeny: BTreeMap<Nuclide, Vec<Progeny>>,
@@ -205,6 +544,12 @@ mod test {
         }
     }
 
+    impl DatasetInfo for TestData {
+        fn dataset_identity(&self) -> DatasetIdentity {
+            DatasetIdentity::new("test")
+        }
+    }
+
     #[test]
     fn bateman_solver() {
         let data = TestData::new();
@@ -238,4 +583,231 @@ mod test {
             )
         );
     }
+
+    #[test]
+    fn decay_series() {
+        let solver = BatemanDecaySolver::new(TestData::new());
+        let mut inv = Inventory::new();
+        inv.add("Nb-99".parse().unwrap(), 1.0);
+        inv.add("Mo-99".parse().unwrap(), 0.5);
+
+        let times = [0., 0.5, 1., 10.];
+        let series = solver.decay_series(&inv, &times);
+        for (res, &t) in series.iter().zip(&times) {
+            assert_eq!(*res, solver.decay(&inv, t));
+        }
+
+        let table = solver.decay_table(&inv, &times);
+        assert_eq!(table.nuclides.len(), 3);
+        assert_eq!(
+            table.column("Tc-99m".parse().unwrap()).unwrap()[2],
+            series[2][&"Tc-99m".parse().unwrap()]
+        );
+        let csv = table.to_csv();
+        let mut lines = csv.lines();
+        assert_eq!(lines.next(), Some("time,Nb-99,Mo-99,Tc-99m"));
+        let row = lines.next().unwrap();
+        assert!(row.starts_with("0,1e0,5e-1,"), "{}", row);
+        assert_eq!(csv.lines().count(), 5);
+    }
+
+    #[test]
+    fn min_branch_ratio() {
+        let data = TestData::new();
+        let full = BatemanDecaySolver::new(data.clone());
+        // Nb-99 -> Mo-99 carries 0.7 of the decays, Mo-99 ends the path
+        let solver = BatemanDecaySolver::with_min_branch_ratio(data, 0.8);
+
+        let mut inv = Inventory::new();
+        inv.add("Nb-99".parse().unwrap(), 1.0);
+
+        let res = solver.decay(&inv, 1.0);
+        let expected = full.decay(&inv, 1.0);
+        assert_eq!(res.len(), 2);
+        assert!(res.get(&"Tc-99m".parse().unwrap()).is_none());
+        for (nuclide, activity) in res.iter() {
+            assert_eq!(expected[nuclide], *activity);
+        }
+    }
+
+    #[test]
+    fn stepping() {
+        let solver = BatemanDecaySolver::new(TestData::new());
+        let (nb99, mo99): (Nuclide, Nuclide) = ("Nb-99".parse().unwrap(), "Mo-99".parse().unwrap());
+        let t0 = "2000-01-01T00:00:00Z".parse().unwrap();
+
+        let mut inv = Inventory::new();
+        inv.add(nb99, 1.0);
+        inv.set_reference_time(Some(t0));
+        let direct = solver.decay(&inv, 1.0);
+        for _ in 0..4 {
+            solver.step(&mut inv, 0.25).unwrap();
+        }
+
+        for (nuclide, activity) in direct.iter() {
+            assert!((inv[nuclide] - activity).abs() < 1e-12);
+        }
+        assert_eq!(
+            inv.reference_time(),
+            Some(t0 + chrono::Duration::seconds(1))
+        );
+        assert_eq!(solver.steps.read().unwrap().len(), 1);
+
+        // modified between steps
+        inv.add(mo99, 1.0);
+        let expected = solver.decay(&inv, 0.25);
+        solver.step(&mut inv, 0.25).unwrap();
+        assert_eq!(*inv, *expected);
+
+        // bounded cache of step lengths
+        for i in 0..2 * MAX_CACHED_STEPS {
+            solver.step(&mut inv, i as f64).unwrap();
+        }
+        assert_eq!(solver.steps.read().unwrap().len(), MAX_CACHED_STEPS);
+
+        let before = inv.clone();
+        assert!(solver.step(&mut inv, -1.).is_err());
+        assert!(solver.step(&mut inv, f64::NAN).is_err());
+        assert!(solver.step(&mut inv, 1e300).is_err());
+        assert_eq!(*inv, *before);
+    }
+
+    #[test]
+    fn step_eviction() {
+        let solver = BatemanDecaySolver::new(TestData::new());
+        let mut inv = Inventory::new();
+        inv.add("Nb-99".parse().unwrap(), 1.0);
+
+        // a hot step length used between distinct ones is kept
+        let hot = 0.5;
+        for i in 1..MAX_CACHED_STEPS {
+            solver.step(&mut inv, hot).unwrap();
+            solver.step(&mut inv, i as f64).unwrap();
+        }
+        assert_eq!(solver.steps.read().unwrap().len(), MAX_CACHED_STEPS);
+        solver.step(&mut inv, hot).unwrap();
+        solver.step(&mut inv, MAX_CACHED_STEPS as f64).unwrap();
+
+        let steps = solver.steps.read().unwrap();
+        assert_eq!(steps.len(), MAX_CACHED_STEPS);
+        assert!(steps.ratios.contains_key(&hot.to_bits()));
+        assert!(!steps.ratios.contains_key(&1f64.to_bits()));
+        assert!(steps.ratios.contains_key(&2f64.to_bits()));
+    }
+
+    #[test]
+    fn integrated_activity() {
+        let solver = BatemanDecaySolver::new(TestData::new());
+        let mut inv = Inventory::new();
+        inv.add("Nb-99".parse().unwrap(), 1.0);
+
+        let res = solver.integrated_activity(&inv, 1.0, 3.0);
+        let l1 = 2.0_f64.ln();
+        assert!(
+            (res[&"Nb-99".parse().unwrap()] - ((-l1).exp() - (-3. * l1).exp()) / l1).abs() < 1e-12
+        );
+
+        for (nuclide, &integral) in res.iter() {
+            let numerical =
+                crate::math::quad::gauss_legendre(|t| solver.decay(&inv, t)[nuclide], 1.0, 3.0, 8);
+            assert!((integral - numerical).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn decay_with_warnings() {
+        let solver = BatemanDecaySolver::new(TestData::new());
+        let mut inv = Inventory::new();
+        inv.add("Nb-99".parse().unwrap(), 1.0);
+        inv.add("Cs-137".parse().unwrap(), 1.0);
+        inv.add(Nuclide::FissionProducts, 1.0);
+
+        let res = solver.decay_with_warnings(&inv, 1.0);
+        assert_eq!(res.value, solver.decay(&inv, 1.0));
+        assert_eq!(
+            res.warnings
+                .nuclides(SkipReason::NoDecayConstant)
+                .collect::<Vec<_>>(),
+            vec!["Cs-137".parse().unwrap()]
+        );
+        assert_eq!(
+            res.warnings
+                .nuclides(SkipReason::FissionProducts)
+                .collect::<Vec<_>>(),
+            vec![Nuclide::FissionProducts]
+        );
+
+        let integrated = solver.integrated_activity_with_warnings(&inv, 0., 1.0);
+        assert_eq!(integrated.warnings, res.warnings);
+        assert_eq!(solver.step(&mut inv.clone(), 1.0).unwrap(), res.warnings);
+        let data = TestData::new();
+        assert_eq!(
+            OdeDecaySolver::new(data.clone())
+                .decay_with_warnings(&inv, 1.0)
+                .warnings,
+            res.warnings
+        );
+        assert_eq!(
+            CramDecaySolver::new(data)
+                .decay_with_warnings(&inv, 1.0)
+                .warnings,
+            res.warnings
+        );
+    }
+
+    #[test]
+    fn bateman_solver_provenance() {
+        let data = TestData::new();
+        let solver = BatemanDecaySolver::new(data);
+
+        let mut inv = Inventory::new();
+        inv.add("Nb-99".parse().unwrap(), 1.0);
+
+        let res = solver.decay_with_provenance(&inv, 1.0);
+
+        assert_eq!(res.value, solver.decay(&inv, 1.0));
+        assert_eq!(res.provenance.dataset.name, "test");
+        assert_eq!(res.provenance.solver, "BatemanDecaySolver");
+        assert_eq!(res.provenance.options["min_branch_ratio"], "0");
+        assert!(serde_json::to_string(&res).is_ok());
+    }
+
+    #[test]
+    fn invalidated_while_decaying() {
+        use crate::dataset::CustomDecayData;
+        use crate::fixtures::FixtureDecayData;
+        use crate::primitive::{HalfLife, TimeUnit};
+
+        let data = Arc::new(CustomDecayData::new(Arc::new(FixtureDecayData::new())));
+        let solver = BatemanDecaySolver::new(data.clone());
+        let sr90: Nuclide = "Sr-90".parse().unwrap();
+        let mut inv = Inventory::new();
+        inv.add(sr90, 1.0);
+        let half_life = HalfLife {
+            value: 28.79,
+            unit: TimeUnit::Year,
+        };
+
+        std::thread::scope(|s| {
+            for _ in 0..4 {
+                s.spawn(|| {
+                    for _ in 0..200 {
+                        solver.decay(&inv, 1.0);
+                        solver.step(&mut inv.clone(), 1.0).unwrap();
+                    }
+                });
+            }
+            for _ in 0..100 {
+                data.set(sr90, half_life, vec![]);
+                data.remove(sr90);
+            }
+            data.set(sr90, half_life, vec![]);
+        });
+
+        // no chain of the data before the last override survives
+        assert_eq!(solver.decay(&inv, 1.0).len(), 1);
+        let mut stepped = inv.clone();
+        solver.step(&mut stepped, 1.0).unwrap();
+        assert_eq!(stepped.len(), 1);
+    }
 }
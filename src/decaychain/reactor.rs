@@ -0,0 +1,235 @@
+use std::str::FromStr;
+
+use super::inventory::MEV;
+use super::{BatemanDecaySolver, Inventory};
+use crate::error::Error;
+use crate::primitive::attr::{DecayConstant, NuclideDecayEnergy, NuclideHalfLife, NuclideProgeny};
+use crate::primitive::Nuclide;
+
+/// Recoverable energy per fission (MeV)
+const ENERGY_PER_FISSION: f64 = 200.;
+
+const LWR_FISSION_YIELDS: &str = include_str!("lwr_fission_yields.csv");
+
+/// Fission product of a [`FissionYields`] table
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FissionProduct {
+    /// Cumulative yield (/fission)
+    Yield(f64),
+    /// In equilibrium with its parent
+    Daughter(Nuclide),
+}
+
+/// Fission product yields, for shutdown inventories of a reactor at constant power
+///
+/// The tables are read from a CSV file of `nuclide,cumulative_yield,parent`
+/// rows, where either the yield or the parent of a short-lived daughter is
+/// given:
+///
+/// ```text
+/// # nuclide,cumulative_yield,parent
+/// Sr-90,0.058,
+/// Y-90,,Sr-90
+/// ```
+///
+/// Blank lines and lines starting with `#` are ignored.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FissionYields {
+    products: Vec<(Nuclide, FissionProduct)>,
+}
+
+impl FissionYields {
+    /// Main fission products of a light water reactor, from the thermal fission
+    /// of U-235 with yields rounded to two significant figures
+    ///
+    /// Actinides are not included, their inventory depends on the enrichment
+    /// and burnup of the fuel; add them from a depletion code with
+    /// [`Inventory::add`].
+    pub fn lwr() -> Self {
+        LWR_FISSION_YIELDS.parse().unwrap()
+    }
+
+    pub fn products(&self) -> &[(Nuclide, FissionProduct)] {
+        &self.products
+    }
+
+    /// Activities (Bq) after `irradiation` (s) at `power` (MW)
+    ///
+    /// Each nuclide with a yield builds up to saturation with its own half-life,
+    /// A = F Y (1 - exp(-λ t)), at F fissions per second of 200 MeV each.
+    /// Neutron capture and the build-up time of precursors are neglected, so
+    /// that nuclides with a large capture cross section (e.g. Xe-135) do not
+    /// belong in a table. Daughters take the activity of their parent times
+    /// the branch ratio.
+    pub fn shutdown_inventory<D>(
+        &self,
+        data: &D,
+        power: f64,
+        irradiation: f64,
+    ) -> Result<Inventory, Error>
+    where
+        D: NuclideHalfLife + NuclideProgeny + ?Sized,
+    {
+        let fission_rate = power * 1e6 / (ENERGY_PER_FISSION * MEV);
+
+        let mut inv = Inventory::new();
+        for &(nuclide, product) in &self.products {
+            if let FissionProduct::Yield(y) = product {
+                let lambda = data.lambda(nuclide)?;
+                inv.add(
+                    nuclide,
+                    fission_rate * y * -(-lambda * irradiation).exp_m1(),
+                );
+            }
+        }
+        for &(nuclide, product) in &self.products {
+            if let FissionProduct::Daughter(parent) = product {
+                let branch: f64 = data
+                    .progeny(parent)?
+                    .iter()
+                    .filter(|p| p.nuclide == nuclide)
+                    .map(|p| p.branch_rate)
+                    .sum();
+                let activity = inv.get(&parent).copied().unwrap_or(0.) * branch;
+                inv.add(nuclide, activity);
+            }
+        }
+
+        Ok(inv)
+    }
+}
+
+impl FromStr for FissionYields {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut products = vec![];
+
+        for line in s.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+            let product = match fields[..] {
+                [_, y, ""] | [_, y] if !y.is_empty() => FissionProduct::Yield(
+                    y.parse().map_err(|_| Error::InvalidFloat(y.to_string()))?,
+                ),
+                [_, "", parent] => FissionProduct::Daughter(parent.parse()?),
+                _ => {
+                    return Err(Error::Unexpected(anyhow::anyhow!(
+                        "invalid fission yield record: {}",
+                        line
+                    )))
+                }
+            };
+            products.push((fields[0].parse()?, product));
+        }
+
+        Ok(Self { products })
+    }
+}
+
+/// Activity and decay heat of an inventory after cooling
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CoolingPoint {
+    /// Time after shutdown (s)
+    pub time: f64,
+    /// (Bq)
+    pub activity: f64,
+    /// (W)
+    pub decay_heat: f64,
+}
+
+impl<D> BatemanDecaySolver<D>
+where
+    D: NuclideProgeny + DecayConstant + NuclideDecayEnergy + ?Sized,
+{
+    /// Activity and decay heat of a shutdown inventory at each cooling time (s)
+    pub fn cooling_curve(
+        &self,
+        shutdown: &Inventory,
+        times: &[f64],
+    ) -> Result<Vec<CoolingPoint>, Error> {
+        times
+            .iter()
+            .map(|&time| {
+                let inv = self.decay(shutdown, time);
+
+                Ok(CoolingPoint {
+                    time,
+                    activity: inv.values().sum(),
+                    decay_heat: inv.decay_heat(&*self.decay_data)?,
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeSet;
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::fixtures::FixtureDecayData;
+    use crate::primitive::{HalfLife, Progeny};
+
+    /// Fixture decay data with round decay energies, not evaluated data
+    struct WithEnergy(FixtureDecayData);
+
+    impl NuclideProgeny for WithEnergy {
+        fn progeny(&self, nuclide: Nuclide) -> Result<Vec<Progeny>, Error> {
+            self.0.progeny(nuclide)
+        }
+    }
+
+    impl NuclideHalfLife for WithEnergy {
+        fn half_life(&self, nuclide: Nuclide) -> Result<HalfLife, Error> {
+            self.0.half_life(nuclide)
+        }
+    }
+
+    impl NuclideDecayEnergy for WithEnergy {
+        fn decay_energy(&self, _: Nuclide) -> Result<f64, Error> {
+            Ok(1.)
+        }
+    }
+
+    #[test]
+    fn lwr_yields() {
+        let yields = FissionYields::lwr();
+        let nuclides: BTreeSet<_> = yields.products().iter().map(|(n, _)| *n).collect();
+        assert_eq!(nuclides.len(), yields.products().len());
+        assert!(nuclides.contains(&"Cs-137".parse().unwrap()));
+    }
+
+    #[test]
+    fn cooling_curve() {
+        let data = Arc::new(WithEnergy(FixtureDecayData::new()));
+        let yields: FissionYields = "Sr-90,0.05,\nY-90,,Sr-90\nCs-137,0.06\nBa-137m,,Cs-137\n"
+            .parse()
+            .unwrap();
+        assert!("Sr-90,,".parse::<FissionYields>().is_err());
+
+        // saturated Y-90 in equilibrium with Sr-90 after a year
+        let year = 365.2422 * 86_400.;
+        let inv = yields.shutdown_inventory(&*data, 1., year).unwrap();
+        let sr90 = inv[&"Sr-90".parse().unwrap()];
+        let fission_rate = 1e6 / (200. * MEV);
+        let expected = fission_rate * 0.05 * (1. - (-(2f64.ln()) / 28.79).exp());
+        assert!((sr90 / expected - 1.).abs() < 1e-9);
+        assert_eq!(inv[&"Y-90".parse().unwrap()], sr90);
+        assert!(
+            (inv[&"Ba-137m".parse().unwrap()] / inv[&"Cs-137".parse().unwrap()] - 0.944).abs()
+                < 1e-12
+        );
+
+        let solver = BatemanDecaySolver::new(data);
+        let curve = solver.cooling_curve(&inv, &[0., year, 10. * year]).unwrap();
+        assert!((curve[0].activity - inv.values().sum::<f64>()).abs() < 1e-6 * curve[0].activity);
+        assert!((curve[0].decay_heat - curve[0].activity * MEV).abs() < 1e-9 * curve[0].decay_heat);
+        assert!(curve[1].decay_heat < curve[0].decay_heat);
+        assert!(curve[2].activity < curve[1].activity);
+    }
+}
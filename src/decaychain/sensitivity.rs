@@ -0,0 +1,356 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use super::{BatemanDecaySolver, Inventory};
+use crate::error::Error;
+use crate::primitive::attr::{NuclideHalfLife, NuclideProgeny};
+use crate::primitive::{HalfLife, Nuclide, Progeny};
+
+/// Decay data parameter perturbed in a [`SensitivityAnalysis`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Parameter {
+    HalfLife(Nuclide),
+    BranchRatio { parent: Nuclide, daughter: Nuclide },
+}
+
+impl std::fmt::Display for Parameter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::HalfLife(nuclide) => write!(f, "T1/2({})", nuclide),
+            Self::BranchRatio { parent, daughter } => write!(f, "BR({} -> {})", parent, daughter),
+        }
+    }
+}
+
+/// Decay data with half-lives and branch ratios scaled by factors
+///
+/// A scaled branch ratio leaves the other branches of the parent unchanged
+/// and is capped at 1.
+pub struct PerturbedDecayData<D: ?Sized> {
+    data: Arc<D>,
+    factors: BTreeMap<Parameter, f64>,
+}
+
+impl<D: ?Sized> PerturbedDecayData<D> {
+    pub fn new(data: Arc<D>, factors: BTreeMap<Parameter, f64>) -> Self {
+        Self { data, factors }
+    }
+
+    fn factor(&self, parameter: Parameter) -> f64 {
+        self.factors.get(&parameter).copied().unwrap_or(1.)
+    }
+}
+
+impl<D: NuclideHalfLife + ?Sized> NuclideHalfLife for PerturbedDecayData<D> {
+    fn half_life(&self, nuclide: Nuclide) -> Result<HalfLife, Error> {
+        let half_life = self.data.half_life(nuclide)?;
+        Ok(HalfLife {
+            value: half_life.value * self.factor(Parameter::HalfLife(nuclide)),
+            unit: half_life.unit,
+        })
+    }
+}
+
+impl<D: NuclideProgeny + ?Sized> NuclideProgeny for PerturbedDecayData<D> {
+    fn progeny(&self, nuclide: Nuclide) -> Result<Vec<Progeny>, Error> {
+        let mut progeny = self.data.progeny(nuclide)?;
+        for daughter in progeny.iter_mut() {
+            let factor = self.factor(Parameter::BranchRatio {
+                parent: nuclide,
+                daughter: daughter.nuclide,
+            });
+            daughter.branch_rate = (daughter.branch_rate * factor).min(1.);
+        }
+        Ok(progeny)
+    }
+}
+
+type Output<'a> = (String, Box<dyn Fn(&Inventory) -> f64 + 'a>);
+
+/// Response of the outputs to one parameter at its lower and upper bound
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sensitivity {
+    pub parameter: Parameter,
+    /// Output values with the parameter at its lower bound
+    pub low: BTreeMap<String, f64>,
+    /// Output values with the parameter at its upper bound
+    pub high: BTreeMap<String, f64>,
+    /// Largest change of an output between the bounds, relative to its
+    /// nominal value
+    pub influence: f64,
+}
+
+/// Spread of an output over the samples of [`SensitivityAnalysis::latin_hypercube`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct OutputSpread {
+    pub nominal: f64,
+    pub mean: f64,
+    pub std_dev: f64,
+    pub min: f64,
+    pub max: f64,
+    /// Pearson correlation of the output with each parameter factor
+    pub correlations: BTreeMap<Parameter, f64>,
+}
+
+/// Spread of outputs of a decay calculation under relative uncertainties of
+/// half-lives and branch ratios
+///
+/// Outputs are functions of the decayed inventory, activities of nuclides
+/// or e.g. a dose computed from it. Each parameter is varied uniformly
+/// within ± its relative uncertainty.
+pub struct SensitivityAnalysis<'a, D: ?Sized> {
+    data: Arc<D>,
+    uncertainties: BTreeMap<Parameter, f64>,
+    outputs: Vec<Output<'a>>,
+}
+
+impl<'a, D> SensitivityAnalysis<'a, D>
+where
+    D: NuclideProgeny + NuclideHalfLife + ?Sized + 'a,
+{
+    pub fn new(data: Arc<D>) -> Self {
+        Self {
+            data,
+            uncertainties: BTreeMap::new(),
+            outputs: vec![],
+        }
+    }
+
+    /// Perturb the half-life of a nuclide by ± `relative`
+    pub fn half_life(mut self, nuclide: Nuclide, relative: f64) -> Self {
+        self.uncertainties
+            .insert(Parameter::HalfLife(nuclide), relative);
+        self
+    }
+
+    /// Perturb the branch ratio from `parent` to `daughter` by ± `relative`
+    pub fn branch_ratio(mut self, parent: Nuclide, daughter: Nuclide, relative: f64) -> Self {
+        self.uncertainties
+            .insert(Parameter::BranchRatio { parent, daughter }, relative);
+        self
+    }
+
+    /// Activity (Bq) of a nuclide, named after it
+    pub fn activity(self, nuclide: Nuclide) -> Self {
+        self.output(&nuclide.to_string(), move |inv| {
+            inv.get(&nuclide).copied().unwrap_or(0.)
+        })
+    }
+
+    /// Output computed from the decayed inventory
+    pub fn output<F: Fn(&Inventory) -> f64 + 'a>(mut self, name: &str, output: F) -> Self {
+        self.outputs.push((name.to_string(), Box::new(output)));
+        self
+    }
+
+    fn evaluate(
+        &self,
+        factors: BTreeMap<Parameter, f64>,
+        inventory: &Inventory,
+        decay_time: f64,
+    ) -> BTreeMap<String, f64> {
+        let data = Arc::new(PerturbedDecayData::new(self.data.clone(), factors));
+        let decayed = BatemanDecaySolver::new(data).decay(inventory, decay_time);
+        self.outputs
+            .iter()
+            .map(|(name, output)| (name.clone(), output(&decayed)))
+            .collect()
+    }
+
+    /// Vary each parameter to its bounds with the others nominal, by
+    /// decreasing influence
+    pub fn one_at_a_time(&self, inventory: &Inventory, decay_time: f64) -> Vec<Sensitivity> {
+        let nominal = self.evaluate(BTreeMap::new(), inventory, decay_time);
+
+        let mut res: Vec<Sensitivity> = self
+            .uncertainties
+            .iter()
+            .map(|(&parameter, &relative)| {
+                let at = |factor| {
+                    self.evaluate(BTreeMap::from([(parameter, factor)]), inventory, decay_time)
+                };
+                let (low, high) = (at(1. - relative), at(1. + relative));
+                let influence = nominal
+                    .iter()
+                    .filter(|(_, &v)| v != 0.)
+                    .map(|(name, &v)| ((high[name] - low[name]) / v).abs())
+                    .fold(0., f64::max);
+
+                Sensitivity {
+                    parameter,
+                    low,
+                    high,
+                    influence,
+                }
+            })
+            .collect();
+        res.sort_by(|a, b| b.influence.total_cmp(&a.influence));
+
+        res
+    }
+
+    /// Spread of the outputs over `samples` Latin hypercube samples of the
+    /// parameters, reproducible for a given seed
+    ///
+    /// Each parameter range is split into `samples` strata, each drawn once
+    /// in a random order; parameters are drawn in their sort order.
+    #[cfg(feature = "rand")]
+    pub fn latin_hypercube(
+        &self,
+        inventory: &Inventory,
+        decay_time: f64,
+        samples: usize,
+        seed: u64,
+    ) -> BTreeMap<String, OutputSpread> {
+        use rand::seq::SliceRandom;
+        use rand::Rng;
+
+        let mut rng = crate::math::random::seeded(seed);
+        let columns: Vec<(Parameter, Vec<f64>)> = self
+            .uncertainties
+            .iter()
+            .map(|(&parameter, &relative)| {
+                let mut strata: Vec<usize> = (0..samples).collect();
+                strata.shuffle(&mut rng);
+                let factors = strata
+                    .into_iter()
+                    .map(|i| {
+                        let u = (i as f64 + rng.random::<f64>()) / samples as f64;
+                        1. + relative * (2. * u - 1.)
+                    })
+                    .collect();
+                (parameter, factors)
+            })
+            .collect();
+
+        let results: Vec<BTreeMap<String, f64>> = (0..samples)
+            .map(|k| {
+                let factors = columns.iter().map(|(p, f)| (*p, f[k])).collect();
+                self.evaluate(factors, inventory, decay_time)
+            })
+            .collect();
+        let nominal = self.evaluate(BTreeMap::new(), inventory, decay_time);
+
+        nominal
+            .into_iter()
+            .map(|(name, nominal)| {
+                let values: Vec<f64> = results.iter().map(|r| r[&name]).collect();
+                let correlations = columns
+                    .iter()
+                    .map(|(p, factors)| (*p, correlation(factors, &values)))
+                    .collect();
+                let n = values.len() as f64;
+                let mean = values.iter().sum::<f64>() / n;
+                let var = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (n - 1.);
+
+                let spread = OutputSpread {
+                    nominal,
+                    mean,
+                    std_dev: var.sqrt(),
+                    min: values.iter().copied().fold(f64::INFINITY, f64::min),
+                    max: values.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+                    correlations,
+                };
+                (name, spread)
+            })
+            .collect()
+    }
+}
+
+/// Pearson correlation coefficient, 0 if either series is constant
+#[cfg(feature = "rand")]
+fn correlation(x: &[f64], y: &[f64]) -> f64 {
+    let n = x.len() as f64;
+    let (mx, my) = (x.iter().sum::<f64>() / n, y.iter().sum::<f64>() / n);
+    let (mut sxy, mut sxx, mut syy) = (0., 0., 0.);
+    for (a, b) in x.iter().zip(y) {
+        sxy += (a - mx) * (b - my);
+        sxx += (a - mx).powi(2);
+        syy += (b - my).powi(2);
+    }
+    if sxx > 0. && syy > 0. {
+        sxy / (sxx * syy).sqrt()
+    } else {
+        0.
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::fixtures::FixtureDecayData;
+
+    const DAY: f64 = 86_400.;
+
+    fn setup() -> (Arc<FixtureDecayData>, Inventory, Nuclide, Nuclide) {
+        let mo99: Nuclide = "Mo-99".parse().unwrap();
+        let tc99m: Nuclide = "Tc-99m".parse().unwrap();
+        let mut inv = Inventory::new();
+        inv.add(mo99, 1.);
+        (Arc::new(FixtureDecayData::new()), inv, mo99, tc99m)
+    }
+
+    #[test]
+    fn perturbed_data() {
+        let (data, _, mo99, tc99m) = setup();
+        let perturbed = PerturbedDecayData::new(
+            data.clone(),
+            BTreeMap::from([
+                (Parameter::HalfLife(mo99), 1.1),
+                (
+                    Parameter::BranchRatio {
+                        parent: mo99,
+                        daughter: tc99m,
+                    },
+                    2.,
+                ),
+            ]),
+        );
+
+        let t = data.half_life(mo99).unwrap().as_sec();
+        assert!((perturbed.half_life(mo99).unwrap().as_sec() / t - 1.1).abs() < 1e-12);
+        let progeny = perturbed.progeny(mo99).unwrap();
+        assert_eq!(
+            progeny
+                .iter()
+                .find(|p| p.nuclide == tc99m)
+                .unwrap()
+                .branch_rate,
+            1.
+        );
+    }
+
+    #[test]
+    fn one_at_a_time() {
+        let (data, inv, mo99, tc99m) = setup();
+        let analysis = SensitivityAnalysis::new(data)
+            .half_life(mo99, 0.1)
+            .half_life(tc99m, 0.01)
+            .branch_ratio(mo99, tc99m, 0.05)
+            .activity(tc99m);
+
+        let res = analysis.one_at_a_time(&inv, 10. * DAY);
+        assert_eq!(res.len(), 3);
+        // ten days are several Mo-99 half-lives
+        assert_eq!(res[0].parameter, Parameter::HalfLife(mo99));
+        assert!(res[0].high["Tc-99m"] > res[0].low["Tc-99m"]);
+        assert!(res.windows(2).all(|w| w[0].influence >= w[1].influence));
+    }
+
+    #[test]
+    #[cfg(feature = "rand")]
+    fn latin_hypercube() {
+        let (data, inv, mo99, tc99m) = setup();
+        let analysis = SensitivityAnalysis::new(data)
+            .half_life(mo99, 0.1)
+            .branch_ratio(mo99, tc99m, 0.05)
+            .activity(tc99m);
+
+        let spread = analysis.latin_hypercube(&inv, 10. * DAY, 50, 7);
+        let tc = &spread["Tc-99m"];
+        assert!(tc.min < tc.nominal && tc.nominal < tc.max);
+        assert!(tc.std_dev > 0.);
+        assert!(tc.correlations[&Parameter::HalfLife(mo99)] > 0.8);
+        assert_eq!(analysis.latin_hypercube(&inv, 10. * DAY, 50, 7), spread);
+    }
+}
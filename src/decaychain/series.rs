@@ -0,0 +1,111 @@
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+use super::{BatemanDecaySolver, Inventory};
+use crate::primitive::attr::{DecayConstant, NuclideProgeny};
+use crate::primitive::Nuclide;
+
+/// Activities (Bq) of each nuclide at each decay time (s), one row per time
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct DecaySeries {
+    pub times: Vec<f64>,
+    pub nuclides: Vec<Nuclide>,
+    /// `activities[i][j]` of `nuclides[j]` at `times[i]`
+    pub activities: Vec<Vec<f64>>,
+}
+
+impl DecaySeries {
+    /// Table of inventories at the given times, nuclides missing from an
+    /// inventory counting as zero
+    pub fn new(times: &[f64], inventories: &[Inventory]) -> Self {
+        let mut nuclides: Vec<Nuclide> = inventories
+            .iter()
+            .flat_map(|inv| inv.keys())
+            .copied()
+            .collect();
+        nuclides.sort();
+        nuclides.dedup();
+
+        let activities = inventories
+            .iter()
+            .map(|inv| {
+                nuclides
+                    .iter()
+                    .map(|n| inv.get(n).copied().unwrap_or(0.))
+                    .collect()
+            })
+            .collect();
+
+        Self {
+            times: times.to_vec(),
+            nuclides,
+            activities,
+        }
+    }
+
+    /// Activities of a nuclide over time
+    pub fn column(&self, nuclide: Nuclide) -> Option<Vec<f64>> {
+        let j = self.nuclides.iter().position(|&n| n == nuclide)?;
+        Some(self.activities.iter().map(|row| row[j]).collect())
+    }
+
+    /// Comma separated values with a header of `time` and the nuclides
+    pub fn to_csv(&self) -> String {
+        let mut s = String::from("time");
+        for nuclide in &self.nuclides {
+            s += &format!(",{}", nuclide);
+        }
+        s += "\n";
+
+        for (time, row) in self.times.iter().zip(&self.activities) {
+            s += &time.to_string();
+            for activity in row {
+                s += &format!(",{:e}", activity);
+            }
+            s += "\n";
+        }
+
+        s
+    }
+}
+
+impl<D> BatemanDecaySolver<D>
+where
+    D: NuclideProgeny + DecayConstant + ?Sized,
+{
+    /// Decay calculation for each of the times in seconds
+    ///
+    /// The decay paths and denominators of the Bateman equations are worked
+    /// out once per nuclide of the inventory, leaving only the exponentials
+    /// for each time.
+    pub fn decay_series(&self, inventory: &Inventory, times: &[f64]) -> Vec<Inventory> {
+        let mut series = vec![Inventory::new(); times.len()];
+
+        for (&nuclide, &activity) in inventory.iter() {
+            let Some(paths) = self.path_terms(nuclide) else {
+                continue;
+            };
+            for (inv, &t) in series.iter_mut().zip(times) {
+                let mut res = BTreeMap::new();
+                for (nuc, factor, terms) in &paths {
+                    *res.entry(*nuc).or_insert(0.) += factor
+                        * terms
+                            .iter()
+                            .map(|&(li, denom)| (-li * t).exp() / denom)
+                            .sum::<f64>();
+                }
+                for (nuc, res) in res {
+                    inv.add(nuc, activity * res);
+                }
+            }
+        }
+
+        series
+    }
+
+    /// [`Self::decay_series`] as a table, e.g. for plotting
+    pub fn decay_table(&self, inventory: &Inventory, times: &[f64]) -> DecaySeries {
+        DecaySeries::new(times, &self.decay_series(inventory, times))
+    }
+}
@@ -0,0 +1,161 @@
+use super::OrganDoseMap;
+use crate::error::Error;
+use crate::primitive::attr::DcfAirSubmersion;
+use crate::primitive::{Nuclide, Organ};
+use crate::provenance::{SkipReason, Warnings, WithWarnings};
+
+/// Hemispherical cloud of finite radius centered on the receptor
+///
+/// Submersion coefficients assume a semi-infinite cloud; a cloud of radius R
+/// gets the fraction 1 - (1 + μR/2) exp(-μR) of that dose, from the uncollided
+/// photon flux with a linear buildup factor 1 + μr.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FiniteCloud {
+    /// Cloud radius (m)
+    pub radius: f64,
+    /// Linear attenuation coefficient of air at the photon energy of interest (1/m)
+    pub attenuation: f64,
+}
+
+impl FiniteCloud {
+    pub fn new(radius: f64, attenuation: f64) -> Self {
+        Self {
+            radius,
+            attenuation,
+        }
+    }
+
+    /// Fraction of the semi-infinite cloud dose
+    pub fn correction(&self) -> f64 {
+        let mu_r = self.attenuation * self.radius.max(0.);
+        1. - (1. + mu_r / 2.) * (-mu_r).exp()
+    }
+}
+
+/// Effective dose rate from submersion in a cloud of the given air concentrations (Bq/m3)
+///
+/// The rate is in the unit of the provider's coefficients (Sv/hr for FGR 12);
+/// nuclides without a coefficient and fission products are not counted, with
+/// a warning for each. `None` for `cloud` means a semi-infinite cloud.
+pub fn submersion_dose_rate<P, I>(
+    provider: &P,
+    concentrations: I,
+    cloud: Option<FiniteCloud>,
+) -> Result<WithWarnings<f64>, Error>
+where
+    P: DcfAirSubmersion + ?Sized,
+    I: IntoIterator<Item = (Nuclide, f64)>,
+{
+    let doses =
+        submersion_organ_dose_rates(provider, concentrations, &[Organ::EffectiveDose], cloud)?;
+    Ok(WithWarnings {
+        value: doses.value.effective().unwrap_or(0.),
+        warnings: doses.warnings,
+    })
+}
+
+/// Dose rate to each of `organs` from submersion in a cloud, see
+/// [`submersion_dose_rate`]
+///
+/// Organs without any coefficient are left out.
+pub fn submersion_organ_dose_rates<P, I>(
+    provider: &P,
+    concentrations: I,
+    organs: &[Organ],
+    cloud: Option<FiniteCloud>,
+) -> Result<WithWarnings<OrganDoseMap>, Error>
+where
+    P: DcfAirSubmersion + ?Sized,
+    I: IntoIterator<Item = (Nuclide, f64)>,
+{
+    let mut warnings = Warnings::new();
+    let mut doses = OrganDoseMap::new();
+    for (nuclide, concentration) in concentrations {
+        if nuclide == Nuclide::FissionProducts {
+            warnings.push(nuclide, SkipReason::FissionProducts);
+            continue;
+        }
+
+        let mut missing = false;
+        for &organ in organs {
+            match provider.dcf_air_submersion(nuclide, organ)?.first() {
+                Some(dcf) => doses.add_dose(organ, dcf.value * concentration),
+                None => missing = true,
+            }
+        }
+        if missing {
+            warnings.push(nuclide, SkipReason::NoDoseCoefficient);
+        }
+    }
+
+    Ok(WithWarnings {
+        value: doses * cloud.map_or(1., |c| c.correction()),
+        warnings,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::primitive::{DcfQueryResult, DcfValue};
+
+    struct TestDcf;
+
+    impl DcfAirSubmersion for TestDcf {
+        fn dcf_air_submersion(
+            &self,
+            nuclide: Nuclide,
+            _organ: Organ,
+        ) -> Result<DcfQueryResult, Error> {
+            let values = (nuclide.to_string() == "Xe-133")
+                .then(|| DcfValue {
+                    value: 5.0e-11,
+                    unit: "Sv/hr per Bq/m3".to_string(),
+                    attr: None,
+                    provenance: None,
+                })
+                .into_iter()
+                .collect();
+
+            Ok(DcfQueryResult::new("test", values))
+        }
+    }
+
+    #[test]
+    fn finite_cloud_correction() {
+        // air at 662 keV: 0.0775 cm2/g x 1.205 kg/m3
+        let mu = 0.0775 * 1.205e-1;
+
+        assert_eq!(FiniteCloud::new(0., mu).correction(), 0.);
+        assert!(FiniteCloud::new(1e4, mu).correction() > 0.999);
+        let half = FiniteCloud::new(1.1462 / mu, mu).correction();
+        assert!((half - 0.5).abs() < 1e-3);
+    }
+
+    #[test]
+    fn cloud_dose_rate() {
+        let conc = [
+            ("Xe-133".parse().unwrap(), 1e3),
+            ("H-3".parse().unwrap(), 1e3),
+        ];
+
+        let infinite = submersion_dose_rate(&TestDcf, conc, None).unwrap();
+        assert!((infinite.value - 5.0e-8).abs() < 1e-20);
+        assert_eq!(
+            infinite
+                .warnings
+                .nuclides(SkipReason::NoDoseCoefficient)
+                .collect::<Vec<_>>(),
+            vec!["H-3".parse().unwrap()]
+        );
+        let infinite = infinite.value;
+
+        let cloud = FiniteCloud::new(100., 0.01);
+        let finite = submersion_dose_rate(&TestDcf, conc, Some(cloud)).unwrap();
+        assert!((finite.value - infinite * cloud.correction()).abs() < 1e-20);
+
+        let organs = [Organ::Skin, Organ::EffectiveDose];
+        let doses = submersion_organ_dose_rates(&TestDcf, conc, &organs, None).unwrap();
+        assert_eq!(doses.value.get(Organ::Skin), Some(infinite));
+    }
+}
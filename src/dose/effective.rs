@@ -0,0 +1,337 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use serde::Serialize;
+
+use super::OrganDoseMap;
+use crate::dataset::DecaySource;
+use crate::decaychain::{DecayChainBuilder, Inventory};
+use crate::error::Error;
+use crate::primitive::attr::{DcfIngestion, DcfInhalation};
+use crate::primitive::dose_coefficient::RespiratoryTractAttr;
+use crate::primitive::{
+    AgeGroup, DcfQueryResult, DcfValue, Nuclide, Organ, Pathway, PulmonaryAbsorptionType,
+};
+use crate::provenance::{SkipReason, Warnings, WithWarnings};
+
+/// Coefficient selection and progeny handling of [`effective_dose`]
+#[derive(Clone, Default)]
+pub struct DoseOptions {
+    /// Chemical compound to pick among the forms listed for a nuclide
+    pub compound: Option<String>,
+    /// Absorption type to pick among the inhalation coefficients of a nuclide
+    pub absorption_type: Option<PulmonaryAbsorptionType>,
+    /// Decay data and half-life (s) below which progeny missing from the
+    /// inventory are added in secular equilibrium with their parents
+    pub progeny: Option<(Arc<dyn DecaySource>, f64)>,
+    /// Take the largest of all listed coefficients of a nuclide when none
+    /// matches the compound and absorption type, instead of leaving it out
+    pub fallback_to_largest: bool,
+}
+
+impl DoseOptions {
+    /// Coefficient matching the compound and absorption type, the largest of
+    /// the matching ones, `None` if none match unless
+    /// [`Self::fallback_to_largest`]
+    pub(super) fn select<'a>(&self, res: &'a DcfQueryResult) -> Option<&'a DcfValue> {
+        let matches = |dcf: &&DcfValue| {
+            let attr = dcf.attr.as_ref();
+            let compound = self
+                .compound
+                .as_deref()
+                .is_none_or(|c| attr.is_some_and(|a| &*a.compound == c));
+            let absorption_type = self.absorption_type.is_none_or(|t| {
+                attr.and_then(|a| a.respiratory_tract_attr.as_ref())
+                    .is_some_and(|r| *r == RespiratoryTractAttr::ICRP66(t))
+            });
+            compound && absorption_type
+        };
+
+        let largest = |values: &mut dyn Iterator<Item = &'a DcfValue>| {
+            values.max_by(|a, b| a.value.total_cmp(&b.value))
+        };
+        largest(&mut res.values.iter().filter(matches))
+            .or_else(|| res.max().filter(|_| self.fallback_to_largest))
+    }
+}
+
+/// Committed dose of an intake and the share of each nuclide
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct DoseBreakdown {
+    /// (Sv)
+    pub total: OrganDoseMap,
+    /// (Sv)
+    pub nuclides: BTreeMap<Nuclide, OrganDoseMap>,
+}
+
+/// Committed effective dose (Sv) of ingesting or inhaling an inventory (Bq),
+/// [`organ_doses`] of [`Organ::EffectiveDose`]
+pub fn effective_dose<P>(
+    inventory: &Inventory,
+    pathway: Pathway,
+    age_group: AgeGroup,
+    provider: &P,
+    options: &DoseOptions,
+) -> Result<WithWarnings<DoseBreakdown>, Error>
+where
+    P: DcfIngestion + DcfInhalation + ?Sized,
+{
+    organ_doses(
+        inventory,
+        pathway,
+        age_group,
+        provider,
+        &[Organ::EffectiveDose],
+        options,
+    )
+}
+
+/// Committed dose (Sv) to each of `organs` of ingesting or inhaling an
+/// inventory (Bq)
+///
+/// Nuclides without a coefficient for an organ and fission products are not
+/// counted, with a warning for each; organs without any coefficient are left
+/// out.
+pub fn organ_doses<P>(
+    inventory: &Inventory,
+    pathway: Pathway,
+    age_group: AgeGroup,
+    provider: &P,
+    organs: &[Organ],
+    options: &DoseOptions,
+) -> Result<WithWarnings<DoseBreakdown>, Error>
+where
+    P: DcfIngestion + DcfInhalation + ?Sized,
+{
+    let query = |nuclide, organ| match pathway {
+        Pathway::Ingestion => provider.dcf_ingestion(nuclide, age_group, organ),
+        Pathway::Inhalation => provider.dcf_inhalation(nuclide, age_group, organ),
+        _ => Err(Error::InvalidPathway(pathway.to_string())),
+    };
+
+    let mut warnings = Warnings::new();
+    let mut breakdown = DoseBreakdown::default();
+    for (nuclide, activity) in with_progeny(inventory, options) {
+        if nuclide == Nuclide::FissionProducts {
+            warnings.push(nuclide, SkipReason::FissionProducts);
+            continue;
+        }
+
+        let mut doses = OrganDoseMap::new();
+        let mut skipped = None;
+        for &organ in organs {
+            let res = query(nuclide, organ)?;
+            match options.select(&res) {
+                Some(dcf) => doses.add_dose(organ, dcf.value * activity),
+                None if res.values.is_empty() => {
+                    skipped = skipped.or(Some(SkipReason::NoDoseCoefficient))
+                }
+                None => skipped = Some(SkipReason::NoMatchingDoseCoefficient),
+            }
+        }
+        if let Some(reason) = skipped {
+            warnings.push(nuclide, reason);
+        }
+        if !doses.is_empty() {
+            breakdown.total += &doses;
+            breakdown.nuclides.insert(nuclide, doses);
+        }
+    }
+
+    Ok(WithWarnings {
+        value: breakdown,
+        warnings,
+    })
+}
+
+/// Activities of the inventory with the short-lived progeny of the options added
+fn with_progeny(inventory: &Inventory, options: &DoseOptions) -> BTreeMap<Nuclide, f64> {
+    let mut activities: BTreeMap<Nuclide, f64> = inventory.iter().map(|(&n, &a)| (n, a)).collect();
+
+    if let Some((data, half_life)) = &options.progeny {
+        for (&parent, &activity) in inventory.iter() {
+            let chain = DecayChainBuilder::new(data.clone())
+                .build(parent)
+                .collapse_short_lived(*half_life);
+            for &(daughter, fraction) in chain.collapsed().get(&parent).into_iter().flatten() {
+                if !inventory.contains_key(&daughter) {
+                    *activities.entry(daughter).or_default() += activity * fraction;
+                }
+            }
+        }
+    }
+
+    activities
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::fixtures::FixtureDecayData;
+    use crate::primitive::BiokineticAttr;
+
+    struct TestDcf;
+
+    fn value(value: f64, compound: &str, absorption_type: PulmonaryAbsorptionType) -> DcfValue {
+        DcfValue {
+            value,
+            unit: "Sv/Bq".to_string(),
+            attr: Some(BiokineticAttr {
+                compound: compound.into(),
+                f1: 0.1,
+                respiratory_tract_attr: Some(RespiratoryTractAttr::ICRP66(absorption_type)),
+            }),
+            provenance: None,
+        }
+    }
+
+    impl DcfIngestion for TestDcf {
+        fn dcf_ingestion(
+            &self,
+            n: Nuclide,
+            _: AgeGroup,
+            _: Organ,
+        ) -> Result<DcfQueryResult, Error> {
+            let values = match n.to_string().as_str() {
+                "Cs-137" => vec![value(1.3e-8, "all", PulmonaryAbsorptionType::Fast)],
+                "Ba-137m" => vec![value(1e-10, "all", PulmonaryAbsorptionType::Fast)],
+                _ => vec![],
+            };
+            Ok(DcfQueryResult::new("test", values))
+        }
+    }
+
+    impl DcfInhalation for TestDcf {
+        fn dcf_inhalation(
+            &self,
+            n: Nuclide,
+            _: AgeGroup,
+            _: Organ,
+        ) -> Result<DcfQueryResult, Error> {
+            let values = match n.to_string().as_str() {
+                "Co-60" => vec![
+                    value(7.1e-9, "unspecified", PulmonaryAbsorptionType::Moderate),
+                    value(1.7e-8, "oxides", PulmonaryAbsorptionType::Slow),
+                ],
+                _ => vec![],
+            };
+            Ok(DcfQueryResult::new("test", values))
+        }
+    }
+
+    #[test]
+    fn inventory_organ_doses() {
+        let mut inv = Inventory::new();
+        inv.add("Cs-137".parse().unwrap(), 1e3);
+        inv.add("Co-60".parse().unwrap(), 1e3);
+
+        let organs = [Organ::EffectiveDose, Organ::Thyroid];
+        let doses = organ_doses(
+            &inv,
+            Pathway::Ingestion,
+            AgeGroup::Adult,
+            &TestDcf,
+            &organs,
+            &DoseOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(
+            doses
+                .warnings
+                .nuclides(SkipReason::NoDoseCoefficient)
+                .collect::<Vec<_>>(),
+            vec!["Co-60".parse().unwrap()]
+        );
+        let doses = doses.value.total;
+        // the test coefficients are the same for every organ
+        assert_eq!(doses.len(), 2);
+        assert!((doses.effective().unwrap() - 1.3e-5).abs() < 1e-18);
+        assert_eq!(doses.get(Organ::Thyroid), doses.effective());
+
+        assert!(organ_doses(
+            &inv,
+            Pathway::AirSubmersion,
+            AgeGroup::Adult,
+            &TestDcf,
+            &organs,
+            &DoseOptions::default(),
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn inventory_dose() {
+        let mut inv = Inventory::new();
+        inv.add("Cs-137".parse().unwrap(), 1e3);
+        inv.add("Co-60".parse().unwrap(), 1e3);
+
+        let ingestion = |options: &DoseOptions| {
+            effective_dose(&inv, Pathway::Ingestion, AgeGroup::Adult, &TestDcf, options).unwrap()
+        };
+        let dose = ingestion(&DoseOptions::default());
+        assert!((dose.value.total.effective().unwrap() - 1.3e-5).abs() < 1e-18);
+        assert_eq!(
+            dose.warnings
+                .nuclides(SkipReason::NoDoseCoefficient)
+                .collect::<Vec<_>>(),
+            vec!["Co-60".parse().unwrap()]
+        );
+
+        // Ba-137m in equilibrium with 94.4% of the Cs-137 decays
+        let options = DoseOptions {
+            progeny: Some((Arc::new(FixtureDecayData::new()), 86_400.)),
+            ..Default::default()
+        };
+        let dose = ingestion(&options).value;
+        let ba137m = dose.nuclides[&"Ba-137m".parse().unwrap()]
+            .effective()
+            .unwrap();
+        assert!((ba137m - 1e3 * 0.944 * 1e-10).abs() < 1e-18);
+
+        let inhalation = |options: &DoseOptions| {
+            effective_dose(
+                &inv,
+                Pathway::Inhalation,
+                AgeGroup::Adult,
+                &TestDcf,
+                options,
+            )
+            .unwrap()
+        };
+        let total = |dose: WithWarnings<DoseBreakdown>| dose.value.total.effective().unwrap_or(0.);
+        assert!((total(inhalation(&DoseOptions::default())) - 1.7e-5).abs() < 1e-18);
+        let moderate = DoseOptions {
+            absorption_type: Some(PulmonaryAbsorptionType::Moderate),
+            ..Default::default()
+        };
+        assert!((total(inhalation(&moderate)) - 7.1e-6).abs() < 1e-18);
+        let unlisted = DoseOptions {
+            compound: Some("chloride".to_string()),
+            ..Default::default()
+        };
+        let dose = inhalation(&unlisted);
+        assert!(dose.value.total.is_empty());
+        assert_eq!(
+            dose.warnings
+                .nuclides(SkipReason::NoMatchingDoseCoefficient)
+                .collect::<Vec<_>>(),
+            vec!["Co-60".parse().unwrap()]
+        );
+        let fallback = DoseOptions {
+            fallback_to_largest: true,
+            ..unlisted
+        };
+        assert!((total(inhalation(&fallback)) - 1.7e-5).abs() < 1e-18);
+
+        assert!(matches!(
+            effective_dose(
+                &inv,
+                Pathway::GroundSurface,
+                AgeGroup::Adult,
+                &TestDcf,
+                &DoseOptions::default()
+            ),
+            Err(Error::InvalidPathway(_))
+        ));
+    }
+}
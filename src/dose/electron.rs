@@ -0,0 +1,130 @@
+use crate::decaychain::Inventory;
+use crate::error::Error;
+use crate::primitive::attr::NuclideElectronLines;
+use crate::primitive::{Energy, Nuclide};
+use crate::provenance::{SkipReason, Warnings, WithWarnings};
+
+/// Discrete internal conversion and Auger electron spectrum
+///
+/// Intensities are yields (/nt) for a nuclide and emission rates (1/s) for an
+/// inventory.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ElectronSpectrum {
+    /// Energy and intensity, by increasing energy, lines of equal energy merged
+    pub lines: Vec<(Energy, f64)>,
+}
+
+impl ElectronSpectrum {
+    /// Electron lines of a nuclide
+    pub fn of<D: NuclideElectronLines + ?Sized>(data: &D, nuclide: Nuclide) -> Result<Self, Error> {
+        Ok(Self::from_lines(data.electron_lines(nuclide)?))
+    }
+
+    /// Electron lines of all nuclides of an inventory, weighted by their
+    /// activities, with the nuclides not listed by the data left out
+    pub fn of_inventory<D: NuclideElectronLines + ?Sized>(
+        data: &D,
+        inventory: &Inventory,
+    ) -> Result<WithWarnings<Self>, Error> {
+        let mut warnings = Warnings::new();
+        let mut lines = vec![];
+        for (&nuclide, &activity) in inventory.iter() {
+            let res = data.electron_lines(nuclide);
+            let Some(emitted) = warnings.lookup(nuclide, SkipReason::NoElectronLines, res)? else {
+                continue;
+            };
+            lines.extend(emitted.into_iter().map(|(e, y)| (e, activity * y)));
+        }
+
+        Ok(WithWarnings {
+            value: Self::from_lines(lines),
+            warnings,
+        })
+    }
+
+    fn from_lines(mut lines: Vec<(Energy, f64)>) -> Self {
+        lines.sort_by(|a, b| a.0.as_ev().total_cmp(&b.0.as_ev()));
+        lines.dedup_by(|line, kept| {
+            let same = line.0 == kept.0;
+            if same {
+                kept.1 += line.1;
+            }
+            same
+        });
+
+        Self { lines }
+    }
+
+    /// Electrons per nuclear transformation, or per second for an inventory
+    pub fn total_yield(&self) -> f64 {
+        self.lines.iter().map(|(_, y)| y).sum()
+    }
+
+    /// Energy emitted as electrons (MeV/nt, or MeV/s for an inventory)
+    pub fn energy_yield(&self) -> f64 {
+        self.lines.iter().map(|(e, y)| e.as_mev() * y).sum()
+    }
+
+    /// Mean electron energy, weighted by intensity
+    pub fn mean_energy(&self) -> Energy {
+        let total = self.total_yield();
+        Energy::mev(if total > 0. {
+            self.energy_yield() / total
+        } else {
+            0.
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Test double with made-up lines
+    struct Lines;
+
+    impl NuclideElectronLines for Lines {
+        fn electron_lines(&self, nuclide: Nuclide) -> Result<Vec<(Energy, f64)>, Error> {
+            match nuclide.to_string().as_str() {
+                "Tc-99m" => Ok(vec![
+                    (Energy::kev(120.), 0.1),
+                    (Energy::kev(2.), 0.5),
+                    (Energy::kev(120.), 0.1),
+                ]),
+                "I-125" => Ok(vec![(Energy::kev(20.), 0.4)]),
+                _ => Err(Error::InvalidNuclide(nuclide.to_string())),
+            }
+        }
+    }
+
+    #[test]
+    fn electron_spectrum() {
+        let tc99m = ElectronSpectrum::of(&Lines, "Tc-99m".parse().unwrap()).unwrap();
+        assert_eq!(tc99m.lines.len(), 2);
+        assert!((tc99m.total_yield() - 0.7).abs() < 1e-12);
+        assert!((tc99m.mean_energy().as_kev() - (24. + 1.) / 0.7).abs() < 1e-9);
+
+        let mut inv = Inventory::new();
+        inv.add("Tc-99m".parse().unwrap(), 1e3);
+        inv.add("I-125".parse().unwrap(), 1e2);
+        inv.add("Cs-137".parse().unwrap(), 1e2);
+        inv.add(Nuclide::FissionProducts, 1e2);
+        let folded = ElectronSpectrum::of_inventory(&Lines, &inv).unwrap();
+        assert_eq!(folded.value.lines.len(), 3);
+        assert!((folded.value.total_yield() - 740.).abs() < 1e-9);
+        assert_eq!(
+            folded
+                .warnings
+                .nuclides(SkipReason::NoElectronLines)
+                .collect::<Vec<_>>(),
+            vec!["Cs-137".parse().unwrap()]
+        );
+        assert_eq!(
+            folded
+                .warnings
+                .nuclides(SkipReason::FissionProducts)
+                .count(),
+            1
+        );
+    }
+}
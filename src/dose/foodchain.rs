@@ -0,0 +1,222 @@
+use std::collections::BTreeMap;
+
+use super::ingestion_coef;
+use crate::error::Error;
+use crate::primitive::attr::{DcfIngestion, DecayConstant, TransferFactor};
+use crate::primitive::{AgeGroup, Nuclide, Symbol, TimeUnit, TransferPathway};
+
+/// Equilibrium food-chain model for a single deposition
+///
+/// Plant concentrations combine direct interception of the deposit and root
+/// uptake from the root zone; milk and meat concentrations follow from
+/// livestock feeding on the contaminated plants. Intakes are averaged over the
+/// first year after the deposition, the intercepted activity being removed by
+/// weathering and all of it by radioactive decay.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FoodChain {
+    /// Fraction of the deposit intercepted by vegetation
+    pub interception: f64,
+    /// Weathering half-life of the intercepted activity (d)
+    pub weathering: f64,
+    /// Standing biomass of vegetation (kg/m2, dry)
+    pub biomass: f64,
+    /// Areal density of the root zone (kg/m2, dry)
+    pub soil_density: f64,
+    /// Feed intake of livestock (kg/d, dry)
+    pub feed_intake: f64,
+    /// Consumption of vegetables (kg/y)
+    pub vegetables: f64,
+    /// Consumption of milk (L/y)
+    pub milk: f64,
+    /// Consumption of meat (kg/y)
+    pub meat: f64,
+}
+
+/// Activity concentrations of foodstuffs from a deposition
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct FoodConcentration {
+    /// (Bq/kg)
+    pub plant: f64,
+    /// (Bq/L)
+    pub milk: f64,
+    /// (Bq/kg)
+    pub meat: f64,
+}
+
+impl FoodChain {
+    /// Food concentrations of a nuclide just after its deposition (Bq/m2);
+    /// missing transfer factors are taken as zero
+    pub fn concentration<T: TransferFactor>(
+        &self,
+        factors: &T,
+        nuclide: Nuclide,
+        deposition: f64,
+    ) -> Result<FoodConcentration, Error> {
+        self.mean_concentration(factors, nuclide, deposition, 1., 1.)
+    }
+
+    /// Food concentrations averaged over the first year, from the averages of
+    /// the intercepted and of the root zone activity relative to the deposit
+    fn mean_concentration<T: TransferFactor>(
+        &self,
+        factors: &T,
+        nuclide: Nuclide,
+        deposition: f64,
+        intercepted: f64,
+        root_zone: f64,
+    ) -> Result<FoodConcentration, Error> {
+        let symbol = match nuclide.z() {
+            Some(z) => Symbol::try_from(z)?,
+            None => return Ok(FoodConcentration::default()),
+        };
+        let factor = |pathway| -> Result<f64, Error> {
+            Ok(factors.transfer_factor(symbol, pathway)?.unwrap_or(0.))
+        };
+
+        let plant = intercepted * self.interception * deposition / self.biomass
+            + root_zone * factor(TransferPathway::SoilToPlant)? * deposition / self.soil_density;
+
+        Ok(FoodConcentration {
+            plant,
+            milk: factor(TransferPathway::FeedToMilk)? * self.feed_intake * plant,
+            meat: factor(TransferPathway::FeedToMeat)? * self.feed_intake * plant,
+        })
+    }
+
+    /// Annual intakes (Bq/y) in the first year after a deposition (Bq/m2)
+    pub fn intake<T, D, I>(
+        &self,
+        factors: &T,
+        decay: &D,
+        deposition: I,
+    ) -> Result<BTreeMap<Nuclide, f64>, Error>
+    where
+        T: TransferFactor,
+        D: DecayConstant + ?Sized,
+        I: IntoIterator<Item = (Nuclide, f64)>,
+    {
+        let year = TimeUnit::Year.as_sec();
+        let weathering = 2f64.ln() / (self.weathering * TimeUnit::Day.as_sec());
+
+        deposition
+            .into_iter()
+            .map(|(nuclide, d)| {
+                let lambda = decay.lambda(nuclide)?;
+                let c = self.mean_concentration(
+                    factors,
+                    nuclide,
+                    d,
+                    mean_fraction((lambda + weathering) * year),
+                    mean_fraction(lambda * year),
+                )?;
+                Ok((
+                    nuclide,
+                    self.vegetables * c.plant + self.milk * c.milk + self.meat * c.meat,
+                ))
+            })
+            .collect()
+    }
+
+    /// Annual committed effective dose (Sv/y) from a deposition (Bq/m2)
+    pub fn ingestion_dose<T, D, P, I>(
+        &self,
+        factors: &T,
+        decay: &D,
+        provider: &P,
+        age_group: AgeGroup,
+        deposition: I,
+    ) -> Result<f64, Error>
+    where
+        T: TransferFactor,
+        D: DecayConstant + ?Sized,
+        P: DcfIngestion,
+        I: IntoIterator<Item = (Nuclide, f64)>,
+    {
+        let mut res = 0.;
+        for (nuclide, intake) in self.intake(factors, decay, deposition)? {
+            res += ingestion_coef(provider, nuclide, age_group)? * intake;
+        }
+
+        Ok(res)
+    }
+}
+
+/// Mean of `exp(-x t)` over `t` in 0..1
+fn mean_fraction(x: f64) -> f64 {
+    if x < 1e-8 {
+        1. - x / 2.
+    } else {
+        -(-x).exp_m1() / x
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::dataset::TransferFactors;
+    use crate::fixtures::FixtureDecayData;
+    use crate::primitive::{DcfQueryResult, DcfValue, Organ};
+
+    struct TestDcf;
+
+    impl DcfIngestion for TestDcf {
+        fn dcf_ingestion(
+            &self,
+            _: Nuclide,
+            _: AgeGroup,
+            _: Organ,
+        ) -> Result<DcfQueryResult, Error> {
+            Ok(DcfQueryResult::new(
+                "test",
+                vec![DcfValue {
+                    value: 1.3e-8,
+                    unit: "Sv/Bq".to_string(),
+                    attr: None,
+                    provenance: None,
+                }],
+            ))
+        }
+    }
+
+    const MODEL: FoodChain = FoodChain {
+        interception: 0.5,
+        weathering: 14.,
+        biomass: 1.,
+        soil_density: 100.,
+        feed_intake: 10.,
+        vegetables: 10.,
+        milk: 100.,
+        meat: 10.,
+    };
+
+    #[test]
+    fn food_chain() {
+        let factors: TransferFactors = "Cs,SoilToPlant,0.1\nCs,FeedToMilk,0.01\nCs,FeedToMeat,0.02"
+            .parse()
+            .unwrap();
+        let cs137: Nuclide = "Cs-137".parse().unwrap();
+
+        let c = MODEL.concentration(&factors, cs137, 1000.).unwrap();
+        assert!((c.plant - 501.).abs() < 1e-9);
+        assert!((c.milk - 50.1).abs() < 1e-9);
+        assert!((c.meat - 100.2).abs() < 1e-9);
+
+        // weathering and decay over the year
+        let data = FixtureDecayData::new();
+        let year = TimeUnit::Year.as_sec();
+        let lambda = data.lambda(cs137).unwrap();
+        let weathering = 2f64.ln() / 14. * 365.2422;
+        let intercepted = (1. - (-weathering - lambda * year).exp()) / (weathering + lambda * year);
+        let root_zone = (1. - (-lambda * year).exp()) / (lambda * year);
+        let plant = 500. * intercepted + 1. * root_zone;
+
+        let intake = MODEL.intake(&factors, &data, [(cs137, 1000.)]).unwrap()[&cs137];
+        assert!((intake - plant * (10. + 100. * 0.1 + 10. * 0.2)).abs() < 1e-6);
+        assert!(intake < 0.1 * (5010. + 5010. + 1002.));
+
+        let dose = MODEL
+            .ingestion_dose(&factors, &data, &TestDcf, AgeGroup::Adult, [(cs137, 1000.)])
+            .unwrap();
+        assert!((dose - 1.3e-8 * intake).abs() < 1e-15);
+    }
+}
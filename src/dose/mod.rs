@@ -0,0 +1,56 @@
+mod cloud;
+mod container;
+mod effective;
+mod electron;
+mod foodchain;
+mod groundshine;
+mod organ;
+#[cfg(feature = "plume")]
+pub mod plume;
+mod radiotoxicity;
+mod resuspension;
+mod skin;
+mod workplace;
+
+pub use cloud::{submersion_dose_rate, submersion_organ_dose_rates, FiniteCloud};
+pub use container::{container_dose_rate, Container, ContainerDoseRate};
+pub use effective::{effective_dose, organ_doses, DoseBreakdown, DoseOptions};
+pub use electron::ElectronSpectrum;
+pub use foodchain::{FoodChain, FoodConcentration};
+pub use groundshine::{groundshine_dose, Weathering};
+pub use organ::OrganDoseMap;
+pub use radiotoxicity::radiotoxicity;
+pub use resuspension::{resuspension_dose, soil_ingestion_dose, ResuspensionFactor};
+pub use skin::{skin_dose_rate, BetaEmission, NuclideBetaEmission, SKIN_DEPTH};
+pub use workplace::{
+    AirMonitoring, AirMonitoringReport, AirSample, InvestigationLevel, WORKER_BREATHING_RATE,
+    WORKING_HOURS,
+};
+
+use crate::error::Error;
+use crate::primitive::attr::{DcfIngestion, DcfInhalation};
+use crate::primitive::{AgeGroup, Nuclide, Organ};
+
+/// Largest ingestion coefficient of the chemical forms listed for a nuclide (0 if none)
+pub(crate) fn ingestion_coef<P: DcfIngestion + ?Sized>(
+    provider: &P,
+    nuclide: Nuclide,
+    age_group: AgeGroup,
+) -> Result<f64, Error> {
+    Ok(provider
+        .dcf_ingestion(nuclide, age_group, Organ::EffectiveDose)?
+        .max()
+        .map_or(0., |dcf| dcf.value))
+}
+
+/// Largest inhalation coefficient of the absorption types listed for a nuclide (0 if none)
+pub(crate) fn inhalation_coef<P: DcfInhalation + ?Sized>(
+    provider: &P,
+    nuclide: Nuclide,
+    age_group: AgeGroup,
+) -> Result<f64, Error> {
+    Ok(provider
+        .dcf_inhalation(nuclide, age_group, Organ::EffectiveDose)?
+        .max()
+        .map_or(0., |dcf| dcf.value))
+}
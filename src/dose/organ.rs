@@ -0,0 +1,118 @@
+use std::collections::BTreeMap;
+use std::ops::{Add, AddAssign, Mul};
+
+use serde::{Deserialize, Serialize};
+
+use crate::primitive::Organ;
+
+/// Dose or dose rate of each organ, in the unit of the coefficients it was
+/// calculated with
+///
+/// Maps add up organ by organ, an organ missing from one map counting as
+/// zero, and scale by a factor, e.g. an occupancy or a duration.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct OrganDoseMap(BTreeMap<Organ, f64>);
+
+impl OrganDoseMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add to the dose of an organ
+    pub fn add_dose(&mut self, organ: Organ, dose: f64) {
+        *self.0.entry(organ).or_default() += dose;
+    }
+
+    pub fn get(&self, organ: Organ) -> Option<f64> {
+        self.0.get(&organ).copied()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Organ, f64)> + '_ {
+        self.0.iter().map(|(&organ, &dose)| (organ, dose))
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Effective dose, if calculated
+    pub fn effective(&self) -> Option<f64> {
+        self.get(Organ::EffectiveDose)
+    }
+
+    /// Organ with the largest dose, effective dose (equivalent) left out
+    pub fn max_organ(&self) -> Option<(Organ, f64)> {
+        self.iter()
+            .filter(|(organ, _)| {
+                !matches!(organ, Organ::EffectiveDose | Organ::EffectiveDoseEquivalent)
+            })
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+    }
+}
+
+impl FromIterator<(Organ, f64)> for OrganDoseMap {
+    fn from_iter<I: IntoIterator<Item = (Organ, f64)>>(iter: I) -> Self {
+        let mut map = Self::new();
+        for (organ, dose) in iter {
+            map.add_dose(organ, dose);
+        }
+        map
+    }
+}
+
+impl AddAssign<&OrganDoseMap> for OrganDoseMap {
+    fn add_assign(&mut self, rhs: &OrganDoseMap) {
+        for (organ, dose) in rhs.iter() {
+            self.add_dose(organ, dose);
+        }
+    }
+}
+
+impl Add for OrganDoseMap {
+    type Output = Self;
+
+    fn add(mut self, rhs: Self) -> Self {
+        self += &rhs;
+        self
+    }
+}
+
+impl Mul<f64> for OrganDoseMap {
+    type Output = Self;
+
+    fn mul(mut self, rhs: f64) -> Self {
+        self.0.values_mut().for_each(|dose| *dose *= rhs);
+        self
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn organ_dose_map() {
+        let a: OrganDoseMap = [(Organ::Thyroid, 2.), (Organ::EffectiveDose, 5.)]
+            .into_iter()
+            .collect();
+        let b: OrganDoseMap = [(Organ::Lungs, 3.), (Organ::EffectiveDose, 1.)]
+            .into_iter()
+            .collect();
+
+        let sum = (a + b) * 2.;
+        assert_eq!(sum.len(), 3);
+        assert_eq!(sum.effective(), Some(12.));
+        assert_eq!(sum.max_organ(), Some((Organ::Lungs, 6.)));
+        assert_eq!(sum.get(Organ::Liver), None);
+        assert_eq!(OrganDoseMap::new().max_organ(), None);
+
+        let json = serde_json::to_string(&sum).unwrap();
+        assert_eq!(json, r#"{"Lungs":6.0,"Thyroid":4.0,"EffectiveDose":12.0}"#);
+        assert_eq!(serde_json::from_str::<OrganDoseMap>(&json).unwrap(), sum);
+    }
+}
@@ -0,0 +1,267 @@
+//! Gaussian plume screening of an atmospheric release
+//!
+//! Ground-level centerline concentrations downwind of a continuous point source,
+//! with Briggs' open-country dispersion parameters. Radioactive decay during
+//! transport and plume depletion are neglected, which is conservative.
+
+use std::collections::BTreeMap;
+use std::f64::consts::PI;
+
+use serde::{Deserialize, Serialize};
+
+use super::{inhalation_coef, submersion_dose_rate};
+use crate::error::Error;
+use crate::primitive::attr::{DcfAirSubmersion, DcfGroundSurface, DcfInhalation};
+use crate::primitive::{AgeGroup, Nuclide, Organ};
+use crate::provenance::WithWarnings;
+
+/// Pasquill-Gifford atmospheric stability class
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum StabilityClass {
+    A,
+    B,
+    C,
+    D,
+    E,
+    F,
+}
+
+serde_plain::derive_display_from_serialize!(StabilityClass);
+serde_plain::derive_fromstr_from_deserialize!(StabilityClass, |e| -> Error {
+    Error::InvalidStabilityClass(e.to_string())
+});
+
+impl StabilityClass {
+    /// Horizontal dispersion parameter (m) at a downwind distance (m)
+    pub fn sigma_y(self, x: f64) -> f64 {
+        let a = match self {
+            Self::A => 0.22,
+            Self::B => 0.16,
+            Self::C => 0.11,
+            Self::D => 0.08,
+            Self::E => 0.06,
+            Self::F => 0.04,
+        };
+        a * x / (1. + 1e-4 * x).sqrt()
+    }
+
+    /// Vertical dispersion parameter (m) at a downwind distance (m)
+    pub fn sigma_z(self, x: f64) -> f64 {
+        match self {
+            Self::A => 0.20 * x,
+            Self::B => 0.12 * x,
+            Self::C => 0.08 * x / (1. + 2e-4 * x).sqrt(),
+            Self::D => 0.06 * x / (1. + 1.5e-3 * x).sqrt(),
+            Self::E => 0.03 * x / (1. + 3e-4 * x),
+            Self::F => 0.016 * x / (1. + 3e-4 * x),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GaussianPlume {
+    /// Effective release height (m)
+    pub height: f64,
+    /// Wind speed at release height (m/s)
+    pub wind_speed: f64,
+    pub stability: StabilityClass,
+    /// Dry deposition velocity (m/s)
+    pub deposition_velocity: f64,
+}
+
+impl GaussianPlume {
+    pub fn new(height: f64, wind_speed: f64, stability: StabilityClass) -> Self {
+        Self {
+            height,
+            wind_speed,
+            stability,
+            deposition_velocity: 1e-3,
+        }
+    }
+
+    /// Ground-level centerline dilution factor χ/Q (s/m3) at a downwind distance (m)
+    ///
+    /// Both the distance and the wind speed must be positive.
+    pub fn dilution_factor(&self, distance: f64) -> Result<f64, Error> {
+        if !(distance > 0. && distance.is_finite()) {
+            return Err(Error::InvalidParameter(format!(
+                "distance {} (m)",
+                distance
+            )));
+        }
+        if !(self.wind_speed > 0. && self.wind_speed.is_finite()) {
+            return Err(Error::InvalidParameter(format!(
+                "wind speed {} (m/s)",
+                self.wind_speed
+            )));
+        }
+        let sigma_y = self.stability.sigma_y(distance);
+        let sigma_z = self.stability.sigma_z(distance);
+
+        Ok((-self.height.powi(2) / (2. * sigma_z.powi(2))).exp()
+            / (PI * sigma_y * sigma_z * self.wind_speed))
+    }
+
+    /// Air concentration and deposition at a downwind distance (m) from a
+    /// release of the given activities (Bq)
+    pub fn screen<I>(&self, release: I, distance: f64) -> Result<PlumeScreening, Error>
+    where
+        I: IntoIterator<Item = (Nuclide, f64)>,
+    {
+        let chi_q = self.dilution_factor(distance)?;
+
+        let air: BTreeMap<Nuclide, f64> = release
+            .into_iter()
+            .map(|(nuclide, activity)| (nuclide, activity * chi_q))
+            .collect();
+        let deposition = air
+            .iter()
+            .map(|(&nuclide, &c)| (nuclide, c * self.deposition_velocity))
+            .collect();
+
+        Ok(PlumeScreening { air, deposition })
+    }
+}
+
+/// Result of a plume screening at one receptor
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlumeScreening {
+    /// Time-integrated air concentration (Bq s/m3)
+    pub air: BTreeMap<Nuclide, f64>,
+    /// Ground deposition (Bq/m2)
+    pub deposition: BTreeMap<Nuclide, f64>,
+}
+
+impl PlumeScreening {
+    /// Effective dose (Sv) from submersion in the passing cloud, for coefficients in Sv/hr per Bq/m3
+    pub fn submersion_dose<P: DcfAirSubmersion>(
+        &self,
+        provider: &P,
+    ) -> Result<WithWarnings<f64>, Error> {
+        let rate = submersion_dose_rate(provider, self.air.iter().map(|(&n, &c)| (n, c)), None)?;
+        Ok(WithWarnings {
+            value: rate.value / 3600.,
+            warnings: rate.warnings,
+        })
+    }
+
+    /// Committed effective dose (Sv) from inhalation at a breathing rate (m3/s),
+    /// with the largest coefficient of each nuclide
+    pub fn inhalation_dose<P: DcfInhalation>(
+        &self,
+        provider: &P,
+        age_group: AgeGroup,
+        breathing_rate: f64,
+    ) -> Result<f64, Error> {
+        let mut res = 0.;
+        for (&nuclide, &c) in &self.air {
+            res += inhalation_coef(provider, nuclide, age_group)? * c * breathing_rate;
+        }
+
+        Ok(res)
+    }
+
+    /// Effective dose rate from the deposited activity, in the unit of the
+    /// provider's coefficients (Sv/hr for FGR 12)
+    pub fn groundshine_dose_rate<P: DcfGroundSurface>(&self, provider: &P) -> Result<f64, Error> {
+        let mut res = 0.;
+        for (&nuclide, &d) in &self.deposition {
+            if let Some(dcf) = provider
+                .dcf_ground_surface(nuclide, Organ::EffectiveDose)?
+                .first()
+            {
+                res += dcf.value * d;
+            }
+        }
+
+        Ok(res)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::primitive::{DcfQueryResult, DcfValue};
+
+    struct TestDcf;
+
+    fn dcf(value: f64, unit: &str) -> DcfValue {
+        DcfValue {
+            value,
+            unit: unit.to_string(),
+            attr: None,
+            provenance: None,
+        }
+    }
+
+    impl DcfAirSubmersion for TestDcf {
+        fn dcf_air_submersion(&self, _: Nuclide, _: Organ) -> Result<DcfQueryResult, Error> {
+            Ok(DcfQueryResult::new(
+                "test",
+                vec![dcf(1e-10, "Sv/hr per Bq/m3")],
+            ))
+        }
+    }
+
+    impl DcfGroundSurface for TestDcf {
+        fn dcf_ground_surface(&self, _: Nuclide, _: Organ) -> Result<DcfQueryResult, Error> {
+            Ok(DcfQueryResult::new(
+                "test",
+                vec![dcf(2e-12, "Sv/hr per Bq/m2")],
+            ))
+        }
+    }
+
+    impl DcfInhalation for TestDcf {
+        fn dcf_inhalation(
+            &self,
+            _: Nuclide,
+            _: AgeGroup,
+            _: Organ,
+        ) -> Result<DcfQueryResult, Error> {
+            Ok(DcfQueryResult::new(
+                "test",
+                vec![dcf(4.6e-9, "Sv/Bq"), dcf(3.9e-8, "Sv/Bq")],
+            ))
+        }
+    }
+
+    #[test]
+    fn dispersion_parameters() {
+        // D class at 1 km: σy ≈ 76 m, σz ≈ 38 m
+        assert!((StabilityClass::D.sigma_y(1000.) - 76.3).abs() < 0.1);
+        assert!((StabilityClass::D.sigma_z(1000.) - 37.9).abs() < 0.1);
+        assert_eq!("F".parse::<StabilityClass>().unwrap(), StabilityClass::F);
+        assert!("G".parse::<StabilityClass>().is_err());
+    }
+
+    #[test]
+    fn plume_screening() {
+        let plume = GaussianPlume::new(0., 1., StabilityClass::D);
+        let chi_q = plume.dilution_factor(1000.).unwrap();
+        assert!((chi_q - 1.0997e-4).abs() / 1.0997e-4 < 1e-2);
+
+        // elevated releases are diluted further at the ground
+        let elevated = GaussianPlume::new(50., 1., StabilityClass::D);
+        assert!(elevated.dilution_factor(1000.).unwrap() < chi_q);
+
+        assert!(plume.dilution_factor(0.).is_err());
+        assert!(GaussianPlume::new(0., 0., StabilityClass::D)
+            .dilution_factor(1000.)
+            .is_err());
+
+        let cs137: Nuclide = "Cs-137".parse().unwrap();
+        let res = plume.screen([(cs137, 1e12)], 1000.).unwrap();
+        assert!((res.air[&cs137] - 1e12 * chi_q).abs() < 1e-3);
+        assert!((res.deposition[&cs137] - 1e9 * chi_q).abs() < 1e-6);
+
+        let air = res.air[&cs137];
+        assert!((res.submersion_dose(&TestDcf).unwrap().value - 1e-10 * air / 3600.).abs() < 1e-18);
+        let inh = res
+            .inhalation_dose(&TestDcf, AgeGroup::Adult, 3.3e-4)
+            .unwrap();
+        assert!((inh - 3.9e-8 * air * 3.3e-4).abs() < 1e-15);
+        let ground = res.groundshine_dose_rate(&TestDcf).unwrap();
+        assert!((ground - 2e-12 * res.deposition[&cs137]).abs() < 1e-18);
+    }
+}
@@ -0,0 +1,221 @@
+use std::collections::btree_map::Entry;
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+use super::DoseOptions;
+use crate::error::Error;
+use crate::primitive::attr::DcfInhalation;
+use crate::primitive::{AgeGroup, Nuclide, Organ};
+use crate::provenance::{SkipReason, Warnings};
+
+/// Breathing rate of a reference worker (m3/h)
+pub const WORKER_BREATHING_RATE: f64 = 1.2;
+
+/// Working hours in a year
+pub const WORKING_HOURS: f64 = 2000.;
+
+/// Air concentrations (Bq/m3) averaged over a sampling period (h)
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AirSample {
+    /// (h)
+    pub duration: f64,
+    /// (Bq/m3)
+    pub concentrations: BTreeMap<Nuclide, f64>,
+}
+
+/// DAC-hours above which an exposure is to be investigated
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct InvestigationLevel {
+    pub name: String,
+    pub dac_hours: f64,
+}
+
+/// Exposure of a worker to airborne activity, see [`AirMonitoring::assess`]
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct AirMonitoringReport {
+    /// Derived air concentration of each nuclide (Bq/m3)
+    pub dac: BTreeMap<Nuclide, f64>,
+    pub dac_hours: BTreeMap<Nuclide, f64>,
+    pub total_dac_hours: f64,
+    /// Committed effective dose (Sv)
+    pub committed_dose: f64,
+    /// Investigation levels reached by the total DAC-hours
+    pub exceeded: Vec<InvestigationLevel>,
+    /// Nuclides without an inhalation coefficient and fission products, not counted
+    pub warnings: Warnings,
+}
+
+/// Derived air concentrations and DAC-hours of a worker breathing the
+/// sampled air, with the inhalation coefficients of [`AgeGroup::Worker`]
+///
+/// The DAC of a nuclide is the concentration that, breathed for a working
+/// year, commits the annual dose limit: one DAC-hour is 1/2000 of the limit.
+pub struct AirMonitoring<'a, P: ?Sized> {
+    provider: &'a P,
+    dose_limit: f64,
+    breathing_rate: f64,
+    options: DoseOptions,
+    levels: Vec<InvestigationLevel>,
+}
+
+impl<'a, P: DcfInhalation + ?Sized> AirMonitoring<'a, P> {
+    /// Monitoring against an annual limit of 20 mSv at the reference breathing rate
+    pub fn new(provider: &'a P) -> Self {
+        Self {
+            provider,
+            dose_limit: 0.02,
+            breathing_rate: WORKER_BREATHING_RATE,
+            options: DoseOptions::default(),
+            levels: vec![],
+        }
+    }
+
+    /// Annual dose limit (Sv)
+    pub fn dose_limit(mut self, dose_limit: f64) -> Self {
+        self.dose_limit = dose_limit;
+        self
+    }
+
+    /// (m3/h)
+    pub fn breathing_rate(mut self, breathing_rate: f64) -> Self {
+        self.breathing_rate = breathing_rate;
+        self
+    }
+
+    /// Compound and absorption type of the coefficients, as for [`super::effective_dose`]
+    pub fn options(mut self, options: DoseOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    pub fn investigation_level(mut self, name: &str, dac_hours: f64) -> Self {
+        self.levels.push(InvestigationLevel {
+            name: name.to_string(),
+            dac_hours,
+        });
+        self
+    }
+
+    /// Inhalation coefficient (Sv/Bq), `None` if not listed or none matches the options
+    fn coef(&self, nuclide: Nuclide) -> Result<Option<f64>, Error> {
+        let res = self
+            .provider
+            .dcf_inhalation(nuclide, AgeGroup::Worker, Organ::EffectiveDose)?;
+        Ok(self.options.select(&res).map(|dcf| dcf.value))
+    }
+
+    /// Derived air concentration (Bq/m3), `None` without an inhalation coefficient
+    pub fn dac(&self, nuclide: Nuclide) -> Result<Option<f64>, Error> {
+        Ok(self
+            .coef(nuclide)?
+            .map(|coef| self.dose_limit / (coef * self.breathing_rate * WORKING_HOURS)))
+    }
+
+    /// DAC-hours and committed dose of breathing the samples one after another
+    pub fn assess(&self, samples: &[AirSample]) -> Result<AirMonitoringReport, Error> {
+        let mut report = AirMonitoringReport::default();
+        let mut dacs = BTreeMap::new();
+
+        for sample in samples {
+            for (&nuclide, &concentration) in &sample.concentrations {
+                if nuclide == Nuclide::FissionProducts {
+                    report.warnings.push(nuclide, SkipReason::FissionProducts);
+                    continue;
+                }
+
+                let dac = match dacs.entry(nuclide) {
+                    Entry::Occupied(entry) => *entry.get(),
+                    Entry::Vacant(entry) => {
+                        let dac = *entry.insert(self.dac(nuclide)?);
+                        match dac {
+                            Some(dac) => {
+                                report.dac.insert(nuclide, dac);
+                            }
+                            None => report.warnings.push(nuclide, SkipReason::NoDoseCoefficient),
+                        }
+                        dac
+                    }
+                };
+                let Some(dac) = dac else {
+                    continue;
+                };
+                *report.dac_hours.entry(nuclide).or_default() +=
+                    concentration / dac * sample.duration;
+            }
+        }
+
+        report.total_dac_hours = report.dac_hours.values().sum();
+        report.committed_dose = report.total_dac_hours * self.dose_limit / WORKING_HOURS;
+        report.exceeded = self
+            .levels
+            .iter()
+            .filter(|level| report.total_dac_hours >= level.dac_hours)
+            .cloned()
+            .collect();
+
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::primitive::{DcfQueryResult, DcfValue};
+
+    struct TestDcf;
+
+    impl DcfInhalation for TestDcf {
+        fn dcf_inhalation(
+            &self,
+            n: Nuclide,
+            _: AgeGroup,
+            _: Organ,
+        ) -> Result<DcfQueryResult, Error> {
+            // made-up coefficient
+            let values = (n.to_string() == "Co-60")
+                .then(|| DcfValue {
+                    value: 1e-8,
+                    unit: "Sv/Bq".to_string(),
+                    attr: None,
+                    provenance: None,
+                })
+                .into_iter()
+                .collect();
+            Ok(DcfQueryResult::new("test", values))
+        }
+    }
+
+    #[test]
+    fn dac_hours() {
+        let co60: Nuclide = "Co-60".parse().unwrap();
+        let monitoring = AirMonitoring::new(&TestDcf).investigation_level("record", 2.);
+
+        // 20 mSv / (1e-8 Sv/Bq × 2400 m3)
+        let dac = monitoring.dac(co60).unwrap().unwrap();
+        assert!((dac - 0.02 / 2.4e-5).abs() < 1e-9);
+
+        let sample = |concentration: f64, duration: f64| AirSample {
+            duration,
+            concentrations: [(co60, concentration), ("H-3".parse().unwrap(), 1e6)]
+                .into_iter()
+                .collect(),
+        };
+        let report = monitoring
+            .assess(&[sample(dac, 1.), sample(dac / 2., 4.)])
+            .unwrap();
+
+        assert!((report.total_dac_hours - 3.).abs() < 1e-12);
+        // breathing 1.2 m3/h of the samples
+        let intake = 1.2 * (dac + dac / 2. * 4.);
+        assert!((report.committed_dose - intake * 1e-8).abs() < 1e-15);
+        assert_eq!(report.exceeded.len(), 1);
+        assert_eq!(
+            report
+                .warnings
+                .nuclides(SkipReason::NoDoseCoefficient)
+                .collect::<Vec<_>>(),
+            vec!["H-3".parse().unwrap()]
+        );
+    }
+}
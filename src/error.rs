@@ -1,6 +1,6 @@
 use chumsky::prelude::Simple;
 
-use crate::primitive::attr::Energy;
+use crate::primitive::Energy;
 
 #[derive(thiserror::Error, Debug)]
 pub enum Error {
@@ -30,16 +30,42 @@ pub enum Error {
     InvalidPathway(String),
     #[error("invalid organ: {0}")]
     InvalidOrgan(String),
+    #[error("invalid activity unit: {0}")]
+    InvalidActivityUnit(String),
+    #[error("invalid stability class: {0}")]
+    InvalidStabilityClass(String),
+    #[error("invalid query: {0}")]
+    InvalidQuery(String),
+    #[error("invalid parameter: {0}")]
+    InvalidParameter(String),
+    #[error("unknown dataset: {0}")]
+    UnknownDataset(String),
+    #[error("unknown pathway: {0}")]
+    UnknownPathway(String),
+    #[error("unsupported plugin API version: {0}")]
+    UnsupportedPluginApi(u32),
+    #[error("unsupported schema version: {0}")]
+    UnsupportedSchemaVersion(u32),
     #[error(transparent)]
     Unexpected(#[from] anyhow::Error),
     #[error(transparent)]
     StdIoError(#[from] std::io::Error),
+    #[error("unsupported compression: {0} (enable the corresponding feature)")]
+    UnsupportedCompression(String),
+    #[error("cancelled")]
+    Cancelled,
     #[error("invalid file path")]
     InvalidFilePath,
     #[error("invalid mdb file")]
     InvalidMdbFile,
     #[error(transparent)]
     MdbSqlError(#[from] mdbsql::Error),
+    #[error(transparent)]
+    JsonError(#[from] serde_json::Error),
+    #[error(transparent)]
+    TomlDeError(#[from] toml::de::Error),
+    #[error(transparent)]
+    TomlSerError(#[from] toml::ser::Error),
 }
 
 // fixme: remove this impl
@@ -0,0 +1,521 @@
+//! C ABI for nuclide, decay and dose coefficient queries
+//!
+//! The shared library `icrp107` is built by the `radioactive-ffi` crate of
+//! the workspace, `cargo build --release -p radioactive-ffi`, and declared to
+//! C and Fortran callers by `include/icrp107.h`, generated from this module
+//! with `cbindgen --config cbindgen.toml --output include/icrp107.h`.
+//!
+//! Nuclides are passed as ids (`ZZZAAAMMMM`, see [`Nuclide::id`]). Functions
+//! return [`ICRP107_OK`] or one of the `ICRP107_ERR_*` codes; the message of
+//! the last error of the calling thread is available from
+//! [`icrp107_last_error`]. Handles returned by the `*_open` functions are
+//! owned by the caller, released with the matching `*_close`, and may be
+//! shared between threads.
+//!
+//! ```c
+//! uint32_t cs137;
+//! icrp107_nuclide_parse("Cs-137", &cs137);
+//!
+//! Icrp107Decay *data = icrp107_decay_open("data/icrp107");
+//! double activity = 1e6, out_activity[16];
+//! uint32_t out_ids[16];
+//! size_t n = 16;
+//! icrp107_decay(data, 1, &cs137, &activity, 86400., out_ids, out_activity, &n);
+//! icrp107_decay_close(data);
+//! ```
+
+use std::cell::RefCell;
+use std::ffi::{c_char, c_int, CStr};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::path::Path;
+use std::sync::Arc;
+
+use crate::dataset::radtoolbox::{icrp68::Icrp68, icrp72::Icrp72};
+use crate::dataset::Icrp107;
+use crate::decaychain::{BatemanDecaySolver, Inventory};
+use crate::error::Error;
+use crate::primitive::attr::{DcfIngestion, DcfInhalation, NuclideHalfLife};
+use crate::primitive::{AgeGroup, DcfQueryResult, Nuclide, Organ};
+
+pub const ICRP107_OK: c_int = 0;
+/// A pointer argument is null
+pub const ICRP107_ERR_NULL: c_int = 1;
+/// An argument could not be parsed
+pub const ICRP107_ERR_INVALID: c_int = 2;
+/// The nuclide is not in the dataset, or has no coefficient
+pub const ICRP107_ERR_NOT_FOUND: c_int = 3;
+/// The output buffer is too small, the required length is written back
+pub const ICRP107_ERR_BUFFER: c_int = 4;
+/// The dataset could not be read
+pub const ICRP107_ERR_DATA: c_int = 5;
+/// Internal error (panic)
+pub const ICRP107_ERR_PANIC: c_int = 6;
+
+thread_local! {
+    static LAST_ERROR: RefCell<String> = const { RefCell::new(String::new()) };
+}
+
+struct Failure(c_int, String);
+
+impl From<Error> for Failure {
+    fn from(e: Error) -> Self {
+        let code = match e {
+            Error::InvalidNuclide(_) => ICRP107_ERR_NOT_FOUND,
+            Error::InvalidSymbol(_)
+            | Error::InvalidState(_)
+            | Error::InvalidAgeGroup(_)
+            | Error::InvalidOrgan(_) => ICRP107_ERR_INVALID,
+            _ => ICRP107_ERR_DATA,
+        };
+
+        Self(code, e.to_string())
+    }
+}
+
+/// Run `f`, recording its error, without unwinding into the caller
+///
+/// Nothing observed by the caller is left half-updated by a panic, the
+/// outputs of a failed call are unspecified.
+fn guard<F: FnOnce() -> Result<(), Failure>>(f: F) -> c_int {
+    let (code, message) = match catch_unwind(AssertUnwindSafe(f)) {
+        Ok(Ok(())) => (ICRP107_OK, String::new()),
+        Ok(Err(Failure(code, message))) => (code, message),
+        Err(_) => (ICRP107_ERR_PANIC, "panic".to_string()),
+    };
+    LAST_ERROR.with(|e| *e.borrow_mut() = message);
+
+    code
+}
+
+fn null() -> Failure {
+    Failure(ICRP107_ERR_NULL, "null pointer".to_string())
+}
+
+unsafe fn str_arg<'a>(s: *const c_char) -> Result<&'a str, Failure> {
+    if s.is_null() {
+        return Err(null());
+    }
+    CStr::from_ptr(s)
+        .to_str()
+        .map_err(|e| Failure(ICRP107_ERR_INVALID, e.to_string()))
+}
+
+fn nuclide_arg(id: u32) -> Result<Nuclide, Failure> {
+    Nuclide::try_from_id(id).map_err(|e| Failure(ICRP107_ERR_INVALID, e.to_string()))
+}
+
+unsafe fn out_arg<'a, T>(p: *mut T) -> Result<&'a mut T, Failure> {
+    p.as_mut().ok_or_else(null)
+}
+
+/// Copy `s` with a trailing NUL into `buf` of `*len` bytes, `*len` is set to the bytes needed
+unsafe fn write_str(s: &str, buf: *mut c_char, len: *mut usize) -> Result<(), Failure> {
+    let len = out_arg(len)?;
+    let capacity = std::mem::replace(len, s.len() + 1);
+    if buf.is_null() || capacity < s.len() + 1 {
+        return Err(Failure(ICRP107_ERR_BUFFER, "buffer too small".to_string()));
+    }
+    std::ptr::copy_nonoverlapping(s.as_ptr() as *const c_char, buf, s.len());
+    *buf.add(s.len()) = 0;
+
+    Ok(())
+}
+
+/// Message of the last error of the calling thread (empty after a success)
+///
+/// # Safety
+///
+/// `buf` must point to `*len` writable bytes, `len` must be valid.
+#[no_mangle]
+pub unsafe extern "C" fn icrp107_last_error(buf: *mut c_char, len: *mut usize) -> c_int {
+    let message = LAST_ERROR.with(|e| e.borrow().clone());
+    match write_str(&message, buf, len) {
+        Ok(()) => ICRP107_OK,
+        Err(Failure(code, _)) => code,
+    }
+}
+
+/// Id of a nuclide given in notation like "Cs-137" or "Tc-99m"
+///
+/// # Safety
+///
+/// `name` must be a NUL-terminated string, `id` must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn icrp107_nuclide_parse(name: *const c_char, id: *mut u32) -> c_int {
+    guard(|| {
+        let nuclide: Nuclide = str_arg(name)?
+            .parse()
+            .map_err(|e: Error| Failure(ICRP107_ERR_INVALID, e.to_string()))?;
+        *out_arg(id)? = nuclide
+            .id()
+            .ok_or_else(|| Failure(ICRP107_ERR_INVALID, nuclide.to_string()))?;
+        Ok(())
+    })
+}
+
+/// Notation of a nuclide id, written NUL-terminated to `buf` of `*len` bytes
+///
+/// # Safety
+///
+/// `buf` must point to `*len` writable bytes, `len` must be valid.
+#[no_mangle]
+pub unsafe extern "C" fn icrp107_nuclide_name(id: u32, buf: *mut c_char, len: *mut usize) -> c_int {
+    guard(|| write_str(&nuclide_arg(id)?.to_string(), buf, len))
+}
+
+/// ICRP-107 decay data with its Bateman solver
+pub struct Icrp107Decay {
+    data: Arc<Icrp107>,
+    solver: Arc<BatemanDecaySolver<Icrp107>>,
+}
+
+/// Open an ICRP-107 data directory, null on failure
+///
+/// # Safety
+///
+/// `path` must be a NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn icrp107_decay_open(path: *const c_char) -> *mut Icrp107Decay {
+    let mut handle = std::ptr::null_mut();
+    guard(|| {
+        let data = Arc::new(Icrp107::open(Path::new(str_arg(path)?))?);
+        handle = Box::into_raw(Box::new(Icrp107Decay {
+            solver: BatemanDecaySolver::new(data.clone()),
+            data,
+        }));
+        Ok(())
+    });
+
+    handle
+}
+
+/// # Safety
+///
+/// `handle` must come from [`icrp107_decay_open`] and not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn icrp107_decay_close(handle: *mut Icrp107Decay) {
+    if !handle.is_null() {
+        drop(Box::from_raw(handle));
+    }
+}
+
+/// Half-life (s)
+///
+/// # Safety
+///
+/// `handle` must be a live handle, `half_life` must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn icrp107_half_life(
+    handle: *const Icrp107Decay,
+    id: u32,
+    half_life: *mut f64,
+) -> c_int {
+    guard(|| {
+        let handle = handle.as_ref().ok_or_else(null)?;
+        *out_arg(half_life)? = handle.data.half_life(nuclide_arg(id)?)?.as_sec();
+        Ok(())
+    })
+}
+
+/// Activities after `time` (s) of a mixture of `n` nuclides
+///
+/// On input `*out_len` is the capacity of `out_ids` and `out_activities`, on
+/// output the number of nuclides written (or needed, with [`ICRP107_ERR_BUFFER`]).
+///
+/// # Safety
+///
+/// `handle` must be a live handle, `ids` and `activities` must point to `n`
+/// values, `out_ids` and `out_activities` to `*out_len` writable values.
+#[no_mangle]
+pub unsafe extern "C" fn icrp107_decay(
+    handle: *const Icrp107Decay,
+    n: usize,
+    ids: *const u32,
+    activities: *const f64,
+    time: f64,
+    out_ids: *mut u32,
+    out_activities: *mut f64,
+    out_len: *mut usize,
+) -> c_int {
+    guard(|| {
+        let handle = handle.as_ref().ok_or_else(null)?;
+        if n > 0 && (ids.is_null() || activities.is_null()) {
+            return Err(null());
+        }
+
+        let mut inventory = Inventory::new();
+        for i in 0..n {
+            let nuclide = nuclide_arg(*ids.add(i))?;
+            handle.data.half_life(nuclide)?;
+            inventory.add(nuclide, *activities.add(i));
+        }
+        let decayed = handle.solver.decay(&inventory, time);
+
+        let out_len = out_arg(out_len)?;
+        let capacity = std::mem::replace(out_len, decayed.len());
+        if capacity < decayed.len() || out_ids.is_null() || out_activities.is_null() {
+            return Err(Failure(ICRP107_ERR_BUFFER, "buffer too small".to_string()));
+        }
+        for (i, (nuclide, &activity)) in decayed.iter().enumerate() {
+            *out_ids.add(i) = nuclide.id().unwrap_or_default();
+            *out_activities.add(i) = activity;
+        }
+
+        Ok(())
+    })
+}
+
+trait Dcf: DcfIngestion + DcfInhalation + Send + Sync {}
+
+impl<T> Dcf for T where T: DcfIngestion + DcfInhalation + Send + Sync {}
+
+/// Internal dose coefficients of a Radiological Toolbox database
+pub struct Icrp107Dcf(Box<dyn Dcf>);
+
+/// Open `icrp68.mdb` or `icrp72.mdb` of the Radiological Toolbox, null on failure
+///
+/// # Safety
+///
+/// `path` must be a NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn icrp107_dcf_open(path: *const c_char) -> *mut Icrp107Dcf {
+    let mut handle = std::ptr::null_mut();
+    guard(|| {
+        let path = Path::new(str_arg(path)?);
+        let dcf: Box<dyn Dcf> = match path.file_stem().and_then(|s| s.to_str()) {
+            Some("icrp68") => Box::new(Icrp68::open(path)?),
+            Some("icrp72") => Box::new(Icrp72::open(path)?),
+            _ => {
+                return Err(Failure(
+                    ICRP107_ERR_INVALID,
+                    format!("unknown database: {}", path.display()),
+                ))
+            }
+        };
+        handle = Box::into_raw(Box::new(Icrp107Dcf(dcf)));
+        Ok(())
+    });
+
+    handle
+}
+
+/// # Safety
+///
+/// `handle` must come from [`icrp107_dcf_open`] and not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn icrp107_dcf_close(handle: *mut Icrp107Dcf) {
+    if !handle.is_null() {
+        drop(Box::from_raw(handle));
+    }
+}
+
+unsafe fn dcf_query<F>(
+    id: u32,
+    age_group: *const c_char,
+    organ: *const c_char,
+    value: *mut f64,
+    query: F,
+) -> c_int
+where
+    F: FnOnce(Nuclide, AgeGroup, Organ) -> Result<DcfQueryResult, Error>,
+{
+    guard(|| {
+        let nuclide = nuclide_arg(id)?;
+        let age_group: AgeGroup = str_arg(age_group)?.parse()?;
+        let organ: Organ = str_arg(organ)?.parse()?;
+        let res = query(nuclide, age_group, organ)?;
+        let max = res
+            .max()
+            .ok_or_else(|| Failure(ICRP107_ERR_NOT_FOUND, format!("{:?}", res.not_found)))?;
+        *out_arg(value)? = max.value;
+        Ok(())
+    })
+}
+
+/// Largest ingestion coefficient (Sv/Bq) of the chemical forms of a nuclide
+///
+/// `age_group` and `organ` are given as in the datasets, e.g. "Adult" and "EffectiveDose".
+///
+/// # Safety
+///
+/// `handle` must be a live handle, `age_group` and `organ` NUL-terminated
+/// strings, `value` valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn icrp107_dcf_ingestion(
+    handle: *const Icrp107Dcf,
+    id: u32,
+    age_group: *const c_char,
+    organ: *const c_char,
+    value: *mut f64,
+) -> c_int {
+    let Some(handle) = handle.as_ref() else {
+        return guard(|| Err(null()));
+    };
+    dcf_query(id, age_group, organ, value, |nuclide, age_group, organ| {
+        handle.0.dcf_ingestion(nuclide, age_group, organ)
+    })
+}
+
+/// Largest inhalation coefficient (Sv/Bq) of the absorption types of a nuclide
+///
+/// # Safety
+///
+/// As [`icrp107_dcf_ingestion`].
+#[no_mangle]
+pub unsafe extern "C" fn icrp107_dcf_inhalation(
+    handle: *const Icrp107Dcf,
+    id: u32,
+    age_group: *const c_char,
+    organ: *const c_char,
+    value: *mut f64,
+) -> c_int {
+    let Some(handle) = handle.as_ref() else {
+        return guard(|| Err(null()));
+    };
+    dcf_query(id, age_group, organ, value, |nuclide, age_group, organ| {
+        handle.0.dcf_inhalation(nuclide, age_group, organ)
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use std::ffi::CString;
+
+    use super::*;
+
+    fn last_error() -> String {
+        let mut buf = [0 as c_char; 64];
+        let mut len = buf.len();
+        unsafe {
+            assert_eq!(icrp107_last_error(buf.as_mut_ptr(), &mut len), ICRP107_OK);
+            CStr::from_ptr(buf.as_ptr()).to_str().unwrap().to_string()
+        }
+    }
+
+    #[test]
+    fn nuclide_round_trip() {
+        let name = CString::new("Tc-99m").unwrap();
+        let mut id = 0;
+        assert_eq!(
+            unsafe { icrp107_nuclide_parse(name.as_ptr(), &mut id) },
+            ICRP107_OK
+        );
+        assert_eq!(Some(id), "Tc-99m".parse::<Nuclide>().unwrap().id());
+
+        let mut buf = [0 as c_char; 4];
+        let mut len = buf.len();
+        let code = unsafe { icrp107_nuclide_name(id, buf.as_mut_ptr(), &mut len) };
+        assert_eq!(code, ICRP107_ERR_BUFFER);
+        assert_eq!(len, 7);
+
+        let mut buf = [0 as c_char; 16];
+        let mut len = buf.len();
+        unsafe {
+            assert_eq!(
+                icrp107_nuclide_name(id, buf.as_mut_ptr(), &mut len),
+                ICRP107_OK
+            );
+            assert_eq!(CStr::from_ptr(buf.as_ptr()).to_str().unwrap(), "Tc-99m");
+        }
+    }
+
+    #[test]
+    fn errors() {
+        let name = CString::new("Xx-1").unwrap();
+        let mut id = 0;
+        let code = unsafe { icrp107_nuclide_parse(name.as_ptr(), &mut id) };
+        assert_eq!(code, ICRP107_ERR_INVALID);
+        assert!(!last_error().is_empty());
+
+        let code = unsafe { icrp107_nuclide_parse(std::ptr::null(), &mut id) };
+        assert_eq!(code, ICRP107_ERR_NULL);
+
+        let path = CString::new("/nonexistent").unwrap();
+        assert!(unsafe { icrp107_decay_open(path.as_ptr()) }.is_null());
+        assert!(unsafe { icrp107_dcf_open(path.as_ptr()) }.is_null());
+
+        let mut value = 0.;
+        let adult = CString::new("Adult").unwrap();
+        let code = unsafe {
+            icrp107_dcf_ingestion(
+                std::ptr::null(),
+                id,
+                adult.as_ptr(),
+                adult.as_ptr(),
+                &mut value,
+            )
+        };
+        assert_eq!(code, ICRP107_ERR_NULL);
+
+        let mut len = 0;
+        let code = unsafe { icrp107_nuclide_name(0, std::ptr::null_mut(), &mut len) };
+        assert_eq!(code, ICRP107_ERR_INVALID);
+    }
+
+    #[test]
+    fn header_codes() {
+        let header = include_str!("../include/icrp107.h");
+        let defines: Vec<(&str, c_int)> = header
+            .lines()
+            .filter_map(|line| {
+                let mut words = line.strip_prefix("#define ICRP107_")?.split_whitespace();
+                Some((words.next()?, words.next()?.parse().ok()?))
+            })
+            .collect();
+
+        assert_eq!(
+            defines,
+            vec![
+                ("OK", ICRP107_OK),
+                ("ERR_NULL", ICRP107_ERR_NULL),
+                ("ERR_INVALID", ICRP107_ERR_INVALID),
+                ("ERR_NOT_FOUND", ICRP107_ERR_NOT_FOUND),
+                ("ERR_BUFFER", ICRP107_ERR_BUFFER),
+                ("ERR_DATA", ICRP107_ERR_DATA),
+                ("ERR_PANIC", ICRP107_ERR_PANIC),
+            ]
+        );
+        for function in [
+            "icrp107_last_error",
+            "icrp107_nuclide_parse",
+            "icrp107_nuclide_name",
+            "icrp107_decay_open",
+            "icrp107_decay_close",
+            "icrp107_half_life",
+            "icrp107_decay(",
+            "icrp107_dcf_open",
+            "icrp107_dcf_close",
+            "icrp107_dcf_ingestion",
+            "icrp107_dcf_inhalation",
+        ] {
+            assert!(header.contains(function), "{} is not declared", function);
+        }
+    }
+
+    #[test]
+    #[ignore]
+    fn decay() {
+        let path = CString::new("data/icrp107").unwrap();
+        let handle = unsafe { icrp107_decay_open(path.as_ptr()) };
+        assert!(!handle.is_null());
+
+        let id = "Sr-90".parse::<Nuclide>().unwrap().id().unwrap();
+        let (mut out_ids, mut out_activities, mut len) = ([0; 4], [0.; 4], 4);
+        let code = unsafe {
+            icrp107_decay(
+                handle,
+                1,
+                &id,
+                &1e6,
+                86400. * 365.,
+                out_ids.as_mut_ptr(),
+                out_activities.as_mut_ptr(),
+                &mut len,
+            )
+        };
+        unsafe { icrp107_decay_close(handle) };
+
+        assert_eq!(code, ICRP107_OK);
+        assert_eq!(len, 2);
+    }
+}
@@ -0,0 +1,359 @@
+//! Fixed-width records, as in the data files of ICRP-107 and other nuclear
+//! data tables
+//!
+//! Records are deserialized with serde from the fields of a [`FieldSet`],
+//! which [`fortran_fields`] builds from the Fortran `FORMAT` the table is
+//! documented with. Nested groups become sequences, so a repeated group
+//! deserializes into a `Vec` of tuples, and blank fields into `None`.
+//!
+//! ```
+//! use radioactive::fixedformat::{fortran_fields, RecordReader};
+//! use radioactive::primitive::Nuclide;
+//! use serde::Deserialize;
+//!
+//! // nuclide, up to two daughters with their branching fractions and half-life (d)
+//! #[derive(Debug, Deserialize)]
+//! struct Row {
+//!     nuclide: Nuclide,
+//!     progeny: Vec<Option<(Nuclide, f64)>>,
+//!     half_life: f64,
+//! }
+//!
+//! let (fields, _width) = fortran_fields("(a8,2(a8,f6.0),f10.0)").unwrap();
+//! let table = "\
+//! nuclide daughters                    half-life
+//! Sr-90   Y-90    1.0                     10512.
+//! Cs-137  Ba-137m 0.946                   10976.
+//! ";
+//!
+//! let rows: Vec<Row> = RecordReader::with_fields(table.as_bytes(), fields)
+//!     .header_lines(1)
+//!     .collect::<Result<_, _>>()
+//!     .unwrap();
+//! assert_eq!(rows.len(), 2);
+//! assert_eq!(rows[1].progeny[0], Some(("Ba-137m".parse().unwrap(), 0.946)));
+//! assert_eq!(rows[1].progeny[1], None);
+//! ```
+//!
+//! A record type implementing [`FixedWidth`] is read with [`RecordReader::new`]
+//! instead, and [`crate::derive_from_str`] implements `FromStr` for it with
+//! [`from_str`].
+
+use std::io::BufRead;
+use std::marker::PhantomData;
+
+use serde::de::DeserializeOwned;
+
+use crate::error::Error;
+
+pub use fixed_width::{FieldConfig, FieldSet, FixedWidth};
+
+/// Widest record (columns) a format may describe, bounding the fields built
+/// from repeat counts
+pub const MAX_RECORD_WIDTH: usize = 1 << 16;
+
+/// Edit descriptors taking a field, besides `x`
+const DESCRIPTORS: [&str; 9] = ["a", "i", "f", "e", "es", "en", "d", "g", "l"];
+
+/// Fields of a Fortran `FORMAT`, e.g. `(a7,2f10.0,3x,2(a7,e11.0))`, and the
+/// record width
+///
+/// Edit descriptors (`a`, `i`, `f`, `e`, `es`, `en`, `d`, `g`, `l`) take a
+/// repeat count and a width, `x` skips columns and parenthesized groups,
+/// which may repeat, become sequences of fields. Precisions (`f10.4`) are
+/// ignored: the text of each field is parsed as written. Any other text, or
+/// a record wider than [`MAX_RECORD_WIDTH`], is an error.
+pub fn fortran_fields(fmt: &str) -> Result<(FieldSet, usize), Error> {
+    let items: String = fmt
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect::<String>()
+        .to_lowercase();
+    items
+        .strip_prefix('(')
+        .and_then(|items| items.strip_suffix(')'))
+        .ok_or_else(|| "not in parentheses".to_string())
+        .and_then(|items| fields_at(items, 0))
+        .map_err(|e| Error::Unexpected(anyhow::anyhow!("invalid fortran format {}: {}", fmt, e)))
+}
+
+/// Fields of the comma separated items of a format, starting at column `offset`
+fn fields_at(items: &str, offset: usize) -> Result<(FieldSet, usize), String> {
+    let mut start = 0;
+    let mut fields = FieldSet::Seq(vec![]);
+
+    for item in split_items(items)? {
+        let digits = item
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(item.len());
+        let rep: usize = match &item[..digits] {
+            "" => 1,
+            rep => rep
+                .parse()
+                .ok()
+                .filter(|&rep| rep > 0)
+                .ok_or_else(|| format!("repeat count {}", rep))?,
+        };
+        let descriptor = &item[digits..];
+
+        if let Some(group) = descriptor
+            .strip_prefix('(')
+            .and_then(|d| d.strip_suffix(')'))
+        {
+            let mut nested_fields = vec![];
+            for _ in 0..rep {
+                let (nested_field, len) = fields_at(group, column(offset, 1, start)?)?;
+                nested_fields.push(nested_field);
+                start = column(start, 1, len)?;
+            }
+            fields = fields.append(FieldSet::Seq(nested_fields));
+            continue;
+        }
+
+        let name_len = descriptor
+            .find(|c: char| !c.is_ascii_alphabetic())
+            .unwrap_or(descriptor.len());
+        let (name, width) = descriptor.split_at(name_len);
+        let len = match width.split_once('.') {
+            _ if width.is_empty() => Some(1),
+            Some((width, precision))
+                if !precision.is_empty() && precision.chars().all(|c| c.is_ascii_digit()) =>
+            {
+                width.parse().ok()
+            }
+            Some(_) => None,
+            None => width.parse().ok(),
+        }
+        .filter(|&len| len > 0)
+        .ok_or_else(|| format!("edit descriptor {}", item))?;
+
+        if name == "x" {
+            start = column(start, rep, len)?;
+        } else if DESCRIPTORS.contains(&name) {
+            for _ in 0..rep {
+                let at = column(offset, 1, start)?;
+                fields = fields.extend(FieldSet::new_field(at..column(at, 1, len)?));
+                start = column(start, 1, len)?;
+            }
+        } else {
+            return Err(format!("edit descriptor {}", item));
+        }
+    }
+
+    Ok((fields, start))
+}
+
+/// Column `start + rep * len`, checked against [`MAX_RECORD_WIDTH`]
+fn column(start: usize, rep: usize, len: usize) -> Result<usize, String> {
+    rep.checked_mul(len)
+        .and_then(|width| start.checked_add(width))
+        .filter(|&column| column <= MAX_RECORD_WIDTH)
+        .ok_or_else(|| format!("record wider than {} columns", MAX_RECORD_WIDTH))
+}
+
+/// Items of a format separated by the commas outside of groups
+fn split_items(items: &str) -> Result<Vec<&str>, String> {
+    let mut split = vec![];
+    let (mut depth, mut begin) = (0usize, 0);
+    for (i, c) in items.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth = depth.checked_sub(1).ok_or("unbalanced parentheses")?,
+            ',' if depth == 0 => {
+                split.push(&items[begin..i]);
+                begin = i + 1;
+            }
+            _ => {}
+        }
+    }
+    split.push(&items[begin..]);
+
+    if depth != 0 {
+        return Err("unbalanced parentheses".to_string());
+    }
+    if split.iter().any(|item| item.is_empty()) {
+        return Err("empty item".to_string());
+    }
+
+    Ok(split)
+}
+
+/// Record of a line
+pub fn from_str<T: FixedWidth + DeserializeOwned>(s: &str) -> Result<T, Error> {
+    fixed_width::from_str(s).map_err(|e| Error::Unexpected(e.into()))
+}
+
+/// Records of the lines of a table, skipping header lines, blank lines and
+/// comments
+///
+/// Errors carry the line number (1-based) of the record.
+pub struct RecordReader<R, T> {
+    reader: R,
+    fields: FieldSet,
+    header_lines: usize,
+    comment: Option<String>,
+    line: usize,
+    _marker: PhantomData<T>,
+}
+
+impl<R: BufRead, T: FixedWidth + DeserializeOwned> RecordReader<R, T> {
+    /// Records of the fields of [`FixedWidth::fields`]
+    pub fn new(reader: R) -> Self {
+        Self::with_fields(reader, T::fields())
+    }
+}
+
+impl<R: BufRead, T: DeserializeOwned> RecordReader<R, T> {
+    /// Records of the given fields, e.g. of [`fortran_fields`]
+    pub fn with_fields(reader: R, fields: FieldSet) -> Self {
+        Self {
+            reader,
+            fields,
+            header_lines: 0,
+            comment: None,
+            line: 0,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Lines to skip at the start of the table
+    pub fn header_lines(mut self, n: usize) -> Self {
+        self.header_lines = n;
+        self
+    }
+
+    /// Skip lines starting with `prefix`
+    pub fn comment(mut self, prefix: &str) -> Self {
+        self.comment = Some(prefix.to_string());
+        self
+    }
+}
+
+impl<R: BufRead, T: DeserializeOwned> Iterator for RecordReader<R, T> {
+    type Item = Result<T, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut buf = String::new();
+        loop {
+            buf.clear();
+            match self.reader.read_line(&mut buf) {
+                Ok(0) => return None,
+                Ok(_) => self.line += 1,
+                Err(e) => return Some(Err(e.into())),
+            }
+
+            let record = buf.trim_end_matches(['\r', '\n']);
+            let comment = self
+                .comment
+                .as_deref()
+                .is_some_and(|prefix| record.starts_with(prefix));
+            if self.line > self.header_lines && !comment && !record.trim().is_empty() {
+                let line = self.line;
+                return Some(
+                    fixed_width::from_str_with_fields(record, self.fields.clone())
+                        .map_err(|e| Error::Unexpected(anyhow::anyhow!("line {}: {}", line, e))),
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use fixed_width::{field, field_seq};
+
+    use super::*;
+
+    #[test]
+    fn fields_of_fortran_format() {
+        let (fields, len) = fortran_fields("(a10,2i10,3f10.0,4e10.0)").unwrap();
+        let fields = fields.flatten();
+
+        assert_eq!(len, 100);
+        for (i, field) in fields.iter().enumerate() {
+            assert_eq!(
+                format!("{:?}", field),
+                format!("{:?}", FieldConfig::new(i * 10..(i + 1) * 10))
+            );
+        }
+
+        let (fields, len) = fortran_fields("(A5, 2X, 2(A3,F4.1))").unwrap();
+        assert_eq!(len, 21);
+        assert_eq!(
+            format!("{:?}", fields),
+            format!(
+                "{:?}",
+                field_seq![
+                    field!(0..5),
+                    field_seq![
+                        field_seq![field!(7..10), field!(10..14)],
+                        field_seq![field!(14..17), field!(17..21)],
+                    ],
+                ]
+            )
+        );
+
+        assert!(fortran_fields("()").is_err());
+        assert!(fortran_fields("(2(a3)5)").is_err());
+    }
+
+    #[test]
+    fn invalid_fortran_formats() {
+        for fmt in [
+            "a7,f10",
+            "(a7,?,f10)",
+            "(a7,,f10)",
+            "(a7,f10.x)",
+            "(a7,q10)",
+            "(a0)",
+            "(0a7)",
+            "(2(a3)",
+            "(a3))",
+            "(99999999999999999999a1)",
+            "(18446744073709551615a2)",
+            "(100000(100000x))",
+            "(65537a1)",
+        ] {
+            assert!(fortran_fields(fmt).is_err(), "{}", fmt);
+        }
+
+        let (_, len) = fortran_fields("(65536a1)").unwrap();
+        assert_eq!(len, MAX_RECORD_WIDTH);
+        let (fields, len) = fortran_fields("(2(a2,1x,2(i1)),es9.2)").unwrap();
+        assert_eq!(len, 19);
+        assert_eq!(fields.flatten().len(), 7);
+    }
+
+    #[test]
+    fn nested_fields_of_fortran_format() {
+        let (fields, _len) = fortran_fields(
+            "(a7,a10,a8,28x,4(a7,6x,e11.0,1x),f7.0,2f8.0,3i4,i5,i4,e11.0,e10.0,e9.0)",
+        )
+        .unwrap();
+
+        let complex_fields = field_seq![
+            field!(0..7),
+            field!(7..17),
+            field!(17..25),
+            field_seq![
+                field_seq![field!(53..60), field!(66..77)],
+                field_seq![field!(78..85), field!(91..102)],
+                field_seq![field!(103..110), field!(116..127)],
+                field_seq![field!(128..135), field!(141..152)],
+            ],
+            field!(153..160),
+            field!(160..168),
+            field!(168..176),
+            field!(176..180),
+            field!(180..184),
+            field!(184..188),
+            field!(188..193),
+            field!(193..197),
+            field!(197..208),
+            field!(208..218),
+            field!(218..227),
+        ];
+
+        assert_eq!(format!("{:?}", fields), format!("{:?}", complex_fields));
+    }
+}
@@ -0,0 +1,268 @@
+//! Small, self-consistent decay dataset for tests
+//!
+//! Half-lives and branch ratios are rounded values from ICRP-107; chains are
+//! cut where a daughter is not part of the fixture, so every progeny listed is
+//! itself a fixture nuclide (or spontaneous fission products).
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use chumsky::prelude::{end, Parser};
+
+use crate::error::Error;
+use crate::primitive::attr::{NuclideDecayMode, NuclideHalfLife, NuclideList, NuclideProgeny};
+use crate::primitive::parser::decaymodeflags;
+use crate::primitive::{DecayModeSet, HalfLife, Nuclide, Progeny, TimeUnit};
+use crate::provenance::{DatasetIdentity, DatasetInfo};
+
+type Row = (
+    &'static str,
+    f64,
+    TimeUnit,
+    &'static str,
+    &'static [(&'static str, f64, &'static str)],
+);
+
+#[rustfmt::skip]
+const NUCLIDES: [Row; 25] = [
+    ("H-3", 12.32, TimeUnit::Year, "B-", &[]),
+    ("C-14", 5.70e3, TimeUnit::Year, "B-", &[]),
+    ("Co-60", 5.2714, TimeUnit::Year, "B-", &[]),
+    ("Sr-90", 28.79, TimeUnit::Year, "B-", &[("Y-90", 1., "B-")]),
+    ("Y-90", 64.10, TimeUnit::Hour, "B-", &[]),
+    ("Mo-99", 65.94, TimeUnit::Hour, "B-", &[("Tc-99m", 0.8773, "B-"), ("Tc-99", 0.1227, "B-")]),
+    ("Tc-99m", 6.015, TimeUnit::Hour, "IT B-", &[("Tc-99", 0.99996, "IT")]),
+    ("Tc-99", 2.111e5, TimeUnit::Year, "B-", &[]),
+    ("I-131", 8.0252, TimeUnit::Day, "B-", &[("Xe-131m", 0.01086, "B-")]),
+    ("Xe-131m", 11.84, TimeUnit::Day, "IT", &[]),
+    ("Cs-137", 30.1671, TimeUnit::Year, "B-", &[("Ba-137m", 0.944, "B-")]),
+    ("Ba-137m", 2.552, TimeUnit::Minute, "IT", &[]),
+    ("Ra-226", 1600., TimeUnit::Year, "A", &[("Rn-222", 1., "A")]),
+    ("Rn-222", 3.8235, TimeUnit::Day, "A", &[("Po-218", 1., "A")]),
+    ("Po-218", 3.098, TimeUnit::Minute, "A B-", &[("Pb-214", 0.9998, "A"), ("At-218", 2e-4, "B-")]),
+    ("At-218", 1.5, TimeUnit::Second, "A", &[("Bi-214", 1., "A")]),
+    ("Pb-214", 26.8, TimeUnit::Minute, "B-", &[("Bi-214", 1., "B-")]),
+    ("Bi-214", 19.9, TimeUnit::Minute, "B- A", &[("Po-214", 0.99979, "B-"), ("Tl-210", 2.1e-4, "A")]),
+    ("Po-214", 164.3, TimeUnit::MicroSecond, "A", &[("Pb-210", 1., "A")]),
+    ("Tl-210", 1.3, TimeUnit::Minute, "B-", &[("Pb-210", 1., "B-")]),
+    ("Pb-210", 22.20, TimeUnit::Year, "B-", &[("Bi-210", 1., "B-")]),
+    ("Bi-210", 5.012, TimeUnit::Day, "B-", &[("Po-210", 1., "B-")]),
+    ("Po-210", 138.376, TimeUnit::Day, "A", &[]),
+    ("Cf-252", 2.645, TimeUnit::Year, "A SF", &[("Cm-248", 0.96908, "A"), ("SF", 0.03092, "SF")]),
+    ("Cm-248", 3.48e5, TimeUnit::Year, "A SF", &[("SF", 0.0839, "SF")]),
+];
+
+struct Entry {
+    half_life: HalfLife,
+    decay_mode: DecayModeSet,
+    progeny: Vec<Progeny>,
+}
+
+fn decay_mode(s: &str) -> DecayModeSet {
+    DecayModeSet(decaymodeflags().then_ignore(end()).parse(s).unwrap())
+}
+
+/// Decay data of the fixture nuclides
+pub struct FixtureDecayData {
+    nuclides: BTreeMap<Nuclide, Entry>,
+}
+
+impl FixtureDecayData {
+    pub fn new() -> Self {
+        let nuclides = NUCLIDES
+            .iter()
+            .map(|&(nuclide, value, unit, mode, progeny)| {
+                let entry = Entry {
+                    half_life: HalfLife { value, unit },
+                    decay_mode: decay_mode(mode),
+                    progeny: progeny
+                        .iter()
+                        .map(|&(daughter, branch_rate, mode)| Progeny {
+                            nuclide: daughter.parse().unwrap(),
+                            branch_rate,
+                            decay_mode: decay_mode(mode),
+                        })
+                        .collect(),
+                };
+                (nuclide.parse().unwrap(), entry)
+            })
+            .collect();
+
+        Self { nuclides }
+    }
+
+    pub fn nuclides(&self) -> impl Iterator<Item = Nuclide> + '_ {
+        self.nuclides.keys().copied()
+    }
+
+    fn entry(&self, nuclide: Nuclide) -> Result<&Entry, Error> {
+        self.nuclides
+            .get(&nuclide)
+            .ok_or_else(|| Error::InvalidNuclide(nuclide.to_string()))
+    }
+}
+
+impl Default for FixtureDecayData {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl NuclideList for FixtureDecayData {
+    fn nuclide_list(&self) -> Result<BTreeSet<Nuclide>, Error> {
+        Ok(self.nuclides().collect())
+    }
+}
+
+impl NuclideProgeny for FixtureDecayData {
+    fn progeny(&self, nuclide: Nuclide) -> Result<Vec<Progeny>, Error> {
+        self.entry(nuclide).map(|e| e.progeny.clone())
+    }
+}
+
+impl NuclideHalfLife for FixtureDecayData {
+    fn half_life(&self, nuclide: Nuclide) -> Result<HalfLife, Error> {
+        self.entry(nuclide).map(|e| e.half_life)
+    }
+}
+
+impl NuclideDecayMode for FixtureDecayData {
+    fn decay_mode(&self, nuclide: Nuclide) -> Result<DecayModeSet, Error> {
+        self.entry(nuclide).map(|e| e.decay_mode)
+    }
+}
+
+impl DatasetInfo for FixtureDecayData {
+    fn dataset_identity(&self) -> DatasetIdentity {
+        DatasetIdentity::new("fixtures")
+    }
+}
+
+/// Directory of a test, unique to the process and the call, removed when dropped
+#[cfg(test)]
+pub(crate) struct TempDir(std::path::PathBuf);
+
+#[cfg(test)]
+impl TempDir {
+    pub fn new(name: &str) -> Self {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        static COUNT: AtomicUsize = AtomicUsize::new(0);
+
+        let path = std::env::temp_dir().join(format!(
+            "radioactive-{}-{}-{}",
+            name,
+            std::process::id(),
+            COUNT.fetch_add(1, Ordering::Relaxed)
+        ));
+        std::fs::create_dir_all(&path).unwrap();
+        Self(path)
+    }
+
+    pub fn path(&self) -> &std::path::Path {
+        &self.0
+    }
+
+    pub fn join(&self, name: &str) -> std::path::PathBuf {
+        self.0.join(name)
+    }
+}
+
+#[cfg(test)]
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+/// proptest strategies for primitive types
+#[cfg(any(test, feature = "proptest"))]
+pub mod strategy {
+    use proptest::prelude::*;
+
+    use super::NUCLIDES;
+    use crate::primitive::{HalfLife, Nuclide, TimeUnit};
+
+    /// Nuclide with Z in 1..=118, a mass number not below Z and a state digit in 0..=2
+    pub fn nuclide() -> impl Strategy<Value = Nuclide> {
+        (1u32..=118)
+            .prop_flat_map(|z| (Just(z), z..=(3 * z).min(300), 0u32..=2))
+            .prop_map(|(z, a, state)| Nuclide::WithId(z * 10_000_000 + a * 10_000 + state))
+    }
+
+    /// Nuclide of the fixture dataset
+    pub fn fixture_nuclide() -> impl Strategy<Value = Nuclide> {
+        prop::sample::select(&NUCLIDES[..]).prop_map(|row| row.0.parse().unwrap())
+    }
+
+    pub fn time_unit() -> impl Strategy<Value = TimeUnit> {
+        prop::sample::select(vec![
+            TimeUnit::MicroSecond,
+            TimeUnit::MilliSecond,
+            TimeUnit::Second,
+            TimeUnit::Minute,
+            TimeUnit::Hour,
+            TimeUnit::Day,
+            TimeUnit::Year,
+        ])
+    }
+
+    pub fn half_life() -> impl Strategy<Value = HalfLife> {
+        (1e-3..1e6f64, time_unit()).prop_map(|(value, unit)| HalfLife { value, unit })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use proptest::prelude::*;
+
+    use super::strategy;
+    use super::*;
+    use crate::decaychain::{BatemanDecaySolver, Inventory};
+
+    #[test]
+    fn fixture_is_self_consistent() {
+        let data = FixtureDecayData::new();
+
+        for nuclide in data.nuclides() {
+            let mut branch_sum = 0.;
+            for daughter in data.progeny(nuclide).unwrap() {
+                branch_sum += daughter.branch_rate;
+                assert!(
+                    daughter.nuclide == Nuclide::FissionProducts
+                        || data.half_life(daughter.nuclide).is_ok()
+                );
+                assert!(data
+                    .decay_mode(nuclide)
+                    .unwrap()
+                    .0
+                    .contains(daughter.decay_mode.0));
+            }
+            assert!(branch_sum <= 1. + 1e-9);
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn nuclide_round_trip(nuclide in strategy::nuclide()) {
+            let parsed: Nuclide = nuclide.to_string().parse().unwrap();
+            prop_assert_eq!(parsed, nuclide);
+        }
+
+        #[test]
+        fn half_life_lambda(half_life in strategy::half_life()) {
+            let ln2 = half_life.as_lambda() * half_life.as_sec();
+            prop_assert!((ln2 - 2f64.ln()).abs() < 1e-12);
+        }
+
+        #[test]
+        fn decay_keeps_parent_activity(nuclide in strategy::fixture_nuclide(), t in 0f64..1e6) {
+            let data = std::sync::Arc::new(FixtureDecayData::new());
+            let solver = BatemanDecaySolver::new(data.clone());
+            let mut inv = Inventory::new();
+            inv.add(nuclide, 1.);
+
+            let res = solver.decay(&inv, t);
+            let expected = (-data.half_life(nuclide).unwrap().as_lambda() * t).exp();
+            prop_assert!((res[&nuclide] - expected).abs() <= 1e-12);
+        }
+    }
+}
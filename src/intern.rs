@@ -0,0 +1,45 @@
+use std::borrow::Cow;
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex, OnceLock};
+
+use serde::{Deserialize, Deserializer};
+
+// Interned strings live for the whole program; they are only used for
+// small vocabularies (transitions, chemical compounds).
+static STRINGS: OnceLock<Mutex<HashSet<Arc<str>>>> = OnceLock::new();
+
+/// Shared copy of a frequently repeated string
+pub(crate) fn intern(s: &str) -> Arc<str> {
+    let mut strings = STRINGS.get_or_init(Default::default).lock().unwrap();
+
+    match strings.get(s) {
+        Some(interned) => interned.clone(),
+        None => {
+            let interned: Arc<str> = Arc::from(s);
+            strings.insert(interned.clone());
+            interned
+        }
+    }
+}
+
+pub(crate) fn deserialize<'de, D>(deserializer: D) -> Result<Arc<str>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let s = Cow::<str>::deserialize(deserializer)?;
+    Ok(intern(&s))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn interned_strings_are_shared() {
+        let a = intern("K-L1");
+        let b = intern(&String::from("K-L1"));
+
+        assert!(Arc::ptr_eq(&a, &b));
+        assert!(!Arc::ptr_eq(&a, &intern("K-L2")));
+    }
+}
@@ -1,5 +1,76 @@
+//! Radiation protection data for Rust
+//!
+//! # Public API
+//!
+//! Types are reached through their module; the crate root does not re-export
+//! anything, so each item has exactly one public path (plus the flattened
+//! re-exports of the module it lives in, e.g. `primitive::Nuclide` for
+//! `primitive::nuclide::Nuclide`).
+//!
+//! | Module | Contents | Stability |
+//! |---|---|---|
+//! | [`primitive`] | nuclides, half-lives, materials, dose coefficients and the data traits in [`primitive::attr`] | stable |
+//! | [`decaychain`] | decay chains, inventories, decay matrices and the Bateman, ODE and CRAM solvers | stable |
+//! | [`dose`] | dose estimates combining decay, dose coefficients and spectra | stable |
+//! | `dose::plume` | Gaussian plume consequence screening (feature `plume`) | unstable |
+//! | [`dataset`] | ICRP-107, NIST and Radiological Toolbox readers | stable at the `dataset::*` re-exports; submodules may change with the file formats |
+//! | [`error`] | [`error::Error`] | stable; new variants may be added |
+//! | [`provenance`] | dataset identity and calculation records | stable |
+//! | [`math`] | interpolation and other numerical utilities | stable |
+//! | `plot` | quick-look plots (feature `plotters`) | unstable |
+//! | `mat` | MATLAB/Octave MAT-file export (feature `matlab`, which needs the HDF5 library) | unstable |
+//! | [`plugin`] | registry of dataset backends provided by other crates | stable |
+//! | `ffi` | C ABI for nuclide, decay and dose coefficient queries (feature `ffi`) | stable |
+//! | [`scenario`] | dose assessments declared in TOML | unstable |
+//! | `serve` | HTTP service for decay and dose coefficient queries (feature `serve`) | unstable |
+//! | [`primitive::parser`] | text parsers used by `FromStr` implementations | unstable; tied to the parser library in use |
+//! | [`decaychain::crosscheck`] | comparison against results of other codes | unstable |
+//! | [`fixedformat`] | Fortran-format field layouts and a reader of fixed-width records | stable, except the `FieldSet`, `FieldConfig` and `FixedWidth` re-exports, which follow the `fixed_width` crate |
+//! | [`fixtures`] | small dataset and proptest strategies for tests | unstable |
+//!
+//! Data files may be stored gzip (feature `gzip`) or zstd (feature `zstd`)
+//! compressed, as `<file>.gz` / `<file>.zst` or under their original names,
+//! and read from zip or tar archives with `open_bundle` (feature `bundle`).
+//!
+//! `Icrp107::sample_decay` draws the particles emitted per disintegration for
+//! Monte Carlo source generation (feature `rand`). Stochastic APIs take a
+//! `&mut impl rand::Rng` or a seed; see `math::random` for the reproducibility
+//! guarantees of seeded results.
+//!
+//! `Icrp107::write_snapshot` saves the parsed decay data to a file that
+//! `Icrp107Snapshot::open` maps read-only in later runs (feature `mmap`).
+//!
+//! `DecayMatrix::to_ndarray` exports decay matrices for transmutation codes
+//! (feature `ndarray`).
+//!
+//! `Icrp107::open_async` parses the data files on the blocking thread pool of
+//! a tokio runtime, with progress notification and cancellation (feature `tokio`).
+//!
+//! `Icrp107::write_hdf5` converts the data files into a single HDF5 file,
+//! read back by `Icrp107Hdf5::open` or by Python and HDFView (feature `hdf5`,
+//! which needs the HDF5 library).
+//!
+//! Stable items are only removed after a release in which they are
+//! `#[deprecated]`; unstable items may change in any release.
+
 pub mod dataset;
 pub mod decaychain;
+pub mod dose;
 pub mod error;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod fixedformat;
+pub mod fixtures;
+mod intern;
 mod macros;
+#[cfg(feature = "matlab")]
+pub mod mat;
+pub mod math;
+#[cfg(feature = "plotters")]
+pub mod plot;
+pub mod plugin;
 pub mod primitive;
+pub mod provenance;
+pub mod scenario;
+#[cfg(feature = "serve")]
+pub mod serve;
@@ -6,14 +6,16 @@ macro_rules! regex {
     }};
 }
 
+/// Implement `FromStr` for a fixed-width record type with
+/// [`fixedformat::from_str`](crate::fixedformat::from_str)
 #[macro_export]
 macro_rules! derive_from_str {
     ($type:ident) => {
-        impl FromStr for $type {
-            type Err = Error;
+        impl std::str::FromStr for $type {
+            type Err = $crate::error::Error;
 
             fn from_str(s: &str) -> Result<Self, Self::Err> {
-                fixed_width::from_str(s).map_err(|e| Error::Unexpected(e.into()))
+                $crate::fixedformat::from_str(s)
             }
         }
     };
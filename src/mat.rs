@@ -0,0 +1,393 @@
+//! MATLAB/Octave MAT-files of decay time series and spectra
+//!
+//! Files are written in the HDF5-based MAT-file format 7.3 (`save -v7.3`),
+//! which has no limit on the size of a variable and is read by MATLAB, Octave
+//! and `h5py`. [`MatFile::save_v5`] writes the Level 5 format (`save -v6`)
+//! instead, for `scipy.io.loadmat` and older versions of Octave.
+
+use std::io::Write;
+use std::path::Path;
+
+use hdf5::types::FixedAscii;
+
+use crate::decaychain::{BatemanDecaySolver, Inventory};
+use crate::error::Error;
+use crate::primitive::attr::{DecayConstant, NuclideProgeny};
+
+const MI_INT8: u32 = 1;
+const MI_UINT16: u32 = 4;
+const MI_INT32: u32 = 5;
+const MI_UINT32: u32 = 6;
+const MI_DOUBLE: u32 = 9;
+const MI_MATRIX: u32 = 14;
+
+const MX_CHAR_CLASS: u32 = 4;
+const MX_DOUBLE_CLASS: u32 = 6;
+
+/// Size of the HDF5 user block holding the 7.3 header
+const USERBLOCK: usize = 512;
+
+/// Values of a variable, stored column by column
+#[derive(Debug)]
+enum Data {
+    Double(Vec<f64>),
+    /// UTF-16 code units
+    Char(Vec<u16>),
+}
+
+#[derive(Debug)]
+struct Variable {
+    name: String,
+    rows: usize,
+    cols: usize,
+    data: Data,
+}
+
+/// Variables of a MAT-file, written in the order they are added
+#[derive(Debug, Default)]
+pub struct MatFile {
+    variables: Vec<Variable>,
+}
+
+impl MatFile {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Real matrix of `rows` x `cols` given in row-major order
+    pub fn matrix(mut self, name: &str, rows: usize, cols: usize, data: &[f64]) -> Self {
+        assert_eq!(rows * cols, data.len(), "{}: size mismatch", name);
+
+        // MAT-files store columns contiguously
+        let real = (0..cols)
+            .flat_map(|j| (0..rows).map(move |i| data[i * cols + j]))
+            .collect();
+        self.variables.push(Variable {
+            name: name.to_string(),
+            rows,
+            cols,
+            data: Data::Double(real),
+        });
+        self
+    }
+
+    /// Column vector
+    pub fn column(self, name: &str, data: &[f64]) -> Self {
+        self.matrix(name, data.len(), 1, data)
+    }
+
+    /// Char matrix with one (blank padded) row per string, `char(names)` in MATLAB
+    pub fn strings<S: AsRef<str>>(mut self, name: &str, strings: &[S]) -> Self {
+        let rows: Vec<Vec<u16>> = strings
+            .iter()
+            .map(|s| s.as_ref().encode_utf16().collect())
+            .collect();
+        let cols = rows.iter().map(Vec::len).max().unwrap_or(0);
+
+        let chars = (0..cols)
+            .flat_map(|j| {
+                rows.iter()
+                    .map(move |row| row.get(j).copied().unwrap_or(b' ' as u16))
+            })
+            .collect();
+        self.variables.push(Variable {
+            name: name.to_string(),
+            rows: rows.len(),
+            cols,
+            data: Data::Char(chars),
+        });
+        self
+    }
+
+    /// Level 5 MAT-file
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = header("5.0", 0x0100);
+        for variable in &self.variables {
+            variable.encode(&mut bytes);
+        }
+
+        bytes
+    }
+
+    /// Write a MAT-file 7.3
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), Error> {
+        let h5_error = |e: hdf5::Error| Error::Unexpected(e.into());
+
+        let file = hdf5::File::with_options()
+            .with_fcpl(|p| p.userblock(USERBLOCK as u64))
+            .create(path.as_ref())
+            .map_err(h5_error)?;
+        for variable in &self.variables {
+            variable.write(&file).map_err(h5_error)?;
+        }
+        file.close().map_err(h5_error)?;
+
+        let mut userblock = header("7.3", 0x0200);
+        userblock.resize(USERBLOCK, 0);
+        std::fs::OpenOptions::new()
+            .write(true)
+            .open(path)?
+            .write_all(&userblock)?;
+        Ok(())
+    }
+
+    /// Write a Level 5 MAT-file
+    pub fn save_v5<P: AsRef<Path>>(&self, path: P) -> Result<(), Error> {
+        std::fs::File::create(path)?.write_all(&self.to_bytes())?;
+        Ok(())
+    }
+}
+
+/// 128-byte header of a MAT-file
+fn header(format: &str, version: u16) -> Vec<u8> {
+    let mut header = format!(
+        "MATLAB {} MAT-file, Platform: {}, Created by: radioactive {}",
+        format,
+        std::env::consts::OS,
+        env!("CARGO_PKG_VERSION")
+    )
+    .into_bytes();
+    header.resize(116, b' ');
+    header.extend_from_slice(&[0; 8]);
+    header.extend_from_slice(&version.to_le_bytes());
+    header.extend_from_slice(b"IM");
+    header
+}
+
+impl Variable {
+    /// Level 5 matrix element
+    fn encode(&self, buf: &mut Vec<u8>) {
+        let dimensions = [
+            (self.rows as i32).to_le_bytes(),
+            (self.cols as i32).to_le_bytes(),
+        ];
+        let (class, ty, data): (_, _, Vec<u8>) = match &self.data {
+            Data::Double(v) => (
+                MX_DOUBLE_CLASS,
+                MI_DOUBLE,
+                v.iter().flat_map(|x| x.to_le_bytes()).collect(),
+            ),
+            Data::Char(v) => (
+                MX_CHAR_CLASS,
+                MI_UINT16,
+                v.iter().flat_map(|c| c.to_le_bytes()).collect(),
+            ),
+        };
+
+        let mut matrix = vec![];
+        element(
+            &mut matrix,
+            MI_UINT32,
+            &[class.to_le_bytes(), [0; 4]].concat(),
+        );
+        element(&mut matrix, MI_INT32, &dimensions.concat());
+        element(&mut matrix, MI_INT8, self.name.as_bytes());
+        element(&mut matrix, ty, &data);
+
+        element(buf, MI_MATRIX, &matrix)
+    }
+
+    /// 7.3 dataset: the dimensions reversed, since HDF5 stores rows
+    /// contiguously, and the class in attributes
+    fn write(&self, file: &hdf5::File) -> hdf5::Result<()> {
+        // empty arrays hold their dimensions
+        if self.rows == 0 || self.cols == 0 {
+            let dims = [self.rows as u64, self.cols as u64];
+            let ds = file
+                .new_dataset_builder()
+                .with_data(&dims[..])
+                .create(self.name.as_str())?;
+            write_attr(&ds, "MATLAB_empty", &1u8)?;
+            return self.write_class(&ds);
+        }
+
+        let shape = (self.cols, self.rows);
+        let ds = match &self.data {
+            Data::Double(v) => file
+                .new_dataset_builder()
+                .with_data(ndarray::ArrayView2::from_shape(shape, v).unwrap())
+                .create(self.name.as_str())?,
+            Data::Char(v) => {
+                let ds = file
+                    .new_dataset_builder()
+                    .with_data(ndarray::ArrayView2::from_shape(shape, v).unwrap())
+                    .create(self.name.as_str())?;
+                write_attr(&ds, "MATLAB_int_decode", &2i32)?;
+                ds
+            }
+        };
+        self.write_class(&ds)
+    }
+
+    fn write_class(&self, ds: &hdf5::Dataset) -> hdf5::Result<()> {
+        match self.data {
+            Data::Double(_) => write_attr(
+                ds,
+                "MATLAB_class",
+                &FixedAscii::<6>::from_ascii("double").unwrap(),
+            ),
+            Data::Char(_) => write_attr(
+                ds,
+                "MATLAB_class",
+                &FixedAscii::<4>::from_ascii("char").unwrap(),
+            ),
+        }
+    }
+}
+
+fn write_attr<T: hdf5::H5Type>(ds: &hdf5::Dataset, name: &str, value: &T) -> hdf5::Result<()> {
+    ds.new_attr::<T>()
+        .shape(())
+        .create(name)?
+        .write_scalar(value)
+}
+
+/// Data element: type, size and data padded to 8 bytes
+fn element(buf: &mut Vec<u8>, ty: u32, data: &[u8]) {
+    buf.extend_from_slice(&ty.to_le_bytes());
+    buf.extend_from_slice(&(data.len() as u32).to_le_bytes());
+    buf.extend_from_slice(data);
+    buf.resize(buf.len() + (8 - data.len() % 8) % 8, 0);
+}
+
+/// Activities of an inventory and its progeny at `times` (s)
+///
+/// Variables: `t` (s, column), `nuclides` (char matrix) and `activity`
+/// (Bq, one row per time and one column per nuclide).
+pub fn decay_series<D, P>(
+    solver: &BatemanDecaySolver<D>,
+    inventory: &Inventory,
+    times: &[f64],
+    path: P,
+) -> Result<(), Error>
+where
+    D: NuclideProgeny + DecayConstant + ?Sized,
+    P: AsRef<Path>,
+{
+    let results: Vec<Inventory> = times.iter().map(|&t| solver.decay(inventory, t)).collect();
+    let mut nuclides: Vec<_> = results.iter().flat_map(|inv| inv.keys().copied()).collect();
+    nuclides.sort();
+    nuclides.dedup();
+
+    let activity: Vec<f64> = results
+        .iter()
+        .flat_map(|inv| nuclides.iter().map(|n| inv.get(n).copied().unwrap_or(0.)))
+        .collect();
+    let names: Vec<String> = nuclides.iter().map(|n| n.to_string()).collect();
+
+    MatFile::new()
+        .column("t", times)
+        .strings("nuclides", &names)
+        .matrix("activity", times.len(), nuclides.len(), &activity)
+        .save(path)
+}
+
+/// Emission lines given as (energy (MeV), yield (/nt))
+///
+/// Variables: `energy` (MeV, column) and `yield` (/nt, column).
+pub fn emission_spectrum<P: AsRef<Path>>(lines: &[(f64, f64)], path: P) -> Result<(), Error> {
+    let (energy, r#yield): (Vec<f64>, Vec<f64>) = lines.iter().copied().unzip();
+
+    MatFile::new()
+        .column("energy", &energy)
+        .column("yield", &r#yield)
+        .save(path)
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::fixtures::{FixtureDecayData, TempDir};
+
+    /// (name, dimensions, data) of each variable
+    fn variables(bytes: &[u8]) -> Vec<(String, (i32, i32), Vec<u8>)> {
+        let u32_at = |b: &[u8], i: usize| u32::from_le_bytes(b[i..i + 4].try_into().unwrap());
+        let i32_at = |b: &[u8], i: usize| i32::from_le_bytes(b[i..i + 4].try_into().unwrap());
+        let padded = |n: usize| n.div_ceil(8) * 8;
+
+        let mut vars = vec![];
+        let mut i = 128;
+        while i < bytes.len() {
+            assert_eq!(u32_at(bytes, i), MI_MATRIX);
+            let size = u32_at(bytes, i + 4) as usize;
+            let m = &bytes[i + 8..i + 8 + size];
+
+            let dims = (i32_at(m, 24), i32_at(m, 28));
+            let name_len = u32_at(m, 36) as usize;
+            let name = String::from_utf8(m[40..40 + name_len].to_vec()).unwrap();
+            let j = 40 + padded(name_len);
+            let data_len = u32_at(m, j + 4) as usize;
+            vars.push((name, dims, m[j + 8..j + 8 + data_len].to_vec()));
+
+            i += 8 + size;
+        }
+
+        vars
+    }
+
+    #[test]
+    fn mat_file() {
+        let bytes = MatFile::new()
+            .matrix("a", 2, 3, &[1., 2., 3., 4., 5., 6.])
+            .strings("names", &["Mo-99", "Tc-99m"])
+            .to_bytes();
+
+        assert!(bytes.starts_with(b"MATLAB 5.0 MAT-file"));
+        assert_eq!(&bytes[124..128], &[0, 1, b'I', b'M']);
+        assert_eq!(bytes.len() % 8, 0);
+
+        let vars = variables(&bytes);
+        assert_eq!(vars[0].0, "a");
+        assert_eq!(vars[0].1, (2, 3));
+        // column-major
+        let second = f64::from_le_bytes(vars[0].2[8..16].try_into().unwrap());
+        assert_eq!(second, 4.);
+
+        assert_eq!(vars[1].0, "names");
+        assert_eq!(vars[1].1, (2, 6));
+        assert_eq!(&vars[1].2[..4], &[b'M', 0, b'T', 0]);
+    }
+
+    #[test]
+    fn decay_series_file() {
+        let solver = BatemanDecaySolver::new(Arc::new(FixtureDecayData::new()));
+        let mut inv = Inventory::new();
+        inv.add("Mo-99".parse().unwrap(), 1e6);
+
+        let dir = TempDir::new("mat");
+        let path = dir.join("decay_series.mat");
+        decay_series(&solver, &inv, &[0., 3600., 86400.], &path).unwrap();
+        let bytes = std::fs::read(&path).unwrap();
+        assert!(bytes.starts_with(b"MATLAB 7.3 MAT-file"));
+        assert_eq!(&bytes[124..128], &[0, 2, b'I', b'M']);
+
+        let file = hdf5::File::open(&path).unwrap();
+        let nuclides = file.dataset("nuclides").unwrap();
+        let activity = file.dataset("activity").unwrap();
+        // dimensions reversed
+        assert_eq!(activity.shape(), vec![nuclides.shape()[1], 3]);
+        let class: FixedAscii<6> = activity
+            .attr("MATLAB_class")
+            .unwrap()
+            .read_scalar()
+            .unwrap();
+        assert_eq!(class.as_str(), "double");
+        let decode: i32 = nuclides
+            .attr("MATLAB_int_decode")
+            .unwrap()
+            .read_scalar()
+            .unwrap();
+        assert_eq!(decode, 2);
+
+        // Level 5
+        let path = dir.join("spectrum.mat");
+        MatFile::new()
+            .column("energy", &[0.14, 0.018])
+            .save_v5(&path)
+            .unwrap();
+        let vars = variables(&std::fs::read(&path).unwrap());
+        assert_eq!((vars[0].0.as_str(), vars[0].1), ("energy", (2, 1)));
+    }
+}
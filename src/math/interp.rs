@@ -0,0 +1,199 @@
+//! Interpolation over sorted grids
+//!
+//! Grids must be sorted in ascending order; points outside the grid, and values
+//! not matching the size of the grid, give `None`.
+
+/// Axis scale of an interpolation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Scale {
+    Linear,
+    Log,
+}
+
+impl Scale {
+    fn to(self, v: f64) -> f64 {
+        match self {
+            Self::Linear => v,
+            Self::Log => v.ln(),
+        }
+    }
+
+    fn from(self, v: f64) -> f64 {
+        match self {
+            Self::Linear => v,
+            Self::Log => v.exp(),
+        }
+    }
+}
+
+/// Index `i` of the grid interval [xs[i], xs[i + 1]] containing `x`
+fn interval(xs: &[f64], x: f64) -> Option<usize> {
+    if xs.len() < 2 || !(xs[0]..=xs[xs.len() - 1]).contains(&x) {
+        return None;
+    }
+
+    Some(xs.partition_point(|&v| v <= x).clamp(1, xs.len() - 1) - 1)
+}
+
+fn between(x0: f64, x1: f64, y0: f64, y1: f64, x: f64, x_scale: Scale, y_scale: Scale) -> f64 {
+    if x0 == x1 || y0 == y1 {
+        return y0;
+    }
+
+    let t = (x_scale.to(x) - x_scale.to(x0)) / (x_scale.to(x1) - x_scale.to(x0));
+    y_scale.from(y_scale.to(y0) + t * (y_scale.to(y1) - y_scale.to(y0)))
+}
+
+/// Piecewise interpolation with the given axis scales
+pub fn interp(xs: &[f64], ys: &[f64], x: f64, x_scale: Scale, y_scale: Scale) -> Option<f64> {
+    if ys.len() != xs.len() {
+        return None;
+    }
+    let i = interval(xs, x)?;
+    Some(between(
+        xs[i],
+        xs[i + 1],
+        ys[i],
+        ys[i + 1],
+        x,
+        x_scale,
+        y_scale,
+    ))
+}
+
+pub fn linear(xs: &[f64], ys: &[f64], x: f64) -> Option<f64> {
+    interp(xs, ys, x, Scale::Linear, Scale::Linear)
+}
+
+pub fn log_log(xs: &[f64], ys: &[f64], x: f64) -> Option<f64> {
+    interp(xs, ys, x, Scale::Log, Scale::Log)
+}
+
+/// Linear in x, logarithmic in y
+pub fn lin_log(xs: &[f64], ys: &[f64], x: f64) -> Option<f64> {
+    interp(xs, ys, x, Scale::Linear, Scale::Log)
+}
+
+/// Interpolation on a tensor grid, with `zs[i][j]` the value at (`xs[i]`, `ys[j]`)
+pub fn interp2d(
+    xs: &[f64],
+    ys: &[f64],
+    zs: &[Vec<f64>],
+    (x, y): (f64, f64),
+    scales: (Scale, Scale, Scale),
+) -> Option<f64> {
+    let (x_scale, y_scale, z_scale) = scales;
+    if zs.len() != xs.len() || zs.iter().any(|row| row.len() != ys.len()) {
+        return None;
+    }
+    let i = interval(xs, x)?;
+    let j = interval(ys, y)?;
+
+    let along_y =
+        |row: &Vec<f64>| between(ys[j], ys[j + 1], row[j], row[j + 1], y, y_scale, z_scale);
+    let (z0, z1) = (along_y(&zs[i]), along_y(&zs[i + 1]));
+
+    Some(between(xs[i], xs[i + 1], z0, z1, x, x_scale, z_scale))
+}
+
+/// Natural cubic spline
+#[derive(Debug, Clone, PartialEq)]
+pub struct CubicSpline {
+    xs: Vec<f64>,
+    ys: Vec<f64>,
+    /// Second derivatives at the grid points
+    m: Vec<f64>,
+}
+
+impl CubicSpline {
+    /// `None` if there are fewer than 3 points or the lengths differ
+    pub fn new(xs: &[f64], ys: &[f64]) -> Option<Self> {
+        let n = xs.len();
+        if n < 3 || ys.len() != n {
+            return None;
+        }
+
+        // tridiagonal system of the natural spline, Thomas algorithm
+        let h: Vec<f64> = xs.windows(2).map(|w| w[1] - w[0]).collect();
+        let mut c = vec![0.; n];
+        let mut d = vec![0.; n];
+        for i in 1..n - 1 {
+            let b = 2. * (h[i - 1] + h[i]) - h[i - 1] * c[i - 1];
+            let r = 6. * ((ys[i + 1] - ys[i]) / h[i] - (ys[i] - ys[i - 1]) / h[i - 1]);
+            c[i] = h[i] / b;
+            d[i] = (r - h[i - 1] * d[i - 1]) / b;
+        }
+        let mut m = vec![0.; n];
+        for i in (1..n - 1).rev() {
+            m[i] = d[i] - c[i] * m[i + 1];
+        }
+
+        Some(Self {
+            xs: xs.to_vec(),
+            ys: ys.to_vec(),
+            m,
+        })
+    }
+
+    pub fn eval(&self, x: f64) -> Option<f64> {
+        let i = interval(&self.xs, x)?;
+        let h = self.xs[i + 1] - self.xs[i];
+        let (a, b) = ((self.xs[i + 1] - x) / h, (x - self.xs[i]) / h);
+
+        Some(
+            a * self.ys[i]
+                + b * self.ys[i + 1]
+                + ((a.powi(3) - a) * self.m[i] + (b.powi(3) - b) * self.m[i + 1]) * h * h / 6.,
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn interp_1d() {
+        let xs = [1., 10., 100.];
+        let ys = [1., 100., 10_000.];
+
+        assert_eq!(linear(&xs, &ys, 5.5), Some(50.5));
+        assert!((log_log(&xs, &ys, 31.622_776_601_683_79).unwrap() - 1000.).abs() < 1e-9);
+        assert!((lin_log(&[0., 1.], &[1., 100.], 0.5).unwrap() - 10.).abs() < 1e-12);
+        assert_eq!(linear(&xs, &ys, 100.), Some(10_000.));
+        assert_eq!(linear(&xs, &ys, 0.5), None);
+        assert_eq!(linear(&xs, &ys, 101.), None);
+        assert_eq!(linear(&xs, &ys[..2], 5.5), None);
+        assert_eq!(log_log(&xs[..2], &ys, 5.5), None);
+    }
+
+    #[test]
+    fn interp_2d() {
+        let xs = [0., 1.];
+        let ys = [0., 2.];
+        let zs = vec![vec![0., 2.], vec![1., 3.]];
+        let lin = (Scale::Linear, Scale::Linear, Scale::Linear);
+
+        assert_eq!(interp2d(&xs, &ys, &zs, (0.5, 1.), lin), Some(1.5));
+        assert_eq!(interp2d(&xs, &ys, &zs, (1., 2.), lin), Some(3.));
+        assert_eq!(interp2d(&xs, &ys, &zs, (2., 1.), lin), None);
+        assert_eq!(interp2d(&xs, &ys, &zs[..1], (0.5, 1.), lin), None);
+        let ragged = vec![vec![0., 2.], vec![1.]];
+        assert_eq!(interp2d(&xs, &ys, &ragged, (0.5, 1.), lin), None);
+    }
+
+    #[test]
+    fn cubic_spline() {
+        let xs: Vec<f64> = (0..=20)
+            .map(|i| i as f64 * 0.1 * std::f64::consts::PI)
+            .collect();
+        let ys: Vec<f64> = xs.iter().map(|x| x.sin()).collect();
+        let spline = CubicSpline::new(&xs, &ys).unwrap();
+
+        for x in [0.3, 1., 2.5, 5.9] {
+            assert!((spline.eval(x).unwrap() - f64::sin(x)).abs() < 1e-3);
+        }
+        assert_eq!(spline.eval(xs[5]), Some(ys[5]));
+        assert!(CubicSpline::new(&xs[..2], &ys[..2]).is_none());
+    }
+}
@@ -0,0 +1,5 @@
+pub mod interp;
+pub mod quad;
+#[cfg(feature = "rand")]
+pub mod random;
+pub mod roots;
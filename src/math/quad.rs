@@ -0,0 +1,128 @@
+//! Numerical integration
+
+/// Subintervals of [a, b] before refinement, so that a narrow peak between
+/// the first three points is not missed
+const SIMPSON_PARTS: usize = 8;
+/// Depth of refinement of a subinterval
+const SIMPSON_DEPTH: u32 = 30;
+/// Evaluations of the integrand
+const SIMPSON_EVALUATIONS: usize = 100_000;
+
+/// Integral of `f` over [a, b] by adaptive Simpson's rule, to the larger of a
+/// relative and an absolute tolerance
+///
+/// `None` if the tolerance is not met within the depth or the number of
+/// evaluations of the integrand, e.g. for a singularity.
+pub fn adaptive_simpson<F: Fn(f64) -> f64>(
+    f: F,
+    a: f64,
+    b: f64,
+    rtol: f64,
+    atol: f64,
+) -> Option<f64> {
+    let h = (b - a) / SIMPSON_PARTS as f64;
+    let mut evaluations = 0;
+    let mut eval = |x: f64| {
+        evaluations += 1;
+        f(x)
+    };
+
+    let mut parts = Vec::with_capacity(SIMPSON_PARTS);
+    let mut fa = eval(a);
+    for i in 0..SIMPSON_PARTS {
+        let (x0, x1) = (a + i as f64 * h, a + (i + 1) as f64 * h);
+        let (fm, fb) = (eval((x0 + x1) / 2.), eval(x1));
+        parts.push(((x0, x1), (fa, fm, fb), (x1 - x0) / 6. * (fa + 4. * fm + fb)));
+        fa = fb;
+    }
+    let estimate: f64 = parts.iter().map(|p| p.2).sum();
+    let tol = (rtol * estimate.abs()).max(atol) / SIMPSON_PARTS as f64;
+
+    let mut res = 0.;
+    for (range, values, whole) in parts {
+        res += simpson_step(&mut eval, range, values, whole, tol, SIMPSON_DEPTH)?;
+    }
+
+    (evaluations <= SIMPSON_EVALUATIONS).then_some(res)
+}
+
+fn simpson_step<F: FnMut(f64) -> f64>(
+    f: &mut F,
+    (a, b): (f64, f64),
+    (fa, fm, fb): (f64, f64, f64),
+    whole: f64,
+    tol: f64,
+    depth: u32,
+) -> Option<f64> {
+    let m = (a + b) / 2.;
+    let (lm, rm) = (f((a + m) / 2.), f((m + b) / 2.));
+    let left = (m - a) / 6. * (fa + 4. * lm + fm);
+    let right = (b - m) / 6. * (fm + 4. * rm + fb);
+    let delta = left + right - whole;
+
+    if delta.abs() <= 15. * tol {
+        Some(left + right + delta / 15.)
+    } else if depth == 0 || !delta.is_finite() {
+        None
+    } else {
+        Some(
+            simpson_step(f, (a, m), (fa, lm, fm), left, tol / 2., depth - 1)?
+                + simpson_step(f, (m, b), (fm, rm, fb), right, tol / 2., depth - 1)?,
+        )
+    }
+}
+
+/// Integral of `f` over [a, b] by 5-point Gauss-Legendre quadrature on `n` equal subintervals
+pub fn gauss_legendre<F: Fn(f64) -> f64>(f: F, a: f64, b: f64, n: usize) -> f64 {
+    const NODES: [(f64, f64); 5] = [
+        (0., 0.568_888_888_888_888_9),
+        (-0.538_469_310_105_683_1, 0.478_628_670_499_366_5),
+        (0.538_469_310_105_683_1, 0.478_628_670_499_366_5),
+        (-0.906_179_845_938_664, 0.236_926_885_056_189_1),
+        (0.906_179_845_938_664, 0.236_926_885_056_189_1),
+    ];
+
+    let n = n.max(1);
+    let h = (b - a) / n as f64;
+    (0..n)
+        .map(|i| {
+            let mid = a + (i as f64 + 0.5) * h;
+            NODES
+                .iter()
+                .map(|&(x, w)| w * f(mid + x * h / 2.))
+                .sum::<f64>()
+                * h
+                / 2.
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn quadrature() {
+        let exact = 1. - (-10f64).exp();
+
+        let simpson = adaptive_simpson(|x| (-x).exp(), 0., 10., 1e-10, 0.).unwrap();
+        assert!((simpson - exact).abs() < 1e-9);
+
+        // a peak between the first points, and a zero integral
+        let peak = |x: f64| (-(x - 1.25).powi(2) * 100.).exp();
+        let simpson = adaptive_simpson(peak, 0., 4., 1e-10, 0.).unwrap();
+        assert!((simpson - std::f64::consts::PI.sqrt() / 10.).abs() < 1e-9);
+        let simpson = adaptive_simpson(|x: f64| x.sin(), -1., 1., 1e-8, 1e-12).unwrap();
+        assert!(simpson.abs() < 1e-12);
+
+        // no convergence at a singularity
+        assert_eq!(adaptive_simpson(|x: f64| 1. / x, -1., 2., 1e-8, 0.), None);
+
+        let gauss = gauss_legendre(|x| (-x).exp(), 0., 10., 10);
+        assert!((gauss - exact).abs() < 1e-9);
+
+        // exact for polynomials up to degree 9
+        let poly = gauss_legendre(|x| x.powi(9), 0., 1., 1);
+        assert!((poly - 0.1).abs() < 1e-14);
+    }
+}
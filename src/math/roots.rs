@@ -0,0 +1,108 @@
+//! Root finding
+
+const MAX_ITERATIONS: usize = 200;
+
+/// Root of `f` in [a, b] by Brent's method
+///
+/// `None` if f(a) and f(b) have the same sign, `f` is not a number within the
+/// interval, or the root is not found to `tol` within 200 iterations.
+pub fn brent<F: Fn(f64) -> f64>(f: F, a: f64, b: f64, tol: f64) -> Option<f64> {
+    let (mut a, mut b) = (a, b);
+    let (mut fa, mut fb) = (f(a), f(b));
+    if fa == 0. {
+        return Some(a);
+    }
+    if fb == 0. {
+        return Some(b);
+    }
+    if fa.is_nan() || fb.is_nan() || fa.signum() == fb.signum() {
+        return None;
+    }
+
+    let (mut c, mut fc) = (a, fa);
+    let mut d = b - a;
+    let mut e = d;
+
+    for _ in 0..MAX_ITERATIONS {
+        if fb.is_nan() {
+            return None;
+        }
+        if fb.signum() == fc.signum() {
+            c = a;
+            fc = fa;
+            d = b - a;
+            e = d;
+        }
+        if fc.abs() < fb.abs() {
+            (a, b, c) = (b, c, b);
+            (fa, fb, fc) = (fb, fc, fb);
+        }
+
+        let tol1 = 2. * f64::EPSILON * b.abs() + tol / 2.;
+        let m = (c - b) / 2.;
+        if m.abs() <= tol1 || fb == 0. {
+            return Some(b);
+        }
+
+        if e.abs() >= tol1 && fa.abs() > fb.abs() {
+            // inverse quadratic interpolation or secant
+            let s = fb / fa;
+            let (mut p, mut q) = if a == c {
+                (2. * m * s, 1. - s)
+            } else {
+                let q = fa / fc;
+                let r = fb / fc;
+                (
+                    s * (2. * m * q * (q - r) - (b - a) * (r - 1.)),
+                    (q - 1.) * (r - 1.) * (s - 1.),
+                )
+            };
+            if p > 0. {
+                q = -q;
+            } else {
+                p = -p;
+            }
+
+            if 2. * p < (3. * m * q - (tol1 * q).abs()).min((e * q).abs()) {
+                e = d;
+                d = p / q;
+            } else {
+                d = m;
+                e = m;
+            }
+        } else {
+            d = m;
+            e = m;
+        }
+
+        a = b;
+        fa = fb;
+        b += if d.abs() > tol1 { d } else { tol1.copysign(m) };
+        fb = f(b);
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn brent_root() {
+        let root = brent(|x| x * x - 2., 0., 2., 1e-12).unwrap();
+        assert!((root - 2f64.sqrt()).abs() < 1e-12);
+
+        let root = brent(|x| x.cos() - x, 0., 1., 1e-12).unwrap();
+        assert!((root - 0.739_085_133_215_160_6).abs() < 1e-12);
+
+        assert_eq!(brent(|x| x * x + 1., -1., 1., 1e-12), None);
+
+        let undefined = |x: f64| match x {
+            x if x < 0.5 => -1.,
+            x if x < 1. => f64::NAN,
+            _ => 1.,
+        };
+        assert_eq!(brent(undefined, 0., 1., 1e-12), None);
+    }
+}
@@ -0,0 +1,202 @@
+//! Quick-look plots of nuclear data
+//!
+//! Files are written as SVG when the path ends with `.svg` and as PNG otherwise.
+
+use std::path::Path;
+
+use plotters::coord::Shift;
+use plotters::prelude::*;
+
+use crate::decaychain::{BatemanDecaySolver, Inventory};
+use crate::error::Error;
+use crate::primitive::attr::{DecayConstant, NuclideProgeny};
+
+const SIZE: (u32, u32) = (800, 600);
+
+type Series = (String, Vec<(f64, f64)>);
+
+fn plot_error<E: std::error::Error + Send + Sync>(e: DrawingAreaErrorKind<E>) -> Error {
+    Error::Unexpected(anyhow::anyhow!("plot error: {}", e))
+}
+
+struct Figure<'a> {
+    title: &'a str,
+    x_desc: &'a str,
+    y_desc: &'a str,
+    /// Draw vertical lines from the bottom of the chart instead of a line series
+    stems: bool,
+}
+
+impl Figure<'_> {
+    fn save<P: AsRef<Path>>(&self, path: P, series: &[Series]) -> Result<(), Error> {
+        let path = path.as_ref();
+        if path.extension().is_some_and(|ext| ext == "svg") {
+            self.draw(SVGBackend::new(path, SIZE).into_drawing_area(), series)
+        } else {
+            self.draw(BitMapBackend::new(path, SIZE).into_drawing_area(), series)
+        }
+    }
+
+    fn draw<DB>(&self, root: DrawingArea<DB, Shift>, series: &[Series]) -> Result<(), Error>
+    where
+        DB: DrawingBackend,
+        DB::ErrorType: 'static,
+    {
+        // log axes only show positive values
+        let points = || {
+            series
+                .iter()
+                .flat_map(|s| &s.1)
+                .filter(|p| p.0 > 0. && p.1 > 0.)
+        };
+        let range = |v: &mut dyn Iterator<Item = f64>| {
+            let (min, max) = v.fold((f64::MAX, f64::MIN), |(a, b), x| (a.min(x), b.max(x)));
+            if min > max {
+                (1., 10.)
+            } else if min == max {
+                (min / 10., max * 10.)
+            } else {
+                (min, max)
+            }
+        };
+        let (x0, x1) = range(&mut points().map(|p| p.0));
+        let (y0, y1) = range(&mut points().map(|p| p.1));
+        let y0 = if self.stems { y0 / 10. } else { y0 };
+
+        root.fill(&WHITE).map_err(plot_error)?;
+        let mut chart = ChartBuilder::on(&root)
+            .caption(self.title, ("sans-serif", 20))
+            .margin(10)
+            .x_label_area_size(40)
+            .y_label_area_size(60)
+            .build_cartesian_2d((x0..x1).log_scale(), (y0..y1).log_scale())
+            .map_err(plot_error)?;
+        chart
+            .configure_mesh()
+            .x_desc(self.x_desc)
+            .y_desc(self.y_desc)
+            .draw()
+            .map_err(plot_error)?;
+
+        for (i, (label, points)) in series.iter().enumerate() {
+            let color = Palette99::pick(i).to_rgba();
+            let points = points.iter().copied().filter(|p| p.0 > 0. && p.1 > 0.);
+
+            if self.stems {
+                chart
+                    .draw_series(
+                        points.map(|(x, y)| PathElement::new(vec![(x, y0), (x, y)], color)),
+                    )
+                    .map_err(plot_error)?;
+            } else {
+                chart
+                    .draw_series(LineSeries::new(points, color))
+                    .map_err(plot_error)?
+                    .label(label.as_str())
+                    .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], color));
+            }
+        }
+
+        if !self.stems && series.len() > 1 {
+            chart
+                .configure_series_labels()
+                .background_style(WHITE.mix(0.8))
+                .border_style(BLACK)
+                .draw()
+                .map_err(plot_error)?;
+        }
+
+        root.present().map_err(plot_error)
+    }
+}
+
+/// Activity (Bq) of an inventory and its progeny at `times` (s)
+pub fn decay_curve<D, P>(
+    solver: &BatemanDecaySolver<D>,
+    inventory: &Inventory,
+    times: &[f64],
+    path: P,
+) -> Result<(), Error>
+where
+    D: NuclideProgeny + DecayConstant,
+    P: AsRef<Path>,
+{
+    let mut series: Vec<Series> = vec![];
+    for &t in times {
+        for (nuclide, &activity) in solver.decay(inventory, t).iter() {
+            let label = nuclide.to_string();
+            match series.iter_mut().find(|s| s.0 == label) {
+                Some(s) => s.1.push((t, activity)),
+                None => series.push((label, vec![(t, activity)])),
+            }
+        }
+    }
+
+    Figure {
+        title: "Decay",
+        x_desc: "Time (s)",
+        y_desc: "Activity (Bq)",
+        stems: false,
+    }
+    .save(path, &series)
+}
+
+/// Emission lines given as (energy (MeV), yield (/nt))
+pub fn emission_spectrum<P: AsRef<Path>>(
+    title: &str,
+    lines: &[(f64, f64)],
+    path: P,
+) -> Result<(), Error> {
+    Figure {
+        title,
+        x_desc: "Energy (MeV)",
+        y_desc: "Yield (/nt)",
+        stems: true,
+    }
+    .save(path, &[(title.to_string(), lines.to_vec())])
+}
+
+/// Mass attenuation coefficients given as (energy (MeV), mu/rho (cm2/g))
+pub fn attenuation_curve<P: AsRef<Path>>(
+    title: &str,
+    points: &[(f64, f64)],
+    path: P,
+) -> Result<(), Error> {
+    Figure {
+        title,
+        x_desc: "Energy (MeV)",
+        y_desc: "mu/rho (cm2/g)",
+        stems: false,
+    }
+    .save(path, &[(title.to_string(), points.to_vec())])
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::fixtures::{FixtureDecayData, TempDir};
+
+    #[test]
+    fn plot_svg() {
+        let dir = TempDir::new("plot");
+        let solver = BatemanDecaySolver::new(Arc::new(FixtureDecayData::new()));
+        let mut inv = Inventory::new();
+        inv.add("Mo-99".parse().unwrap(), 1e6);
+        let times: Vec<f64> = (1..=20).map(|i| 3600. * 1.5f64.powi(i)).collect();
+
+        let path = dir.join("decay_curve.svg");
+        decay_curve(&solver, &inv, &times, &path).unwrap();
+        let svg = std::fs::read_to_string(&path).unwrap();
+        assert!(svg.contains("<svg") && svg.contains("Tc-99m"));
+
+        let path = dir.join("spectrum.svg");
+        emission_spectrum("Co-60", &[(1.173, 0.9985), (1.332, 0.9998)], &path).unwrap();
+        assert!(std::fs::read_to_string(&path).unwrap().contains("<svg"));
+
+        let path = dir.join("attenuation.png");
+        attenuation_curve("Pb", &[(0.1, 5.55), (1., 0.071), (10., 0.0497)], &path).unwrap();
+        assert!(std::fs::metadata(&path).unwrap().len() > 0);
+    }
+}
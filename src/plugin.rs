@@ -0,0 +1,193 @@
+//! Dataset backends provided by other crates
+//!
+//! A backend implements one or more of the object-safe dataset traits and is
+//! registered under a name:
+//!
+//! | Trait | Data |
+//! |---|---|
+//! | [`DecaySource`] | half-lives, decay modes and progeny |
+//! | [`DcfSource`] | ingestion and inhalation dose coefficients |
+//! | [`PhotonSource`] | photon lines and air kerma-rate constants |
+//! | [`AttenuationSource`] | atomic masses and mass attenuation coefficients |
+//!
+//! These traits and their supertraits in [`crate::primitive::attr`] follow the
+//! stability rules of the crate; [`PLUGIN_API_VERSION`] is raised whenever a
+//! release changes them.
+//!
+//! ```
+//! # use std::sync::Arc;
+//! # use radioactive::error::Error;
+//! # use radioactive::fixtures::FixtureDecayData;
+//! # use radioactive::plugin::{Plugin, Registry};
+//! struct Fixtures;
+//!
+//! impl Plugin for Fixtures {
+//!     fn register(&self, registry: &mut Registry) -> Result<(), Error> {
+//!         registry.register_decay("fixtures", Arc::new(FixtureDecayData::new()));
+//!         Ok(())
+//!     }
+//! }
+//!
+//! let mut registry = Registry::new();
+//! registry.install(&Fixtures).unwrap();
+//! assert!(registry.decay("fixtures").is_ok());
+//! ```
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use crate::dataset::{AttenuationSource, DcfSource, DecaySource, PhotonSource};
+use crate::error::Error;
+
+/// Version of the dataset traits a backend is built against
+pub const PLUGIN_API_VERSION: u32 = 1;
+
+/// Crate shipping dataset backends
+pub trait Plugin {
+    /// Version of the dataset traits the plugin implements
+    fn api_version(&self) -> u32 {
+        PLUGIN_API_VERSION
+    }
+
+    /// Add the backends of the plugin to the registry
+    fn register(&self, registry: &mut Registry) -> Result<(), Error>;
+}
+
+/// Dataset backends by name
+///
+/// Registering a name again replaces the backend, which is returned.
+#[derive(Clone, Default)]
+pub struct Registry {
+    decay: BTreeMap<String, Arc<dyn DecaySource>>,
+    dcf: BTreeMap<String, Arc<dyn DcfSource>>,
+    photons: BTreeMap<String, Arc<dyn PhotonSource>>,
+    attenuation: BTreeMap<String, Arc<dyn AttenuationSource>>,
+}
+
+impl Registry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register the backends of a plugin built against this version of the traits
+    pub fn install(&mut self, plugin: &dyn Plugin) -> Result<(), Error> {
+        if plugin.api_version() != PLUGIN_API_VERSION {
+            return Err(Error::UnsupportedPluginApi(plugin.api_version()));
+        }
+
+        plugin.register(self)
+    }
+
+    pub fn register_decay(
+        &mut self,
+        name: &str,
+        source: Arc<dyn DecaySource>,
+    ) -> Option<Arc<dyn DecaySource>> {
+        self.decay.insert(name.to_string(), source)
+    }
+
+    pub fn register_dcf(
+        &mut self,
+        name: &str,
+        source: Arc<dyn DcfSource>,
+    ) -> Option<Arc<dyn DcfSource>> {
+        self.dcf.insert(name.to_string(), source)
+    }
+
+    pub fn register_photons(
+        &mut self,
+        name: &str,
+        source: Arc<dyn PhotonSource>,
+    ) -> Option<Arc<dyn PhotonSource>> {
+        self.photons.insert(name.to_string(), source)
+    }
+
+    pub fn register_attenuation(
+        &mut self,
+        name: &str,
+        source: Arc<dyn AttenuationSource>,
+    ) -> Option<Arc<dyn AttenuationSource>> {
+        self.attenuation.insert(name.to_string(), source)
+    }
+
+    pub fn decay(&self, name: &str) -> Result<Arc<dyn DecaySource>, Error> {
+        lookup(&self.decay, name)
+    }
+
+    pub fn dcf(&self, name: &str) -> Result<Arc<dyn DcfSource>, Error> {
+        lookup(&self.dcf, name)
+    }
+
+    pub fn photons(&self, name: &str) -> Result<Arc<dyn PhotonSource>, Error> {
+        lookup(&self.photons, name)
+    }
+
+    pub fn attenuation(&self, name: &str) -> Result<Arc<dyn AttenuationSource>, Error> {
+        lookup(&self.attenuation, name)
+    }
+
+    /// Names of the decay data backends
+    pub fn decay_names(&self) -> impl Iterator<Item = &str> {
+        self.decay.keys().map(String::as_str)
+    }
+
+    /// Names of the dose coefficient backends
+    pub fn dcf_names(&self) -> impl Iterator<Item = &str> {
+        self.dcf.keys().map(String::as_str)
+    }
+
+    /// Names of the photon emission backends
+    pub fn photon_names(&self) -> impl Iterator<Item = &str> {
+        self.photons.keys().map(String::as_str)
+    }
+
+    /// Names of the attenuation backends
+    pub fn attenuation_names(&self) -> impl Iterator<Item = &str> {
+        self.attenuation.keys().map(String::as_str)
+    }
+}
+
+fn lookup<T: ?Sized>(sources: &BTreeMap<String, Arc<T>>, name: &str) -> Result<Arc<T>, Error> {
+    sources
+        .get(name)
+        .cloned()
+        .ok_or_else(|| Error::UnknownDataset(name.to_string()))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::fixtures::FixtureDecayData;
+
+    struct Future;
+
+    impl Plugin for Future {
+        fn api_version(&self) -> u32 {
+            PLUGIN_API_VERSION + 1
+        }
+
+        fn register(&self, registry: &mut Registry) -> Result<(), Error> {
+            registry.register_decay("future", Arc::new(FixtureDecayData::new()));
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn registry() {
+        let mut registry = Registry::new();
+        assert!(registry
+            .register_decay("fixtures", Arc::new(FixtureDecayData::new()))
+            .is_none());
+        assert_eq!(registry.decay_names().collect::<Vec<_>>(), vec!["fixtures"]);
+        assert!(matches!(
+            registry.dcf("fixtures"),
+            Err(Error::UnknownDataset(_))
+        ));
+
+        assert!(matches!(
+            registry.install(&Future),
+            Err(Error::UnsupportedPluginApi(2))
+        ));
+        assert!(registry.decay("future").is_err());
+    }
+}
@@ -1,16 +1,60 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 
-use super::dose_coefficient::{AgeGroup, DcfValue, Organ};
+use super::dose_coefficient::{AgeGroup, DcfQueryResult, Organ, TransferPathway};
 use super::notation::{Material, Symbol};
 use super::nuclide::{HalfLife, Nuclide, Progeny};
-use super::DecayModeSet;
+use super::unit::Energy;
+use super::{DecayMode, DecayModeSet};
 use crate::error::Error;
 
-/// Energy in eV
-pub type Energy = u32;
+/// Nuclides covered by a dataset
+pub trait NuclideList {
+    fn nuclide_list(&self) -> Result<BTreeSet<Nuclide>, Error>;
+}
 
 pub trait NuclideProgeny {
     fn progeny(&self, nuclide: Nuclide) -> Result<Vec<Progeny>, Error>;
+
+    /// Generation of the data, changed whenever progeny or half-lives change
+    ///
+    /// Solvers keep decay chains worked out from the data until it changes;
+    /// datasets that never change keep the default of zero.
+    fn epoch(&self) -> u64 {
+        0
+    }
+
+    /// Progeny reached through the decay mode
+    fn progeny_by_mode(&self, nuclide: Nuclide, mode: DecayMode) -> Result<Vec<Progeny>, Error> {
+        let mut progeny = self.progeny(nuclide)?;
+        progeny.retain(|p| p.decay_mode.0.contains(mode));
+        Ok(progeny)
+    }
+
+    /// Nuclides reached by successive alpha decays, starting with the nuclide
+    ///
+    /// The most probable branch is followed where several alpha decays lead
+    /// to different nuclides.
+    fn alpha_chain(&self, nuclide: Nuclide) -> Result<Vec<Nuclide>, Error> {
+        let mut chain = vec![nuclide];
+        let mut progeny = self.progeny_by_mode(nuclide, DecayMode::Alpha)?;
+
+        while let Some(next) = progeny
+            .into_iter()
+            .max_by(|a, b| a.branch_rate.total_cmp(&b.branch_rate))
+        {
+            if chain.contains(&next.nuclide) {
+                break;
+            }
+            chain.push(next.nuclide);
+            // stable end members are not listed by every dataset
+            progeny = match self.progeny_by_mode(next.nuclide, DecayMode::Alpha) {
+                Err(Error::InvalidNuclide(_)) => vec![],
+                res => res?,
+            };
+        }
+
+        Ok(chain)
+    }
 }
 
 pub trait NuclideHalfLife {
@@ -22,6 +66,26 @@ pub trait NuclideDecayMode {
     fn decay_mode(&self, nuclide: Nuclide) -> Result<DecayModeSet, Error>;
 }
 
+pub trait NuclidePhotonLines {
+    /// Photon lines as energy and yield (/nt), X-rays and annihilation photons included
+    fn photon_lines(&self, nuclide: Nuclide) -> Result<Vec<(Energy, f64)>, Error>;
+}
+
+pub trait NuclideElectronLines {
+    /// Internal conversion and Auger electron lines as energy and yield (/nt)
+    fn electron_lines(&self, nuclide: Nuclide) -> Result<Vec<(Energy, f64)>, Error>;
+}
+
+pub trait NuclideAirKerma {
+    /// Air kerma-rate constant of a point source (Gy m2 / Bq s)
+    fn air_kerma_constant(&self, nuclide: Nuclide) -> Result<f64, Error>;
+}
+
+pub trait NuclideDecayEnergy {
+    /// Energy emitted as alpha, electron and photon radiation (MeV/nt), neutrinos excluded
+    fn decay_energy(&self, nuclide: Nuclide) -> Result<f64, Error>;
+}
+
 pub trait DecayConstant {
     // Decay constant (s-1)
     fn lambda(&self, nuclide: Nuclide) -> Result<f64, Error>;
@@ -29,7 +93,7 @@ pub trait DecayConstant {
 
 impl<T> DecayConstant for T
 where
-    T: NuclideHalfLife,
+    T: NuclideHalfLife + ?Sized,
 {
     fn lambda(&self, nuclide: Nuclide) -> Result<f64, Error> {
         self.half_life(nuclide).map(|hl| hl.as_lambda())
@@ -46,6 +110,11 @@ pub trait AtomicMass {
     fn atomic_mass(&self, symbol: Symbol) -> Result<f64, Error>;
 }
 
+pub trait NuclideAtomicMass {
+    /// Atomic mass of a nuclide (amu)
+    fn nuclide_atomic_mass(&self, nuclide: Nuclide) -> Result<f64, Error>;
+}
+
 pub trait MassAttenuationCoefficient {
     /// Mass attenuation coefficient (cm2/g)
     fn mass_attenuation_coefficient(
@@ -62,7 +131,7 @@ pub trait MeanFreePath {
 
 impl<T> MeanFreePath for T
 where
-    T: MassAttenuationCoefficient,
+    T: MassAttenuationCoefficient + ?Sized,
 {
     fn mfp(&self, material: &Material, energy: Energy) -> Result<f64, Error> {
         self.mass_attenuation_coefficient(material, energy)
@@ -106,59 +175,89 @@ pub fn z_eff(composition: &BTreeMap<Symbol, f64>) -> f64 {
 
 /// Air submersion dose conversion factor
 pub trait DcfAirSubmersion {
-    fn dcf_air_submersion(&self, nuclide: Nuclide, organ: Organ)
-        -> Result<Option<DcfValue>, Error>;
+    fn dcf_air_submersion(&self, nuclide: Nuclide, organ: Organ) -> Result<DcfQueryResult, Error>;
 }
 
 /// Water immersion dose conversion factor
 pub trait DcfWaterImmersion {
-    fn dcf_water_immersion(
-        &self,
-        nuclide: Nuclide,
-        organ: Organ,
-    ) -> Result<Option<DcfValue>, Error>;
+    fn dcf_water_immersion(&self, nuclide: Nuclide, organ: Organ) -> Result<DcfQueryResult, Error>;
 }
 
 /// Ground surface irradiation dose conversion factor
 pub trait DcfGroundSurface {
-    fn dcf_ground_surface(&self, nuclide: Nuclide, organ: Organ)
-        -> Result<Option<DcfValue>, Error>;
+    fn dcf_ground_surface(&self, nuclide: Nuclide, organ: Organ) -> Result<DcfQueryResult, Error>;
 }
 
 /// Soil (1cm) irradiation dose conversion factor
 pub trait DcfSoilOneCm {
-    fn dcf_soil_1cm(&self, nuclide: Nuclide, organ: Organ) -> Result<Option<DcfValue>, Error>;
+    fn dcf_soil_1cm(&self, nuclide: Nuclide, organ: Organ) -> Result<DcfQueryResult, Error>;
 }
 
 /// Soil (5cm) irradiation dose conversion factor
 pub trait DcfSoilFiveCm {
-    fn dcf_soil_5cm(&self, nuclide: Nuclide, organ: Organ) -> Result<Option<DcfValue>, Error>;
+    fn dcf_soil_5cm(&self, nuclide: Nuclide, organ: Organ) -> Result<DcfQueryResult, Error>;
 }
 
 /// Soil (15cm) irradiation dose conversion factor
 pub trait DcfSoilFifteenCm {
-    fn dcf_soil_15cm(&self, nuclide: Nuclide, organ: Organ) -> Result<Option<DcfValue>, Error>;
+    fn dcf_soil_15cm(&self, nuclide: Nuclide, organ: Organ) -> Result<DcfQueryResult, Error>;
 }
 
 /// Soil irradiation dose conversion factor
 pub trait DcfSoilInfinite {
-    fn dcf_soil_infinite(&self, nuclide: Nuclide, organ: Organ) -> Result<Option<DcfValue>, Error>;
+    fn dcf_soil_infinite(&self, nuclide: Nuclide, organ: Organ) -> Result<DcfQueryResult, Error>;
 }
 
+/// Ingestion dose coefficient of each chemical form
 pub trait DcfIngestion {
     fn dcf_ingestion(
         &self,
         nuclide: Nuclide,
         age_group: AgeGroup,
         organ: Organ,
-    ) -> Result<Vec<DcfValue>, Error>;
+    ) -> Result<DcfQueryResult, Error>;
 }
 
+/// Inhalation dose coefficient of each chemical form and absorption type
 pub trait DcfInhalation {
     fn dcf_inhalation(
         &self,
         nuclide: Nuclide,
         age_group: AgeGroup,
         organ: Organ,
-    ) -> Result<Vec<DcfValue>, Error>;
+    ) -> Result<DcfQueryResult, Error>;
+}
+
+/// Element transfer factor of the food chain
+pub trait TransferFactor {
+    fn transfer_factor(
+        &self,
+        symbol: Symbol,
+        pathway: TransferPathway,
+    ) -> Result<Option<f64>, Error>;
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::fixtures::FixtureDecayData;
+
+    #[test]
+    fn alpha_progeny() {
+        let data = FixtureDecayData::new();
+        let po218 = "Po-218".parse().unwrap();
+
+        let beta = data.progeny_by_mode(po218, DecayMode::BetaMinus).unwrap();
+        assert_eq!(beta.len(), 1);
+        assert_eq!(beta[0].nuclide, "At-218".parse().unwrap());
+
+        let chain: Vec<String> = data
+            .alpha_chain("Ra-226".parse().unwrap())
+            .unwrap()
+            .iter()
+            .map(|n| n.to_string())
+            .collect();
+        assert_eq!(chain, vec!["Ra-226", "Rn-222", "Po-218", "Pb-214"]);
+        assert!(data.alpha_chain("U-238".parse().unwrap()).is_err());
+    }
 }
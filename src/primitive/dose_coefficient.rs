@@ -1,3 +1,5 @@
+use std::sync::Arc;
+
 use crate::error::Error;
 use serde::{Deserialize, Serialize};
 
@@ -19,10 +21,26 @@ serde_plain::derive_fromstr_from_deserialize!(Pathway, |e| -> Error {
     Error::InvalidPathway(e.to_string())
 });
 
+/// Transfer of an element through the food chain
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+pub enum TransferPathway {
+    /// Soil to plant concentration ratio, Bq/kg (plant, dry) per Bq/kg (soil, dry)
+    SoilToPlant,
+    /// Feed to milk transfer coefficient (d/L)
+    FeedToMilk,
+    /// Feed to meat transfer coefficient (d/kg)
+    FeedToMeat,
+}
+
+serde_plain::derive_display_from_serialize!(TransferPathway);
+serde_plain::derive_fromstr_from_deserialize!(TransferPathway, |e| -> Error {
+    Error::InvalidPathway(e.to_string())
+});
+
 #[derive(Debug, PartialEq)]
 pub struct BiokineticAttr {
     /// Chemical compound
-    pub compound: String,
+    pub compound: Arc<str>,
     /// Gastroinstestinal absorption factor
     pub f1: f64,
     /// Clearance class or pulmonary absorption type
@@ -35,6 +53,15 @@ pub enum RespiratoryTractAttr {
     ICRP66(PulmonaryAbsorptionType),
 }
 
+impl std::fmt::Display for RespiratoryTractAttr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::ICRP30(class) => write!(f, "{}", class),
+            Self::ICRP66(absorption_type) => write!(f, "{}", absorption_type),
+        }
+    }
+}
+
 /// Pulmonary Absorption Type of respiratory tract model of ICRP 66
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
 pub enum PulmonaryAbsorptionType {
@@ -105,7 +132,7 @@ serde_plain::derive_fromstr_from_deserialize!(AgeGroup, |e| -> Error {
     Error::InvalidAgeGroup(e.to_string())
 });
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Hash, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
 pub enum Organ {
     Adrenals,
     UrinaryBladder,
@@ -148,4 +175,177 @@ pub struct DcfValue {
     pub value: f64,
     pub unit: String,
     pub attr: Option<BiokineticAttr>,
+    /// Where the value was read from, for citing it in reports
+    pub provenance: Option<DcfProvenance>,
+}
+
+/// Table cell a dose coefficient was read from
+#[derive(Debug, Clone, PartialEq)]
+pub struct DcfProvenance {
+    /// Publication, e.g. "ICRP 72"
+    pub dataset: String,
+    pub table: String,
+    pub column: String,
+    /// Columns and values identifying the row
+    pub row: Vec<(String, String)>,
+}
+
+impl DcfProvenance {
+    pub fn new(dataset: &str, table: &str, column: &str) -> Self {
+        Self {
+            dataset: dataset.to_string(),
+            table: table.to_string(),
+            column: column.to_string(),
+            row: vec![],
+        }
+    }
+
+    /// Add a column identifying the row
+    pub fn key<V: std::fmt::Display>(mut self, column: &str, value: V) -> Self {
+        self.row.push((column.to_string(), value.to_string()));
+        self
+    }
+}
+
+impl std::fmt::Display for DcfProvenance {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}, {}, {}", self.dataset, self.table, self.column)?;
+        for (column, value) in &self.row {
+            write!(f, ", {} = {}", column, value)?;
+        }
+        Ok(())
+    }
+}
+
+/// Why a dose coefficient query found no value
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotFoundReason {
+    /// The nuclide is not listed
+    Nuclide,
+    /// The dataset has no coefficients for the age group
+    AgeGroup,
+    /// The dataset has no coefficients for the organ
+    Organ,
+}
+
+/// Result of a dose coefficient query
+#[derive(Debug, PartialEq)]
+pub struct DcfQueryResult {
+    /// Coefficients of each chemical form or absorption type (one for external exposure)
+    pub values: Vec<DcfValue>,
+    /// Dataset the query was answered from
+    pub source: String,
+    /// Set when `values` is empty
+    pub not_found: Option<NotFoundReason>,
+}
+
+impl DcfQueryResult {
+    pub fn new(source: &str, values: Vec<DcfValue>) -> Self {
+        let not_found = values.is_empty().then_some(NotFoundReason::Nuclide);
+
+        Self {
+            values,
+            source: source.to_string(),
+            not_found,
+        }
+    }
+
+    pub fn not_found(source: &str, reason: NotFoundReason) -> Self {
+        Self {
+            values: vec![],
+            source: source.to_string(),
+            not_found: Some(reason),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    pub fn first(&self) -> Option<&DcfValue> {
+        self.values.first()
+    }
+
+    /// Largest coefficient, the conservative choice among chemical forms
+    pub fn max(&self) -> Option<&DcfValue> {
+        self.values
+            .iter()
+            .max_by(|a, b| a.value.total_cmp(&b.value))
+    }
+}
+
+impl std::fmt::Display for DcfQueryResult {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(reason) = self.not_found {
+            return writeln!(f, "{}: not found ({:?})", self.source, reason);
+        }
+
+        writeln!(
+            f,
+            "{:<10}{:>10}{:>8}{:>14}  unit",
+            "compound", "f1", "class", "value"
+        )?;
+        for dcf in &self.values {
+            let (compound, f1, class) = match &dcf.attr {
+                Some(attr) => (
+                    attr.compound.to_string(),
+                    format!("{}", attr.f1),
+                    attr.respiratory_tract_attr
+                        .as_ref()
+                        .map_or("-".to_string(), |a| a.to_string()),
+                ),
+                None => ("-".to_string(), "-".to_string(), "-".to_string()),
+            };
+            writeln!(
+                f,
+                "{:<10}{:>10}{:>8}{:>14.3e}  {}",
+                compound, f1, class, dcf.value, dcf.unit
+            )?;
+        }
+
+        write!(f, "({})", self.source)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn query_result_table() {
+        let result = DcfQueryResult::new(
+            "ICRP 68",
+            vec![DcfValue {
+                value: 1.8e-11,
+                unit: "Sv/Bq".to_string(),
+                attr: Some(BiokineticAttr {
+                    compound: "HTO".into(),
+                    f1: 1.,
+                    respiratory_tract_attr: Some(RespiratoryTractAttr::ICRP66(
+                        PulmonaryAbsorptionType::Vapor,
+                    )),
+                }),
+                provenance: None,
+            }],
+        );
+        let table = result.to_string();
+
+        assert!(table.lines().nth(1).unwrap().starts_with("HTO"));
+        assert!(table.contains("1.800e-11  Sv/Bq"));
+        assert!(table.ends_with("(ICRP 68)"));
+
+        let none = DcfQueryResult::not_found("FGR 12", NotFoundReason::AgeGroup);
+        assert_eq!(none.to_string(), "FGR 12: not found (AgeGroup)\n");
+    }
+
+    #[test]
+    fn provenance() {
+        let provenance = DcfProvenance::new("ICRP 72", "Ingestion Adult", "E")
+            .key("Nuclide", "Cs-137")
+            .key("f1", "1.0");
+        assert_eq!(
+            provenance.to_string(),
+            "ICRP 72, Ingestion Adult, E, Nuclide = Cs-137, f1 = 1.0"
+        );
+    }
 }
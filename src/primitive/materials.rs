@@ -0,0 +1,182 @@
+use std::sync::Arc;
+
+use super::attr::AtomicMass;
+use super::notation::{Material, MaterialBuilder, Symbol};
+use crate::error::Error;
+
+/// Compound of the catalog, with its density (g/cm3) and weight fractions
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CatalogMaterial {
+    pub name: &'static str,
+    pub density: f64,
+    pub weight_fraction: &'static [(Symbol, f64)],
+}
+
+use Symbol::*;
+
+/// Compositions of the NIST X-ray attenuation (Hubbell & Seltzer, table 2)
+/// and ESTAR material tables, under their NIST names
+#[rustfmt::skip]
+pub const CATALOG: &[CatalogMaterial] = &[
+    CatalogMaterial {
+        name: "Air, Dry (near sea level)",
+        density: 1.205e-3,
+        weight_fraction: &[(C, 0.000124), (N, 0.755268), (O, 0.231781), (Ar, 0.012827)],
+    },
+    CatalogMaterial {
+        name: "Bone, Cortical (ICRU-44)",
+        density: 1.92,
+        weight_fraction: &[
+            (H, 0.034), (C, 0.155), (N, 0.042), (O, 0.435), (Na, 0.001), (Mg, 0.002),
+            (P, 0.103), (S, 0.003), (Ca, 0.225),
+        ],
+    },
+    CatalogMaterial {
+        name: "Concrete, Barite",
+        density: 3.35,
+        weight_fraction: &[
+            (H, 0.003585), (O, 0.311622), (Mg, 0.001195), (Al, 0.004183), (Si, 0.010457),
+            (S, 0.107858), (Ca, 0.050194), (Fe, 0.047505), (Ba, 0.463400),
+        ],
+    },
+    CatalogMaterial {
+        name: "Concrete, Ordinary",
+        density: 2.3,
+        weight_fraction: &[
+            (H, 0.022100), (C, 0.002484), (O, 0.574930), (Na, 0.015208), (Mg, 0.001266),
+            (Al, 0.019953), (Si, 0.304627), (K, 0.010045), (Ca, 0.042951), (Fe, 0.006435),
+        ],
+    },
+    CatalogMaterial {
+        name: "Concrete, Portland",
+        density: 2.3,
+        weight_fraction: &[
+            (H, 0.010000), (C, 0.001000), (O, 0.529107), (Na, 0.016000), (Mg, 0.002000),
+            (Al, 0.033872), (Si, 0.337021), (K, 0.013000), (Ca, 0.044000), (Fe, 0.014000),
+        ],
+    },
+    CatalogMaterial {
+        name: "Glass, Lead",
+        density: 6.22,
+        weight_fraction: &[
+            (O, 0.156453), (Si, 0.080866), (Ti, 0.008092), (As, 0.002651), (Pb, 0.751938),
+        ],
+    },
+    CatalogMaterial {
+        name: "Polyethylene",
+        density: 0.94,
+        weight_fraction: &[(H, 0.143711), (C, 0.856289)],
+    },
+    CatalogMaterial {
+        name: "Polymethyl Methacrylate (Lucite, Perspex)",
+        density: 1.19,
+        weight_fraction: &[(H, 0.080538), (C, 0.599848), (O, 0.319614)],
+    },
+    CatalogMaterial {
+        name: "Polystyrene",
+        density: 1.06,
+        weight_fraction: &[(H, 0.077418), (C, 0.922582)],
+    },
+    CatalogMaterial {
+        name: "Tissue, Soft (ICRP)",
+        density: 1.03,
+        weight_fraction: &[
+            (H, 0.104472), (C, 0.232190), (N, 0.024880), (O, 0.630238), (Na, 0.001130),
+            (Mg, 0.000130), (P, 0.001330), (S, 0.001990), (Cl, 0.001340), (K, 0.001990),
+            (Ca, 0.000230), (Fe, 0.000050), (Zn, 0.000030),
+        ],
+    },
+    CatalogMaterial {
+        name: "Tissue, Soft (ICRU-44)",
+        density: 1.06,
+        weight_fraction: &[
+            (H, 0.102), (C, 0.143), (N, 0.034), (O, 0.708), (Na, 0.002), (P, 0.003),
+            (S, 0.003), (Cl, 0.002), (K, 0.003),
+        ],
+    },
+    CatalogMaterial {
+        name: "Water, Liquid",
+        density: 1.0,
+        weight_fraction: &[(H, 0.111898), (O, 0.888102)],
+    },
+];
+
+/// Aliases of catalog names, e.g. `water` or `PMMA`
+const ALIASES: &[(&str, &str)] = &[
+    ("air", "Air, Dry (near sea level)"),
+    ("concrete", "Concrete, Ordinary"),
+    ("lead glass", "Glass, Lead"),
+    ("pmma", "Polymethyl Methacrylate (Lucite, Perspex)"),
+    ("soft tissue", "Tissue, Soft (ICRP)"),
+    ("water", "Water, Liquid"),
+];
+
+/// Catalog entry of a name or alias, in any case
+pub fn find(name: &str) -> Option<&'static CatalogMaterial> {
+    let name = name.trim();
+    let name = ALIASES
+        .iter()
+        .find(|(alias, _)| alias.eq_ignore_ascii_case(name))
+        .map_or(name, |(_, name)| name);
+
+    CATALOG.iter().find(|m| m.name.eq_ignore_ascii_case(name))
+}
+
+impl<D> MaterialBuilder<D>
+where
+    D: AtomicMass + ?Sized,
+{
+    /// Weight fractions and density of a catalog material, see [`find`]
+    pub fn catalog(self, name: &str) -> Result<Self, Error> {
+        let material = find(name)
+            .ok_or_else(|| Error::Unexpected(anyhow::anyhow!("unknown material: {}", name)))?;
+
+        Ok(self
+            .weight_fraction(material.weight_fraction.iter().copied().collect())?
+            .density(material.density))
+    }
+}
+
+impl Material {
+    /// Unit weight of a catalog material, see [`find`]
+    pub fn from_catalog<D: AtomicMass + ?Sized>(name: &str, data: Arc<D>) -> Result<Self, Error> {
+        MaterialBuilder::new(data).catalog(name)?.weight(1.).build()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    struct MassNumber;
+
+    impl AtomicMass for MassNumber {
+        // rough atomic masses, twice the atomic number
+        fn atomic_mass(&self, symbol: Symbol) -> Result<f64, Error> {
+            Ok(2. * symbol as u8 as f64)
+        }
+    }
+
+    #[test]
+    fn catalog() {
+        for material in CATALOG {
+            let total: f64 = material.weight_fraction.iter().map(|(_, w)| w).sum();
+            assert!((total - 1.).abs() < 2e-6, "{}", material.name);
+        }
+        for (_, name) in ALIASES {
+            assert!(find(name).is_some(), "{}", name);
+        }
+
+        let concrete = Material::from_catalog("concrete, portland", Arc::new(MassNumber)).unwrap();
+        assert_eq!(concrete.density(), 2.3);
+        assert_eq!(concrete.weight_fraction()[&Symbol::Si], 0.337021);
+
+        let water = Material::from_catalog("Water", Arc::new(MassNumber)).unwrap();
+        // H2O with masses of 2 and 16
+        let ratio = water.composition()[&Symbol::H] / water.composition()[&Symbol::O];
+        assert!((ratio - 0.111898 / 2. / (0.888102 / 16.)).abs() < 1e-12);
+
+        assert!(find("Unobtainium").is_none());
+        assert!(Material::from_catalog("Unobtainium", Arc::new(MassNumber)).is_err());
+    }
+}
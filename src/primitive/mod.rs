@@ -1,16 +1,25 @@
 pub mod attr;
 pub mod dose_coefficient;
+pub mod materials;
 pub mod notation;
 pub mod nuclide;
 pub mod parser;
+pub mod unit;
 
 pub use attr::{
     AtomicMass, DcfAirSubmersion, DcfGroundSurface, DcfIngestion, DcfInhalation, DcfSoilFifteenCm,
     DcfSoilFiveCm, DcfSoilInfinite, DcfSoilOneCm, DcfWaterImmersion, DecayConstant,
-    MassAttenuationCoefficient, NuclideDecayMode, NuclideHalfLife, NuclideProgeny,
+    MassAttenuationCoefficient, NuclideAirKerma, NuclideAtomicMass, NuclideDecayEnergy,
+    NuclideDecayMode, NuclideElectronLines, NuclideHalfLife, NuclideList, NuclidePhotonLines,
+    NuclideProgeny, TransferFactor,
 };
 pub use dose_coefficient::{
-    AgeGroup, BiokineticAttr, ClearanceClass, DcfValue, Organ, Pathway, PulmonaryAbsorptionType,
+    AgeGroup, BiokineticAttr, ClearanceClass, DcfProvenance, DcfQueryResult, DcfValue,
+    NotFoundReason, Organ, Pathway, PulmonaryAbsorptionType, TransferPathway,
 };
-pub use notation::{Material, MaterialBuilder, Symbol};
-pub use nuclide::{DecayMode, DecayModeSet, HalfLife, Nuclide, Progeny, TimeUnit};
+pub use notation::{Material, MaterialBuilder, MixBy, Symbol};
+pub use nuclide::{
+    normalize_nuclide_list, DecayMode, DecayModeSet, HalfLife, HalfLifeBinning, HalfLifeBins,
+    HalfLifeFormat, Nuclide, ParseIssue, Progeny, TimeUnit,
+};
+pub use unit::{si_prefixed, ActivityUnit, Energy, NumberFormat};
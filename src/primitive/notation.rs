@@ -96,7 +96,14 @@ impl Compound {
     }
 }
 
-pub struct MaterialBuilder<D> {
+/// Fractions of the materials of a mixture, see [`MaterialBuilder::mix`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MixBy {
+    Mass,
+    Volume,
+}
+
+pub struct MaterialBuilder<D: ?Sized> {
     data: Arc<D>,
     composition: BTreeMap<Symbol, f64>,
     weight_fraction: BTreeMap<Symbol, f64>,
@@ -106,7 +113,7 @@ pub struct MaterialBuilder<D> {
 
 impl<D> MaterialBuilder<D>
 where
-    D: AtomicMass,
+    D: AtomicMass + ?Sized,
 {
     pub fn new(data: Arc<D>) -> Self {
         Self {
@@ -182,6 +189,41 @@ where
         Ok(self)
     }
 
+    /// Homogenized mixture of materials and their mass or volume fractions,
+    /// with the density of the materials taking up their own volumes
+    ///
+    /// Fractions are normalized to their sum.
+    pub fn mix(self, materials: &[(Material, f64)], by: MixBy) -> Result<Self, Error> {
+        // mass of each material per unit total of the fractions
+        let masses: Vec<f64> = materials
+            .iter()
+            .map(|(material, f)| match by {
+                MixBy::Mass => *f,
+                MixBy::Volume => f * material.density(),
+            })
+            .collect();
+        let mass: f64 = masses.iter().sum();
+        let volume: f64 = materials
+            .iter()
+            .zip(&masses)
+            .map(|((material, _), m)| m / material.density())
+            .sum();
+        if !masses.iter().all(|&m| m >= 0.) || mass <= 0. {
+            return Err(anyhow::anyhow!("invalid mixture fractions").into());
+        }
+
+        let mut weight_fraction = BTreeMap::new();
+        for ((material, _), m) in materials.iter().zip(&masses) {
+            for (&symbol, &w) in material.weight_fraction() {
+                *weight_fraction.entry(symbol).or_default() += w * m / mass;
+            }
+        }
+
+        Ok(self
+            .weight_fraction(weight_fraction)?
+            .density(mass / volume))
+    }
+
     pub fn density(mut self, density: f64) -> Self {
         self.density = Some(density);
 
@@ -280,4 +322,41 @@ mod test {
         assert_eq!(material.weight_fraction().get(&Symbol::H), Some(&0.25));
         assert_eq!(material.weight_fraction().get(&Symbol::O), Some(&0.75));
     }
+
+    #[test]
+    fn mixture() {
+        let data = Arc::new(TestData {});
+        let build = |formula: &str, density: f64| {
+            MaterialBuilder::new(data.clone())
+                .formula(formula)
+                .unwrap()
+                .weight(1.)
+                .density(density)
+                .build()
+                .unwrap()
+        };
+        let (light, heavy) = (build("H", 1.), build("O", 3.));
+        let materials = [(light, 1.), (heavy, 1.)];
+
+        let by_mass = MaterialBuilder::new(data.clone())
+            .mix(&materials, MixBy::Mass)
+            .unwrap()
+            .weight(1.)
+            .build()
+            .unwrap();
+        assert_eq!(by_mass.weight_fraction()[&Symbol::H], 0.5);
+        // 2 g in 1 + 1/3 cm3
+        assert_eq!(by_mass.density(), 1.5);
+
+        let by_volume = MaterialBuilder::new(data.clone())
+            .mix(&materials, MixBy::Volume)
+            .unwrap()
+            .weight(1.)
+            .build()
+            .unwrap();
+        assert_eq!(by_volume.weight_fraction()[&Symbol::H], 0.25);
+        assert_eq!(by_volume.density(), 2.);
+
+        assert!(MaterialBuilder::new(data).mix(&[], MixBy::Mass).is_err());
+    }
 }
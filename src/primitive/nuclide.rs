@@ -7,15 +7,19 @@ use float_pretty_print::PrettyPrintFloat;
 use num_derive::FromPrimitive;
 use num_traits::FromPrimitive;
 use serde::{Deserialize, Serialize};
-use serde_with::DeserializeFromStr;
+use serde_with::{DeserializeFromStr, SerializeDisplay};
 
+use super::attr::NuclideHalfLife;
 use super::notation::Symbol;
 use super::parser::{halflife, nuclide};
+use super::unit::significant;
 use crate::error::Error;
 
 pub use decay_mode::{DecayMode, DecayModeSet};
 
-#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord, DeserializeFromStr)]
+#[derive(
+    Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord, DeserializeFromStr, SerializeDisplay,
+)]
 pub enum Nuclide {
     /// Nuclide with canonical id
     WithId(u32),
@@ -24,6 +28,26 @@ pub enum Nuclide {
 }
 
 impl Nuclide {
+    /// Nuclide of a canonical id `ZZZAAA000M`
+    ///
+    /// The atomic number must be that of an element, the mass number between
+    /// the atomic number and 300, and the metastable state 0, 1 or 2.
+    pub fn try_from_id(id: u32) -> Result<Self, Error> {
+        let (z, a, rest, state) = (
+            id / 10_000_000,
+            (id / 10_000) % 1_000,
+            (id / 10) % 1_000,
+            id % 10,
+        );
+        let valid = (1..=118).contains(&z) && (z..=300).contains(&a) && rest == 0 && state <= 2;
+
+        if valid {
+            Ok(Self::WithId(id))
+        } else {
+            Err(Error::InvalidNuclide(id.to_string()))
+        }
+    }
+
     pub fn id(&self) -> Option<u32> {
         match self {
             Self::WithId(id) => Some(*id),
@@ -47,6 +71,14 @@ impl Nuclide {
             None => None,
         }
     }
+
+    /// Ground state of a metastable state, the nuclide itself otherwise
+    pub fn ground_state(&self) -> Self {
+        match self {
+            Self::WithId(id) => Self::WithId(id - id % 10),
+            Self::FissionProducts => Self::FissionProducts,
+        }
+    }
 }
 
 impl Display for Nuclide {
@@ -79,6 +111,135 @@ impl FromStr for Nuclide {
     }
 }
 
+/// Problem with an entry of a nuclide list, see [`normalize_nuclide_list`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseIssue {
+    /// Blank entry
+    Empty { index: usize },
+    /// Entry in no known notation
+    Invalid { index: usize, input: String },
+    /// Entry naming the nuclide of an earlier entry at `first`
+    Duplicate {
+        index: usize,
+        first: usize,
+        nuclide: Nuclide,
+    },
+}
+
+impl ParseIssue {
+    /// Position of the entry in the list
+    pub fn index(&self) -> usize {
+        match self {
+            Self::Empty { index } | Self::Invalid { index, .. } | Self::Duplicate { index, .. } => {
+                *index
+            }
+        }
+    }
+}
+
+/// Nuclide in any case and with the mass number before or after the symbol,
+/// e.g. `cs137`, `CS 137`, `137Cs`, `99mTc` or `Tc_99m`
+pub(crate) fn parse_lenient(s: &str) -> Result<Nuclide, Error> {
+    if let Ok(nuclide) = s.parse() {
+        return Ok(nuclide);
+    }
+    let invalid = || Error::InvalidNuclide(s.to_string());
+
+    // runs of letters and digits, separators dropped
+    let mut runs: Vec<String> = vec![];
+    for c in s
+        .chars()
+        .filter(|c| !c.is_whitespace() && !matches!(c, '-' | '_'))
+    {
+        if !c.is_ascii_alphanumeric() {
+            return Err(invalid());
+        }
+        match runs.last_mut() {
+            Some(run) if run.starts_with(|r: char| r.is_ascii_digit()) == c.is_ascii_digit() => {
+                run.push(c)
+            }
+            _ => runs.push(c.to_string()),
+        }
+    }
+
+    let symbol = |s: &str| -> Option<String> {
+        let mut chars = s.chars();
+        let first = chars.next()?.to_ascii_uppercase();
+        let symbol = std::iter::once(first)
+            .chain(chars.map(|c| c.to_ascii_lowercase()))
+            .collect::<String>();
+        symbol.parse::<Symbol>().is_ok().then_some(symbol)
+    };
+    let canonical = match runs.as_slice() {
+        [letters, mass] if mass.starts_with(|c: char| c.is_ascii_digit()) => {
+            format!("{}-{}", symbol(letters).ok_or_else(invalid)?, mass)
+        }
+        [letters, mass, state] if mass.starts_with(|c: char| c.is_ascii_digit()) => format!(
+            "{}-{}{}",
+            symbol(letters).ok_or_else(invalid)?,
+            mass,
+            state.to_ascii_lowercase()
+        ),
+        // mass number first, optionally followed by the state
+        [mass, letters] if mass.starts_with(|c: char| c.is_ascii_digit()) => {
+            match symbol(letters) {
+                Some(symbol) => format!("{}-{}", symbol, mass),
+                None => {
+                    let (state, letters) = letters.split_at(1);
+                    format!(
+                        "{}-{}{}",
+                        symbol(letters).ok_or_else(invalid)?,
+                        mass,
+                        state.to_ascii_lowercase()
+                    )
+                }
+            }
+        }
+        _ => return Err(invalid()),
+    };
+
+    canonical.parse().map_err(|_| invalid())
+}
+
+/// Nuclides of a user supplied list in mixed notations, in the order of their
+/// first appearance, with the entries left out
+///
+/// Besides the notations of [`Nuclide::from_str`], entries may be in any case,
+/// have the mass number first and use spaces or underscores as separators.
+pub fn normalize_nuclide_list(entries: &[String]) -> (Vec<Nuclide>, Vec<ParseIssue>) {
+    let mut nuclides = vec![];
+    let mut first = std::collections::HashMap::new();
+    let mut issues = vec![];
+
+    for (index, entry) in entries.iter().enumerate() {
+        let input = entry.trim();
+        if input.is_empty() {
+            issues.push(ParseIssue::Empty { index });
+            continue;
+        }
+
+        match parse_lenient(input) {
+            Ok(nuclide) => match first.get(&nuclide) {
+                Some(&first) => issues.push(ParseIssue::Duplicate {
+                    index,
+                    first,
+                    nuclide,
+                }),
+                None => {
+                    first.insert(nuclide, index);
+                    nuclides.push(nuclide);
+                }
+            },
+            Err(_) => issues.push(ParseIssue::Invalid {
+                index,
+                input: input.to_string(),
+            }),
+        }
+    }
+
+    (nuclides, issues)
+}
+
 #[repr(u8)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, FromPrimitive)]
 pub enum MetastableState {
@@ -223,7 +384,7 @@ pub mod decay_mode {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
 pub enum TimeUnit {
     #[serde(rename = "us")]
     MicroSecond,
@@ -303,12 +464,228 @@ impl FromStr for HalfLife {
 
 impl std::fmt::Display for HalfLife {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let number_str = PrettyPrintFloat(self.value).to_string();
-        match number_str.strip_suffix(".0") {
-            Some(number_str) => write!(f, "{} {}", number_str, self.unit),
-            None => write!(f, "{} {}", number_str, self.unit),
+        write!(f, "{}", HalfLifeFormat::new().format(self))
+    }
+}
+
+/// Formatting options of [`HalfLife`]
+///
+/// ```
+/// # use radioactive::primitive::{HalfLife, HalfLifeFormat, TimeUnit};
+/// let t: HalfLife = "30.05 y".parse().unwrap();
+/// let fmt = HalfLifeFormat::new().significant_figures(4).uncertainty(0.08);
+/// assert_eq!(fmt.format(&t), "30.05 ± 0.08 y");
+/// let fmt = HalfLifeFormat::new().unit(TimeUnit::Day).significant_figures(6);
+/// assert_eq!(fmt.format(&t), "10975.5 d");
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HalfLifeFormat {
+    significant_figures: Option<usize>,
+    unit: Option<TimeUnit>,
+    scientific: bool,
+    uncertainty: Option<f64>,
+}
+
+impl HalfLifeFormat {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn significant_figures(mut self, n: usize) -> Self {
+        self.significant_figures = Some(n.max(1));
+        self
+    }
+
+    /// Convert to the given unit instead of the unit of the half-life
+    pub fn unit(mut self, unit: TimeUnit) -> Self {
+        self.unit = Some(unit);
+        self
+    }
+
+    pub fn scientific(mut self, scientific: bool) -> Self {
+        self.scientific = scientific;
+        self
+    }
+
+    /// Absolute uncertainty, in the unit of the half-life
+    pub fn uncertainty(mut self, uncertainty: f64) -> Self {
+        self.uncertainty = Some(uncertainty);
+        self
+    }
+
+    pub fn format(&self, half_life: &HalfLife) -> String {
+        let unit = self.unit.unwrap_or(half_life.unit);
+        let scale = half_life.unit.as_sec() / unit.as_sec();
+        let value = half_life.value * scale;
+        let uncertainty = self.uncertainty.map(|u| u * scale);
+
+        if self.scientific {
+            let digits = self.significant_figures.unwrap_or(4);
+            let (mantissa, exp) = significant(value, digits);
+            match uncertainty {
+                Some(u) => format!(
+                    "({} ± {:.*})e{} {}",
+                    mantissa,
+                    digits - 1,
+                    u / 10f64.powi(exp),
+                    exp,
+                    unit
+                ),
+                None => format!("{}e{} {}", mantissa, exp, unit),
+            }
+        } else {
+            let number = |x: f64, decimals: Option<usize>| match decimals {
+                Some(decimals) => format!("{:.*}", decimals, x),
+                None => {
+                    let s = PrettyPrintFloat(x).to_string();
+                    s.strip_suffix(".0").map_or(s.clone(), |s| s.to_string())
+                }
+            };
+            let decimals = self
+                .significant_figures
+                .map(|n| (n as i32 - 1 - significant(value, n).1).max(0) as usize);
+            match uncertainty {
+                Some(u) => format!(
+                    "{} ± {} {}",
+                    number(value, decimals),
+                    number(u, decimals),
+                    unit
+                ),
+                None => format!("{} {}", number(value, decimals), unit),
+            }
+        }
+    }
+}
+
+/// Half-life ranges bounded by ascending edges, e.g. for coloring a chart of
+/// nuclides or the longevity profile of an inventory
+///
+/// A half-life equal to an edge falls into the range above it. The default
+/// ranges are <1 s, 1 s–1 h, 1 h–1 d, 1 d–1 y, 1 y–1000 y, 1000 y–1e6 y and
+/// >1e6 y.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HalfLifeBins {
+    edges: Vec<HalfLife>,
+}
+
+impl Default for HalfLifeBins {
+    fn default() -> Self {
+        let edge = |value, unit| HalfLife { value, unit };
+        Self::new([
+            edge(1., TimeUnit::Second),
+            edge(1., TimeUnit::Hour),
+            edge(1., TimeUnit::Day),
+            edge(1., TimeUnit::Year),
+            edge(1e3, TimeUnit::Year),
+            edge(1e6, TimeUnit::Year),
+        ])
+    }
+}
+
+impl HalfLifeBins {
+    pub fn new<I: IntoIterator<Item = HalfLife>>(edges: I) -> Self {
+        let mut edges: Vec<HalfLife> = edges.into_iter().collect();
+        edges.sort_by(|a, b| a.as_sec().total_cmp(&b.as_sec()));
+        edges.dedup();
+        Self { edges }
+    }
+
+    /// Ranges of one decade each from `from` up to `to` (s), with open
+    /// ranges below and above
+    ///
+    /// The bounds must be finite with `0 < from < to`.
+    pub fn decades(from: f64, to: f64) -> Result<Self, Error> {
+        if !(from > 0. && from < to && to.is_finite()) {
+            return Err(Error::InvalidParameter(format!(
+                "half-life decades from {} to {} (s)",
+                from, to
+            )));
+        }
+
+        let (from, to) = (from.log10().floor() as i32, to.log10().ceil() as i32);
+        Ok(Self::new((from..=to).map(|exp| HalfLife {
+            value: 10f64.powi(exp),
+            unit: TimeUnit::Second,
+        })))
+    }
+
+    /// Number of ranges, one more than the number of edges
+    pub fn len(&self) -> usize {
+        self.edges.len() + 1
+    }
+
+    pub fn is_empty(&self) -> bool {
+        false
+    }
+
+    pub fn edges(&self) -> &[HalfLife] {
+        &self.edges
+    }
+
+    /// Range of a half-life
+    pub fn bin(&self, half_life: &HalfLife) -> usize {
+        let t = half_life.as_sec();
+        self.edges.partition_point(|edge| edge.as_sec() <= t)
+    }
+
+    /// Label of a range such as `1 h–1 d`, `<1 s` or `>1e6 y`
+    ///
+    /// Edges are written in their own unit, in scientific notation from
+    /// 10000 up and below 0.001, independently of the [`HalfLife`] display.
+    pub fn label(&self, bin: usize) -> String {
+        let edge = |t: &HalfLife| {
+            if (1e-3..1e4).contains(&t.value.abs()) {
+                format!("{} {}", t.value, t.unit)
+            } else {
+                format!("{:e} {}", t.value, t.unit)
+            }
+        };
+        let lower = bin.checked_sub(1).and_then(|i| self.edges.get(i));
+        match (lower, self.edges.get(bin)) {
+            (Some(lower), Some(upper)) => format!("{}–{}", edge(lower), edge(upper)),
+            (None, Some(upper)) => format!("<{}", edge(upper)),
+            (Some(lower), None) => format!(">{}", edge(lower)),
+            (None, None) => "all".to_string(),
         }
     }
+
+    pub fn labels(&self) -> Vec<String> {
+        (0..self.len()).map(|bin| self.label(bin)).collect()
+    }
+
+    /// Nuclides grouped by the range of their half-lives
+    pub fn classify<D, I>(&self, data: &D, nuclides: I) -> HalfLifeBinning
+    where
+        D: NuclideHalfLife + ?Sized,
+        I: IntoIterator<Item = Nuclide>,
+    {
+        let mut members = vec![vec![]; self.len()];
+        let mut unknown = vec![];
+        for nuclide in nuclides {
+            match data.half_life(nuclide) {
+                Ok(half_life) => members[self.bin(&half_life)].push(nuclide),
+                Err(_) => unknown.push(nuclide),
+            }
+        }
+
+        HalfLifeBinning { members, unknown }
+    }
+}
+
+/// Nuclides in each range of [`HalfLifeBins`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct HalfLifeBinning {
+    /// Nuclides of each range, in the order given
+    pub members: Vec<Vec<Nuclide>>,
+    /// Nuclides without a half-life, e.g. stable ones
+    pub unknown: Vec<Nuclide>,
+}
+
+impl HalfLifeBinning {
+    /// Number of nuclides in each range
+    pub fn counts(&self) -> Vec<usize> {
+        self.members.iter().map(Vec::len).collect()
+    }
 }
 
 impl PartialEq for HalfLife {
@@ -333,6 +710,143 @@ mod test {
         assert!(cc99.is_err());
     }
 
+    #[test]
+    fn normalize_list() {
+        let entries: Vec<String> = [
+            "Cs-137",
+            " cs137 ",
+            "137Cs",
+            "CS 137",
+            "Tc_99m",
+            "99mTc",
+            "tc99M",
+            "",
+            "Xx-1",
+            "Co-60!",
+            "531310000",
+            "I-131",
+            "SF",
+            "99Mo",
+        ]
+        .map(String::from)
+        .to_vec();
+        let (nuclides, issues) = normalize_nuclide_list(&entries);
+
+        let expected: Vec<Nuclide> = ["Cs-137", "Tc-99m", "I-131", "SF", "Mo-99"]
+            .iter()
+            .map(|s| s.parse().unwrap())
+            .collect();
+        assert_eq!(nuclides, expected);
+
+        let cs137 = expected[0];
+        let tc99m = expected[1];
+        assert_eq!(
+            issues,
+            vec![
+                ParseIssue::Duplicate {
+                    index: 1,
+                    first: 0,
+                    nuclide: cs137
+                },
+                ParseIssue::Duplicate {
+                    index: 2,
+                    first: 0,
+                    nuclide: cs137
+                },
+                ParseIssue::Duplicate {
+                    index: 3,
+                    first: 0,
+                    nuclide: cs137
+                },
+                ParseIssue::Duplicate {
+                    index: 5,
+                    first: 4,
+                    nuclide: tc99m
+                },
+                ParseIssue::Duplicate {
+                    index: 6,
+                    first: 4,
+                    nuclide: tc99m
+                },
+                ParseIssue::Empty { index: 7 },
+                ParseIssue::Invalid {
+                    index: 8,
+                    input: "Xx-1".to_string()
+                },
+                ParseIssue::Invalid {
+                    index: 9,
+                    input: "Co-60!".to_string()
+                },
+                ParseIssue::Duplicate {
+                    index: 11,
+                    first: 10,
+                    nuclide: expected[2]
+                },
+            ]
+        );
+        assert_eq!(
+            issues.iter().map(ParseIssue::index).collect::<Vec<_>>(),
+            [1, 2, 3, 5, 6, 7, 8, 9, 11]
+        );
+
+        // unknown states
+        let (nuclides, issues) =
+            normalize_nuclide_list(&["Tc-99x".to_string(), "99xTc".to_string()]);
+        assert!(nuclides.is_empty());
+        assert_eq!(issues.len(), 2);
+    }
+
+    #[test]
+    fn half_life_bins() {
+        let bins = HalfLifeBins::default();
+        assert_eq!(bins.len(), 7);
+        assert_eq!(bins.label(0), "<1 s");
+        assert_eq!(bins.label(2), "1 h–1 d");
+        assert_eq!(bins.label(5), "1000 y–1e6 y");
+        assert_eq!(bins.label(6), ">1e6 y");
+        assert_eq!(
+            HalfLifeBins::decades(1e-4, 1e-3).unwrap().labels(),
+            ["<1e-4 s", "1e-4 s–0.001 s", ">0.001 s"]
+        );
+
+        let t = |s: &str| -> HalfLife { s.parse().unwrap() };
+        assert_eq!(bins.bin(&t("1 ms")), 0);
+        assert_eq!(bins.bin(&t("1 s")), 1);
+        assert_eq!(bins.bin(&t("60 m")), 2);
+        assert_eq!(bins.bin(&t("30.05 y")), 4);
+        assert_eq!(bins.bin(&t("4.468e9 y")), 6);
+
+        let decades = HalfLifeBins::decades(1., 1e3).unwrap();
+        assert_eq!(decades.edges().len(), 4);
+        assert_eq!(decades.bin(&t("50 s")), 2);
+        for (from, to) in [
+            (0., 1.),
+            (-1., 1.),
+            (1., 1.),
+            (1e3, 1.),
+            (f64::NAN, 1.),
+            (1., f64::INFINITY),
+        ] {
+            assert!(HalfLifeBins::decades(from, to).is_err());
+        }
+        assert_eq!(
+            HalfLifeBins::new([t("1 d"), t("1 s"), t("24 h")]).edges(),
+            [t("1 s"), t("1 d")]
+        );
+    }
+
+    #[test]
+    fn nuclide_from_id() {
+        let tc99m = Nuclide::try_from_id(430990001).unwrap();
+        assert_eq!(&tc99m.to_string(), "Tc-99m");
+        assert!(Nuclide::try_from_id(10010000).is_ok());
+
+        // no element, mass number below Z or too large, unknown state, stray digits
+        for id in [0, 1190000000, 430420000, 433010000, 430990003, 430990010] {
+            assert!(Nuclide::try_from_id(id).is_err(), "{}", id);
+        }
+    }
+
     #[test]
     fn nuclide_to_string() {
         let i131: Nuclide = "I-131".parse().unwrap();
@@ -388,6 +902,46 @@ mod test {
         assert_eq!(t3.to_string(), "1.1 s");
     }
 
+    #[test]
+    fn halflife_format() {
+        let t: HalfLife = "30.05y".parse().unwrap();
+
+        let fmt = HalfLifeFormat::new().significant_figures(2);
+        assert_eq!(fmt.format(&t), "30 y");
+        assert_eq!(fmt.scientific(true).format(&t), "3.0e1 y");
+        assert_eq!(
+            HalfLifeFormat::new()
+                .significant_figures(4)
+                .uncertainty(0.08)
+                .format(&t),
+            "30.05 ± 0.08 y"
+        );
+        assert_eq!(
+            HalfLifeFormat::new()
+                .scientific(true)
+                .significant_figures(4)
+                .uncertainty(0.08)
+                .format(&t),
+            "(3.005 ± 0.008)e1 y"
+        );
+
+        // rounding up to the next decade
+        let t: HalfLife = "9.996y".parse().unwrap();
+        let fmt = HalfLifeFormat::new().significant_figures(3);
+        assert_eq!(fmt.format(&t), "10.0 y");
+        assert_eq!(fmt.scientific(true).format(&t), "1.00e1 y");
+        let t: HalfLife = "999.999d".parse().unwrap();
+        let fmt = HalfLifeFormat::new().significant_figures(4);
+        assert_eq!(fmt.format(&t), "1000 d");
+        assert_eq!(fmt.scientific(true).format(&t), "1.000e3 d");
+
+        let t: HalfLife = "6h".parse().unwrap();
+        assert_eq!(
+            HalfLifeFormat::new().unit(TimeUnit::Minute).format(&t),
+            "360 m"
+        );
+    }
+
     #[test]
     fn halflife_as_sec() {
         let t1: HalfLife = "1us".parse().unwrap();
@@ -48,10 +48,12 @@ pub fn nuclide() -> impl Parser<char, Nuclide, Error = Simple<char>> {
         .chain(meta)
         .map(|v| Nuclide::WithId(v.into_iter().sum())));
 
-    let with_id = text::int(10).try_map(|s: String, span| {
+    let with_id = text::int(10).try_map(|s: String, span: std::ops::Range<usize>| {
         s.parse::<u32>()
-            .map_err(|e| Simple::custom(span, format!("{}", e)))
-            .map(Nuclide::WithId)
+            .map_err(|e| Simple::custom(span.clone(), format!("{}", e)))
+            .and_then(|id| {
+                Nuclide::try_from_id(id).map_err(|e| Simple::custom(span, format!("{}", e)))
+            })
     });
 
     with_name.or(with_id)
@@ -188,6 +190,8 @@ mod test {
         let tc99m_from_id = nuclide().parse("430990001").unwrap();
         assert_eq!(tc99m_from_id, Nuclide::WithId(43_099_0001));
 
+        assert!(nuclide().parse("430990003").is_err());
+
         let tc99o = nuclide().parse("Tc-99o");
         assert!(tc99o.is_err());
 
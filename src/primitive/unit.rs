@@ -0,0 +1,250 @@
+use serde::{Deserialize, Serialize};
+
+use crate::error::Error;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ActivityUnit {
+    #[default]
+    Bq,
+    #[serde(rename = "kBq")]
+    KiloBq,
+    #[serde(rename = "MBq")]
+    MegaBq,
+    #[serde(rename = "GBq")]
+    GigaBq,
+    #[serde(rename = "TBq")]
+    TeraBq,
+    Ci,
+    #[serde(rename = "mCi")]
+    MilliCi,
+    #[serde(rename = "uCi")]
+    MicroCi,
+    #[serde(rename = "dpm")]
+    Dpm,
+}
+
+serde_plain::derive_display_from_serialize!(ActivityUnit);
+serde_plain::derive_fromstr_from_deserialize!(ActivityUnit, |e| -> Error {
+    Error::InvalidActivityUnit(e.to_string())
+});
+
+impl ActivityUnit {
+    /// Activity of one unit in Bq
+    pub fn as_bq(&self) -> f64 {
+        match self {
+            Self::Bq => 1.,
+            Self::KiloBq => 1e3,
+            Self::MegaBq => 1e6,
+            Self::GigaBq => 1e9,
+            Self::TeraBq => 1e12,
+            Self::Ci => 3.7e10,
+            Self::MilliCi => 3.7e7,
+            Self::MicroCi => 3.7e4,
+            Self::Dpm => 60_f64.recip(),
+        }
+    }
+}
+
+/// Energy of a particle or photon
+///
+/// Datasets tabulate energies in eV, keV or MeV; the explicit constructors
+/// keep the unit visible wherever a value enters or leaves a trait.
+#[derive(Debug, Default, Clone, Copy, PartialEq, PartialOrd, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Energy {
+    /// (eV)
+    ev: f64,
+}
+
+impl Energy {
+    pub const fn ev(ev: f64) -> Self {
+        Self { ev }
+    }
+
+    pub fn kev(kev: f64) -> Self {
+        Self::ev(kev * 1e3)
+    }
+
+    pub fn mev(mev: f64) -> Self {
+        Self::ev(mev * 1e6)
+    }
+
+    pub fn as_ev(&self) -> f64 {
+        self.ev
+    }
+
+    pub fn as_kev(&self) -> f64 {
+        self.ev * 1e-3
+    }
+
+    pub fn as_mev(&self) -> f64 {
+        self.ev * 1e-6
+    }
+}
+
+impl std::fmt::Display for Energy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", si_prefixed(self.ev, "eV"))
+    }
+}
+
+const SI_PREFIXES: [(f64, &str); 11] = [
+    (1e18, "E"),
+    (1e15, "P"),
+    (1e12, "T"),
+    (1e9, "G"),
+    (1e6, "M"),
+    (1e3, "k"),
+    (1., ""),
+    (1e-3, "m"),
+    (1e-6, "μ"),
+    (1e-9, "n"),
+    (1e-12, "p"),
+];
+
+/// Format a value with the SI prefix that keeps 1 <= |mantissa| < 1000, e.g. "1.50 MBq"
+pub fn si_prefixed(value: f64, unit: &str) -> String {
+    if value == 0. || !value.is_finite() {
+        return format!("{} {}", value, unit);
+    }
+
+    let (scale, prefix) = si_prefix(value, |m| format!("{:.2}", m).parse().unwrap_or(m));
+    format!("{:.2} {}{}", value / scale, prefix, unit)
+}
+
+/// Prefix of a value, the next one up if the mantissa rounds to 1000
+fn si_prefix<F: Fn(f64) -> f64>(value: f64, round: F) -> (f64, &'static str) {
+    let i = SI_PREFIXES
+        .iter()
+        .position(|(scale, _)| value.abs() >= *scale)
+        .unwrap_or(SI_PREFIXES.len() - 1);
+    if i > 0 && round(value / SI_PREFIXES[i].0).abs() >= 1000. {
+        SI_PREFIXES[i - 1]
+    } else {
+        SI_PREFIXES[i]
+    }
+}
+
+/// Mantissa and exponent of a value rounded to significant digits, the
+/// exponent being that after rounding, e.g. ("1.0000", 1) for 9.99996 at 5
+pub(crate) fn significant(value: f64, digits: usize) -> (String, i32) {
+    let scientific = format!("{:.*e}", digits.max(1) - 1, value);
+    match scientific.split_once('e') {
+        Some((mantissa, exp)) => (mantissa.to_string(), exp.parse().unwrap_or(0)),
+        None => (scientific, 0),
+    }
+}
+
+/// Formatting of the numbers written by the table and DOT exporters
+///
+/// Values are rounded to a number of significant digits, trailing zeros
+/// dropped, and written in scientific notation outside of
+/// `scientific_below <= |value| < scientific_above`. The exporters take a
+/// format in their `_with` variants and use [`NumberFormat::DEFAULT`]
+/// otherwise, as does `Display`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NumberFormat {
+    pub significant_digits: usize,
+    pub scientific_below: f64,
+    pub scientific_above: f64,
+}
+
+impl Default for NumberFormat {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+impl NumberFormat {
+    pub const DEFAULT: Self = Self {
+        significant_digits: 5,
+        scientific_below: 1e-3,
+        scientific_above: 1e6,
+    };
+
+    pub fn format(&self, value: f64) -> String {
+        if value == 0. || !value.is_finite() {
+            return value.to_string();
+        }
+
+        let digits = self.significant_digits.max(1);
+        let (mantissa, exp) = significant(value, digits);
+
+        let magnitude = 10f64.powi(exp);
+        if magnitude < self.scientific_below || magnitude >= self.scientific_above {
+            format!("{}e{}", trim_zeros(&mantissa), exp)
+        } else {
+            match usize::try_from(digits as i32 - 1 - exp) {
+                Ok(decimals) => trim_zeros(&format!("{:.*}", decimals, value)).to_string(),
+                // rounded to tens or above
+                Err(_) => format!(
+                    "{:.0}",
+                    mantissa.parse::<f64>().unwrap_or(value) * magnitude
+                ),
+            }
+        }
+    }
+
+    /// Value with the SI prefix that keeps 1 <= |mantissa| < 1000, e.g. "1.5 MBq"
+    pub fn si_prefixed(&self, value: f64, unit: &str) -> String {
+        if value == 0. || !value.is_finite() {
+            return format!("{} {}", value, unit);
+        }
+
+        let (scale, prefix) = si_prefix(value, |m| self.format(m).parse().unwrap_or(m));
+        format!("{} {}{}", self.format(value / scale), prefix, unit)
+    }
+}
+
+fn trim_zeros(s: &str) -> &str {
+    if s.contains('.') {
+        s.trim_end_matches('0').trim_end_matches('.')
+    } else {
+        s
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn prefixed() {
+        assert_eq!(si_prefixed(1.5e6, "Bq"), "1.50 MBq");
+        assert_eq!(si_prefixed(999., "Bq"), "999.00 Bq");
+        assert_eq!(si_prefixed(2.5e-5, "Sv"), "25.00 μSv");
+        assert_eq!(si_prefixed(0., "Bq"), "0 Bq");
+        // rounding up to the next prefix
+        assert_eq!(si_prefixed(999.996, "Bq"), "1.00 kBq");
+        assert_eq!(si_prefixed(999.994, "Bq"), "999.99 Bq");
+        assert_eq!(si_prefixed(-999_999.9, "Bq"), "-1.00 MBq");
+    }
+
+    #[test]
+    fn number_format() {
+        let format = NumberFormat::default();
+        assert_eq!(format.format(0.9998), "0.9998");
+        assert_eq!(format.format(1.0), "1");
+        assert_eq!(format.format(2.918_463e-6), "2.9185e-6");
+        assert_eq!(format.format(9.999_996), "10");
+        assert_eq!(format.format(-1.5e7), "-1.5e7");
+        assert_eq!(format.si_prefixed(1.5e6, "Bq"), "1.5 MBq");
+        assert_eq!(format.si_prefixed(999_999.9, "Bq"), "1 MBq");
+        assert_eq!(format.si_prefixed(999.994, "Bq"), "999.99 Bq");
+        assert_eq!(format.format(999_999.7), "1e6");
+
+        let format = NumberFormat {
+            significant_digits: 2,
+            ..format
+        };
+        assert_eq!(format.format(123.456), "120");
+    }
+
+    #[test]
+    fn energy() {
+        assert_eq!(Energy::mev(1.), Energy::kev(1e3));
+        assert!((Energy::kev(661.657).as_mev() - 0.661657).abs() < 1e-12);
+        assert!(Energy::kev(511.) < Energy::mev(1.));
+        assert_eq!(Energy::mev(1.5).to_string(), "1.50 MeV");
+    }
+}
@@ -0,0 +1,181 @@
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::time::Instant;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::error::Error;
+use crate::primitive::Nuclide;
+
+/// Identity of the dataset a calculation was based on
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DatasetIdentity {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<PathBuf>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub checksum: Option<String>,
+}
+
+impl DatasetIdentity {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            path: None,
+            version: None,
+            checksum: None,
+        }
+    }
+}
+
+pub trait DatasetInfo {
+    fn dataset_identity(&self) -> DatasetIdentity;
+}
+
+/// Record of how a result was computed
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Provenance {
+    pub dataset: DatasetIdentity,
+    /// Solver type
+    pub solver: String,
+    /// Solver options (cutoffs, tolerances, ...)
+    pub options: BTreeMap<String, String>,
+    /// Version of this crate
+    pub crate_version: String,
+    /// Time the calculation was started
+    pub started_at: DateTime<Utc>,
+    /// Wall time of the calculation (s)
+    pub elapsed: f64,
+}
+
+impl Provenance {
+    pub(crate) fn start(dataset: DatasetIdentity, solver: &str) -> ProvenanceRecorder {
+        ProvenanceRecorder {
+            provenance: Self {
+                dataset,
+                solver: solver.to_string(),
+                options: BTreeMap::new(),
+                crate_version: env!("CARGO_PKG_VERSION").to_string(),
+                started_at: Utc::now(),
+                elapsed: 0.,
+            },
+            instant: Instant::now(),
+        }
+    }
+}
+
+pub(crate) struct ProvenanceRecorder {
+    provenance: Provenance,
+    instant: Instant,
+}
+
+impl ProvenanceRecorder {
+    /// Record an option of the calculation
+    pub fn option<T: ToString>(mut self, name: &str, value: T) -> Self {
+        self.provenance
+            .options
+            .insert(name.to_string(), value.to_string());
+        self
+    }
+
+    pub fn finish(mut self) -> Provenance {
+        self.provenance.elapsed = self.instant.elapsed().as_secs_f64();
+        self.provenance
+    }
+}
+
+/// Result with the provenance record of its calculation
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WithProvenance<T> {
+    pub value: T,
+    pub provenance: Provenance,
+}
+
+/// Why a nuclide was left out of a calculation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SkipReason {
+    /// No half-life in the decay data
+    NoDecayConstant,
+    /// No dose coefficient for the pathway
+    NoDoseCoefficient,
+    /// Dose coefficients listed, none of them for the requested compound or
+    /// absorption type
+    NoMatchingDoseCoefficient,
+    /// No air kerma-rate constant
+    NoAirKermaConstant,
+    /// Not listed by the photon emission data
+    NoPhotonLines,
+    /// Not listed by the electron emission data
+    NoElectronLines,
+    /// Spontaneous fission products, which have no data of their own
+    FissionProducts,
+}
+
+/// Nuclide left out of a calculation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Warning {
+    pub nuclide: Nuclide,
+    pub reason: SkipReason,
+}
+
+/// Nuclides left out of a calculation, to audit the completeness of a result
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Warnings(Vec<Warning>);
+
+impl Warnings {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, nuclide: Nuclide, reason: SkipReason) {
+        self.0.push(Warning { nuclide, reason });
+    }
+
+    pub fn extend(&mut self, other: Warnings) {
+        self.0.extend(other.0);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Warning> {
+        self.0.iter()
+    }
+
+    /// Data of a nuclide looked up with `res`, `None` with a warning for
+    /// fission products or a nuclide not listed by the data
+    pub(crate) fn lookup<T>(
+        &mut self,
+        nuclide: Nuclide,
+        reason: SkipReason,
+        res: Result<T, Error>,
+    ) -> Result<Option<T>, Error> {
+        match (nuclide, res) {
+            (Nuclide::FissionProducts, _) => self.push(nuclide, SkipReason::FissionProducts),
+            (_, Err(Error::InvalidNuclide(_))) => self.push(nuclide, reason),
+            (_, res) => return res.map(Some),
+        }
+
+        Ok(None)
+    }
+
+    /// Nuclides left out for a reason
+    pub fn nuclides(&self, reason: SkipReason) -> impl Iterator<Item = Nuclide> + '_ {
+        self.0
+            .iter()
+            .filter(move |w| w.reason == reason)
+            .map(|w| w.nuclide)
+    }
+}
+
+/// Result with the nuclides left out of its calculation
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WithWarnings<T> {
+    pub value: T,
+    pub warnings: Warnings,
+}
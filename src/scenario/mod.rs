@@ -0,0 +1,699 @@
+//! Dose assessments declared in TOML
+//!
+//! A scenario gives an inventory, the times after its reference time at which
+//! it is assessed and the receptors exposed to it:
+//!
+//! ```toml
+//! decay_times = [0.0, 86400.0]
+//! age_group = "Adult"
+//!
+//! [inventory]
+//! version = 1
+//! nuclides = [{ nuclide = "Cs-137", activity = 1.0, unit = "GBq" }]
+//!
+//! [[receptors]]
+//! name = "worker"
+//! intakes = [{ pathway = "Inhalation", fraction = 1e-6, absorption_type = "F" }]
+//!
+//! [receptors.external]
+//! distance = 2.0
+//! duration = 3600.0
+//! shielding = [{ formula = "Pb", density = 11.35, thickness = 0.5 }]
+//!
+//! [[receptors.pathways]]
+//! model = "groundshine"
+//! fraction = 1e-4
+//! ```
+//!
+//! [`Scenario::run`] decays the inventory to each time and evaluates every
+//! receptor with the datasets of a [`Toolkit`]. Pathways are evaluated by the
+//! [`PathwayCalculator`] registered under their model name; the built-in
+//! [`Submersion`], [`Groundshine`] and [`Ingestion`] calculators are
+//! registered like site-specific ones.
+
+mod pathway;
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use crate::dataset::{AttenuationSource, DcfSource, DecaySource, PhotonSource};
+use crate::decaychain::{BatemanDecaySolver, Inventory};
+use crate::dose::{effective_dose, DoseBreakdown, DoseOptions, OrganDoseMap};
+use crate::error::Error;
+use crate::primitive::attr::MeanFreePath;
+use crate::primitive::{
+    AgeGroup, Energy, Material, MaterialBuilder, Pathway, PulmonaryAbsorptionType,
+};
+use crate::provenance::{Provenance, SkipReason, Warnings, WithProvenance, WithWarnings};
+
+pub use pathway::{
+    Groundshine, Ingestion, PathwayCalculator, PathwayInput, PathwayRegistry, Submersion,
+};
+
+/// Datasets a scenario is assessed with
+#[derive(Clone)]
+pub struct Toolkit {
+    decay: Arc<dyn DecaySource>,
+    dcf: Option<Arc<dyn DcfSource>>,
+    photons: Option<Arc<dyn PhotonSource>>,
+    attenuation: Option<Arc<dyn AttenuationSource>>,
+    pathways: PathwayRegistry,
+}
+
+impl Toolkit {
+    pub fn new(decay: Arc<dyn DecaySource>) -> Self {
+        Self {
+            decay,
+            dcf: None,
+            photons: None,
+            attenuation: None,
+            pathways: PathwayRegistry::new(),
+        }
+    }
+
+    /// Dose coefficients, needed by intakes
+    pub fn with_dcf(mut self, dcf: Arc<dyn DcfSource>) -> Self {
+        self.dcf = Some(dcf);
+        self
+    }
+
+    /// Air kerma-rate constants and photon lines, needed by external exposure
+    pub fn with_photons(mut self, photons: Arc<dyn PhotonSource>) -> Self {
+        self.photons = Some(photons);
+        self
+    }
+
+    /// Attenuation coefficients, needed by shielding
+    pub fn with_attenuation(mut self, attenuation: Arc<dyn AttenuationSource>) -> Self {
+        self.attenuation = Some(attenuation);
+        self
+    }
+
+    /// Pathway calculators, needed by pathways
+    pub fn with_pathways(mut self, pathways: PathwayRegistry) -> Self {
+        self.pathways = pathways;
+        self
+    }
+}
+
+fn missing(dataset: &str) -> Error {
+    anyhow::anyhow!("the toolkit has no {}", dataset).into()
+}
+
+fn adult() -> AgeGroup {
+    AgeGroup::Adult
+}
+
+fn reference_time() -> Vec<f64> {
+    vec![0.]
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Scenario {
+    pub inventory: Inventory,
+    /// Times after the reference time of the inventory (s)
+    #[serde(default = "reference_time")]
+    pub decay_times: Vec<f64>,
+    #[serde(default = "adult")]
+    pub age_group: AgeGroup,
+    /// Half-life (s) below which progeny missing from the inventory are
+    /// added in secular equilibrium with their parents for intakes
+    pub equilibrium_half_life: Option<f64>,
+    pub receptors: Vec<Receptor>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Receptor {
+    pub name: String,
+    #[serde(default)]
+    pub intakes: Vec<Intake>,
+    pub external: Option<External>,
+    #[serde(default)]
+    pub pathways: Vec<PathwayExposure>,
+}
+
+/// Intake of a fraction of the inventory
+#[derive(Debug, Clone, Deserialize)]
+pub struct Intake {
+    /// `Ingestion` or `Inhalation`
+    pub pathway: Pathway,
+    pub fraction: f64,
+    /// Chemical compound, see [`DoseOptions`]
+    pub compound: Option<String>,
+    /// Absorption type, see [`DoseOptions`]
+    pub absorption_type: Option<PulmonaryAbsorptionType>,
+}
+
+/// Exposure to the inventory as an unshielded or slab-shielded point source
+#[derive(Debug, Clone, Deserialize)]
+pub struct External {
+    /// (m)
+    pub distance: f64,
+    /// Exposure time (s), short compared with the half-lives
+    pub duration: f64,
+    #[serde(default)]
+    pub shielding: Vec<Shield>,
+}
+
+fn whole() -> f64 {
+    1.
+}
+
+/// Exposure by a registered pathway calculator to a fraction of the inventory
+#[derive(Debug, Clone, Deserialize)]
+pub struct PathwayExposure {
+    /// Name of the calculator in the [`PathwayRegistry`] of the toolkit
+    pub model: String,
+    /// Concentration per activity of the inventory, in the unit of the model
+    #[serde(default = "whole")]
+    pub fraction: f64,
+    #[serde(default)]
+    pub parameters: BTreeMap<String, f64>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Shield {
+    /// Chemical formula, e.g. "Pb" or "H2O"
+    pub formula: String,
+    /// (g/cm3)
+    pub density: f64,
+    /// (cm)
+    pub thickness: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ScenarioResults {
+    pub steps: Vec<StepResults>,
+}
+
+/// Results at one decay time
+#[derive(Debug, Clone, Serialize)]
+pub struct StepResults {
+    /// (s)
+    pub time: f64,
+    pub inventory: Inventory,
+    pub receptors: Vec<ReceptorResults>,
+    /// Nuclides of the scenario inventory left out of the decay
+    pub warnings: Warnings,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ReceptorResults {
+    pub name: String,
+    pub intakes: Vec<IntakeResults>,
+    /// Air kerma of the external exposure (Gy)
+    pub air_kerma: Option<f64>,
+    pub pathways: Vec<PathwayResults>,
+    /// Nuclides left out of the intakes, the external exposure and the pathways
+    pub warnings: Warnings,
+}
+
+impl ReceptorResults {
+    /// Committed effective dose of all intakes (Sv)
+    pub fn committed_dose(&self) -> f64 {
+        self.intakes
+            .iter()
+            .filter_map(|i| i.dose.total.effective())
+            .sum()
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct IntakeResults {
+    pub pathway: Pathway,
+    pub dose: DoseBreakdown,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PathwayResults {
+    pub model: String,
+    /// Dose rate of each organ (Sv/s), the provenance options being the parameters
+    pub dose_rate: WithProvenance<OrganDoseMap>,
+}
+
+impl Scenario {
+    pub fn from_toml(s: &str) -> Result<Self, Error> {
+        Ok(toml::from_str(s)?)
+    }
+
+    pub fn run(&self, toolkit: &Toolkit) -> Result<ScenarioResults, Error> {
+        let solver = BatemanDecaySolver::new(toolkit.decay.clone());
+        let shields = self.shields(toolkit)?;
+
+        let steps = self
+            .decay_times
+            .iter()
+            .map(|&time| {
+                let WithWarnings {
+                    value: inventory,
+                    warnings,
+                } = solver.decay_with_warnings(&self.inventory, time);
+                let receptors = self
+                    .receptors
+                    .iter()
+                    .zip(&shields)
+                    .map(|(receptor, shields)| {
+                        self.receptor(toolkit, receptor, shields, &inventory)
+                    })
+                    .collect::<Result<_, Error>>()?;
+
+                Ok(StepResults {
+                    time,
+                    inventory,
+                    receptors,
+                    warnings,
+                })
+            })
+            .collect::<Result<_, Error>>()?;
+
+        Ok(ScenarioResults { steps })
+    }
+
+    /// Shielding materials of each receptor
+    fn shields(&self, toolkit: &Toolkit) -> Result<Vec<Vec<(Material, f64)>>, Error> {
+        self.receptors
+            .iter()
+            .map(|receptor| {
+                let shielding = receptor.external.iter().flat_map(|e| &e.shielding);
+                shielding
+                    .map(|shield| {
+                        let data = toolkit
+                            .attenuation
+                            .clone()
+                            .ok_or_else(|| missing("attenuation coefficients"))?;
+                        let material = MaterialBuilder::new(data)
+                            .formula(&shield.formula)?
+                            .density(shield.density)
+                            .weight(1.)
+                            .build()?;
+                        Ok((material, shield.thickness))
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn receptor(
+        &self,
+        toolkit: &Toolkit,
+        receptor: &Receptor,
+        shields: &[(Material, f64)],
+        inventory: &Inventory,
+    ) -> Result<ReceptorResults, Error> {
+        let mut warnings = Warnings::new();
+        let mut intakes = vec![];
+        for intake in &receptor.intakes {
+            let dcf = toolkit
+                .dcf
+                .as_deref()
+                .ok_or_else(|| missing("dose coefficients"))?;
+            let options = DoseOptions {
+                compound: intake.compound.clone(),
+                absorption_type: intake.absorption_type,
+                progeny: self
+                    .equilibrium_half_life
+                    .map(|t| (toolkit.decay.clone(), t)),
+                ..Default::default()
+            };
+
+            let mut taken = Inventory::new();
+            for (&nuclide, &activity) in inventory.iter() {
+                taken.add(nuclide, activity * intake.fraction);
+            }
+            let dose = effective_dose(&taken, intake.pathway, self.age_group, dcf, &options)?;
+            warnings.extend(dose.warnings);
+            intakes.push(IntakeResults {
+                pathway: intake.pathway,
+                dose: dose.value,
+            });
+        }
+
+        let air_kerma = match &receptor.external {
+            Some(external) => Some(air_kerma(
+                toolkit,
+                external,
+                shields,
+                inventory,
+                &mut warnings,
+            )?),
+            None => None,
+        };
+
+        let pathways = receptor
+            .pathways
+            .iter()
+            .map(|exposure| self.pathway(toolkit, exposure, inventory, &mut warnings))
+            .collect::<Result<_, Error>>()?;
+
+        Ok(ReceptorResults {
+            name: receptor.name.clone(),
+            intakes,
+            air_kerma,
+            pathways,
+            warnings,
+        })
+    }
+
+    fn pathway(
+        &self,
+        toolkit: &Toolkit,
+        exposure: &PathwayExposure,
+        inventory: &Inventory,
+        warnings: &mut Warnings,
+    ) -> Result<PathwayResults, Error> {
+        let calculator = toolkit.pathways.get(&exposure.model)?;
+
+        let mut concentrations = Inventory::new();
+        for (&nuclide, &activity) in inventory.iter() {
+            concentrations.add(nuclide, activity * exposure.fraction);
+        }
+        let input = PathwayInput {
+            concentrations: &concentrations,
+            age_group: self.age_group,
+            parameters: &exposure.parameters,
+        };
+
+        let recorder = exposure.parameters.iter().fold(
+            Provenance::start(calculator.dataset_identity(), &exposure.model),
+            |recorder, (name, value)| recorder.option(name, value),
+        );
+        let WithWarnings {
+            value,
+            warnings: skipped,
+        } = calculator.dose_rate(&input)?;
+        warnings.extend(skipped);
+        let provenance = recorder.finish();
+
+        Ok(PathwayResults {
+            model: exposure.model.clone(),
+            dose_rate: WithProvenance { value, provenance },
+        })
+    }
+}
+
+/// Air kerma (Gy) of a point source behind slab shields
+///
+/// Each nuclide is attenuated by the uncollided transmission of its photon
+/// lines weighted by energy, without buildup; lines below the energy range
+/// of the attenuation data are taken as absorbed.
+fn air_kerma(
+    toolkit: &Toolkit,
+    external: &External,
+    shields: &[(Material, f64)],
+    inventory: &Inventory,
+    warnings: &mut Warnings,
+) -> Result<f64, Error> {
+    let photons = toolkit
+        .photons
+        .as_deref()
+        .ok_or_else(|| missing("photon data"))?;
+
+    let mut rate = 0.;
+    for (&nuclide, &activity) in inventory.iter() {
+        let constant = match photons.air_kerma_constant(nuclide) {
+            Ok(constant) => constant,
+            Err(Error::InvalidNuclide(_)) => {
+                warnings.push(nuclide, SkipReason::NoAirKermaConstant);
+                continue;
+            }
+            Err(e) => return Err(e),
+        };
+        let transmission = match toolkit.attenuation.as_deref() {
+            Some(data) if !shields.is_empty() => {
+                transmission(data, &photons.photon_lines(nuclide)?, shields)?
+            }
+            _ => 1.,
+        };
+        rate += activity * constant * transmission;
+    }
+
+    Ok(rate / external.distance.powi(2) * external.duration)
+}
+
+fn transmission(
+    data: &dyn AttenuationSource,
+    lines: &[(Energy, f64)],
+    shields: &[(Material, f64)],
+) -> Result<f64, Error> {
+    let (mut emitted, mut transmitted) = (0., 0.);
+    for &(energy, r#yield) in lines {
+        let mut t = 1.;
+        for (material, thickness) in shields {
+            t *= match data.mfp(material, energy) {
+                Ok(mfp) => (-thickness / mfp).exp(),
+                Err(Error::InvalidEnergy(_)) => 0.,
+                Err(e) => return Err(e),
+            };
+        }
+        emitted += r#yield * energy.as_mev();
+        transmitted += r#yield * energy.as_mev() * t;
+    }
+
+    Ok(if emitted > 0. {
+        transmitted / emitted
+    } else {
+        1.
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::fixtures::FixtureDecayData;
+    use crate::primitive::attr::{
+        AtomicMass, DcfIngestion, DcfInhalation, MassAttenuationCoefficient, NuclideAirKerma,
+        NuclidePhotonLines,
+    };
+    use crate::primitive::{DcfQueryResult, DcfValue, Nuclide, Organ, Symbol};
+
+    /// Round test values, not evaluated data
+    struct TestData;
+
+    impl DcfIngestion for TestData {
+        fn dcf_ingestion(
+            &self,
+            n: Nuclide,
+            _: AgeGroup,
+            _: Organ,
+        ) -> Result<DcfQueryResult, Error> {
+            let values = match n.to_string().as_str() {
+                "Cs-137" => vec![DcfValue {
+                    value: 1e-8,
+                    unit: "Sv/Bq".to_string(),
+                    attr: None,
+                    provenance: None,
+                }],
+                _ => vec![],
+            };
+            Ok(DcfQueryResult::new("test", values))
+        }
+    }
+
+    impl DcfInhalation for TestData {
+        fn dcf_inhalation(
+            &self,
+            _: Nuclide,
+            _: AgeGroup,
+            _: Organ,
+        ) -> Result<DcfQueryResult, Error> {
+            Ok(DcfQueryResult::new("test", vec![]))
+        }
+    }
+
+    impl NuclidePhotonLines for TestData {
+        fn photon_lines(&self, n: Nuclide) -> Result<Vec<(Energy, f64)>, Error> {
+            Ok(match n.to_string().as_str() {
+                "Ba-137m" => vec![(Energy::mev(0.5), 1.)],
+                _ => vec![],
+            })
+        }
+    }
+
+    impl NuclideAirKerma for TestData {
+        fn air_kerma_constant(&self, n: Nuclide) -> Result<f64, Error> {
+            match n.to_string().as_str() {
+                "Ba-137m" => Ok(1e-17),
+                _ => Err(Error::InvalidNuclide(n.to_string())),
+            }
+        }
+    }
+
+    impl AtomicMass for TestData {
+        fn atomic_mass(&self, _: Symbol) -> Result<f64, Error> {
+            Ok(200.)
+        }
+    }
+
+    impl MassAttenuationCoefficient for TestData {
+        fn mass_attenuation_coefficient(&self, _: &Material, _: Energy) -> Result<f64, Error> {
+            Ok(0.1)
+        }
+    }
+
+    const SCENARIO: &str = r#"
+        decay_times = [0.0, 86400.0]
+
+        [inventory]
+        version = 1
+        nuclides = [{ nuclide = "Cs-137", activity = 1.0, unit = "GBq" }]
+
+        [[receptors]]
+        name = "worker"
+        intakes = [{ pathway = "Ingestion", fraction = 1e-6 }]
+
+        [receptors.external]
+        distance = 2.0
+        duration = 3600.0
+        shielding = [{ formula = "Pb", density = 10.0, thickness = 0.5 }]
+    "#;
+
+    #[test]
+    fn run_scenario() {
+        let scenario = Scenario::from_toml(SCENARIO).unwrap();
+        assert_eq!(scenario.age_group, AgeGroup::Adult);
+
+        let data = Arc::new(TestData);
+        let toolkit = Toolkit::new(Arc::new(FixtureDecayData::new()))
+            .with_dcf(data.clone())
+            .with_photons(data.clone())
+            .with_attenuation(data);
+        let results = scenario.run(&toolkit).unwrap();
+        assert_eq!(results.steps.len(), 2);
+
+        let worker = &results.steps[0].receptors[0];
+        assert_eq!(worker.name, "worker");
+        assert!((worker.committed_dose() - 1e-5).abs() < 1e-15);
+        assert_eq!(worker.air_kerma, Some(0.));
+        // Cs-137 has no air kerma-rate constant of its own
+        assert_eq!(
+            worker
+                .warnings
+                .nuclides(SkipReason::NoAirKermaConstant)
+                .collect::<Vec<_>>(),
+            vec!["Cs-137".parse().unwrap()]
+        );
+        assert!(results.steps[0].warnings.is_empty());
+
+        // Ba-137m in equilibrium behind 5 g/cm2 of lead
+        let step = &results.steps[1];
+        let ba137m = step.inventory[&"Ba-137m".parse().unwrap()];
+        let expected = ba137m * 1e-17 * (-0.5f64).exp() / 4. * 3600.;
+        let air_kerma = step.receptors[0].air_kerma.unwrap();
+        assert!((air_kerma / expected - 1.).abs() < 1e-12);
+
+        let toolkit = Toolkit::new(Arc::new(FixtureDecayData::new()));
+        assert!(scenario.run(&toolkit).is_err());
+    }
+
+    /// Site-specific model with a made-up dose rate per activity
+    struct Pond;
+
+    impl PathwayCalculator for Pond {
+        fn dose_rate(&self, input: &PathwayInput) -> Result<WithWarnings<OrganDoseMap>, Error> {
+            let rate = input.concentrations.values().sum::<f64>() * input.parameter("factor")?;
+            Ok(WithWarnings {
+                value: [(Organ::EffectiveDose, rate)].into_iter().collect(),
+                warnings: Warnings::new(),
+            })
+        }
+
+        fn dataset_identity(&self) -> crate::provenance::DatasetIdentity {
+            crate::provenance::DatasetIdentity::new("pond")
+        }
+    }
+
+    const PATHWAYS: &str = r#"
+        [inventory]
+        version = 1
+        nuclides = [{ nuclide = "Cs-137", activity = 1.0, unit = "Bq" }]
+
+        [[receptors]]
+        name = "angler"
+        pathways = [
+            { model = "fish", fraction = 0.5, parameters = { intake_rate = 2.0 } },
+            { model = "pond", parameters = { factor = 3.0 } },
+        ]
+    "#;
+
+    #[test]
+    fn run_pathways() {
+        let scenario = Scenario::from_toml(PATHWAYS).unwrap();
+
+        let mut pathways = PathwayRegistry::new();
+        pathways.register("fish", Arc::new(Ingestion::new(Arc::new(TestData), "test")));
+        assert!(pathways.register("pond", Arc::new(Pond)).is_none());
+        assert_eq!(pathways.names().collect::<Vec<_>>(), vec!["fish", "pond"]);
+
+        let toolkit = Toolkit::new(Arc::new(FixtureDecayData::new())).with_pathways(pathways);
+        let results = scenario.run(&toolkit).unwrap();
+        let angler = &results.steps[0].receptors[0];
+
+        let fish = &angler.pathways[0].dose_rate;
+        assert!((fish.value.effective().unwrap() - 1e-8).abs() < 1e-20);
+        assert_eq!(fish.provenance.dataset.name, "test");
+        assert_eq!(fish.provenance.solver, "fish");
+        assert_eq!(fish.provenance.options["intake_rate"], "2");
+
+        let pond = &angler.pathways[1].dose_rate;
+        assert_eq!(pond.value.effective(), Some(3.));
+        assert_eq!(pond.provenance.dataset.name, "pond");
+
+        let toolkit = Toolkit::new(Arc::new(FixtureDecayData::new()));
+        assert!(matches!(
+            scenario.run(&toolkit),
+            Err(Error::UnknownPathway(_))
+        ));
+    }
+
+    #[test]
+    fn pathway_warnings() {
+        let scenario = Scenario::from_toml(
+            r#"
+            [inventory]
+            version = 1
+            nuclides = [
+                { nuclide = "Cs-137", activity = 1.0 },
+                { nuclide = "Sr-90", activity = 1.0 },
+            ]
+
+            [[receptors]]
+            name = "angler"
+            pathways = [{ model = "fish", parameters = { intake_rate = 1.0 } }]
+            "#,
+        )
+        .unwrap();
+
+        let mut pathways = PathwayRegistry::new();
+        pathways.register("fish", Arc::new(Ingestion::new(Arc::new(TestData), "test")));
+        let toolkit = Toolkit::new(Arc::new(FixtureDecayData::new())).with_pathways(pathways);
+        let results = scenario.run(&toolkit).unwrap();
+        let angler = &results.steps[0].receptors[0];
+
+        let skipped = angler
+            .warnings
+            .nuclides(SkipReason::NoDoseCoefficient)
+            .collect::<Vec<Nuclide>>();
+        assert!(skipped.contains(&"Sr-90".parse().unwrap()));
+        assert!(!skipped.contains(&"Cs-137".parse().unwrap()));
+        assert_eq!(angler.pathways[0].dose_rate.value.effective(), Some(1e-8));
+
+        let mut concentrations = Inventory::new();
+        concentrations.add(Nuclide::FissionProducts, 1.);
+        let input = PathwayInput {
+            concentrations: &concentrations,
+            age_group: AgeGroup::Adult,
+            parameters: &[("intake_rate".to_string(), 1.)].into_iter().collect(),
+        };
+        let rate = Ingestion::new(Arc::new(TestData), "test")
+            .dose_rate(&input)
+            .unwrap();
+        assert_eq!(
+            rate.warnings
+                .nuclides(SkipReason::FissionProducts)
+                .collect::<Vec<_>>(),
+            vec![Nuclide::FissionProducts]
+        );
+    }
+}
@@ -0,0 +1,228 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use crate::decaychain::Inventory;
+use crate::dose::{submersion_dose_rate, FiniteCloud, OrganDoseMap};
+use crate::error::Error;
+use crate::primitive::attr::{DcfAirSubmersion, DcfGroundSurface, DcfIngestion};
+use crate::primitive::{AgeGroup, Nuclide, Organ};
+use crate::provenance::{DatasetIdentity, SkipReason, Warnings, WithWarnings};
+
+/// Concentrations and receptor a pathway is evaluated for
+#[derive(Debug, Clone, Copy)]
+pub struct PathwayInput<'a> {
+    /// Inventory of the scenario times the `fraction` of the pathway, in the
+    /// unit the calculator expects (e.g. Bq/m3 of air, Bq/m2 of ground)
+    pub concentrations: &'a Inventory,
+    pub age_group: AgeGroup,
+    /// Model parameters given with the pathway
+    pub parameters: &'a BTreeMap<String, f64>,
+}
+
+impl PathwayInput<'_> {
+    /// Value of a required parameter
+    pub fn parameter(&self, name: &str) -> Result<f64, Error> {
+        self.parameters
+            .get(name)
+            .copied()
+            .ok_or_else(|| anyhow::anyhow!("missing pathway parameter: {}", name).into())
+    }
+}
+
+/// Dose rate model of an exposure pathway
+///
+/// Scenario receptors select calculators by the name they are registered
+/// under in a [`PathwayRegistry`]; results are recorded with the dataset
+/// identity of the calculator and the parameters as provenance.
+pub trait PathwayCalculator: Send + Sync {
+    /// Dose rate of each organ (Sv/s), at least the effective dose rate, and
+    /// the nuclides left out of it
+    fn dose_rate(&self, input: &PathwayInput) -> Result<WithWarnings<OrganDoseMap>, Error>;
+
+    /// Dataset the model is based on
+    fn dataset_identity(&self) -> DatasetIdentity;
+}
+
+/// Pathway calculators by name
+///
+/// Registering a name again replaces the calculator, which is returned.
+#[derive(Clone, Default)]
+pub struct PathwayRegistry {
+    calculators: BTreeMap<String, Arc<dyn PathwayCalculator>>,
+}
+
+impl PathwayRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn register(
+        &mut self,
+        name: &str,
+        calculator: Arc<dyn PathwayCalculator>,
+    ) -> Option<Arc<dyn PathwayCalculator>> {
+        self.calculators.insert(name.to_string(), calculator)
+    }
+
+    pub fn get(&self, name: &str) -> Result<Arc<dyn PathwayCalculator>, Error> {
+        self.calculators
+            .get(name)
+            .cloned()
+            .ok_or_else(|| Error::UnknownPathway(name.to_string()))
+    }
+
+    /// Names of the calculators
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.calculators.keys().map(String::as_str)
+    }
+}
+
+/// Submersion in a cloud of air concentrations (Bq/m3), coefficients in
+/// Sv/hr per Bq/m3 (FGR 12)
+///
+/// Parameters `cloud_radius` (m) and `attenuation` (1/m) together select a
+/// [`FiniteCloud`]; the cloud is semi-infinite otherwise.
+pub struct Submersion<P: ?Sized> {
+    provider: Arc<P>,
+    dataset: DatasetIdentity,
+}
+
+impl<P: ?Sized> Submersion<P> {
+    pub fn new(provider: Arc<P>, dataset: &str) -> Self {
+        Self {
+            provider,
+            dataset: DatasetIdentity::new(dataset),
+        }
+    }
+}
+
+impl<P> PathwayCalculator for Submersion<P>
+where
+    P: DcfAirSubmersion + Send + Sync + ?Sized,
+{
+    fn dose_rate(&self, input: &PathwayInput) -> Result<WithWarnings<OrganDoseMap>, Error> {
+        let cloud = match (
+            input.parameters.get("cloud_radius"),
+            input.parameters.get("attenuation"),
+        ) {
+            (Some(&radius), Some(&attenuation)) => Some(FiniteCloud::new(radius, attenuation)),
+            _ => None,
+        };
+        let concentrations = input.concentrations.iter().map(|(&n, &c)| (n, c));
+        let rate = submersion_dose_rate(&*self.provider, concentrations, cloud)?;
+        Ok(WithWarnings {
+            value: [(Organ::EffectiveDose, rate.value / 3600.)]
+                .into_iter()
+                .collect(),
+            warnings: rate.warnings,
+        })
+    }
+
+    fn dataset_identity(&self) -> DatasetIdentity {
+        self.dataset.clone()
+    }
+}
+
+/// External exposure to a ground deposition (Bq/m2), coefficients in
+/// Sv/hr per Bq/m2 (FGR 12)
+pub struct Groundshine<P: ?Sized> {
+    provider: Arc<P>,
+    dataset: DatasetIdentity,
+}
+
+impl<P: ?Sized> Groundshine<P> {
+    pub fn new(provider: Arc<P>, dataset: &str) -> Self {
+        Self {
+            provider,
+            dataset: DatasetIdentity::new(dataset),
+        }
+    }
+}
+
+impl<P> PathwayCalculator for Groundshine<P>
+where
+    P: DcfGroundSurface + Send + Sync + ?Sized,
+{
+    fn dose_rate(&self, input: &PathwayInput) -> Result<WithWarnings<OrganDoseMap>, Error> {
+        let mut warnings = Warnings::new();
+        let mut res = 0.;
+        for (&nuclide, &deposition) in input.concentrations.iter() {
+            if nuclide == Nuclide::FissionProducts {
+                warnings.push(nuclide, SkipReason::FissionProducts);
+                continue;
+            }
+            match self
+                .provider
+                .dcf_ground_surface(nuclide, Organ::EffectiveDose)?
+                .first()
+            {
+                Some(dcf) => res += dcf.value * deposition,
+                None => warnings.push(nuclide, SkipReason::NoDoseCoefficient),
+            }
+        }
+
+        Ok(WithWarnings {
+            value: [(Organ::EffectiveDose, res / 3600.)].into_iter().collect(),
+            warnings,
+        })
+    }
+
+    fn dataset_identity(&self) -> DatasetIdentity {
+        self.dataset.clone()
+    }
+}
+
+/// Committed effective dose per unit time of consuming foodstuff or water
+/// of the given concentrations (Bq/kg)
+///
+/// Requires the parameter `intake_rate` (kg/s).
+pub struct Ingestion<P: ?Sized> {
+    provider: Arc<P>,
+    dataset: DatasetIdentity,
+}
+
+impl<P: ?Sized> Ingestion<P> {
+    pub fn new(provider: Arc<P>, dataset: &str) -> Self {
+        Self {
+            provider,
+            dataset: DatasetIdentity::new(dataset),
+        }
+    }
+}
+
+impl<P> PathwayCalculator for Ingestion<P>
+where
+    P: DcfIngestion + Send + Sync + ?Sized,
+{
+    fn dose_rate(&self, input: &PathwayInput) -> Result<WithWarnings<OrganDoseMap>, Error> {
+        let intake_rate = input.parameter("intake_rate")?;
+
+        let mut warnings = Warnings::new();
+        let mut res = 0.;
+        for (&nuclide, &concentration) in input.concentrations.iter() {
+            if nuclide == Nuclide::FissionProducts {
+                warnings.push(nuclide, SkipReason::FissionProducts);
+                continue;
+            }
+            match self
+                .provider
+                .dcf_ingestion(nuclide, input.age_group, Organ::EffectiveDose)?
+                .max()
+            {
+                Some(dcf) => res += dcf.value * concentration,
+                None => warnings.push(nuclide, SkipReason::NoDoseCoefficient),
+            }
+        }
+
+        Ok(WithWarnings {
+            value: [(Organ::EffectiveDose, res * intake_rate)]
+                .into_iter()
+                .collect(),
+            warnings,
+        })
+    }
+
+    fn dataset_identity(&self) -> DatasetIdentity {
+        self.dataset.clone()
+    }
+}
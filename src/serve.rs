@@ -0,0 +1,326 @@
+//! HTTP service for decay and dose coefficient queries
+//!
+//! | Endpoint | Response |
+//! |---|---|
+//! | `GET /nuclides/{nuclide}` | half-life and progeny (JSON) |
+//! | `POST /decay` | `{"inventory": <inventory document>, "time": <s>}` decayed inventory document (JSON) |
+//! | `GET /chains/{nuclide}/dot` | decay chain (Graphviz DOT) |
+//! | `GET /dcf/{pathway}/{nuclide}?age_group=Adult&organ=EffectiveDose` | dose coefficients (JSON), `Ingestion` or `Inhalation` |
+//!
+//! Datasets are shared by all requests and queried on the runtime threads, so
+//! they should be loaded beforehand (e.g. with `Icrp107::open_async`).
+
+use std::sync::Arc;
+
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::dataset::{DcfSource, DecaySource};
+use crate::decaychain::{to_dot, BatemanDecaySolver, DecayChainBuilder, Inventory};
+use crate::error::Error;
+use crate::primitive::{AgeGroup, Nuclide, Organ, Pathway};
+
+/// Datasets shared by the handlers
+#[derive(Clone)]
+pub struct ServiceState {
+    decay: Arc<dyn DecaySource>,
+    solver: Arc<BatemanDecaySolver<dyn DecaySource>>,
+    dcf: Option<Arc<dyn DcfSource>>,
+}
+
+impl ServiceState {
+    pub fn new(decay: Arc<dyn DecaySource>) -> Self {
+        Self {
+            solver: BatemanDecaySolver::new(decay.clone()),
+            decay,
+            dcf: None,
+        }
+    }
+
+    /// Serve `/dcf` from this dataset, which answers 404 otherwise
+    pub fn with_dcf(mut self, dcf: Arc<dyn DcfSource>) -> Self {
+        self.dcf = Some(dcf);
+        self
+    }
+}
+
+pub fn router(state: ServiceState) -> Router {
+    Router::new()
+        .route("/nuclides/{nuclide}", get(nuclide))
+        .route("/decay", post(decay))
+        .route("/chains/{nuclide}/dot", get(chain_dot))
+        .route("/dcf/{pathway}/{nuclide}", get(dcf))
+        .with_state(state)
+}
+
+/// Serve the endpoints on `listener` until the task is dropped
+pub async fn serve(listener: tokio::net::TcpListener, state: ServiceState) -> std::io::Result<()> {
+    axum::serve(listener, router(state)).await
+}
+
+struct ApiError(StatusCode, String);
+
+impl From<Error> for ApiError {
+    fn from(e: Error) -> Self {
+        let status = match e {
+            Error::InvalidNuclide(_) => StatusCode::NOT_FOUND,
+            Error::InvalidSymbol(_)
+            | Error::InvalidState(_)
+            | Error::InvalidAgeGroup(_)
+            | Error::InvalidOrgan(_)
+            | Error::InvalidPathway(_)
+            | Error::InvalidFloat(_)
+            | Error::InvalidActivityUnit(_)
+            | Error::UnsupportedSchemaVersion(_) => StatusCode::BAD_REQUEST,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+
+        Self(status, e.to_string())
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.0, self.1).into_response()
+    }
+}
+
+fn parse<T: std::str::FromStr<Err = Error>>(s: &str) -> Result<T, ApiError> {
+    s.parse()
+        .map_err(|e: Error| ApiError(StatusCode::BAD_REQUEST, e.to_string()))
+}
+
+async fn nuclide(
+    State(state): State<ServiceState>,
+    Path(nuclide): Path<String>,
+) -> Result<Json<Value>, ApiError> {
+    let nuclide: Nuclide = parse(&nuclide)?;
+    let half_life = state.decay.half_life(nuclide)?;
+    let progeny: Vec<Value> = state
+        .decay
+        .progeny(nuclide)?
+        .iter()
+        .map(|p| {
+            json!({
+                "nuclide": p.nuclide,
+                "branch_rate": p.branch_rate,
+                "decay_mode": p.decay_mode.to_string(),
+            })
+        })
+        .collect();
+
+    Ok(Json(json!({
+        "nuclide": nuclide,
+        "half_life": half_life.to_string(),
+        "half_life_seconds": half_life.as_sec(),
+        "decay_mode": state.decay.decay_mode(nuclide)?.to_string(),
+        "progeny": progeny,
+        "source": state.decay.dataset_identity().name,
+    })))
+}
+
+#[derive(Deserialize)]
+struct DecayRequest {
+    inventory: Inventory,
+    /// (s)
+    time: f64,
+}
+
+async fn decay(
+    State(state): State<ServiceState>,
+    Json(request): Json<DecayRequest>,
+) -> Result<Json<Inventory>, ApiError> {
+    if !request.time.is_finite() {
+        return Err(Error::InvalidFloat(request.time.to_string()).into());
+    }
+
+    Ok(Json(state.solver.decay(&request.inventory, request.time)))
+}
+
+async fn chain_dot(
+    State(state): State<ServiceState>,
+    Path(nuclide): Path<String>,
+) -> Result<String, ApiError> {
+    let nuclide: Nuclide = parse(&nuclide)?;
+    state.decay.half_life(nuclide)?;
+
+    let chain = DecayChainBuilder::new(state.decay.clone()).build(nuclide);
+    Ok(to_dot(&chain, true))
+}
+
+#[derive(Deserialize)]
+struct DcfQuery {
+    age_group: Option<String>,
+    organ: Option<String>,
+}
+
+async fn dcf(
+    State(state): State<ServiceState>,
+    Path((pathway, nuclide)): Path<(String, String)>,
+    Query(query): Query<DcfQuery>,
+) -> Result<Json<Value>, ApiError> {
+    let dcf = state.dcf.as_ref().ok_or_else(|| {
+        ApiError(
+            StatusCode::NOT_FOUND,
+            "no dose coefficients loaded".to_string(),
+        )
+    })?;
+    let nuclide: Nuclide = parse(&nuclide)?;
+    let age_group = query
+        .age_group
+        .as_deref()
+        .map_or(Ok(AgeGroup::Adult), parse)?;
+    let organ = query
+        .organ
+        .as_deref()
+        .map_or(Ok(Organ::EffectiveDose), parse)?;
+
+    let res = match parse(&pathway)? {
+        Pathway::Ingestion => dcf.dcf_ingestion(nuclide, age_group, organ)?,
+        Pathway::Inhalation => dcf.dcf_inhalation(nuclide, age_group, organ)?,
+        pathway => {
+            return Err(ApiError(
+                StatusCode::BAD_REQUEST,
+                format!("unsupported pathway: {}", pathway),
+            ))
+        }
+    };
+
+    let values: Vec<Value> = res
+        .values
+        .iter()
+        .map(|v| {
+            let attr = v.attr.as_ref();
+            json!({
+                "value": v.value,
+                "unit": v.unit,
+                "compound": attr.map(|a| a.compound.to_string()),
+                "f1": attr.map(|a| a.f1),
+                "class": attr
+                    .and_then(|a| a.respiratory_tract_attr.as_ref())
+                    .map(|c| c.to_string()),
+                "provenance": v.provenance.as_ref().map(|p| p.to_string()),
+            })
+        })
+        .collect();
+
+    Ok(Json(json!({
+        "nuclide": nuclide,
+        "values": values,
+        "source": res.source,
+        "not_found": res.not_found.map(|r| format!("{:?}", r)),
+    })))
+}
+
+#[cfg(test)]
+mod test {
+    use axum::body::{to_bytes, Body};
+    use axum::http::Request;
+    use tower::ServiceExt;
+
+    use super::*;
+    use crate::fixtures::FixtureDecayData;
+    use crate::primitive::attr::{DcfIngestion, DcfInhalation};
+    use crate::primitive::{DcfQueryResult, DcfValue};
+
+    struct TestDcf;
+
+    impl DcfIngestion for TestDcf {
+        fn dcf_ingestion(
+            &self,
+            _: Nuclide,
+            _: AgeGroup,
+            _: Organ,
+        ) -> Result<DcfQueryResult, Error> {
+            Ok(DcfQueryResult::new(
+                "test",
+                vec![DcfValue {
+                    value: 1.3e-8,
+                    unit: "Sv/Bq".to_string(),
+                    attr: None,
+                    provenance: None,
+                }],
+            ))
+        }
+    }
+
+    impl DcfInhalation for TestDcf {
+        fn dcf_inhalation(
+            &self,
+            _: Nuclide,
+            _: AgeGroup,
+            _: Organ,
+        ) -> Result<DcfQueryResult, Error> {
+            Err(Error::InvalidNuclide("test".to_string()))
+        }
+    }
+
+    fn request(router: &Router, request: Request<Body>) -> (StatusCode, String) {
+        tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap()
+            .block_on(async {
+                let response = router.clone().oneshot(request).await.unwrap();
+                let status = response.status();
+                let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+                (status, String::from_utf8(body.to_vec()).unwrap())
+            })
+    }
+
+    fn get(router: &Router, uri: &str) -> (StatusCode, String) {
+        request(router, Request::get(uri).body(Body::empty()).unwrap())
+    }
+
+    #[test]
+    fn endpoints() {
+        let state =
+            ServiceState::new(Arc::new(FixtureDecayData::new())).with_dcf(Arc::new(TestDcf));
+        let router = router(state);
+
+        let (status, body) = get(&router, "/nuclides/Sr-90");
+        assert_eq!(status, StatusCode::OK);
+        let info: Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(info["progeny"][0]["nuclide"], "Y-90");
+        assert_eq!(info["source"], "fixtures");
+
+        assert_eq!(get(&router, "/nuclides/U-238").0, StatusCode::NOT_FOUND);
+        assert_eq!(get(&router, "/nuclides/X-1").0, StatusCode::BAD_REQUEST);
+
+        let (status, body) = get(&router, "/chains/Sr-90/dot");
+        assert_eq!(status, StatusCode::OK);
+        assert!(body.contains("Y-90"));
+
+        let decay = json!({
+            "inventory": { "version": 1, "nuclides": [{ "nuclide": "Sr-90", "activity": 1e6 }] },
+            "time": 86400.,
+        });
+        let (status, body) = request(
+            &router,
+            Request::post("/decay")
+                .header("content-type", "application/json")
+                .body(Body::from(decay.to_string()))
+                .unwrap(),
+        );
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(Inventory::from_json(&body).unwrap().len(), 2);
+
+        let (status, body) = get(&router, "/dcf/Ingestion/Sr-90?age_group=Adult");
+        assert_eq!(status, StatusCode::OK);
+        let dcf: Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(dcf["values"][0]["value"], 1.3e-8);
+
+        assert_eq!(
+            get(&router, "/dcf/Inhalation/Sr-90").0,
+            StatusCode::NOT_FOUND
+        );
+        assert_eq!(
+            get(&router, "/dcf/GroundSurface/Sr-90").0,
+            StatusCode::BAD_REQUEST
+        );
+    }
+}
@@ -0,0 +1,109 @@
+//! Snapshot of the parsed values of every nuclide of the ICRP-107 data files.
+//!
+//! Run with `cargo test --features dataset-tests` after downloading the
+//! datasets with `data/download.py` (run from the `data` directory). The first
+//! run writes `tests/snapshots/icrp107.txt`, one digest per nuclide of its
+//! half-life, decay modes, branch sum and spectrum record counts; later runs
+//! list the nuclides whose digest changed. Set `UPDATE_SNAPSHOTS=1` to accept
+//! the current values after an intended change of the readers.
+
+#![cfg(feature = "dataset-tests")]
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use sha1::{Digest, Sha1};
+
+use radioactive::dataset::Icrp107;
+use radioactive::primitive::attr::{
+    NuclideDecayMode, NuclideHalfLife, NuclideList, NuclideProgeny,
+};
+use radioactive::primitive::Nuclide;
+
+const ICRP107_PATH: &str = "data/icrp107";
+const SNAPSHOT_PATH: &str = "tests/snapshots/icrp107.txt";
+
+/// Parsed values of a nuclide, as written into its digest
+fn record(data: &Icrp107, nuclide: Nuclide) -> String {
+    let half_life = data.half_life(nuclide).unwrap();
+    let branches: f64 = data
+        .progeny(nuclide)
+        .unwrap()
+        .iter()
+        .map(|p| p.branch_rate)
+        .sum();
+
+    format!(
+        "{} {} {} {} rad={} bet={} ack={} nsf={}",
+        half_life.value,
+        half_life.unit,
+        data.decay_mode(nuclide).unwrap(),
+        branches,
+        data.rad_spectrum(nuclide).unwrap().len(),
+        data.bet_spectrum(nuclide).unwrap().len(),
+        data.ack_spectrum(nuclide).unwrap().len(),
+        data.nsf_spectrum(nuclide).unwrap().len(),
+    )
+}
+
+fn digest(record: &str) -> String {
+    let hash = format!("{:x}", Sha1::digest(record.as_bytes()));
+    hash[..16].to_string()
+}
+
+fn read_snapshot(path: &Path) -> BTreeMap<String, String> {
+    fs::read_to_string(path)
+        .unwrap()
+        .lines()
+        .filter_map(|line| line.split_once(' '))
+        .map(|(nuclide, hash)| (nuclide.to_string(), hash.to_string()))
+        .collect()
+}
+
+#[test]
+fn icrp107_snapshot() {
+    if !Path::new(ICRP107_PATH).is_dir() {
+        eprintln!("{} not found, snapshot skipped", ICRP107_PATH);
+        return;
+    }
+    let data = Icrp107::open(ICRP107_PATH).unwrap();
+
+    let mut records = BTreeMap::new();
+    for nuclide in data.nuclide_list().unwrap() {
+        let record = record(&data, nuclide);
+        records.insert(nuclide.to_string(), (digest(&record), record));
+    }
+
+    let path = Path::new(SNAPSHOT_PATH);
+    if !path.exists() || std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        let snapshot: String = records
+            .iter()
+            .map(|(nuclide, (hash, _))| format!("{} {}\n", nuclide, hash))
+            .collect();
+        fs::write(path, snapshot).unwrap();
+        return;
+    }
+
+    let snapshot = read_snapshot(path);
+    let mut diffs = vec![];
+    for (nuclide, (hash, record)) in &records {
+        match snapshot.get(nuclide) {
+            Some(expected) if expected == hash => {}
+            Some(_) => diffs.push(format!("changed {}: {}", nuclide, record)),
+            None => diffs.push(format!("added {}: {}", nuclide, record)),
+        }
+    }
+    for nuclide in snapshot.keys().filter(|n| !records.contains_key(*n)) {
+        diffs.push(format!("removed {}", nuclide));
+    }
+
+    assert!(
+        diffs.is_empty(),
+        "{} nuclides differ from {}:\n{}",
+        diffs.len(),
+        SNAPSHOT_PATH,
+        diffs.join("\n")
+    );
+}
@@ -0,0 +1,113 @@
+//! Regression tests against published reference values.
+//!
+//! Run with `cargo test --features validation` after downloading the datasets
+//! with `data/download.py` (run from the `data` directory).
+
+#![cfg(feature = "validation")]
+
+use std::sync::Arc;
+
+use radioactive::dataset::{Icrp107, NistMassAttenCoef};
+use radioactive::decaychain::{BatemanDecaySolver, Inventory};
+use radioactive::primitive::attr::MeanFreePath;
+use radioactive::primitive::{Energy, MaterialBuilder, Nuclide};
+
+const ICRP107_PATH: &str = "data/icrp107";
+const NIST_PATH: &str = "data/XrayMassAttenCoef";
+
+const DAY: f64 = 86_400.;
+const YEAR: f64 = 365.2422 * DAY;
+
+fn icrp107() -> Arc<Icrp107> {
+    Arc::new(Icrp107::open(ICRP107_PATH).expect("ICRP-107 data not found"))
+}
+
+fn nist() -> Arc<NistMassAttenCoef> {
+    Arc::new(NistMassAttenCoef::open(NIST_PATH).expect("NIST data not found"))
+}
+
+fn nuclide(s: &str) -> Nuclide {
+    s.parse().unwrap()
+}
+
+fn assert_rel_close(value: f64, reference: f64, rtol: f64) {
+    assert!(
+        ((value - reference) / reference).abs() <= rtol,
+        "{} differs from reference {} by more than {}",
+        value,
+        reference,
+        rtol
+    );
+}
+
+// Ra-226 (1600 y) and Rn-222 (3.8235 d) reach secular equilibrium within a
+// month, Pb-210 (22.20 y) grows in as 1 - exp(-λt).
+#[test]
+fn ra226_chain_ingrowth() {
+    let solver = BatemanDecaySolver::new(icrp107());
+    let mut inv = Inventory::new();
+    inv.add(nuclide("Ra-226"), 1.);
+
+    let month = solver.decay(&inv, 30. * DAY);
+    assert_rel_close(month[&nuclide("Ra-226")], 1., 1e-4);
+    assert_rel_close(month[&nuclide("Rn-222")], 0.9957, 1e-3);
+    assert_rel_close(month[&nuclide("Po-214")], 0.9957, 2e-3);
+
+    let century = solver.decay(&inv, 100. * YEAR);
+    assert_rel_close(century[&nuclide("Ra-226")], 0.9576, 1e-3);
+    assert_rel_close(century[&nuclide("Pb-210")], 0.9264, 5e-3);
+}
+
+#[test]
+fn cs137_half_life() {
+    let solver = BatemanDecaySolver::new(icrp107());
+    let mut inv = Inventory::new();
+    inv.add(nuclide("Cs-137"), 1.);
+
+    let res = solver.decay(&inv, 30.1671 * YEAR);
+    assert_rel_close(res[&nuclide("Cs-137")], 0.5, 1e-4);
+}
+
+// Air kerma-rate constants (Gy m2 / Bq s): Co-60 0.305 and Ba-137m 0.0825 mGy m2 / GBq h
+#[test]
+fn air_kerma_constants() {
+    let data = icrp107();
+    let ndx = data.ndx().unwrap();
+
+    let per_gbq_h = 1e-3 / (1e9 * 3600.);
+    assert_rel_close(
+        ndx[&nuclide("Co-60")].air_kerma_const,
+        0.305 * per_gbq_h,
+        0.05,
+    );
+    assert_rel_close(
+        ndx[&nuclide("Ba-137m")].air_kerma_const,
+        0.0825 * per_gbq_h,
+        0.05,
+    );
+}
+
+// Half-value layers at 1 MeV: lead 0.86 cm, water 9.8 cm
+#[test]
+fn half_value_layers() {
+    let data = nist();
+
+    let lead = MaterialBuilder::new(data.clone())
+        .formula("Pb")
+        .unwrap()
+        .density(11.35)
+        .weight(1.)
+        .build()
+        .unwrap();
+    let water = MaterialBuilder::new(data.clone())
+        .formula("H2O")
+        .unwrap()
+        .density(1.)
+        .weight(1.)
+        .build()
+        .unwrap();
+
+    let hvl = |mfp: f64| mfp * 2f64.ln();
+    assert_rel_close(hvl(data.mfp(&lead, Energy::mev(1.)).unwrap()), 0.86, 0.01);
+    assert_rel_close(hvl(data.mfp(&water, Energy::mev(1.)).unwrap()), 9.8, 0.01);
+}